@@ -2,7 +2,7 @@
 ///
 /// This example shows how to use Polyphonica's real-time engine for streaming audio synthesis.
 /// Perfect for integration into applications requiring procedural music generation.
-use polyphonica::{AdsrEnvelope, RealtimeEngine, Waveform};
+use polyphonica::{AdsrEnvelope, EnvelopeCurve, RealtimeEngine, Waveform};
 use std::time::Duration;
 
 fn main() {
@@ -15,9 +15,11 @@ fn main() {
     // Define a musical envelope
     let envelope = AdsrEnvelope {
         attack_secs: 0.1,   // Quick attack
+        hold_secs: 0.0,
         decay_secs: 0.2,    // Medium decay
         sustain_level: 0.6, // 60% sustain
         release_secs: 0.5,  // Smooth release
+        curve: EnvelopeCurve::LINEAR,
     };
 
     println!(
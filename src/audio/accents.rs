@@ -5,7 +5,7 @@ use crate::timing::ClickType;
 /// This module provides specialized accent sound generation for musical
 /// applications, particularly metronomes where accented beats need to be
 /// clearly distinguishable from regular beats.
-use crate::{AdsrEnvelope, SampleData, Waveform};
+use crate::{AdsrEnvelope, EnvelopeCurve, SampleData, Waveform};
 
 /// Accent sound generator for rhythmic emphasis
 ///
@@ -62,9 +62,11 @@ impl AccentSoundGenerator {
             1600.0,           // Higher pitch than base 800Hz
             AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.1, // Longer decay than base
                 sustain_level: 0.0,
                 release_secs: 0.05,
+                curve: EnvelopeCurve::LINEAR,
             },
         )
     }
@@ -76,9 +78,11 @@ impl AccentSoundGenerator {
             2000.0,           // Much higher pitch than base 1000Hz
             AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.12, // Longer decay
                 sustain_level: 0.0,
                 release_secs: 0.06,
+                curve: EnvelopeCurve::LINEAR,
             },
         )
     }
@@ -90,9 +94,11 @@ impl AccentSoundGenerator {
             1600.0,             // Higher pitch than base 800Hz
             AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.2, // Longer decay
                 sustain_level: 0.0,
                 release_secs: 0.15,
+                curve: EnvelopeCurve::LINEAR,
             },
         )
     }
@@ -104,9 +110,11 @@ impl AccentSoundGenerator {
             2400.0,         // Much higher pitch than base 1200Hz
             AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.06, // Longer decay
                 sustain_level: 0.0,
                 release_secs: 0.04,
+                curve: EnvelopeCurve::LINEAR,
             },
         )
     }
@@ -162,11 +170,26 @@ pub fn get_accent_sound(
     AccentSoundGenerator::get_accent_sound(click_type, sample_adapter)
 }
 
+/// Get accent sound parameters for a click type along with their calibration gain
+///
+/// The fourth value is the calibration gain for the accent sound, so accents
+/// match the loudness of every other click; a gain override set for
+/// `click_type` applies to its accent as well.
+pub fn get_calibrated_accent_sound(
+    click_type: ClickType,
+    sample_adapter: &AudioSampleAdapter,
+) -> (Waveform, f32, AdsrEnvelope, f32) {
+    let (waveform, frequency, envelope) = get_accent_sound(click_type, sample_adapter);
+    let gain = sample_adapter.click_gain(click_type, true, &waveform, frequency, &envelope);
+    (waveform, frequency, envelope, gain)
+}
+
 /// Implementation for the new modular SampleManager
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::kick::KickSynth;
     use crate::audio::synthesis::AudioSampleAdapter;
     use std::collections::HashMap;
 
@@ -180,7 +203,8 @@ mod tests {
             AccentSoundGenerator::get_accent_sound(ClickType::AcousticSnare, &adapter);
 
         // Should fall back to synthetic kick since no samples available
-        assert_eq!(freq, 60.0); // Synthetic kick frequency
+        assert_eq!(freq, KickSynth::new().end_frequency_hz); // Synthetic kick frequency
+        assert!(matches!(waveform, Waveform::DrumSample(_)));
     }
 
     #[test]
@@ -0,0 +1,351 @@
+/// Spectral and loudness analysis for adaptive mixing
+///
+/// This module provides pure functions that summarise a buffer for automatic
+/// mixing decisions: spectral centroid for brightness and a simplified
+/// integrated loudness in the style of ITU-R BS.1770 (LUFS). LevelAnalysis
+/// reports peak and RMS levels and where a finished render clips, to decide
+/// whether to normalize or limit before export.
+use super::effects::{BiquadCoefficients, BiquadState, ShelfKind};
+use super::fft::{hann_window, Fft};
+use crate::inter_sample_peak_at;
+
+/// Largest FFT frame used by `spectral_centroid`
+const CENTROID_FRAME_SIZE: usize = 2048;
+
+/// Loudness block length in seconds (BS.1770 momentary window)
+const LOUDNESS_BLOCK_SECS: f32 = 0.4;
+
+/// Blocks quieter than this never count toward integrated loudness
+const ABSOLUTE_GATE_LUFS: f32 = -70.0;
+
+/// Blocks this far below the ungated loudness are dropped
+const RELATIVE_GATE_LU: f32 = -10.0;
+
+/// Brightness of a buffer: the magnitude-weighted mean frequency in Hz
+///
+/// The buffer is analysed in Hann-windowed frames of up to 2048 samples with
+/// 50% overlap and the magnitude spectra are summed before weighting, so a
+/// bright signal reports a higher centroid than a bass-heavy one. Returns 0.0
+/// for silence, an empty buffer, or a zero sample rate.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::analysis::spectral_centroid;
+///
+/// let tone: Vec<f32> = (0..4096)
+///     .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 44100.0).sin())
+///     .collect();
+/// let centroid = spectral_centroid(&tone, 44100);
+/// assert!((centroid - 1000.0).abs() < 50.0);
+/// ```
+pub fn spectral_centroid(samples: &[f32], sample_rate: u32) -> f32 {
+    if samples.len() < 2 || sample_rate == 0 {
+        return 0.0;
+    }
+
+    let frame_size = CENTROID_FRAME_SIZE
+        .min(samples.len().next_power_of_two() / 2)
+        .max(2);
+    let hop = frame_size / 2;
+    let fft = Fft::new(frame_size);
+    let window = hann_window(frame_size);
+
+    let mut magnitudes = vec![0.0f32; frame_size / 2 + 1];
+    let mut re = vec![0.0f32; frame_size];
+    let mut im = vec![0.0f32; frame_size];
+    let mut start = 0;
+    while start + frame_size <= samples.len() {
+        for (i, (sample, weight)) in samples[start..start + frame_size]
+            .iter()
+            .zip(&window)
+            .enumerate()
+        {
+            re[i] = sample * weight;
+            im[i] = 0.0;
+        }
+        fft.forward(&mut re, &mut im);
+        for (bin, magnitude) in magnitudes.iter_mut().enumerate() {
+            *magnitude += (re[bin] * re[bin] + im[bin] * im[bin]).sqrt();
+        }
+        start += hop;
+    }
+
+    let bin_hz = sample_rate as f32 / frame_size as f32;
+    let total: f32 = magnitudes.iter().sum();
+    if total <= f32::EPSILON {
+        return 0.0;
+    }
+    let weighted: f32 = magnitudes
+        .iter()
+        .enumerate()
+        .map(|(bin, magnitude)| bin as f32 * bin_hz * magnitude)
+        .sum();
+    weighted / total
+}
+
+/// Integrated loudness of a mono buffer in LUFS (simplified BS.1770)
+///
+/// The signal is K-weighted (a +4 dB high shelf and a 38 Hz high-pass), split
+/// into 400 ms blocks with 75% overlap, and gated at -70 LUFS and then 10 LU
+/// below the ungated level. Buffers shorter than one block are measured as a
+/// single block. Returns `f32::NEG_INFINITY` for silence or invalid input.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::analysis::integrated_loudness;
+///
+/// let quiet = vec![0.01; 44100];
+/// let loud = vec![0.1; 44100];
+/// assert!(integrated_loudness(&loud, 44100) > integrated_loudness(&quiet, 44100));
+/// ```
+pub fn integrated_loudness(samples: &[f32], sample_rate: u32) -> f32 {
+    if samples.is_empty() || sample_rate == 0 {
+        return f32::NEG_INFINITY;
+    }
+
+    let weighted = k_weight(samples, sample_rate as f32);
+    let block = ((LOUDNESS_BLOCK_SECS * sample_rate as f32) as usize).clamp(1, weighted.len());
+    let step = (block / 4).max(1);
+
+    let mut block_powers = Vec::new();
+    let mut start = 0;
+    while start + block <= weighted.len() {
+        let power = weighted[start..start + block]
+            .iter()
+            .map(|sample| sample * sample)
+            .sum::<f32>()
+            / block as f32;
+        block_powers.push(power);
+        start += step;
+    }
+
+    let gated_mean = |threshold_lufs: f32| {
+        let kept: Vec<f32> = block_powers
+            .iter()
+            .copied()
+            .filter(|&power| power_to_lufs(power) > threshold_lufs)
+            .collect();
+        (!kept.is_empty()).then(|| kept.iter().sum::<f32>() / kept.len() as f32)
+    };
+
+    let Some(ungated) = gated_mean(ABSOLUTE_GATE_LUFS) else {
+        return f32::NEG_INFINITY;
+    };
+    let relative_gate = power_to_lufs(ungated) + RELATIVE_GATE_LU;
+    power_to_lufs(gated_mean(relative_gate.max(ABSOLUTE_GATE_LUFS)).unwrap_or(ungated))
+}
+
+/// Peak, RMS, and clipping summary of a finished mono buffer
+///
+/// Levels are in dBFS relative to a full-scale sample value of 1.0, so a
+/// full-scale sine reads 0 dBFS peak and about -3 dBFS RMS. A sample is
+/// clipped when it is at or beyond full scale, or when the Catmull-Rom curve
+/// between it and the next sample overshoots full scale (a likely
+/// inter-sample peak). Non-finite samples count as clipped and are left out
+/// of the levels. Analyze each channel of a stereo render separately.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::analysis::LevelAnalysis;
+///
+/// let mut render = vec![0.25; 1000];
+/// render[500] = 1.5;
+/// let levels = LevelAnalysis::analyze(&render);
+/// assert_eq!(levels.clip_count(), 1);
+/// assert!(levels.peak_dbfs > 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelAnalysis {
+    /// Largest absolute sample value in dBFS
+    pub peak_dbfs: f32,
+    /// Estimated inter-sample (true) peak in dBFS
+    pub true_peak_dbfs: f32,
+    /// RMS level in dBFS
+    pub rms_dbfs: f32,
+    /// Sample peak over RMS in dB (0.0 for silence)
+    pub crest_factor_db: f32,
+    /// First sample of each run of clipped samples, in buffer order
+    pub clip_positions: Vec<usize>,
+}
+
+impl LevelAnalysis {
+    /// Scan a buffer for its levels and clips
+    pub fn analyze(samples: &[f32]) -> Self {
+        let mut peak = 0.0f32;
+        let mut true_peak = 0.0f32;
+        let mut sum_squares = 0.0f64;
+        let mut finite_count = 0usize;
+        let mut clip_positions = Vec::new();
+        let mut in_clip = false;
+
+        for (index, &sample) in samples.iter().enumerate() {
+            let segment_peak = inter_sample_peak_at(samples, index);
+            true_peak = true_peak.max(segment_peak);
+            let clipped = !sample.is_finite() || sample.abs() >= 1.0 || segment_peak > 1.0;
+            if clipped && !in_clip {
+                clip_positions.push(index);
+            }
+            in_clip = clipped;
+
+            if sample.is_finite() {
+                peak = peak.max(sample.abs());
+                sum_squares += (sample as f64).powi(2);
+                finite_count += 1;
+            }
+        }
+
+        let rms = if finite_count > 0 {
+            (sum_squares / finite_count as f64).sqrt() as f32
+        } else {
+            0.0
+        };
+        let (peak_dbfs, rms_dbfs) = (amplitude_to_dbfs(peak), amplitude_to_dbfs(rms));
+        Self {
+            peak_dbfs,
+            true_peak_dbfs: amplitude_to_dbfs(true_peak),
+            rms_dbfs,
+            crest_factor_db: if rms > 0.0 { peak_dbfs - rms_dbfs } else { 0.0 },
+            clip_positions,
+        }
+    }
+
+    /// Number of clipped runs found
+    pub fn clip_count(&self) -> usize {
+        self.clip_positions.len()
+    }
+
+    /// Gain in dB that would bring the true peak to full scale
+    pub fn headroom_db(&self) -> f32 {
+        -self.true_peak_dbfs
+    }
+}
+
+/// Level of an amplitude relative to full scale
+fn amplitude_to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        20.0 * amplitude.log10()
+    }
+}
+
+/// Loudness of a mean-square block power
+fn power_to_lufs(power: f32) -> f32 {
+    if power <= 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        -0.691 + 10.0 * power.log10()
+    }
+}
+
+/// Apply the BS.1770 K-weighting pre-filter
+fn k_weight(samples: &[f32], sample_rate: f32) -> Vec<f32> {
+    let shelf = BiquadCoefficients::shelf(ShelfKind::High, 1681.97, 4.0, sample_rate);
+    let high_pass = BiquadCoefficients::high_pass(38.14, 0.5, sample_rate);
+    let (mut shelf_state, mut high_pass_state) = (BiquadState::default(), BiquadState::default());
+
+    samples
+        .iter()
+        .map(|&sample| {
+            let shelved = shelf_state.process(&shelf, sample);
+            high_pass_state.process(&high_pass, shelved)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn sine(frequency: f32, amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f32 / 44100.0).sin())
+            .collect()
+    }
+
+    fn mix(a: &[f32], b: &[f32]) -> Vec<f32> {
+        a.iter().zip(b).map(|(x, y)| x + y).collect()
+    }
+
+    #[test]
+    fn test_bright_signal_has_higher_centroid() {
+        let bass_heavy = mix(&sine(80.0, 0.8, 8192), &sine(4000.0, 0.1, 8192));
+        let bright = mix(&sine(80.0, 0.1, 8192), &sine(4000.0, 0.8, 8192));
+
+        let bass_centroid = spectral_centroid(&bass_heavy, 44100);
+        let bright_centroid = spectral_centroid(&bright, 44100);
+        assert!(bright_centroid > bass_centroid * 3.0);
+        assert!(bright_centroid < 4000.0 && bass_centroid > 80.0);
+
+        assert_eq!(spectral_centroid(&vec![0.0; 4096], 44100), 0.0);
+        assert_eq!(spectral_centroid(&[], 44100), 0.0);
+    }
+
+    #[test]
+    fn test_louder_input_reports_higher_loudness() {
+        let quiet = sine(1000.0, 0.05, 44100);
+        let loud = sine(1000.0, 0.5, 44100);
+
+        let quiet_lufs = integrated_loudness(&quiet, 44100);
+        let loud_lufs = integrated_loudness(&loud, 44100);
+        assert!((loud_lufs - quiet_lufs - 20.0).abs() < 0.1);
+
+        // A full-scale 1 kHz sine reads about -3 LUFS
+        let full_scale = integrated_loudness(&sine(1000.0, 1.0, 44100), 44100);
+        assert!((full_scale + 3.0).abs() < 0.5, "{}", full_scale);
+
+        assert_eq!(
+            integrated_loudness(&vec![0.0; 44100], 44100),
+            f32::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn test_level_analysis_measures_sine_and_locates_clips() {
+        let mut render = sine(1000.0, 0.5, 44100);
+        let levels = LevelAnalysis::analyze(&render);
+        assert!(
+            (levels.peak_dbfs + 6.02).abs() < 0.01,
+            "{}",
+            levels.peak_dbfs
+        );
+        assert!((levels.rms_dbfs + 9.03).abs() < 0.01, "{}", levels.rms_dbfs);
+        assert!((levels.crest_factor_db - 3.01).abs() < 0.02);
+        assert!(levels.true_peak_dbfs >= levels.peak_dbfs);
+        assert!((levels.headroom_db() - 6.02).abs() < 0.05);
+        assert_eq!(levels.clip_count(), 0);
+
+        // A two-sample over, a full-scale sample, a non-finite sample, and a
+        // pair of samples whose interpolated curve overshoots full scale
+        render[1000] = 1.2;
+        render[1001] = 1.1;
+        render[20000] = -1.0;
+        render[25000] = f32::NAN;
+        render[29999..30003].copy_from_slice(&[0.5, 0.95, 0.95, 0.5]);
+        let levels = LevelAnalysis::analyze(&render);
+
+        assert_eq!(levels.clip_count(), 4, "{:?}", levels.clip_positions);
+        for (position, injected) in levels
+            .clip_positions
+            .iter()
+            .zip([1000, 20000, 25000, 30000])
+        {
+            assert!(
+                position.abs_diff(injected) <= 1,
+                "{} vs {}",
+                position,
+                injected
+            );
+        }
+        assert!((levels.peak_dbfs - 20.0 * 1.2f32.log10()).abs() < 1e-4);
+        assert!(levels.headroom_db() < 0.0);
+
+        let silence = LevelAnalysis::analyze(&vec![0.0; 100]);
+        assert_eq!(silence.rms_dbfs, f32::NEG_INFINITY);
+        assert_eq!(silence.crest_factor_db, 0.0);
+    }
+}
@@ -0,0 +1,202 @@
+/// Output dither and noise shaping for 16-bit export
+///
+/// This module converts floating-point audio to 16-bit integers for WAV
+/// export. Plain truncation turns quantization error into distortion that
+/// follows the signal on quiet tails; TPDF dither replaces it with a steady
+/// noise floor, and noise shaping moves that floor toward high frequencies.
+use crate::SynthRng;
+
+/// Full-scale value of a 16-bit sample
+const I16_SCALE: f32 = i16::MAX as f32;
+
+/// Dithering applied when reducing samples to 16 bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DitherMode {
+    /// Truncate without dither
+    #[default]
+    Off,
+    /// Triangular (TPDF) dither of ±1 LSB before rounding
+    Tpdf,
+    /// TPDF dither with first-order error feedback pushing noise up in frequency
+    NoiseShaped,
+}
+
+impl DitherMode {
+    /// Get all available dither modes
+    pub fn all() -> Vec<DitherMode> {
+        vec![DitherMode::Off, DitherMode::Tpdf, DitherMode::NoiseShaped]
+    }
+
+    /// Get the display name for this dither mode
+    pub fn name(self) -> &'static str {
+        match self {
+            DitherMode::Off => "Off",
+            DitherMode::Tpdf => "TPDF",
+            DitherMode::NoiseShaped => "Noise Shaped",
+        }
+    }
+}
+
+/// Stateful float-to-16-bit converter for one channel
+///
+/// Noise shaping carries error from sample to sample, so use one Ditherer
+/// per channel and feed it the samples in order.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::audio::dither::{DitherMode, Ditherer};
+///
+/// let samples = vec![0.0, 0.25, -0.5];
+/// let mut ditherer = Ditherer::new(DitherMode::Tpdf);
+/// let pcm: Vec<i16> = ditherer.quantize_buffer_i16(&samples);
+/// assert_eq!(pcm.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ditherer {
+    /// Dither applied to each sample
+    mode: DitherMode,
+
+    /// Dither noise source
+    rng: SynthRng,
+
+    /// Quantization error of the previous sample in LSBs (noise shaping)
+    error: f32,
+}
+
+impl Ditherer {
+    /// Create a converter using the given dither mode
+    pub fn new(mode: DitherMode) -> Self {
+        Self {
+            mode,
+            rng: SynthRng::default(),
+            error: 0.0,
+        }
+    }
+
+    /// Seed the dither noise for reproducible output
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng.seed(seed);
+        self
+    }
+
+    /// Get the dither mode
+    pub fn mode(&self) -> DitherMode {
+        self.mode
+    }
+
+    /// Convert one sample in [-1.0, 1.0] to 16 bits
+    pub fn quantize_i16(&mut self, sample: f32) -> i16 {
+        let scaled = sample.clamp(-1.0, 1.0) * I16_SCALE;
+        let quantized = match self.mode {
+            DitherMode::Off => scaled.trunc(),
+            DitherMode::Tpdf => (scaled + self.tpdf()).round(),
+            DitherMode::NoiseShaped => {
+                let shaped = scaled - self.error;
+                let quantized = (shaped + self.tpdf()).round();
+                self.error = quantized - shaped;
+                quantized
+            }
+        };
+        quantized.clamp(i16::MIN as f32, i16::MAX as f32) as i16
+    }
+
+    /// Convert a buffer of samples in order
+    pub fn quantize_buffer_i16(&mut self, samples: &[f32]) -> Vec<i16> {
+        samples
+            .iter()
+            .map(|&sample| self.quantize_i16(sample))
+            .collect()
+    }
+
+    /// Triangular noise spanning ±1 LSB
+    fn tpdf(&mut self) -> f32 {
+        self.rng.next_f32() - self.rng.next_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: usize = 48000;
+
+    /// One second of a 1 kHz sine only a few LSBs tall
+    fn quiet_sine() -> Vec<f32> {
+        (0..SAMPLE_RATE)
+            .map(|i| 3.3 / I16_SCALE * (2.0 * PI * 1000.0 * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    /// Quantization error in LSBs
+    fn quantization_error(mode: DitherMode, signal: &[f32]) -> Vec<f32> {
+        let pcm = Ditherer::new(mode).quantize_buffer_i16(signal);
+        pcm.iter()
+            .zip(signal)
+            .map(|(&q, &x)| q as f32 - x * I16_SCALE)
+            .collect()
+    }
+
+    /// Amplitude of the error at a whole-number frequency over one second
+    fn harmonic_amplitude(error: &[f32], frequency: f32) -> f32 {
+        let (mut re, mut im) = (0.0f64, 0.0f64);
+        for (i, &e) in error.iter().enumerate() {
+            let angle =
+                2.0 * std::f64::consts::PI * frequency as f64 * i as f64 / SAMPLE_RATE as f64;
+            re += e as f64 * angle.cos();
+            im += e as f64 * angle.sin();
+        }
+        (2.0 * (re * re + im * im).sqrt() / error.len() as f64) as f32
+    }
+
+    /// Normalized correlation between error and signal
+    fn correlation(error: &[f32], signal: &[f32]) -> f32 {
+        let dot: f32 = error.iter().zip(signal).map(|(e, x)| e * x).sum();
+        let error_norm: f32 = error.iter().map(|e| e * e).sum::<f32>().sqrt();
+        let signal_norm: f32 = signal.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (error_norm * signal_norm)
+    }
+
+    #[test]
+    fn test_dither_decorrelates_quantization_error() {
+        let signal = quiet_sine();
+        let truncated = quantization_error(DitherMode::Off, &signal);
+
+        // Truncation error tracks the signal and piles up on its harmonics
+        assert!(correlation(&truncated, &signal).abs() > 0.3);
+        let truncated_third = harmonic_amplitude(&truncated, 3000.0);
+        assert!(truncated_third > 0.05);
+
+        for mode in [DitherMode::Tpdf, DitherMode::NoiseShaped] {
+            let error = quantization_error(mode, &signal);
+            assert!(correlation(&error, &signal).abs() < 0.05, "{:?}", mode);
+            for harmonic in [1000.0, 3000.0, 5000.0] {
+                let amplitude = harmonic_amplitude(&error, harmonic);
+                assert!(
+                    amplitude < truncated_third / 3.0,
+                    "{:?} at {}",
+                    mode,
+                    harmonic
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_noise_shaping_moves_noise_up_in_frequency() {
+        let error = quantization_error(DitherMode::NoiseShaped, &vec![0.0; SAMPLE_RATE]);
+        let low: f32 = (1..20)
+            .map(|k| harmonic_amplitude(&error, k as f32 * 100.0))
+            .sum();
+        let high: f32 = (1..20)
+            .map(|k| harmonic_amplitude(&error, 20000.0 + k as f32 * 100.0))
+            .sum();
+        assert!(high > low * 4.0);
+
+        // Full scale and beyond stays in range
+        let mut ditherer = Ditherer::new(DitherMode::NoiseShaped);
+        assert_eq!(ditherer.quantize_i16(2.0), i16::MAX);
+        assert!(ditherer.quantize_i16(-1.0) <= -i16::MAX + 1);
+    }
+}
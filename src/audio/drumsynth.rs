@@ -0,0 +1,725 @@
+/// Synthesized snare, hi-hat, and clap sounds
+///
+/// This module adds parameterized snare, hi-hat, and clap synthesis alongside
+/// KickSynth and bundles all of them in SynthDrumKit, so a playable kit exists
+/// without any sample files. Like the kick, each piece is rendered into a
+/// one-shot drum sample and played through the engine from there.
+use super::kick::KickSynth;
+use crate::samples::HIHAT_CHOKE_GROUP;
+use crate::{
+    AdsrEnvelope, EnvelopeCurve, Interpolation, NoiseFilter, RealtimeEngine, SampleData,
+    SampleMetadata, SoundEvent, Waveform,
+};
+use std::f32::consts::PI;
+use std::sync::Mutex;
+
+/// Sample rate synthesized drums are rendered at for the engine
+pub const DRUM_RENDER_SAMPLE_RATE: u32 = 44100;
+
+/// Level falling along a quadratic curve from 1.0 to silence over `decay_secs`
+fn decay_curve(time_secs: f32, decay_secs: f32) -> f32 {
+    if time_secs < 0.0 || decay_secs <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - time_secs / decay_secs).max(0.0).powi(2)
+}
+
+/// Number of samples covering `duration_secs`
+fn render_length(duration_secs: f32, sample_rate: f32) -> usize {
+    (duration_secs * sample_rate).ceil() as usize
+}
+
+/// Wrap rendered samples as one-shot sample data
+fn one_shot(name: &str, samples: Vec<f32>, sample_rate: u32, base_frequency: f32) -> SampleData {
+    SampleData {
+        metadata: SampleMetadata {
+            filename: name.to_string(),
+            duration_secs: samples.len() as f32 / sample_rate.max(1) as f32,
+            channels: 1,
+            bits_per_sample: 32,
+        },
+        samples,
+        sample_rate,
+        base_frequency,
+        loop_start: None,
+        loop_end: None,
+        interpolation: Interpolation::default(),
+        stereo_channels: None,
+    }
+}
+
+/// Flat envelope passing a pre-shaped hit at full level for `duration_secs`
+pub(crate) fn gate_envelope(duration_secs: f32) -> AdsrEnvelope {
+    AdsrEnvelope {
+        attack_secs: 0.0,
+        hold_secs: duration_secs,
+        decay_secs: 0.0,
+        sustain_level: 0.0,
+        release_secs: 0.001,
+        curve: EnvelopeCurve::LINEAR,
+    }
+}
+
+/// Engine parameters for a rendered one-shot
+///
+/// The hit's shape is already in the samples, so the envelope is a flat gate
+/// over its length rather than a second decay.
+fn one_shot_params(sample_data: SampleData, frequency: f32) -> (Waveform, f32, AdsrEnvelope) {
+    let envelope = gate_envelope(sample_data.metadata.duration_secs);
+    (Waveform::DrumSample(sample_data), frequency, envelope)
+}
+
+/// Parameters for a synthesized snare drum
+///
+/// A sine "head" tone and low-passed "wire" noise each fall to silence along
+/// their own quadratic decay; `noise_mix` balances the two.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::audio::drumsynth::SnareSynth;
+///
+/// let snare = SnareSynth::new()
+///     .with_body(200.0, 0.1)
+///     .with_noise_mix(0.7);
+/// let samples = snare.render(44100);
+/// assert!(!samples.is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnareSynth {
+    /// Frequency of the head tone in Hz
+    pub body_frequency_hz: f32,
+
+    /// Decay time of the head tone in seconds
+    pub body_decay_secs: f32,
+
+    /// Low-pass cutoff of the wire noise in Hz
+    pub noise_cutoff_hz: f32,
+
+    /// Decay time of the wire noise in seconds
+    pub noise_decay_secs: f32,
+
+    /// Balance between head tone (0.0) and wire noise (1.0)
+    pub noise_mix: f32,
+}
+
+impl SnareSynth {
+    /// Create a general-purpose acoustic-style snare
+    pub fn new() -> Self {
+        Self {
+            body_frequency_hz: 185.0,
+            body_decay_secs: 0.12,
+            noise_cutoff_hz: 8000.0,
+            noise_decay_secs: 0.2,
+            noise_mix: 0.6,
+        }
+    }
+
+    /// Set the head tone frequency and decay time
+    pub fn with_body(mut self, frequency_hz: f32, decay_secs: f32) -> Self {
+        self.body_frequency_hz = frequency_hz.max(1.0);
+        self.body_decay_secs = decay_secs.max(0.001);
+        self
+    }
+
+    /// Set the wire noise cutoff and decay time
+    pub fn with_noise(mut self, cutoff_hz: f32, decay_secs: f32) -> Self {
+        self.noise_cutoff_hz = cutoff_hz.max(10.0);
+        self.noise_decay_secs = decay_secs.max(0.001);
+        self
+    }
+
+    /// Set the tone/noise balance (clamped to 0.0-1.0)
+    pub fn with_noise_mix(mut self, noise_mix: f32) -> Self {
+        self.noise_mix = noise_mix.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Total length of the hit in seconds
+    pub fn duration_secs(&self) -> f32 {
+        self.body_decay_secs.max(self.noise_decay_secs)
+    }
+
+    /// Render the hit at the given sample rate
+    pub fn render(&self, sample_rate: u32) -> Vec<f32> {
+        let sample_rate = sample_rate.max(1) as f32;
+        let mut noise = NoiseFilter::new();
+
+        (0..render_length(self.duration_secs(), sample_rate))
+            .map(|i| {
+                let time_secs = i as f32 / sample_rate;
+                let body = (2.0 * PI * self.body_frequency_hz * time_secs).sin()
+                    * decay_curve(time_secs, self.body_decay_secs);
+                let wires = noise.process(self.noise_cutoff_hz, 0.0, sample_rate)
+                    * decay_curve(time_secs, self.noise_decay_secs);
+                (body * (1.0 - self.noise_mix) + wires * self.noise_mix).clamp(-1.0, 1.0)
+            })
+            .collect()
+    }
+
+    /// Render the hit as one-shot sample data
+    pub fn to_sample_data(&self, sample_rate: u32) -> SampleData {
+        one_shot(
+            "snare_synth",
+            self.render(sample_rate),
+            sample_rate,
+            self.body_frequency_hz,
+        )
+    }
+
+    /// Waveform, frequency, and envelope for triggering the snare in the engine
+    ///
+    /// Renders the hit on every call; keep the result instead of calling this
+    /// for each trigger.
+    pub fn audio_params(&self) -> (Waveform, f32, AdsrEnvelope) {
+        one_shot_params(
+            self.to_sample_data(DRUM_RENDER_SAMPLE_RATE),
+            self.body_frequency_hz,
+        )
+    }
+}
+
+impl Default for SnareSynth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parameters for a synthesized hi-hat
+///
+/// A burst of white noise with its low end removed by a one-pole high-pass,
+/// falling to silence along a quadratic decay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HiHatSynth {
+    /// High-pass cutoff removing the body of the noise in Hz
+    pub highpass_hz: f32,
+
+    /// Decay time to silence in seconds
+    pub decay_secs: f32,
+}
+
+impl HiHatSynth {
+    /// Short, tight closed hi-hat
+    pub fn closed() -> Self {
+        Self {
+            highpass_hz: 7000.0,
+            decay_secs: 0.05,
+        }
+    }
+
+    /// Ringing open hi-hat
+    pub fn open() -> Self {
+        Self {
+            highpass_hz: 6000.0,
+            decay_secs: 0.35,
+        }
+    }
+
+    /// Set the high-pass cutoff
+    pub fn with_highpass(mut self, highpass_hz: f32) -> Self {
+        self.highpass_hz = highpass_hz.max(0.0);
+        self
+    }
+
+    /// Set the decay time
+    pub fn with_decay(mut self, decay_secs: f32) -> Self {
+        self.decay_secs = decay_secs.max(0.001);
+        self
+    }
+
+    /// Render the hit at the given sample rate
+    pub fn render(&self, sample_rate: u32) -> Vec<f32> {
+        let sample_rate = sample_rate.max(1) as f32;
+        let mut noise = NoiseFilter::new();
+        let coefficient = (-2.0 * PI * self.highpass_hz / sample_rate).exp();
+        let mut low = 0.0f32;
+
+        (0..render_length(self.decay_secs, sample_rate))
+            .map(|i| {
+                let white = noise.next_white();
+                low = (1.0 - coefficient) * white + coefficient * low;
+                let time_secs = i as f32 / sample_rate;
+                ((white - low) * decay_curve(time_secs, self.decay_secs)).clamp(-1.0, 1.0)
+            })
+            .collect()
+    }
+
+    /// Render the hit as one-shot sample data
+    pub fn to_sample_data(&self, sample_rate: u32) -> SampleData {
+        one_shot(
+            "hihat_synth",
+            self.render(sample_rate),
+            sample_rate,
+            self.highpass_hz,
+        )
+    }
+
+    /// Waveform, frequency, and envelope for triggering the hi-hat in the engine
+    ///
+    /// Renders the hit on every call; keep the result instead of calling this
+    /// for each trigger.
+    pub fn audio_params(&self) -> (Waveform, f32, AdsrEnvelope) {
+        one_shot_params(
+            self.to_sample_data(DRUM_RENDER_SAMPLE_RATE),
+            self.highpass_hz,
+        )
+    }
+}
+
+impl Default for HiHatSynth {
+    fn default() -> Self {
+        Self::closed()
+    }
+}
+
+/// Parameters for a synthesized hand clap
+///
+/// Several short bursts of low-passed noise in quick succession imitate hands
+/// landing slightly apart; the last burst rings on as the room tail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClapSynth {
+    /// Number of bursts, including the final one with the tail
+    pub burst_count: u32,
+
+    /// Time between burst onsets in seconds
+    pub burst_spacing_secs: f32,
+
+    /// Decay time of each burst before the last in seconds
+    pub burst_decay_secs: f32,
+
+    /// Decay time of the final burst in seconds
+    pub tail_decay_secs: f32,
+
+    /// Low-pass cutoff of the noise in Hz
+    pub cutoff_hz: f32,
+}
+
+impl ClapSynth {
+    /// Create a three-burst clap with a short tail
+    pub fn new() -> Self {
+        Self {
+            burst_count: 3,
+            burst_spacing_secs: 0.012,
+            burst_decay_secs: 0.008,
+            tail_decay_secs: 0.15,
+            cutoff_hz: 2500.0,
+        }
+    }
+
+    /// Set the number of bursts (at least one) and the time between them
+    pub fn with_bursts(mut self, burst_count: u32, spacing_secs: f32) -> Self {
+        self.burst_count = burst_count.max(1);
+        self.burst_spacing_secs = spacing_secs.max(0.0);
+        self
+    }
+
+    /// Set the decay time of the final burst
+    pub fn with_tail(mut self, tail_decay_secs: f32) -> Self {
+        self.tail_decay_secs = tail_decay_secs.max(0.001);
+        self
+    }
+
+    /// Total length of the hit in seconds
+    pub fn duration_secs(&self) -> f32 {
+        self.burst_count.saturating_sub(1) as f32 * self.burst_spacing_secs + self.tail_decay_secs
+    }
+
+    /// Amplitude envelope level at `time_secs` into the hit
+    ///
+    /// Each burst restarts at full level; overlapping bursts take the louder.
+    pub fn amplitude_at(&self, time_secs: f32) -> f32 {
+        (0..self.burst_count)
+            .map(|burst| {
+                let decay_secs = if burst + 1 == self.burst_count {
+                    self.tail_decay_secs
+                } else {
+                    self.burst_decay_secs
+                };
+                decay_curve(
+                    time_secs - burst as f32 * self.burst_spacing_secs,
+                    decay_secs,
+                )
+            })
+            .fold(0.0, f32::max)
+    }
+
+    /// Render the hit at the given sample rate
+    pub fn render(&self, sample_rate: u32) -> Vec<f32> {
+        let sample_rate = sample_rate.max(1) as f32;
+        let mut noise = NoiseFilter::new();
+
+        (0..render_length(self.duration_secs(), sample_rate))
+            .map(|i| {
+                let time_secs = i as f32 / sample_rate;
+                let burst = noise.process(self.cutoff_hz, 0.3, sample_rate);
+                (burst * self.amplitude_at(time_secs)).clamp(-1.0, 1.0)
+            })
+            .collect()
+    }
+
+    /// Render the hit as one-shot sample data
+    pub fn to_sample_data(&self, sample_rate: u32) -> SampleData {
+        one_shot(
+            "clap_synth",
+            self.render(sample_rate),
+            sample_rate,
+            self.cutoff_hz,
+        )
+    }
+
+    /// Waveform, frequency, and envelope for triggering the clap in the engine
+    ///
+    /// Renders the hit on every call; keep the result instead of calling this
+    /// for each trigger.
+    pub fn audio_params(&self) -> (Waveform, f32, AdsrEnvelope) {
+        one_shot_params(self.to_sample_data(DRUM_RENDER_SAMPLE_RATE), self.cutoff_hz)
+    }
+}
+
+impl Default for ClapSynth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pieces of the synthesized drum kit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SynthDrumPiece {
+    Kick,
+    Snare,
+    ClosedHiHat,
+    OpenHiHat,
+    Clap,
+}
+
+impl SynthDrumPiece {
+    /// Get all kit pieces
+    pub fn all() -> Vec<SynthDrumPiece> {
+        vec![
+            SynthDrumPiece::Kick,
+            SynthDrumPiece::Snare,
+            SynthDrumPiece::ClosedHiHat,
+            SynthDrumPiece::OpenHiHat,
+            SynthDrumPiece::Clap,
+        ]
+    }
+
+    /// Get the display name for this piece
+    pub fn name(self) -> &'static str {
+        match self {
+            SynthDrumPiece::Kick => "Kick",
+            SynthDrumPiece::Snare => "Snare",
+            SynthDrumPiece::ClosedHiHat => "Closed Hi-Hat",
+            SynthDrumPiece::OpenHiHat => "Open Hi-Hat",
+            SynthDrumPiece::Clap => "Clap",
+        }
+    }
+}
+
+/// Engine parameters rendered for a piece, with the voicing they came from
+type RenderedPiece<S> = Option<(S, (Waveform, f32, AdsrEnvelope))>;
+
+/// Rendered pieces reused by `SynthDrumKit::trigger`
+#[derive(Debug, Clone, Default)]
+struct RenderedKit {
+    kick: RenderedPiece<KickSynth>,
+    snare: RenderedPiece<SnareSynth>,
+    closed_hihat: RenderedPiece<HiHatSynth>,
+    open_hihat: RenderedPiece<HiHatSynth>,
+    clap: RenderedPiece<ClapSynth>,
+}
+
+/// Cached parameters for `synth`, rendered again only if its voicing changed
+fn rendered_piece<S: Clone + PartialEq>(
+    slot: &mut RenderedPiece<S>,
+    synth: &S,
+    render: fn(&S) -> (Waveform, f32, AdsrEnvelope),
+) -> (Waveform, f32, AdsrEnvelope) {
+    match slot {
+        Some((voicing, params)) if voicing == synth => params.clone(),
+        _ => {
+            let params = render(synth);
+            *slot = Some((synth.clone(), params.clone()));
+            params
+        }
+    }
+}
+
+/// A complete drum kit synthesized without sample files
+///
+/// Each piece's parameters are public for tweaking. Every piece is rendered
+/// when the kit is built and again only after its parameters change, so
+/// `trigger` plays a cached drum sample. The hi-hats share the preset kits'
+/// `HIHAT_CHOKE_GROUP`, so a closed hat cuts off a ringing open one.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::audio::drumsynth::{SynthDrumKit, SynthDrumPiece};
+/// use polyphonica::{render_event, RealtimeEngine};
+///
+/// let kit = SynthDrumKit::new();
+///
+/// // Offline
+/// let clap = render_event(&kit.sound_event(SynthDrumPiece::Clap), 44100);
+/// assert!(!clap.is_empty());
+///
+/// // Real time
+/// let mut engine = RealtimeEngine::new(44100.0);
+/// kit.trigger(&mut engine, SynthDrumPiece::Snare, 0.8);
+/// ```
+#[derive(Debug)]
+pub struct SynthDrumKit {
+    pub kick: KickSynth,
+    pub snare: SnareSynth,
+    pub closed_hihat: HiHatSynth,
+    pub open_hihat: HiHatSynth,
+    pub clap: ClapSynth,
+    rendered: Mutex<RenderedKit>,
+}
+
+impl SynthDrumKit {
+    /// Create a kit from each piece's default voicing
+    pub fn new() -> Self {
+        let kit = Self {
+            kick: KickSynth::new(),
+            snare: SnareSynth::new(),
+            closed_hihat: HiHatSynth::closed(),
+            open_hihat: HiHatSynth::open(),
+            clap: ClapSynth::new(),
+            rendered: Mutex::new(RenderedKit::default()),
+        };
+        for piece in SynthDrumPiece::all() {
+            kit.rendered_params(piece);
+        }
+        kit
+    }
+
+    /// Waveform, frequency, and envelope for triggering a piece in the engine
+    ///
+    /// The piece is rendered on every call; `trigger` reuses a cached render.
+    pub fn audio_params(&self, piece: SynthDrumPiece) -> (Waveform, f32, AdsrEnvelope) {
+        match piece {
+            SynthDrumPiece::Kick => self.kick.audio_params(),
+            SynthDrumPiece::Snare => self.snare.audio_params(),
+            SynthDrumPiece::ClosedHiHat => self.closed_hihat.audio_params(),
+            SynthDrumPiece::OpenHiHat => self.open_hihat.audio_params(),
+            SynthDrumPiece::Clap => self.clap.audio_params(),
+        }
+    }
+
+    /// A piece as a sound event for offline rendering
+    pub fn sound_event(&self, piece: SynthDrumPiece) -> SoundEvent {
+        let (waveform, frequency, envelope) = self.audio_params(piece);
+        SoundEvent {
+            waveform,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration_secs: envelope.attack_secs
+                + envelope.hold_secs
+                + envelope.decay_secs
+                + envelope.release_secs,
+            envelope,
+        }
+    }
+
+    /// Parameters for a piece from the render cache, re-rendering it if it was re-voiced
+    fn rendered_params(&self, piece: SynthDrumPiece) -> (Waveform, f32, AdsrEnvelope) {
+        let mut rendered = self.rendered.lock().unwrap();
+        match piece {
+            SynthDrumPiece::Kick => {
+                rendered_piece(&mut rendered.kick, &self.kick, KickSynth::audio_params)
+            }
+            SynthDrumPiece::Snare => {
+                rendered_piece(&mut rendered.snare, &self.snare, SnareSynth::audio_params)
+            }
+            SynthDrumPiece::ClosedHiHat => rendered_piece(
+                &mut rendered.closed_hihat,
+                &self.closed_hihat,
+                HiHatSynth::audio_params,
+            ),
+            SynthDrumPiece::OpenHiHat => rendered_piece(
+                &mut rendered.open_hihat,
+                &self.open_hihat,
+                HiHatSynth::audio_params,
+            ),
+            SynthDrumPiece::Clap => {
+                rendered_piece(&mut rendered.clap, &self.clap, ClapSynth::audio_params)
+            }
+        }
+    }
+
+    /// Trigger a piece in the engine at the given volume
+    ///
+    /// Plays the piece's cached render; a piece whose parameters changed since
+    /// it was last rendered is rendered once more first.
+    pub fn trigger(
+        &self,
+        engine: &mut RealtimeEngine,
+        piece: SynthDrumPiece,
+        volume: f32,
+    ) -> Option<u32> {
+        let (waveform, frequency, envelope) = self.rendered_params(piece);
+        match piece {
+            SynthDrumPiece::ClosedHiHat | SynthDrumPiece::OpenHiHat => engine
+                .trigger_note_in_choke_group(
+                    HIHAT_CHOKE_GROUP,
+                    waveform,
+                    frequency,
+                    envelope,
+                    volume,
+                ),
+            _ => engine.trigger_note_with_volume(waveform, frequency, envelope, volume),
+        }
+    }
+}
+
+impl Default for SynthDrumKit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for SynthDrumKit {
+    fn clone(&self) -> Self {
+        Self {
+            kick: self.kick.clone(),
+            snare: self.snare.clone(),
+            closed_hihat: self.closed_hihat.clone(),
+            open_hihat: self.open_hihat.clone(),
+            clap: self.clap.clone(),
+            rendered: Mutex::new(self.rendered.lock().unwrap().clone()),
+        }
+    }
+}
+
+impl PartialEq for SynthDrumKit {
+    fn eq(&self, other: &Self) -> bool {
+        self.kick == other.kick
+            && self.snare == other.snare
+            && self.closed_hihat == other.closed_hihat
+            && self.open_hihat == other.open_hihat
+            && self.clap == other.clap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::analysis::spectral_centroid;
+    use crate::render_event;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    /// Amplitude of a frequency across a window, by correlation
+    fn tone_amplitude(samples: &[f32], frequency: f32) -> f32 {
+        let (mut re, mut im) = (0.0f32, 0.0f32);
+        for (i, &x) in samples.iter().enumerate() {
+            let angle = 2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32;
+            re += x * angle.cos();
+            im += x * angle.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / samples.len() as f32
+    }
+
+    /// Ratio of the RMS of the first difference to the RMS of the signal
+    ///
+    /// Near zero for low tones, up to about 1.4 for white noise.
+    fn roughness(samples: &[f32]) -> f32 {
+        let energy: f32 = samples.iter().map(|x| x * x).sum();
+        let diff_energy: f32 = samples.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum();
+        (diff_energy / energy).sqrt()
+    }
+
+    #[test]
+    fn test_snare_has_noise_and_tonal_body() {
+        let window = SAMPLE_RATE as usize / 10;
+        let snare = SnareSynth::new().render(SAMPLE_RATE);
+        let tone_only = SnareSynth::new().with_noise_mix(0.0).render(SAMPLE_RATE);
+        let noise_only = SnareSynth::new().with_noise_mix(1.0).render(SAMPLE_RATE);
+
+        // The head tone shows up at its frequency, and the noise makes it rough
+        assert!(tone_amplitude(&snare[..window], 185.0) > 0.05);
+        assert!(tone_amplitude(&noise_only[..window], 185.0) < 0.02);
+        assert!(roughness(&snare) > 0.3, "{}", roughness(&snare));
+        assert!(roughness(&tone_only) < 0.05, "{}", roughness(&tone_only));
+        assert_eq!(snare.len(), (0.2 * SAMPLE_RATE as f32).ceil() as usize);
+    }
+
+    #[test]
+    fn test_hihats_are_bright_and_open_rings_longer() {
+        let closed = HiHatSynth::closed().render(SAMPLE_RATE);
+        let open = HiHatSynth::open().render(SAMPLE_RATE);
+
+        assert!(open.len() > closed.len() * 5);
+        for hat in [&closed, &open] {
+            let centroid = spectral_centroid(hat, SAMPLE_RATE);
+            assert!(centroid > 8000.0, "centroid {}", centroid);
+        }
+        assert!(closed.iter().any(|s| s.abs() > 0.3));
+    }
+
+    #[test]
+    fn test_clap_shows_separate_bursts() {
+        let clap = ClapSynth::new();
+        assert_eq!(clap.amplitude_at(0.0), 1.0);
+        assert_eq!(clap.amplitude_at(0.010), 0.0);
+        assert_eq!(clap.amplitude_at(0.024), 1.0);
+
+        // Count bursts rising out of silence in 1 ms RMS windows
+        let samples = clap.render(SAMPLE_RATE);
+        let rms: Vec<f32> = samples
+            .chunks(SAMPLE_RATE as usize / 1000)
+            .map(|chunk| (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+            .collect();
+        let mut onsets = usize::from(rms[0] > 0.05);
+        onsets += rms
+            .windows(2)
+            .filter(|pair| pair[0] < 0.02 && pair[1] > 0.05)
+            .count();
+        assert_eq!(onsets, 3);
+
+        let five = ClapSynth::new().with_bursts(5, 0.012);
+        assert!(five.duration_secs() > clap.duration_secs());
+    }
+
+    #[test]
+    fn test_kit_renders_and_triggers_every_piece() {
+        let kit = SynthDrumKit::new();
+        for piece in SynthDrumPiece::all() {
+            let rendered = render_event(&kit.sound_event(piece), SAMPLE_RATE);
+            assert!(rendered.iter().any(|s| s.abs() > 0.1), "{}", piece.name());
+        }
+
+        // A closed hat chokes the open hat
+        let mut engine = RealtimeEngine::new(SAMPLE_RATE as f32);
+        kit.trigger(&mut engine, SynthDrumPiece::OpenHiHat, 1.0);
+        engine.process_buffer(&mut vec![0.0; 441]);
+        kit.trigger(&mut engine, SynthDrumPiece::ClosedHiHat, 1.0);
+        kit.trigger(&mut engine, SynthDrumPiece::Snare, 1.0);
+        assert_eq!(engine.get_active_voice_count(), 3);
+        engine.process_buffer(&mut vec![0.0; 882]);
+        assert_eq!(engine.get_active_voice_count(), 2);
+    }
+
+    #[test]
+    fn test_kit_triggers_cached_renders_until_revoiced() {
+        let mut kit = SynthDrumKit::new();
+        let cached = |kit: &SynthDrumKit| kit.rendered.lock().unwrap().snare.clone().unwrap();
+        let (voicing, params) = cached(&kit);
+        assert_eq!(voicing, kit.snare);
+        assert_eq!(params, kit.audio_params(SynthDrumPiece::Snare));
+
+        let mut engine = RealtimeEngine::new(SAMPLE_RATE as f32);
+        kit.trigger(&mut engine, SynthDrumPiece::Snare, 1.0);
+        assert_eq!(cached(&kit).1, params);
+
+        // Re-voicing a piece renders it again on its next trigger
+        kit.snare = SnareSynth::new().with_noise_mix(0.0);
+        kit.trigger(&mut engine, SynthDrumPiece::Snare, 1.0);
+        let (voicing, revoiced) = cached(&kit);
+        assert_eq!(voicing, kit.snare);
+        assert_ne!(revoiced, params);
+        assert_eq!(revoiced, kit.audio_params(SynthDrumPiece::Snare));
+    }
+}
@@ -0,0 +1,1425 @@
+/// Audio effects for post-synthesis signal processing
+///
+/// This module defines the common interface for in-place buffer effects and
+/// provides the built-in effect implementations. Effects own all of their
+/// state (delay lines, envelopes) and allocate only at construction, so they
+/// can run inside the audio callback.
+use crate::timing::NoteDivision;
+use crate::AtomicF32;
+use std::f32::consts::PI;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// In-place audio effect processor
+///
+/// Implementations process mono f32 buffers in place and must not allocate
+/// in `process`. Effects are `Send` so graphs containing them can be moved
+/// into the audio thread.
+pub trait AudioEffect: Send {
+    /// Process a buffer of samples in place
+    fn process(&mut self, buffer: &mut [f32]);
+
+    /// Clear all internal state (delay lines, envelopes) without changing settings
+    fn reset(&mut self);
+
+    /// Short display name for UIs and debugging
+    fn name(&self) -> &'static str;
+}
+
+/// Magnitude below which recursive DSP state is flushed to exact zero
+///
+/// About -300 dBFS: far below anything audible, yet far above the subnormal
+/// range (below ~1.2e-38) whose arithmetic stalls some CPUs as feedback paths
+/// decay toward silence.
+pub const DENORMAL_THRESHOLD: f32 = 1e-15;
+
+/// Flush a feedback value to zero once it decays below `DENORMAL_THRESHOLD`
+#[inline]
+pub fn flush_denormal(value: f32) -> f32 {
+    if value.abs() < DENORMAL_THRESHOLD {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Freeverb-style comb filter delay tunings (in samples at 44.1kHz)
+const COMB_TUNINGS: [usize; 4] = [1116, 1188, 1277, 1356];
+/// Freeverb-style allpass delay tunings (in samples at 44.1kHz)
+const ALLPASS_TUNINGS: [usize; 2] = [556, 441];
+/// Fixed input gain applied before the comb bank
+const REVERB_INPUT_GAIN: f32 = 0.015;
+
+/// Lowpass-feedback comb filter used by the reverb tank
+#[derive(Debug, Clone)]
+struct CombFilter {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32,
+}
+
+impl CombFilter {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(1)],
+            index: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = flush_denormal(output * (1.0 - damping) + self.filter_store * damping);
+        self.buffer[self.index] = flush_denormal(input + self.filter_store * feedback);
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.index = 0;
+        self.filter_store = 0.0;
+    }
+}
+
+/// Schroeder allpass diffuser used by the reverb tank
+#[derive(Debug, Clone)]
+struct AllpassFilter {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl AllpassFilter {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(1)],
+            index: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.index];
+        let output = buffered - input;
+        self.buffer[self.index] = flush_denormal(input + buffered * 0.5);
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.index = 0;
+    }
+}
+
+/// Algorithmic reverb based on the Freeverb topology
+///
+/// Four parallel damped comb filters feed two series allpass diffusers.
+/// Delay lines are sized for the sample rate at construction time.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::effects::{AudioEffect, Reverb};
+///
+/// let mut reverb = Reverb::new(44100.0);
+/// reverb.set_room_size(0.8);
+/// reverb.set_wet(0.3);
+///
+/// let mut buffer = vec![0.0; 512];
+/// buffer[0] = 1.0;
+/// reverb.process(&mut buffer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reverb {
+    combs: Vec<CombFilter>,
+    allpasses: Vec<AllpassFilter>,
+    /// Room size (0.0 to 1.0), mapped to comb feedback
+    room_size: f32,
+    /// High-frequency damping inside the tank (0.0 to 1.0)
+    damping: f32,
+    /// Reverberated signal level (0.0 to 1.0)
+    wet: f32,
+    /// Original signal level (0.0 to 1.0)
+    dry: f32,
+}
+
+impl Reverb {
+    /// Create a reverb with delay lines sized for the given sample rate
+    pub fn new(sample_rate: f32) -> Self {
+        let scale = sample_rate.max(1.0) / 44100.0;
+        Self {
+            combs: COMB_TUNINGS
+                .iter()
+                .map(|&tuning| CombFilter::new((tuning as f32 * scale) as usize))
+                .collect(),
+            allpasses: ALLPASS_TUNINGS
+                .iter()
+                .map(|&tuning| AllpassFilter::new((tuning as f32 * scale) as usize))
+                .collect(),
+            room_size: 0.5,
+            damping: 0.5,
+            wet: 0.3,
+            dry: 1.0,
+        }
+    }
+
+    /// Set room size (0.0 to 1.0) - larger rooms ring longer
+    pub fn set_room_size(&mut self, room_size: f32) {
+        self.room_size = room_size.clamp(0.0, 1.0);
+    }
+
+    /// Set high-frequency damping (0.0 to 1.0)
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 1.0);
+    }
+
+    /// Set reverberated signal level (0.0 to 1.0)
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Set original signal level (0.0 to 1.0)
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Get current room size
+    pub fn room_size(&self) -> f32 {
+        self.room_size
+    }
+
+    /// Comb feedback scaled into Freeverb's stable 0.7-0.98 range
+    fn feedback(&self) -> f32 {
+        0.7 + self.room_size * 0.28
+    }
+}
+
+impl AudioEffect for Reverb {
+    fn process(&mut self, buffer: &mut [f32]) {
+        let feedback = self.feedback();
+        let damping = self.damping * 0.4;
+
+        for sample in buffer.iter_mut() {
+            let input = *sample * REVERB_INPUT_GAIN;
+
+            let mut wet_sample = 0.0;
+            for comb in &mut self.combs {
+                wet_sample += comb.process(input, feedback, damping);
+            }
+            for allpass in &mut self.allpasses {
+                wet_sample = allpass.process(wet_sample);
+            }
+
+            *sample = *sample * self.dry + wet_sample * self.wet;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.combs.iter_mut().for_each(CombFilter::reset);
+        self.allpasses.iter_mut().for_each(AllpassFilter::reset);
+    }
+
+    fn name(&self) -> &'static str {
+        "Reverb"
+    }
+}
+
+/// Lowest level tracked by dynamics processors, used in place of -inf dB
+const MIN_LEVEL_DB: f32 = -120.0;
+
+/// Convert a linear amplitude to decibels, flooring silence at `MIN_LEVEL_DB`
+fn linear_to_db(value: f32) -> f32 {
+    if value <= 0.0 {
+        MIN_LEVEL_DB
+    } else {
+        (20.0 * value.log10()).max(MIN_LEVEL_DB)
+    }
+}
+
+/// Convert decibels to a linear amplitude
+fn db_to_linear(db: f32) -> f32 {
+    10.0_f32.powf(db / 20.0)
+}
+
+/// One-pole smoothing coefficient reaching ~63% of a step after `time_secs`
+fn time_constant_coefficient(time_secs: f32, sample_rate: f32) -> f32 {
+    if time_secs <= 0.0 || sample_rate <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / (time_secs * sample_rate)).exp()
+    }
+}
+
+/// Soft-knee feed-forward compressor for the master bus
+///
+/// The gain computer works in the decibel domain with a quadratic soft knee,
+/// and the resulting gain reduction is smoothed by an attack/release envelope
+/// follower. Attack and release times are one-pole time constants: the gain
+/// reduction covers ~63% of a step change after the configured time.
+///
+/// The current gain reduction is published to a shared [`AtomicF32`] so a UI
+/// thread can meter it without locking the audio engine.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::effects::{AudioEffect, Compressor};
+///
+/// let mut compressor = Compressor::new(44100.0);
+/// compressor.set_threshold_db(-18.0);
+/// compressor.set_ratio(4.0);
+/// compressor.set_auto_makeup(true);
+///
+/// let meter = compressor.gain_reduction_meter();
+/// let mut buffer = vec![0.5; 512];
+/// compressor.process(&mut buffer);
+/// let reduction_db = meter.load(std::sync::atomic::Ordering::Relaxed);
+/// assert!(reduction_db > 0.0);
+/// ```
+#[derive(Debug)]
+pub struct Compressor {
+    sample_rate: f32,
+    /// Level above which compression starts (dBFS)
+    threshold_db: f32,
+    /// Input:output slope above the threshold (>= 1.0)
+    ratio: f32,
+    /// Time constant for increasing gain reduction
+    attack_secs: f32,
+    /// Time constant for recovering from gain reduction
+    release_secs: f32,
+    /// Width of the soft knee centred on the threshold (dB, 0 = hard knee)
+    knee_db: f32,
+    /// Manual makeup gain applied after compression (dB)
+    makeup_gain_db: f32,
+    /// Derive makeup gain from threshold and ratio instead of `makeup_gain_db`
+    auto_makeup: bool,
+    attack_coefficient: f32,
+    release_coefficient: f32,
+    /// Smoothed gain reduction in dB (positive values attenuate)
+    envelope_db: f32,
+    /// Shared meter updated once per processed block
+    meter: Arc<AtomicF32>,
+}
+
+impl Compressor {
+    /// Create a compressor with gentle bus-glue defaults
+    ///
+    /// Defaults: -18 dB threshold, 2:1 ratio, 10 ms attack, 100 ms release,
+    /// 6 dB knee, no makeup gain.
+    pub fn new(sample_rate: f32) -> Self {
+        let mut compressor = Self {
+            sample_rate,
+            threshold_db: -18.0,
+            ratio: 2.0,
+            attack_secs: 0.01,
+            release_secs: 0.1,
+            knee_db: 6.0,
+            makeup_gain_db: 0.0,
+            auto_makeup: false,
+            attack_coefficient: 0.0,
+            release_coefficient: 0.0,
+            envelope_db: 0.0,
+            meter: Arc::new(AtomicF32::new(0.0)),
+        };
+        compressor.update_coefficients();
+        compressor
+    }
+
+    /// Set threshold in dBFS
+    pub fn set_threshold_db(&mut self, threshold_db: f32) {
+        self.threshold_db = threshold_db.min(0.0);
+    }
+
+    /// Set compression ratio (1.0 = no compression)
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(1.0);
+    }
+
+    /// Set attack time in seconds
+    pub fn set_attack(&mut self, attack_secs: f32) {
+        self.attack_secs = attack_secs.max(0.0);
+        self.update_coefficients();
+    }
+
+    /// Set release time in seconds
+    pub fn set_release(&mut self, release_secs: f32) {
+        self.release_secs = release_secs.max(0.0);
+        self.update_coefficients();
+    }
+
+    /// Set soft knee width in dB (0.0 for a hard knee)
+    pub fn set_knee_db(&mut self, knee_db: f32) {
+        self.knee_db = knee_db.max(0.0);
+    }
+
+    /// Set manual makeup gain in dB (ignored while auto makeup is enabled)
+    pub fn set_makeup_gain_db(&mut self, makeup_gain_db: f32) {
+        self.makeup_gain_db = makeup_gain_db;
+    }
+
+    /// Enable or disable automatic makeup gain
+    pub fn set_auto_makeup(&mut self, auto_makeup: bool) {
+        self.auto_makeup = auto_makeup;
+    }
+
+    /// Update the sample rate used for attack/release timing
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_coefficients();
+    }
+
+    /// Get threshold in dBFS
+    pub fn threshold_db(&self) -> f32 {
+        self.threshold_db
+    }
+
+    /// Get compression ratio
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Current smoothed gain reduction in dB (0.0 = no reduction)
+    pub fn gain_reduction_db(&self) -> f32 {
+        self.envelope_db
+    }
+
+    /// Shared gain reduction meter for reading from a UI thread
+    pub fn gain_reduction_meter(&self) -> Arc<AtomicF32> {
+        Arc::clone(&self.meter)
+    }
+
+    /// Makeup gain currently applied, in dB
+    pub fn effective_makeup_db(&self) -> f32 {
+        if self.auto_makeup {
+            // Restore a full-scale signal to roughly its original level
+            self.static_gain_reduction_db(0.0)
+        } else {
+            self.makeup_gain_db
+        }
+    }
+
+    /// Static gain computer: reduction in dB for a given input level
+    pub fn static_gain_reduction_db(&self, input_db: f32) -> f32 {
+        let overshoot = input_db - self.threshold_db;
+        let slope = 1.0 - 1.0 / self.ratio;
+
+        if self.knee_db > 0.0 && 2.0 * overshoot.abs() <= self.knee_db {
+            let knee_position = overshoot + self.knee_db / 2.0;
+            slope * knee_position * knee_position / (2.0 * self.knee_db)
+        } else if overshoot > 0.0 {
+            slope * overshoot
+        } else {
+            0.0
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        self.attack_coefficient = time_constant_coefficient(self.attack_secs, self.sample_rate);
+        self.release_coefficient = time_constant_coefficient(self.release_secs, self.sample_rate);
+    }
+}
+
+impl AudioEffect for Compressor {
+    fn process(&mut self, buffer: &mut [f32]) {
+        let makeup_db = self.effective_makeup_db();
+
+        for sample in buffer.iter_mut() {
+            let target_db = self.static_gain_reduction_db(linear_to_db(sample.abs()));
+            let coefficient = if target_db > self.envelope_db {
+                self.attack_coefficient
+            } else {
+                self.release_coefficient
+            };
+            self.envelope_db =
+                flush_denormal(target_db + coefficient * (self.envelope_db - target_db));
+
+            *sample *= db_to_linear(makeup_db - self.envelope_db);
+        }
+
+        self.meter.store(self.envelope_db, Ordering::Relaxed);
+    }
+
+    fn reset(&mut self) {
+        self.envelope_db = 0.0;
+        self.meter.store(0.0, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> &'static str {
+        "Compressor"
+    }
+}
+
+/// One-pole lowpass coefficient for a -3 dB point at `cutoff_hz`
+fn one_pole_coefficient(cutoff_hz: f32, sample_rate: f32) -> f32 {
+    if cutoff_hz <= 0.0 || sample_rate <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-2.0 * std::f32::consts::PI * cutoff_hz / sample_rate).exp()
+    }
+}
+
+/// Harmonic exciter that adds brightness to thin material
+///
+/// The input is split with a one-pole high-pass filter, the high band is
+/// driven through a `tanh` saturator to synthesize upper harmonics, and the
+/// result is high-passed again and mixed back on top of the untouched dry
+/// signal. `amount` scales the added band linearly, so 0.0 is a bypass.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::effects::{AudioEffect, Exciter};
+///
+/// let mut exciter = Exciter::new(44100.0);
+/// exciter.set_cutoff_hz(2500.0);
+/// exciter.set_drive(4.0);
+/// exciter.set_amount(0.4);
+///
+/// let mut buffer = vec![0.0; 512];
+/// exciter.process(&mut buffer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Exciter {
+    sample_rate: f32,
+    /// Corner frequency of the band that gets saturated (Hz)
+    cutoff_hz: f32,
+    /// Gain into the saturator (>= 1.0); higher values add more harmonics
+    drive: f32,
+    /// Level of the synthesized band mixed over the dry signal (0.0 to 1.0)
+    amount: f32,
+    coefficient: f32,
+    /// Lowpass state used to split off the high band before saturation
+    split_state: f32,
+    /// Lowpass state used to keep only the high band after saturation
+    harmonic_state: f32,
+}
+
+impl Exciter {
+    /// Create an exciter with subtle defaults
+    ///
+    /// Defaults: 3 kHz cutoff, drive 4.0, amount 0.3.
+    pub fn new(sample_rate: f32) -> Self {
+        let mut exciter = Self {
+            sample_rate,
+            cutoff_hz: 3000.0,
+            drive: 4.0,
+            amount: 0.3,
+            coefficient: 0.0,
+            split_state: 0.0,
+            harmonic_state: 0.0,
+        };
+        exciter.update_coefficient();
+        exciter
+    }
+
+    /// Set the high-pass corner of the excited band in Hz
+    pub fn set_cutoff_hz(&mut self, cutoff_hz: f32) {
+        self.cutoff_hz = cutoff_hz.clamp(20.0, self.sample_rate.max(40.0) / 2.0);
+        self.update_coefficient();
+    }
+
+    /// Set saturator drive (1.0 = nearly linear)
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive = drive.max(1.0);
+    }
+
+    /// Set level of the added harmonics (0.0 to 1.0)
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount.clamp(0.0, 1.0);
+    }
+
+    /// Update the sample rate used by the band-split filters
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_coefficient();
+    }
+
+    /// Get the high-pass corner in Hz
+    pub fn cutoff_hz(&self) -> f32 {
+        self.cutoff_hz
+    }
+
+    /// Get saturator drive
+    pub fn drive(&self) -> f32 {
+        self.drive
+    }
+
+    /// Get level of the added harmonics
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    fn update_coefficient(&mut self) {
+        self.coefficient = one_pole_coefficient(self.cutoff_hz, self.sample_rate);
+    }
+}
+
+impl AudioEffect for Exciter {
+    fn process(&mut self, buffer: &mut [f32]) {
+        if self.amount <= 0.0 {
+            return;
+        }
+
+        // Normalise so a full-scale high band saturates to full scale
+        let normalisation = 1.0 / self.drive.tanh();
+
+        for sample in buffer.iter_mut() {
+            self.split_state =
+                flush_denormal(self.split_state + self.coefficient * (*sample - self.split_state));
+            let high_band = *sample - self.split_state;
+
+            let saturated = (high_band * self.drive).tanh() * normalisation;
+            self.harmonic_state = flush_denormal(
+                self.harmonic_state + self.coefficient * (saturated - self.harmonic_state),
+            );
+            let harmonics = saturated - self.harmonic_state;
+
+            *sample += harmonics * self.amount;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.split_state = 0.0;
+        self.harmonic_state = 0.0;
+    }
+
+    fn name(&self) -> &'static str {
+        "Exciter"
+    }
+}
+
+/// Largest channel count `ToneControl::process_interleaved` keeps state for
+pub const MAX_TONE_CHANNELS: usize = 8;
+
+/// Shelf gains below this magnitude are treated as flat
+const FLAT_SHELF_DB: f32 = 1e-3;
+
+/// Which side of the corner frequency a shelf boosts or cuts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ShelfKind {
+    Low,
+    High,
+}
+
+/// Normalised biquad coefficients (a0 = 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct BiquadCoefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl BiquadCoefficients {
+    const IDENTITY: Self = Self {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    /// RBJ cookbook shelving filter with a shelf slope of 1
+    pub(super) fn shelf(
+        kind: ShelfKind,
+        frequency_hz: f32,
+        gain_db: f32,
+        sample_rate: f32,
+    ) -> Self {
+        if gain_db.abs() < FLAT_SHELF_DB || sample_rate <= 0.0 {
+            return Self::IDENTITY;
+        }
+
+        let a = 10.0_f32.powf(gain_db / 40.0);
+        let omega = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+        let two_sqrt_a_alpha = 2.0 * a.sqrt() * sin / std::f32::consts::SQRT_2;
+
+        let (b0, b1, b2, a0, a1, a2) = match kind {
+            ShelfKind::Low => (
+                a * ((a + 1.0) - (a - 1.0) * cos + two_sqrt_a_alpha),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - two_sqrt_a_alpha),
+                (a + 1.0) + (a - 1.0) * cos + two_sqrt_a_alpha,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - two_sqrt_a_alpha,
+            ),
+            ShelfKind::High => (
+                a * ((a + 1.0) + (a - 1.0) * cos + two_sqrt_a_alpha),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - two_sqrt_a_alpha),
+                (a + 1.0) - (a - 1.0) * cos + two_sqrt_a_alpha,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - two_sqrt_a_alpha,
+            ),
+        };
+
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    /// RBJ cookbook second-order high-pass filter
+    pub(super) fn high_pass(frequency_hz: f32, q: f32, sample_rate: f32) -> Self {
+        if sample_rate <= 0.0 || q <= 0.0 {
+            return Self::IDENTITY;
+        }
+
+        let omega = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+
+    /// RBJ cookbook second-order low-pass filter
+    pub(super) fn low_pass(frequency_hz: f32, q: f32, sample_rate: f32) -> Self {
+        if sample_rate <= 0.0 || q <= 0.0 {
+            return Self::IDENTITY;
+        }
+
+        let omega = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 - cos) / 2.0 / a0,
+            b1: (1.0 - cos) / a0,
+            b2: (1.0 - cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+
+    /// RBJ cookbook band-pass filter with 0 dB peak gain
+    pub(super) fn band_pass(frequency_hz: f32, q: f32, sample_rate: f32) -> Self {
+        if sample_rate <= 0.0 || q <= 0.0 {
+            return Self::IDENTITY;
+        }
+
+        let omega = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: alpha / a0,
+            b1: 0.0,
+            b2: -alpha / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+
+    /// RBJ cookbook notch filter
+    pub(super) fn notch(frequency_hz: f32, q: f32, sample_rate: f32) -> Self {
+        if sample_rate <= 0.0 || q <= 0.0 {
+            return Self::IDENTITY;
+        }
+
+        let omega = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: 1.0 / a0,
+            b1: -2.0 * cos / a0,
+            b2: 1.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+}
+
+/// Transposed direct form II delay state for one biquad on one channel
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct BiquadState {
+    z1: f32,
+    z2: f32,
+}
+
+impl BiquadState {
+    pub(super) fn process(&mut self, c: &BiquadCoefficients, input: f32) -> f32 {
+        let output = c.b0 * input + self.z1;
+        self.z1 = flush_denormal(c.b1 * input - c.a1 * output + self.z2);
+        self.z2 = flush_denormal(c.b2 * input - c.a2 * output);
+        output
+    }
+}
+
+/// Two-band shelving tone control for the master output
+///
+/// A low shelf and a high shelf (RBJ biquads) boost or cut everything below
+/// and above their corner frequencies. Both shelves default to 0 dB, which
+/// bypasses processing entirely. `process_interleaved` keeps separate filter
+/// state per channel so it can run directly on a stereo output buffer.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::effects::{AudioEffect, ToneControl};
+///
+/// let mut tone = ToneControl::new(44100.0);
+/// tone.set_low_gain_db(-3.0);
+/// tone.set_high_gain_db(4.0);
+/// tone.set_high_frequency_hz(6000.0);
+///
+/// let mut stereo = vec![0.0; 1024];
+/// tone.process_interleaved(&mut stereo, 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ToneControl {
+    sample_rate: f32,
+    /// Low shelf corner frequency (Hz)
+    low_frequency_hz: f32,
+    /// Low shelf gain (dB, negative cuts)
+    low_gain_db: f32,
+    /// High shelf corner frequency (Hz)
+    high_frequency_hz: f32,
+    /// High shelf gain (dB, negative cuts)
+    high_gain_db: f32,
+    low_coefficients: BiquadCoefficients,
+    high_coefficients: BiquadCoefficients,
+    /// Per-channel (low shelf, high shelf) filter state
+    states: [(BiquadState, BiquadState); MAX_TONE_CHANNELS],
+}
+
+impl ToneControl {
+    /// Maximum boost or cut per shelf in dB
+    pub const MAX_GAIN_DB: f32 = 24.0;
+
+    /// Create a flat tone control
+    ///
+    /// Defaults: low shelf at 200 Hz, high shelf at 4 kHz, both 0 dB.
+    pub fn new(sample_rate: f32) -> Self {
+        let mut tone = Self {
+            sample_rate,
+            low_frequency_hz: 200.0,
+            low_gain_db: 0.0,
+            high_frequency_hz: 4000.0,
+            high_gain_db: 0.0,
+            low_coefficients: BiquadCoefficients::IDENTITY,
+            high_coefficients: BiquadCoefficients::IDENTITY,
+            states: [Default::default(); MAX_TONE_CHANNELS],
+        };
+        tone.update_coefficients();
+        tone
+    }
+
+    /// Set low shelf gain in dB (clamped to +/-24 dB)
+    pub fn set_low_gain_db(&mut self, gain_db: f32) {
+        self.low_gain_db = gain_db.clamp(-Self::MAX_GAIN_DB, Self::MAX_GAIN_DB);
+        self.update_coefficients();
+    }
+
+    /// Set low shelf corner frequency in Hz
+    pub fn set_low_frequency_hz(&mut self, frequency_hz: f32) {
+        self.low_frequency_hz = self.clamp_frequency(frequency_hz);
+        self.update_coefficients();
+    }
+
+    /// Set high shelf gain in dB (clamped to +/-24 dB)
+    pub fn set_high_gain_db(&mut self, gain_db: f32) {
+        self.high_gain_db = gain_db.clamp(-Self::MAX_GAIN_DB, Self::MAX_GAIN_DB);
+        self.update_coefficients();
+    }
+
+    /// Set high shelf corner frequency in Hz
+    pub fn set_high_frequency_hz(&mut self, frequency_hz: f32) {
+        self.high_frequency_hz = self.clamp_frequency(frequency_hz);
+        self.update_coefficients();
+    }
+
+    /// Update the sample rate used to compute the shelf filters
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.low_frequency_hz = self.clamp_frequency(self.low_frequency_hz);
+        self.high_frequency_hz = self.clamp_frequency(self.high_frequency_hz);
+        self.update_coefficients();
+    }
+
+    /// Get low shelf gain in dB
+    pub fn low_gain_db(&self) -> f32 {
+        self.low_gain_db
+    }
+
+    /// Get low shelf corner frequency in Hz
+    pub fn low_frequency_hz(&self) -> f32 {
+        self.low_frequency_hz
+    }
+
+    /// Get high shelf gain in dB
+    pub fn high_gain_db(&self) -> f32 {
+        self.high_gain_db
+    }
+
+    /// Get high shelf corner frequency in Hz
+    pub fn high_frequency_hz(&self) -> f32 {
+        self.high_frequency_hz
+    }
+
+    /// Whether both shelves are flat and processing is skipped
+    pub fn is_flat(&self) -> bool {
+        self.low_gain_db.abs() < FLAT_SHELF_DB && self.high_gain_db.abs() < FLAT_SHELF_DB
+    }
+
+    /// Process an interleaved multi-channel buffer in place
+    ///
+    /// Channels beyond `MAX_TONE_CHANNELS` are passed through unchanged.
+    pub fn process_interleaved(&mut self, buffer: &mut [f32], channels: usize) {
+        if self.is_flat() || channels == 0 {
+            return;
+        }
+
+        for frame in buffer.chunks_mut(channels) {
+            for (sample, (low, high)) in frame.iter_mut().zip(self.states.iter_mut()) {
+                let shelved = low.process(&self.low_coefficients, *sample);
+                *sample = high.process(&self.high_coefficients, shelved);
+            }
+        }
+    }
+
+    fn clamp_frequency(&self, frequency_hz: f32) -> f32 {
+        frequency_hz.clamp(20.0, (self.sample_rate * 0.45).max(20.0))
+    }
+
+    fn update_coefficients(&mut self) {
+        self.low_coefficients = BiquadCoefficients::shelf(
+            ShelfKind::Low,
+            self.low_frequency_hz,
+            self.low_gain_db,
+            self.sample_rate,
+        );
+        self.high_coefficients = BiquadCoefficients::shelf(
+            ShelfKind::High,
+            self.high_frequency_hz,
+            self.high_gain_db,
+            self.sample_rate,
+        );
+    }
+}
+
+impl AudioEffect for ToneControl {
+    fn process(&mut self, buffer: &mut [f32]) {
+        self.process_interleaved(buffer, 1);
+    }
+
+    fn reset(&mut self) {
+        self.states = [Default::default(); MAX_TONE_CHANNELS];
+    }
+
+    fn name(&self) -> &'static str {
+        "ToneControl"
+    }
+}
+
+/// Tempo-synced auto-pan sweeping the signal across the stereo field
+///
+/// A sine LFO moves the pan position once per note division at the current
+/// tempo. Each frame of an interleaved stereo buffer is summed to mono and
+/// re-panned with the equal-power law, so a centered signal sits 3 dB below
+/// full scale in each channel and the total power stays constant as it moves.
+/// This is a stereo effect and does not implement the mono `AudioEffect` trait.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::effects::AutoPan;
+/// use polyphonica::timing::NoteDivision;
+///
+/// let mut pan = AutoPan::new(44100.0);
+/// pan.set_tempo_bpm(120.0);
+/// pan.set_division(NoteDivision::Whole); // one sweep per 4/4 bar
+/// pan.set_depth(0.8);
+///
+/// let mut stereo = vec![0.5; 1024];
+/// pan.process_stereo(&mut stereo);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutoPan {
+    sample_rate: f32,
+    /// Tempo the LFO is synced to (BPM)
+    tempo_bpm: f32,
+    /// Length of one LFO cycle
+    division: NoteDivision,
+    /// Sweep width (0.0 = centered, 1.0 = hard left to hard right)
+    depth: f32,
+    /// LFO phase in cycles (0.0 to 1.0)
+    phase: f32,
+}
+
+impl AutoPan {
+    /// Create an auto-pan at depth 1.0, one cycle per bar at 120 BPM
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            tempo_bpm: 120.0,
+            division: NoteDivision::Whole,
+            depth: 1.0,
+            phase: 0.0,
+        }
+    }
+
+    /// Set the tempo the sweep is synced to
+    pub fn set_tempo_bpm(&mut self, tempo_bpm: f32) {
+        self.tempo_bpm = tempo_bpm.max(0.0);
+    }
+
+    /// Set the note length of one full sweep
+    pub fn set_division(&mut self, division: NoteDivision) {
+        self.division = division;
+    }
+
+    /// Set the sweep depth (clamped to 0.0-1.0)
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth.clamp(0.0, 1.0);
+    }
+
+    /// Update the sample rate used to advance the LFO
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Get the synced tempo in BPM
+    pub fn tempo_bpm(&self) -> f32 {
+        self.tempo_bpm
+    }
+
+    /// Get the note length of one sweep
+    pub fn division(&self) -> NoteDivision {
+        self.division
+    }
+
+    /// Get the sweep depth
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Sweep rate in Hz at the current tempo
+    pub fn rate_hz(&self) -> f32 {
+        let cycle_secs = self.division.duration_secs(self.tempo_bpm);
+        if cycle_secs > 0.0 {
+            1.0 / cycle_secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Current pan position (-1.0 = left, 0.0 = center, 1.0 = right)
+    pub fn pan_position(&self) -> f32 {
+        self.depth * (2.0 * PI * self.phase).sin()
+    }
+
+    /// Restart the sweep from the center, moving right
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    /// Process an interleaved stereo buffer in place
+    pub fn process_stereo(&mut self, buffer: &mut [f32]) {
+        let phase_step = if self.sample_rate > 0.0 {
+            self.rate_hz() / self.sample_rate
+        } else {
+            0.0
+        };
+
+        for frame in buffer.chunks_exact_mut(2) {
+            let mono = (frame[0] + frame[1]) * 0.5;
+            let angle = (self.pan_position() + 1.0) * PI / 4.0;
+            frame[0] = mono * angle.cos();
+            frame[1] = mono * angle.sin();
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverb_produces_tail() {
+        let mut reverb = Reverb::new(44100.0);
+        reverb.set_dry(0.0);
+        reverb.set_wet(1.0);
+
+        let mut buffer = vec![0.0; 4410];
+        buffer[0] = 1.0;
+        reverb.process(&mut buffer);
+
+        // Nothing arrives before the shortest comb delay, then the tail starts
+        assert!(buffer[..1000].iter().all(|s| s.abs() < 1e-6));
+        assert!(buffer[1000..].iter().any(|s| s.abs() > 1e-4));
+    }
+
+    #[test]
+    fn test_reverb_reset_clears_tail() {
+        let mut reverb = Reverb::new(44100.0);
+        let mut buffer = vec![0.0; 2048];
+        buffer[0] = 1.0;
+        reverb.process(&mut buffer);
+
+        reverb.reset();
+        let mut silence = vec![0.0; 2048];
+        reverb.process(&mut silence);
+        assert!(silence.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_reverb_tail_flushes_to_exact_zero() {
+        let mut reverb = Reverb::new(44100.0);
+        reverb.set_room_size(0.0);
+        reverb.set_dry(0.0);
+        reverb.set_wet(1.0);
+
+        let mut buffer = vec![0.0; 44100 * 4];
+        buffer[0] = 1.0;
+        reverb.process(&mut buffer);
+
+        // The tail never lingers in the subnormal range, it ends in true silence
+        assert!(buffer.iter().all(|s| !s.is_subnormal()));
+        assert!(buffer[44100 * 3..].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_reverb_parameter_clamping() {
+        let mut reverb = Reverb::new(48000.0);
+        reverb.set_room_size(2.0);
+        assert_eq!(reverb.room_size(), 1.0);
+        assert!(reverb.feedback() < 1.0);
+    }
+
+    fn settle(compressor: &mut Compressor, level: f32, samples: usize) -> Vec<f32> {
+        let mut buffer = vec![level; samples];
+        compressor.process(&mut buffer);
+        buffer
+    }
+
+    /// Samples until the gain reduction covers `fraction` of a step change
+    fn samples_to_reach(
+        compressor: &mut Compressor,
+        level: f32,
+        start_db: f32,
+        target_db: f32,
+        fraction: f32,
+    ) -> usize {
+        let goal = start_db + (target_db - start_db) * fraction;
+        let mut sample = [level];
+        for count in 1..200_000 {
+            compressor.process(&mut sample);
+            sample[0] = level;
+            let reduction = compressor.gain_reduction_db();
+            if (target_db > start_db && reduction >= goal)
+                || (target_db < start_db && reduction <= goal)
+            {
+                return count;
+            }
+        }
+        panic!("gain reduction never reached {} dB", goal);
+    }
+
+    #[test]
+    fn test_compressor_ratio_above_threshold() {
+        let mut compressor = Compressor::new(44100.0);
+        compressor.set_threshold_db(-20.0);
+        compressor.set_ratio(4.0);
+        compressor.set_knee_db(0.0);
+
+        // 0.5 is ~-6 dBFS: 14 dB over threshold should come out 3.5 dB over
+        let output = settle(&mut compressor, 0.5, 44100);
+        let input_db = linear_to_db(0.5);
+        let expected_db = -20.0 + (input_db + 20.0) / 4.0;
+        let output_db = linear_to_db(*output.last().unwrap());
+        assert!(
+            (output_db - expected_db).abs() < 0.05,
+            "{} vs {}",
+            output_db,
+            expected_db
+        );
+    }
+
+    #[test]
+    fn test_compressor_passes_signal_below_threshold() {
+        let mut compressor = Compressor::new(44100.0);
+        compressor.set_threshold_db(-6.0);
+        compressor.set_knee_db(0.0);
+
+        let output = settle(&mut compressor, 0.1, 4410);
+        assert!(output.iter().all(|s| (*s - 0.1).abs() < 1e-6));
+        assert_eq!(compressor.gain_reduction_db(), 0.0);
+    }
+
+    #[test]
+    fn test_compressor_soft_knee_is_gradual() {
+        let mut compressor = Compressor::new(44100.0);
+        compressor.set_threshold_db(-20.0);
+        compressor.set_ratio(4.0);
+        compressor.set_knee_db(10.0);
+
+        // Reduction starts below the threshold and matches the hard knee above it
+        assert_eq!(compressor.static_gain_reduction_db(-26.0), 0.0);
+        assert!(compressor.static_gain_reduction_db(-22.0) > 0.0);
+        let at_threshold = compressor.static_gain_reduction_db(-20.0);
+        assert!(at_threshold > 0.0 && at_threshold < 1.0);
+        assert!((compressor.static_gain_reduction_db(-10.0) - 7.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_compressor_attack_and_release_timing() {
+        let sample_rate = 44100.0;
+        let mut compressor = Compressor::new(sample_rate);
+        compressor.set_threshold_db(-20.0);
+        compressor.set_ratio(4.0);
+        compressor.set_knee_db(0.0);
+        compressor.set_attack(0.01);
+        compressor.set_release(0.1);
+
+        let target = compressor.static_gain_reduction_db(linear_to_db(0.5));
+        let one_minus_inv_e = 1.0 - (-1.0_f32).exp();
+
+        let attack_samples = samples_to_reach(&mut compressor, 0.5, 0.0, target, one_minus_inv_e);
+        let attack_secs = attack_samples as f32 / sample_rate;
+        assert!(
+            (attack_secs - 0.01).abs() < 0.001,
+            "attack {}s",
+            attack_secs
+        );
+
+        settle(&mut compressor, 0.5, 44100);
+        let start = compressor.gain_reduction_db();
+        let release_samples = samples_to_reach(&mut compressor, 0.01, start, 0.0, one_minus_inv_e);
+        let release_secs = release_samples as f32 / sample_rate;
+        assert!(
+            (release_secs - 0.1).abs() < 0.01,
+            "release {}s",
+            release_secs
+        );
+    }
+
+    #[test]
+    fn test_compressor_release_settles_to_exact_zero() {
+        let mut compressor = Compressor::new(44100.0);
+        compressor.set_release(0.01);
+        settle(&mut compressor, 1.0, 4410);
+        assert!(compressor.gain_reduction_db() > 0.0);
+
+        // Silence releases the envelope into true zero, not a subnormal tail
+        settle(&mut compressor, 0.0, 44100);
+        assert_eq!(compressor.gain_reduction_db(), 0.0);
+    }
+
+    #[test]
+    fn test_compressor_makeup_and_metering() {
+        let mut compressor = Compressor::new(44100.0);
+        compressor.set_threshold_db(-12.0);
+        compressor.set_ratio(3.0);
+        compressor.set_knee_db(0.0);
+        compressor.set_auto_makeup(true);
+
+        // Auto makeup restores a full-scale signal to full scale
+        assert!((compressor.effective_makeup_db() - 8.0).abs() < 1e-4);
+        let output = settle(&mut compressor, 1.0, 44100);
+        assert!((output.last().unwrap() - 1.0).abs() < 0.01);
+
+        let meter = compressor.gain_reduction_meter();
+        assert!((meter.load(Ordering::Relaxed) - 8.0).abs() < 0.01);
+
+        compressor.reset();
+        assert_eq!(meter.load(Ordering::Relaxed), 0.0);
+
+        compressor.set_auto_makeup(false);
+        compressor.set_makeup_gain_db(6.0);
+        assert_eq!(compressor.effective_makeup_db(), 6.0);
+    }
+
+    fn sine(frequency: f32, sample_rate: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    /// Magnitude of a single frequency bin (Goertzel algorithm)
+    fn tone_magnitude(buffer: &[f32], frequency: f32, sample_rate: f32) -> f32 {
+        let omega = 2.0 * std::f32::consts::PI * frequency / sample_rate;
+        let coefficient = 2.0 * omega.cos();
+        let (mut previous, mut before_previous) = (0.0, 0.0);
+        for &sample in buffer {
+            let current = sample + coefficient * previous - before_previous;
+            before_previous = previous;
+            previous = current;
+        }
+        let power = previous * previous + before_previous * before_previous
+            - coefficient * previous * before_previous;
+        power.max(0.0).sqrt() / buffer.len() as f32
+    }
+
+    fn excite(amount: f32, input: &[f32]) -> Vec<f32> {
+        let mut exciter = Exciter::new(44100.0);
+        exciter.set_cutoff_hz(2000.0);
+        exciter.set_amount(amount);
+        let mut buffer = input.to_vec();
+        exciter.process(&mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn test_exciter_adds_harmonics() {
+        // 1 kHz over 4410 samples is a whole number of cycles
+        let input = sine(1000.0, 44100.0, 4410);
+        let third_in = tone_magnitude(&input, 3000.0, 44100.0);
+        assert!(third_in < 1e-4);
+
+        let light = tone_magnitude(&excite(0.25, &input), 3000.0, 44100.0);
+        let heavy = tone_magnitude(&excite(0.5, &input), 3000.0, 44100.0);
+        assert!(
+            light > 100.0 * third_in.max(1e-6),
+            "third harmonic {}",
+            light
+        );
+        assert!((heavy / light - 2.0).abs() < 0.05, "{} vs {}", heavy, light);
+    }
+
+    #[test]
+    fn test_exciter_zero_amount_is_bypass() {
+        let input = sine(1000.0, 44100.0, 1024);
+        assert_eq!(excite(0.0, &input), input);
+
+        let mut exciter = Exciter::new(44100.0);
+        exciter.set_amount(2.0);
+        exciter.set_drive(0.1);
+        assert_eq!(exciter.amount(), 1.0);
+        assert_eq!(exciter.drive(), 1.0);
+    }
+
+    /// Deterministic white noise in [-0.5, 0.5)
+    fn white_noise(samples: usize) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..samples)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+            })
+            .collect()
+    }
+
+    /// Summed magnitude over a set of frequency bins
+    fn band_level(buffer: &[f32], frequencies: &[f32]) -> f32 {
+        frequencies
+            .iter()
+            .map(|&frequency| tone_magnitude(buffer, frequency, 44100.0))
+            .sum()
+    }
+
+    const LOW_BAND: [f32; 4] = [40.0, 60.0, 80.0, 100.0];
+    const HIGH_BAND: [f32; 4] = [10000.0, 12000.0, 14000.0, 16000.0];
+
+    fn shelve(low_gain_db: f32, high_gain_db: f32, input: &[f32]) -> Vec<f32> {
+        let mut tone = ToneControl::new(44100.0);
+        tone.set_low_gain_db(low_gain_db);
+        tone.set_high_gain_db(high_gain_db);
+        let mut buffer = input.to_vec();
+        tone.process(&mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn test_tone_control_high_shelf_boost() {
+        let noise = white_noise(44100);
+        let boosted = shelve(0.0, 6.0, &noise);
+
+        // +6 dB is a factor of ~2 well above the corner, little change well below
+        let high_ratio = band_level(&boosted, &HIGH_BAND) / band_level(&noise, &HIGH_BAND);
+        let low_ratio = band_level(&boosted, &LOW_BAND) / band_level(&noise, &LOW_BAND);
+        assert!((high_ratio - 2.0).abs() < 0.2, "high ratio {}", high_ratio);
+        assert!((low_ratio - 1.0).abs() < 0.1, "low ratio {}", low_ratio);
+    }
+
+    #[test]
+    fn test_tone_control_low_shelf_cut() {
+        let noise = white_noise(44100);
+        let cut = shelve(-12.0, 0.0, &noise);
+
+        // -12 dB is a factor of ~0.25 well below the corner
+        let low_ratio = band_level(&cut, &LOW_BAND) / band_level(&noise, &LOW_BAND);
+        let high_ratio = band_level(&cut, &HIGH_BAND) / band_level(&noise, &HIGH_BAND);
+        assert!(low_ratio < 0.35, "low ratio {}", low_ratio);
+        assert!((high_ratio - 1.0).abs() < 0.1, "high ratio {}", high_ratio);
+    }
+
+    #[test]
+    fn test_tone_control_flat_is_bypass_and_channels_independent() {
+        let noise = white_noise(1024);
+        assert_eq!(shelve(0.0, 0.0, &noise), noise);
+
+        // Interleaving the same signal on both channels gives identical output
+        let mut tone = ToneControl::new(44100.0);
+        tone.set_high_gain_db(50.0);
+        assert_eq!(tone.high_gain_db(), ToneControl::MAX_GAIN_DB);
+        let mut stereo: Vec<f32> = noise.iter().flat_map(|&s| [s, s]).collect();
+        tone.process_interleaved(&mut stereo, 2);
+        let mono = shelve(0.0, ToneControl::MAX_GAIN_DB, &noise);
+        for (frame, expected) in stereo.chunks(2).zip(&mono) {
+            assert_eq!(frame[0], *expected);
+            assert_eq!(frame[1], *expected);
+        }
+    }
+
+    /// Left minus right level of each frame of a panned constant tone
+    fn pan_balance(pan: &mut AutoPan, frames: usize) -> Vec<f32> {
+        let mut stereo = vec![0.5; frames * 2];
+        pan.process_stereo(&mut stereo);
+        stereo.chunks(2).map(|frame| frame[0] - frame[1]).collect()
+    }
+
+    #[test]
+    fn test_auto_pan_oscillates_at_synced_rate() {
+        // A quarter-note sweep at 120 BPM is 2 Hz: four cycles per second
+        let mut pan = AutoPan::new(1000.0);
+        pan.set_division(NoteDivision::Quarter);
+        assert_eq!(pan.rate_hz(), 2.0);
+
+        let balance = pan_balance(&mut pan, 1000);
+        let crossings = balance
+            .windows(2)
+            .filter(|pair| pair[0] >= 0.0 && pair[1] < 0.0)
+            .count();
+        assert_eq!(crossings, 2);
+
+        // Hard right a quarter cycle in, hard left three quarters in, equal power throughout
+        assert!(balance[125] < -0.35 && balance[375] > 0.35);
+        let mut stereo = vec![0.5; 2000];
+        pan.reset();
+        pan.process_stereo(&mut stereo);
+        for frame in stereo.chunks(2) {
+            let power = frame[0] * frame[0] + frame[1] * frame[1];
+            assert!((power - 0.25).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_auto_pan_depth_zero_is_centered() {
+        let mut pan = AutoPan::new(1000.0);
+        pan.set_division(NoteDivision::Sixteenth);
+        pan.set_depth(0.0);
+
+        let mut stereo = vec![0.5; 2000];
+        pan.process_stereo(&mut stereo);
+        for frame in stereo.chunks(2) {
+            assert_eq!(frame[0], frame[1]);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+/// Radix-2 FFT with precomputed tables, and the Hann window
+///
+/// This module provides Fft, an in-place iterative radix-2 transform of one
+/// fixed power-of-two length. Its twiddle factors and bit-reversal table are
+/// computed once up front, so work that transforms frame after frame
+/// (spectrum displays, spectral centroid, block convolution) neither calls
+/// sin/cos per butterfly nor allocates.
+use std::f32::consts::PI;
+
+/// In-place complex FFT of a fixed power-of-two length
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::fft::Fft;
+///
+/// let fft = Fft::new(8);
+/// let mut re = vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+/// let mut im = vec![0.0; 8];
+/// fft.forward(&mut re, &mut im);
+///
+/// // An impulse has a flat spectrum
+/// assert!(re.iter().all(|&bin| (bin - 1.0).abs() < 1e-6));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fft {
+    /// Transform length, a power of two
+    size: usize,
+
+    /// Twiddle factors (cos, sin) for the largest butterfly stage
+    twiddles: Vec<(f32, f32)>,
+
+    /// Bit-reversed index of each position
+    bit_reverse: Vec<usize>,
+}
+
+impl Fft {
+    /// Create a transform of `size` points, rounded up to a power of two (at least 2)
+    pub fn new(size: usize) -> Self {
+        let size = size.max(2).next_power_of_two();
+        let twiddles = (0..size / 2)
+            .map(|k| {
+                let (sin, cos) = (-2.0 * PI * k as f32 / size as f32).sin_cos();
+                (cos, sin)
+            })
+            .collect();
+        let bits = size.trailing_zeros();
+        let bit_reverse = (0..size)
+            .map(|i| i.reverse_bits() >> (usize::BITS - bits))
+            .collect();
+
+        Self {
+            size,
+            twiddles,
+            bit_reverse,
+        }
+    }
+
+    /// Transform length in points
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Forward transform of `re` + i·`im` in place
+    ///
+    /// Both slices must be exactly `size` long. The result is unscaled.
+    pub fn forward(&self, re: &mut [f32], im: &mut [f32]) {
+        let n = self.size;
+        assert!(
+            re.len() == n && im.len() == n,
+            "FFT buffers must match its size"
+        );
+
+        for (i, &j) in self.bit_reverse.iter().enumerate() {
+            if i < j {
+                re.swap(i, j);
+                im.swap(i, j);
+            }
+        }
+
+        let mut length = 2;
+        while length <= n {
+            let stride = n / length;
+            for start in (0..n).step_by(length) {
+                for k in 0..length / 2 {
+                    let (cos, sin) = self.twiddles[k * stride];
+                    let (a, b) = (start + k, start + k + length / 2);
+                    let t_re = re[b] * cos - im[b] * sin;
+                    let t_im = re[b] * sin + im[b] * cos;
+                    re[b] = re[a] - t_re;
+                    im[b] = im[a] - t_im;
+                    re[a] += t_re;
+                    im[a] += t_im;
+                }
+            }
+            length <<= 1;
+        }
+    }
+
+    /// Inverse transform of `re` + i·`im` in place, scaled by 1/size
+    ///
+    /// Undoes `forward`, so a forward and inverse pair returns the input.
+    pub fn inverse(&self, re: &mut [f32], im: &mut [f32]) {
+        im.iter_mut().for_each(|value| *value = -*value);
+        self.forward(re, im);
+        let scale = 1.0 / self.size as f32;
+        re.iter_mut().for_each(|value| *value *= scale);
+        im.iter_mut().for_each(|value| *value *= -scale);
+    }
+}
+
+/// Periodic Hann window of `size` coefficients
+pub fn hann_window(size: usize) -> Vec<f32> {
+    (0..size)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / size as f32).cos())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_matches_direct_dft() {
+        let fft = Fft::new(16);
+        let signal: Vec<f32> = (0..16).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+        let mut re = signal.clone();
+        let mut im = vec![0.0; 16];
+        fft.forward(&mut re, &mut im);
+
+        for bin in 0..16 {
+            let (mut dft_re, mut dft_im) = (0.0f32, 0.0f32);
+            for (i, &x) in signal.iter().enumerate() {
+                let angle = -2.0 * PI * (bin * i) as f32 / 16.0;
+                dft_re += x * angle.cos();
+                dft_im += x * angle.sin();
+            }
+            assert!((re[bin] - dft_re).abs() < 1e-4, "bin {}", bin);
+            assert!((im[bin] - dft_im).abs() < 1e-4, "bin {}", bin);
+        }
+    }
+
+    #[test]
+    fn test_inverse_restores_the_input() {
+        let fft = Fft::new(32);
+        let signal: Vec<f32> = (0..32).map(|i| (i as f32 * 0.7).sin()).collect();
+        let mut re = signal.clone();
+        let mut im = vec![0.0; 32];
+        fft.forward(&mut re, &mut im);
+        fft.inverse(&mut re, &mut im);
+
+        for (restored, original) in re.iter().zip(&signal) {
+            assert!((restored - original).abs() < 1e-5);
+        }
+        assert!(im.iter().all(|value| value.abs() < 1e-5));
+    }
+
+    #[test]
+    fn test_sizes_round_up_and_window_is_symmetric() {
+        assert_eq!(Fft::new(0).size(), 2);
+        assert_eq!(Fft::new(1000).size(), 1024);
+
+        let window = hann_window(8);
+        assert_eq!(window[0], 0.0);
+        assert!((window[4] - 1.0).abs() < 1e-6);
+        assert!((window[1] - window[7]).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,253 @@
+/// Biquad filters for shaping the engine output
+///
+/// This module provides Biquad, a second-order IIR filter with low-pass,
+/// high-pass, band-pass, and notch modes using the RBJ Audio EQ Cookbook
+/// coefficients. It processes mono buffers in place, so it can run directly
+/// after `RealtimeEngine::process_buffer` in an audio callback.
+use super::effects::{AudioEffect, BiquadCoefficients, BiquadState};
+
+/// Lowest cutoff frequency accepted, in Hz
+pub const MIN_CUTOFF_HZ: f32 = 10.0;
+
+/// Lowest Q accepted (very broad response)
+pub const MIN_Q: f32 = 0.1;
+
+/// Highest Q accepted (very narrow response)
+pub const MAX_Q: f32 = 40.0;
+
+/// Q of a maximally flat (Butterworth) low-pass or high-pass response
+pub const BUTTERWORTH_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Response shape of a biquad filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Passes frequencies below the cutoff
+    LowPass,
+    /// Passes frequencies above the cutoff
+    HighPass,
+    /// Passes a band around the cutoff with 0 dB peak gain
+    BandPass,
+    /// Removes a narrow band around the cutoff
+    Notch,
+}
+
+/// Second-order IIR filter using RBJ cookbook coefficients
+///
+/// The cutoff is clamped to between `MIN_CUTOFF_HZ` and just below Nyquist,
+/// and Q to `MIN_Q`-`MAX_Q`, so every setting is stable. Changing a setting
+/// recomputes the coefficients but keeps the filter state, so sweeps do not
+/// click.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::filter::{Biquad, FilterMode, BUTTERWORTH_Q};
+/// use polyphonica::RealtimeEngine;
+///
+/// let mut engine = RealtimeEngine::new(44100.0);
+/// let mut lowpass = Biquad::new(FilterMode::LowPass, 2000.0, BUTTERWORTH_Q, 44100.0);
+///
+/// let mut buffer = vec![0.0; 512];
+/// engine.process_buffer(&mut buffer);
+/// lowpass.process_buffer(&mut buffer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Biquad {
+    mode: FilterMode,
+    cutoff_hz: f32,
+    q: f32,
+    sample_rate: f32,
+    coefficients: BiquadCoefficients,
+    state: BiquadState,
+}
+
+impl Biquad {
+    /// Create a filter with the given mode, cutoff frequency, and Q
+    pub fn new(mode: FilterMode, cutoff_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let sample_rate = sample_rate.max(1.0);
+        let cutoff_hz = clamp_cutoff(cutoff_hz, sample_rate);
+        let q = q.clamp(MIN_Q, MAX_Q);
+        Self {
+            mode,
+            cutoff_hz,
+            q,
+            sample_rate,
+            coefficients: coefficients_for(mode, cutoff_hz, q, sample_rate),
+            state: BiquadState::default(),
+        }
+    }
+
+    /// Response shape of the filter
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+
+    /// Change the response shape
+    pub fn set_mode(&mut self, mode: FilterMode) {
+        self.mode = mode;
+        self.update_coefficients();
+    }
+
+    /// Cutoff (or centre) frequency in Hz
+    pub fn cutoff_hz(&self) -> f32 {
+        self.cutoff_hz
+    }
+
+    /// Set the cutoff (or centre) frequency in Hz
+    pub fn set_cutoff_hz(&mut self, cutoff_hz: f32) {
+        self.cutoff_hz = clamp_cutoff(cutoff_hz, self.sample_rate);
+        self.update_coefficients();
+    }
+
+    /// Resonance, or bandwidth for the band-pass and notch modes
+    pub fn q(&self) -> f32 {
+        self.q
+    }
+
+    /// Set the resonance (clamped to 0.1-40)
+    pub fn set_q(&mut self, q: f32) {
+        self.q = q.clamp(MIN_Q, MAX_Q);
+        self.update_coefficients();
+    }
+
+    /// Filter one sample
+    pub fn process_sample(&mut self, input: f32) -> f32 {
+        self.state.process(&self.coefficients, input)
+    }
+
+    /// Filter a mono buffer in place
+    pub fn process_buffer(&mut self, buffer: &mut [f32]) {
+        for sample in buffer.iter_mut() {
+            *sample = self.state.process(&self.coefficients, *sample);
+        }
+    }
+
+    /// Clear the filter's memory of previous samples
+    pub fn reset(&mut self) {
+        self.state = BiquadState::default();
+    }
+
+    fn update_coefficients(&mut self) {
+        self.coefficients = coefficients_for(self.mode, self.cutoff_hz, self.q, self.sample_rate);
+    }
+}
+
+/// Keep a cutoff between `MIN_CUTOFF_HZ` and just below Nyquist (NaN goes to the top)
+fn clamp_cutoff(cutoff_hz: f32, sample_rate: f32) -> f32 {
+    let nyquist_limit = (sample_rate * 0.49).max(MIN_CUTOFF_HZ);
+    if cutoff_hz.is_nan() {
+        return nyquist_limit;
+    }
+    cutoff_hz.clamp(MIN_CUTOFF_HZ, nyquist_limit)
+}
+
+fn coefficients_for(
+    mode: FilterMode,
+    cutoff_hz: f32,
+    q: f32,
+    sample_rate: f32,
+) -> BiquadCoefficients {
+    match mode {
+        FilterMode::LowPass => BiquadCoefficients::low_pass(cutoff_hz, q, sample_rate),
+        FilterMode::HighPass => BiquadCoefficients::high_pass(cutoff_hz, q, sample_rate),
+        FilterMode::BandPass => BiquadCoefficients::band_pass(cutoff_hz, q, sample_rate),
+        FilterMode::Notch => BiquadCoefficients::notch(cutoff_hz, q, sample_rate),
+    }
+}
+
+impl AudioEffect for Biquad {
+    fn process(&mut self, buffer: &mut [f32]) {
+        self.process_buffer(buffer);
+    }
+
+    fn reset(&mut self) {
+        Biquad::reset(self);
+    }
+
+    fn name(&self) -> &'static str {
+        "Biquad Filter"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+    fn sine(frequency: f32, sample_rate: f32, length: usize) -> Vec<f32> {
+        (0..length)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    /// RMS level after the filter has settled (second half of the buffer)
+    fn settled_rms(filter: &mut Biquad, mut samples: Vec<f32>) -> f32 {
+        filter.reset();
+        filter.process_buffer(&mut samples);
+        let settled = &samples[samples.len() / 2..];
+        (settled.iter().map(|s| s * s).sum::<f32>() / settled.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_decaying_resonance_flushes_to_exact_zero() {
+        let mut lowpass = Biquad::new(FilterMode::LowPass, 200.0, 5.0, 44100.0);
+        let mut ringing = vec![0.0; 44100 * 2];
+        ringing[0] = 1.0;
+        lowpass.process_buffer(&mut ringing);
+
+        // The resonance rings for a while, then stops dead instead of decaying
+        // through the subnormal range forever
+        assert!(ringing[1000..2000].iter().any(|s| s.abs() > 1e-3));
+        assert!(ringing.iter().all(|s| !s.is_subnormal()));
+        assert!(ringing[44100..].iter().all(|s| *s == 0.0));
+        assert_eq!(lowpass.process_sample(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_highpass_dc_decays_to_zero() {
+        let mut highpass = Biquad::new(FilterMode::HighPass, 100.0, BUTTERWORTH_Q, 44100.0);
+        let mut dc = vec![0.5; 44100];
+        highpass.process_buffer(&mut dc);
+
+        // The step passes at first, then decays away
+        assert!(dc[0] > 0.4);
+        assert!(dc[4410..].iter().all(|s| s.abs() < 1e-4), "{}", dc[4410]);
+    }
+
+    #[test]
+    fn test_lowpass_attenuates_high_sine() {
+        let sample_rate = 44100.0;
+        let mut lowpass = Biquad::new(FilterMode::LowPass, 1000.0, BUTTERWORTH_Q, sample_rate);
+
+        // 200 Hz passes nearly untouched; 10 kHz is over three octaves into the stop band
+        let low = settled_rms(&mut lowpass, sine(200.0, sample_rate, 8820));
+        let high = settled_rms(&mut lowpass, sine(10000.0, sample_rate, 8820));
+        assert!(low > 0.68, "{}", low);
+        assert!(high < 0.01, "{}", high);
+    }
+
+    #[test]
+    fn test_band_pass_and_notch_around_centre() {
+        let sample_rate = 44100.0;
+        let mut filter = Biquad::new(FilterMode::BandPass, 1000.0, 2.0, sample_rate);
+
+        // Band-pass keeps its centre at unity gain and cuts well away from it
+        let centre = settled_rms(&mut filter, sine(1000.0, sample_rate, 8820));
+        let side = settled_rms(&mut filter, sine(8000.0, sample_rate, 8820));
+        assert!((centre - FRAC_1_SQRT_2).abs() < 0.01, "{}", centre);
+        assert!(side < 0.1, "{}", side);
+
+        // The notch does the opposite
+        filter.set_mode(FilterMode::Notch);
+        let centre = settled_rms(&mut filter, sine(1000.0, sample_rate, 8820));
+        let side = settled_rms(&mut filter, sine(8000.0, sample_rate, 8820));
+        assert!(centre < 0.01, "{}", centre);
+        assert!(side > 0.68, "{}", side);
+
+        // Out-of-range settings are clamped to a stable filter
+        filter.set_cutoff_hz(30000.0);
+        filter.set_q(0.0);
+        assert_eq!(filter.cutoff_hz(), sample_rate * 0.49);
+        assert_eq!(filter.q(), MIN_Q);
+    }
+}
@@ -0,0 +1,487 @@
+use super::effects::AudioEffect;
+/// Audio routing graph for voice groups, effects, and sends
+///
+/// This module provides a lightweight processing graph so that different
+/// voice groups can be routed through different effect chains (for example
+/// the kick through one reverb and the lead through another) before being
+/// summed at the master output. All buffers are allocated when nodes are
+/// added; processing a block never allocates.
+use std::fmt;
+
+/// Handle to a node within an [`AudioGraph`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// Processing performed by a graph node
+pub enum GraphNode {
+    /// Voice group input, filled by the caller before each `process` call
+    Group,
+    /// In-place effect applied to the summed node inputs
+    Effect(Box<dyn AudioEffect>),
+    /// Gain stage for routing a scaled copy of a signal to another node
+    Send {
+        /// Linear send level (0.0 to 1.0)
+        gain: f32,
+    },
+    /// Final mix bus written to the output buffer
+    Master,
+}
+
+impl fmt::Debug for GraphNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphNode::Group => write!(f, "Group"),
+            GraphNode::Effect(effect) => write!(f, "Effect({})", effect.name()),
+            GraphNode::Send { gain } => write!(f, "Send {{ gain: {} }}", gain),
+            GraphNode::Master => write!(f, "Master"),
+        }
+    }
+}
+
+/// Errors that can occur while building an audio graph
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum GraphError {
+    #[error("Unknown graph node: {0:?}")]
+    UnknownNode(NodeId),
+
+    #[error("Connection would create a cycle: {0:?} -> {1:?}")]
+    CycleDetected(NodeId, NodeId),
+
+    #[error("Invalid connection: {0}")]
+    InvalidConnection(String),
+}
+
+/// Audio routing graph processed in topological order per buffer
+///
+/// Nodes are voice groups, effects, sends, and a single master bus. Each node
+/// sums the output of every node connected into it, applies its own
+/// processing, and makes the result available to downstream nodes.
+///
+/// # Real-time Safety
+///
+/// Node buffers are sized to `max_block_size` when nodes are added and the
+/// processing order is recomputed only when connections change, so
+/// [`AudioGraph::process`] is allocation-free.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::effects::Reverb;
+/// use polyphonica::audio::graph::AudioGraph;
+/// use polyphonica::RealtimeEngine;
+///
+/// # fn example() -> Result<(), polyphonica::audio::graph::GraphError> {
+/// let mut graph = AudioGraph::new(512);
+/// let drums = graph.add_group();
+/// let room = graph.add_effect(Box::new(Reverb::new(44100.0)));
+/// graph.connect(drums, room)?;
+/// graph.connect(room, graph.master())?;
+///
+/// // In the audio callback: render each group, then process the graph
+/// let mut drum_engine = RealtimeEngine::new(44100.0);
+/// let mut output = vec![0.0; 512];
+/// drum_engine.process_buffer(&mut graph.group_buffer_mut(drums)[..output.len()]);
+/// graph.process(&mut output);
+/// # Ok(())
+/// # }
+/// ```
+pub struct AudioGraph {
+    /// Node processors indexed by NodeId
+    nodes: Vec<GraphNode>,
+    /// Per-node working buffers, each `max_block_size` long
+    buffers: Vec<Vec<f32>>,
+    /// Directed connections (source, destination)
+    connections: Vec<(usize, usize)>,
+    /// Node indices in topological processing order
+    order: Vec<usize>,
+    /// Largest block that can be processed in one call
+    max_block_size: usize,
+    /// Index of the master node
+    master: usize,
+}
+
+impl AudioGraph {
+    /// Create a graph containing only a master bus
+    pub fn new(max_block_size: usize) -> Self {
+        let mut graph = Self {
+            nodes: Vec::new(),
+            buffers: Vec::new(),
+            connections: Vec::new(),
+            order: Vec::new(),
+            max_block_size,
+            master: 0,
+        };
+        graph.master = graph.add_node(GraphNode::Master).0;
+        graph
+    }
+
+    /// Get the master bus node
+    pub fn master(&self) -> NodeId {
+        NodeId(self.master)
+    }
+
+    /// Get the largest block size accepted by `process`
+    pub fn max_block_size(&self) -> usize {
+        self.max_block_size
+    }
+
+    /// Add a voice group input node
+    pub fn add_group(&mut self) -> NodeId {
+        self.add_node(GraphNode::Group)
+    }
+
+    /// Add an effect node
+    pub fn add_effect(&mut self, effect: Box<dyn AudioEffect>) -> NodeId {
+        self.add_node(GraphNode::Effect(effect))
+    }
+
+    /// Add a send node with the given linear gain
+    pub fn add_send(&mut self, gain: f32) -> NodeId {
+        self.add_node(GraphNode::Send {
+            gain: gain.clamp(0.0, 1.0),
+        })
+    }
+
+    /// Change the gain of an existing send node
+    pub fn set_send_gain(&mut self, node: NodeId, gain: f32) -> Result<(), GraphError> {
+        match self.nodes.get_mut(node.0) {
+            Some(GraphNode::Send { gain: current }) => {
+                *current = gain.clamp(0.0, 1.0);
+                Ok(())
+            }
+            Some(_) => Err(GraphError::InvalidConnection(format!(
+                "{:?} is not a send node",
+                node
+            ))),
+            None => Err(GraphError::UnknownNode(node)),
+        }
+    }
+
+    /// Get mutable access to an effect node's processor
+    pub fn effect_mut(&mut self, node: NodeId) -> Option<&mut dyn AudioEffect> {
+        match self.nodes.get_mut(node.0) {
+            Some(GraphNode::Effect(effect)) => Some(effect.as_mut()),
+            _ => None,
+        }
+    }
+
+    /// Connect the output of `from` into the input of `to`
+    ///
+    /// Rejects connections that would create a cycle, that feed a group node,
+    /// or that leave the master bus.
+    pub fn connect(&mut self, from: NodeId, to: NodeId) -> Result<(), GraphError> {
+        for node in [from, to] {
+            if node.0 >= self.nodes.len() {
+                return Err(GraphError::UnknownNode(node));
+            }
+        }
+        if from.0 == self.master {
+            return Err(GraphError::InvalidConnection(
+                "Master bus cannot feed other nodes".to_string(),
+            ));
+        }
+        if matches!(self.nodes[to.0], GraphNode::Group) {
+            return Err(GraphError::InvalidConnection(
+                "Group nodes cannot receive inputs".to_string(),
+            ));
+        }
+        if self.connections.contains(&(from.0, to.0)) {
+            return Ok(());
+        }
+        if from == to || self.reaches(to.0, from.0) {
+            return Err(GraphError::CycleDetected(from, to));
+        }
+
+        self.connections.push((from.0, to.0));
+        self.rebuild_order();
+        Ok(())
+    }
+
+    /// Remove a connection if present
+    pub fn disconnect(&mut self, from: NodeId, to: NodeId) {
+        self.connections.retain(|&edge| edge != (from.0, to.0));
+        self.rebuild_order();
+    }
+
+    /// Buffer that a voice group renders into before `process` is called
+    ///
+    /// Group buffers are cleared after each `process` call, so a group that
+    /// is not rendered for a block contributes silence.
+    pub fn group_buffer_mut(&mut self, node: NodeId) -> &mut [f32] {
+        debug_assert!(matches!(self.nodes[node.0], GraphNode::Group));
+        &mut self.buffers[node.0]
+    }
+
+    /// Process one block through the graph and write the master bus to `output`
+    ///
+    /// Blocks longer than `max_block_size` are truncated; the remainder of
+    /// `output` is filled with silence.
+    pub fn process(&mut self, output: &mut [f32]) {
+        let len = output.len().min(self.max_block_size);
+
+        for order_index in 0..self.order.len() {
+            let node_index = self.order[order_index];
+
+            // Take the node's buffer so upstream buffers can be read while it is written
+            let mut buffer = std::mem::take(&mut self.buffers[node_index]);
+            let block = &mut buffer[..len];
+
+            if !matches!(self.nodes[node_index], GraphNode::Group) {
+                block.fill(0.0);
+            }
+            for &(source, destination) in &self.connections {
+                if destination == node_index {
+                    for (dst, src) in block.iter_mut().zip(&self.buffers[source][..len]) {
+                        *dst += *src;
+                    }
+                }
+            }
+
+            match &mut self.nodes[node_index] {
+                GraphNode::Group | GraphNode::Master => {}
+                GraphNode::Effect(effect) => effect.process(block),
+                GraphNode::Send { gain } => block.iter_mut().for_each(|s| *s *= *gain),
+            }
+
+            self.buffers[node_index] = buffer;
+        }
+
+        output[..len].copy_from_slice(&self.buffers[self.master][..len]);
+        output[len..].fill(0.0);
+
+        // Groups are re-rendered by the caller every block
+        for (node, buffer) in self.nodes.iter().zip(self.buffers.iter_mut()) {
+            if matches!(node, GraphNode::Group) {
+                buffer.fill(0.0);
+            }
+        }
+    }
+
+    /// Reset all effect state (reverb tails, envelopes) and clear buffers
+    pub fn reset(&mut self) {
+        for node in &mut self.nodes {
+            if let GraphNode::Effect(effect) = node {
+                effect.reset();
+            }
+        }
+        for buffer in &mut self.buffers {
+            buffer.fill(0.0);
+        }
+    }
+
+    /// Get the number of nodes in the graph (including master)
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn add_node(&mut self, node: GraphNode) -> NodeId {
+        self.nodes.push(node);
+        self.buffers.push(vec![0.0; self.max_block_size]);
+        self.rebuild_order();
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Check whether `to` is reachable from `from` along existing connections
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut stack = vec![from];
+        let mut visited = vec![false; self.nodes.len()];
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+            if std::mem::replace(&mut visited[node], true) {
+                continue;
+            }
+            stack.extend(
+                self.connections
+                    .iter()
+                    .filter(|(source, _)| *source == node)
+                    .map(|&(_, destination)| destination),
+            );
+        }
+        false
+    }
+
+    /// Recompute topological order (Kahn's algorithm); called only when topology changes
+    fn rebuild_order(&mut self) {
+        let mut in_degree = vec![0usize; self.nodes.len()];
+        for &(_, destination) in &self.connections {
+            in_degree[destination] += 1;
+        }
+
+        let mut ready: Vec<usize> = (0..self.nodes.len())
+            .filter(|&node| in_degree[node] == 0)
+            .collect();
+        self.order.clear();
+
+        while let Some(node) = ready.pop() {
+            self.order.push(node);
+            for &(source, destination) in &self.connections {
+                if source == node {
+                    in_degree[destination] -= 1;
+                    if in_degree[destination] == 0 {
+                        ready.push(destination);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for AudioGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioGraph")
+            .field("nodes", &self.nodes)
+            .field("connections", &self.connections)
+            .field("max_block_size", &self.max_block_size)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::effects::Reverb;
+
+    const BLOCK: usize = 4096;
+
+    fn reverb(room_size: f32) -> Reverb {
+        let mut reverb = Reverb::new(44100.0);
+        reverb.set_room_size(room_size);
+        reverb.set_dry(0.0);
+        reverb.set_wet(1.0);
+        reverb
+    }
+
+    fn impulse(position: usize) -> Vec<f32> {
+        let mut buffer = vec![0.0; BLOCK];
+        buffer[position] = 1.0;
+        buffer
+    }
+
+    fn processed(mut effect: Reverb, input: &[f32]) -> Vec<f32> {
+        let mut buffer = input.to_vec();
+        effect.process(&mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn test_graph_master_only_is_silent() {
+        let mut graph = AudioGraph::new(256);
+        let mut output = vec![1.0; 256];
+        graph.process(&mut output);
+        assert!(output.iter().all(|s| *s == 0.0));
+        assert_eq!(graph.node_count(), 1);
+    }
+
+    #[test]
+    fn test_groups_routed_to_separate_reverbs() {
+        let mut graph = AudioGraph::new(BLOCK);
+        let kick = graph.add_group();
+        let lead = graph.add_group();
+        let small_room = graph.add_effect(Box::new(reverb(0.1)));
+        let large_hall = graph.add_effect(Box::new(reverb(0.95)));
+        let master = graph.master();
+
+        graph.connect(kick, small_room).unwrap();
+        graph.connect(lead, large_hall).unwrap();
+        graph.connect(small_room, master).unwrap();
+        graph.connect(large_hall, master).unwrap();
+
+        let kick_input = impulse(0);
+        let lead_input = impulse(100);
+        graph.group_buffer_mut(kick).copy_from_slice(&kick_input);
+        graph.group_buffer_mut(lead).copy_from_slice(&lead_input);
+
+        let mut output = vec![0.0; BLOCK];
+        graph.process(&mut output);
+
+        // Master must equal each group processed by its own reverb only
+        let expected_kick = processed(reverb(0.1), &kick_input);
+        let expected_lead = processed(reverb(0.95), &lead_input);
+        for i in 0..BLOCK {
+            let expected = expected_kick[i] + expected_lead[i];
+            assert!((output[i] - expected).abs() < 1e-6, "mismatch at {}", i);
+        }
+
+        // Swapping the assignments must produce a different mix
+        let swapped_kick = processed(reverb(0.95), &kick_input);
+        let swapped_lead = processed(reverb(0.1), &lead_input);
+        let difference: f32 = (0..BLOCK)
+            .map(|i| (output[i] - swapped_kick[i] - swapped_lead[i]).abs())
+            .sum();
+        assert!(difference > 1e-3);
+    }
+
+    #[test]
+    fn test_send_scales_signal() {
+        let mut graph = AudioGraph::new(64);
+        let group = graph.add_group();
+        let send = graph.add_send(0.5);
+        graph.connect(group, send).unwrap();
+        graph.connect(send, graph.master()).unwrap();
+
+        graph.group_buffer_mut(group).fill(0.8);
+        let mut output = vec![0.0; 64];
+        graph.process(&mut output);
+        assert!(output.iter().all(|s| (*s - 0.4).abs() < 1e-6));
+
+        // Group buffers are cleared after processing
+        graph.process(&mut output);
+        assert!(output.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_dry_and_send_paths_sum_at_master() {
+        let mut graph = AudioGraph::new(64);
+        let group = graph.add_group();
+        let send = graph.add_send(0.25);
+        graph.connect(group, graph.master()).unwrap();
+        graph.connect(group, send).unwrap();
+        graph.connect(send, graph.master()).unwrap();
+
+        graph.group_buffer_mut(group)[0] = 1.0;
+        let mut output = vec![0.0; 64];
+        graph.process(&mut output);
+        assert!((output[0] - 1.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cycle_rejected() {
+        let mut graph = AudioGraph::new(64);
+        let first = graph.add_send(1.0);
+        let second = graph.add_send(1.0);
+        graph.connect(first, second).unwrap();
+
+        assert_eq!(
+            graph.connect(second, first),
+            Err(GraphError::CycleDetected(second, first))
+        );
+        assert!(graph.connect(first, first).is_err());
+    }
+
+    #[test]
+    fn test_invalid_connections_rejected() {
+        let mut graph = AudioGraph::new(64);
+        let group = graph.add_group();
+        let send = graph.add_send(1.0);
+
+        assert!(graph.connect(send, group).is_err());
+        assert!(graph.connect(graph.master(), send).is_err());
+        assert!(graph.connect(send, NodeId(42)).is_err());
+        assert!(graph.set_send_gain(group, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_oversized_block_is_truncated() {
+        let mut graph = AudioGraph::new(32);
+        let group = graph.add_group();
+        graph.connect(group, graph.master()).unwrap();
+        graph.group_buffer_mut(group).fill(0.5);
+
+        let mut output = vec![1.0; 48];
+        graph.process(&mut output);
+        assert!(output[..32].iter().all(|s| *s == 0.5));
+        assert!(output[32..].iter().all(|s| *s == 0.0));
+    }
+}
@@ -0,0 +1,254 @@
+/// Synthesized kick drums with pitch and amplitude envelopes
+///
+/// This module provides KickSynth, which renders a kick from a sine body
+/// whose pitch sweeps quickly downward, shaped by an attack/decay amplitude
+/// envelope and an optional noise click on the attack. The result plays
+/// through the engine as a drum sample, so it works anywhere a ClickType does.
+use super::drumsynth::{gate_envelope, DRUM_RENDER_SAMPLE_RATE};
+use crate::{AdsrEnvelope, Interpolation, SampleData, SampleMetadata, SynthRng, Waveform};
+use std::f32::consts::PI;
+
+/// Length of the click transient in seconds
+const CLICK_SECS: f32 = 0.003;
+
+/// Parameters for a synthesized kick drum
+///
+/// The body frequency glides exponentially from `start_frequency_hz` to
+/// `end_frequency_hz` over `pitch_sweep_secs`, then holds. The amplitude
+/// rises linearly over `attack_secs` and falls along a quadratic curve to
+/// silence over `decay_secs`.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::audio::kick::KickSynth;
+/// use polyphonica::RealtimeEngine;
+///
+/// let kick = KickSynth::new()
+///     .with_pitch_sweep(180.0, 45.0, 0.06)
+///     .with_click(0.4);
+///
+/// let mut engine = RealtimeEngine::new(44100.0);
+/// let (waveform, frequency, envelope) = kick.audio_params();
+/// engine.trigger_note(waveform, frequency, envelope);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KickSynth {
+    /// Body frequency at the start of the hit in Hz
+    pub start_frequency_hz: f32,
+
+    /// Body frequency the sweep settles at in Hz
+    pub end_frequency_hz: f32,
+
+    /// Time the pitch sweep takes in seconds
+    pub pitch_sweep_secs: f32,
+
+    /// Amplitude attack time in seconds
+    pub attack_secs: f32,
+
+    /// Amplitude decay time to silence in seconds
+    pub decay_secs: f32,
+
+    /// Level of the noise click on the attack (0.0 = none)
+    pub click_level: f32,
+}
+
+impl KickSynth {
+    /// Create a general-purpose acoustic-style kick
+    pub fn new() -> Self {
+        Self {
+            start_frequency_hz: 150.0,
+            end_frequency_hz: 50.0,
+            pitch_sweep_secs: 0.05,
+            attack_secs: 0.001,
+            decay_secs: 0.4,
+            click_level: 0.3,
+        }
+    }
+
+    /// Short, higher-tuned kick with a stronger click
+    pub fn tight() -> Self {
+        Self::new()
+            .with_pitch_sweep(200.0, 65.0, 0.03)
+            .with_amplitude_envelope(0.001, 0.22)
+            .with_click(0.5)
+    }
+
+    /// Set the pitch sweep start and end frequencies and its duration
+    pub fn with_pitch_sweep(mut self, start_hz: f32, end_hz: f32, sweep_secs: f32) -> Self {
+        self.start_frequency_hz = start_hz.max(1.0);
+        self.end_frequency_hz = end_hz.max(1.0);
+        self.pitch_sweep_secs = sweep_secs.max(0.0);
+        self
+    }
+
+    /// Set the amplitude attack and decay times
+    pub fn with_amplitude_envelope(mut self, attack_secs: f32, decay_secs: f32) -> Self {
+        self.attack_secs = attack_secs.max(0.0);
+        self.decay_secs = decay_secs.max(0.001);
+        self
+    }
+
+    /// Set the click transient level (clamped to 0.0-1.0)
+    pub fn with_click(mut self, level: f32) -> Self {
+        self.click_level = level.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Total length of the hit in seconds
+    pub fn duration_secs(&self) -> f32 {
+        self.attack_secs + self.decay_secs
+    }
+
+    /// Instantaneous body frequency at `time_secs` into the hit
+    pub fn frequency_at(&self, time_secs: f32) -> f32 {
+        if self.pitch_sweep_secs <= 0.0 || time_secs >= self.pitch_sweep_secs {
+            return self.end_frequency_hz;
+        }
+        let progress = time_secs.max(0.0) / self.pitch_sweep_secs;
+        self.start_frequency_hz * (self.end_frequency_hz / self.start_frequency_hz).powf(progress)
+    }
+
+    /// Amplitude envelope level at `time_secs` into the hit
+    pub fn amplitude_at(&self, time_secs: f32) -> f32 {
+        if time_secs < 0.0 {
+            0.0
+        } else if time_secs < self.attack_secs {
+            time_secs / self.attack_secs
+        } else {
+            let remaining = 1.0 - (time_secs - self.attack_secs) / self.decay_secs;
+            remaining.max(0.0).powi(2)
+        }
+    }
+
+    /// Render the hit at the given sample rate
+    pub fn render(&self, sample_rate: u32) -> Vec<f32> {
+        let sample_rate = sample_rate.max(1) as f32;
+        let length = (self.duration_secs() * sample_rate).ceil() as usize;
+        let click_samples = (CLICK_SECS * sample_rate) as usize;
+        let mut rng = SynthRng::default();
+        let mut phase = 0.0f32;
+
+        (0..length)
+            .map(|i| {
+                let time_secs = i as f32 / sample_rate;
+                let body = phase.sin();
+                phase =
+                    (phase + 2.0 * PI * self.frequency_at(time_secs) / sample_rate) % (2.0 * PI);
+
+                let click = if i < click_samples {
+                    self.click_level * rng.next_bipolar() * (1.0 - i as f32 / click_samples as f32)
+                } else {
+                    0.0
+                };
+                ((body + click) * self.amplitude_at(time_secs)).clamp(-1.0, 1.0)
+            })
+            .collect()
+    }
+
+    /// Render the hit as one-shot sample data
+    pub fn to_sample_data(&self, sample_rate: u32) -> SampleData {
+        let samples = self.render(sample_rate);
+        SampleData {
+            metadata: SampleMetadata {
+                filename: "kick_synth".to_string(),
+                duration_secs: samples.len() as f32 / sample_rate.max(1) as f32,
+                channels: 1,
+                bits_per_sample: 32,
+            },
+            samples,
+            sample_rate,
+            base_frequency: self.end_frequency_hz,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
+        }
+    }
+
+    /// Waveform, frequency, and envelope for triggering the kick in the engine
+    ///
+    /// The kick is rendered at `DRUM_RENDER_SAMPLE_RATE` on every call and
+    /// played as a drum sample under a flat gate, as its shaping is already
+    /// rendered. Keep the result rather than calling this for each trigger.
+    pub fn audio_params(&self) -> (Waveform, f32, AdsrEnvelope) {
+        let sample_data = self.to_sample_data(DRUM_RENDER_SAMPLE_RATE);
+        let envelope = gate_envelope(sample_data.metadata.duration_secs);
+        (
+            Waveform::DrumSample(sample_data),
+            self.end_frequency_hz,
+            envelope,
+        )
+    }
+}
+
+impl Default for KickSynth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frequency estimated from the zero crossings in a window of the render
+    fn crossing_frequency(samples: &[f32], start: usize, window: usize, sample_rate: f32) -> f32 {
+        let crossings = samples[start..start + window]
+            .windows(2)
+            .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+            .count();
+        crossings as f32 * sample_rate / window as f32
+    }
+
+    #[test]
+    fn test_pitch_sweeps_down_over_configured_time() {
+        let kick = KickSynth::new()
+            .with_pitch_sweep(200.0, 50.0, 0.1)
+            .with_click(0.0);
+
+        assert_eq!(kick.frequency_at(0.0), 200.0);
+        assert!((kick.frequency_at(0.05) - 100.0).abs() < 0.01);
+        assert_eq!(kick.frequency_at(0.1), 50.0);
+        assert_eq!(kick.frequency_at(0.3), 50.0);
+
+        // The rendered body slows down from the start of the sweep to its end
+        let samples = kick.render(44100);
+        let early = crossing_frequency(&samples, 0, 2205, 44100.0);
+        let late = crossing_frequency(&samples, 4410, 8820, 44100.0);
+        assert!(early > 120.0, "early {}", early);
+        assert!((late - 50.0).abs() < 10.0, "late {}", late);
+    }
+
+    #[test]
+    fn test_amplitude_follows_envelope() {
+        let kick = KickSynth::new()
+            .with_amplitude_envelope(0.01, 0.2)
+            .with_click(0.0);
+
+        assert_eq!(kick.amplitude_at(0.005), 0.5);
+        assert_eq!(kick.amplitude_at(0.01), 1.0);
+        assert!((kick.amplitude_at(0.11) - 0.25).abs() < 1e-6);
+        assert!(kick.amplitude_at(0.21) < 1e-6);
+
+        let samples = kick.render(44100);
+        assert_eq!(samples.len(), (0.21f32 * 44100.0).ceil() as usize);
+        let peak = |from: f32, to: f32| {
+            samples[(from * 44100.0) as usize..(to * 44100.0) as usize]
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+        assert!(peak(0.01, 0.03) > 0.8);
+        assert!(peak(0.1, 0.12) < 0.3);
+        assert!(peak(0.19, 0.21) < 0.01);
+    }
+
+    #[test]
+    fn test_click_adds_attack_transient() {
+        let plain = KickSynth::new().with_click(0.0).render(44100);
+        let clicked = KickSynth::new().with_click(1.0).render(44100);
+
+        assert_ne!(plain[..132], clicked[..132]);
+        assert_eq!(plain[200..], clicked[200..]);
+    }
+}
@@ -1,5 +1,27 @@
 /// Audio accent management for dynamic rhythm emphasis.
 pub mod accents;
+/// Spectral centroid, integrated loudness, and peak/clip level analysis for mixing and mastering.
+pub mod analysis;
+/// Guitar cabinet simulation by impulse-response convolution with speaker cuts.
+pub mod cabsim;
+/// TPDF dither and noise shaping for 16-bit export.
+pub mod dither;
+/// Snare, hi-hat, and clap synthesis and a sample-free SynthDrumKit.
+pub mod drumsynth;
+/// Radix-2 FFT with precomputed twiddle and bit-reversal tables, and the Hann window.
+pub mod fft;
+/// In-place audio effects (reverb, compressor, exciter, tone control, auto-pan) and the common effect trait.
+pub mod effects;
+/// RBJ biquad low-pass, high-pass, band-pass, and notch filters.
+pub mod filter;
+/// Routing graph connecting voice groups, effects, sends, and the master bus.
+pub mod graph;
+/// Kick drum synthesis with pitch sweep, amplitude envelope, and click transient.
+pub mod kick;
+/// Note repeat retriggering a drum sound at a tempo-synced rate while held.
+pub mod repeat;
+/// Lock-free tap of the output mix for oscilloscope and level displays.
+pub mod scope;
 /// CPAL integration and audio stream management for real-time output.
 pub mod stream;
 /// Audio Processing Module for Polyphonica
@@ -11,6 +33,14 @@ pub mod stream;
 /// - **Synthesis**: Audio parameter generation and waveform synthesis
 /// - **Stream**: CPAL integration and audio stream management
 /// - **Accents**: Specialized accent sound generation for metronomes
+/// - **Effects**: In-place buffer effects behind the `AudioEffect` trait
+/// - **Graph**: Routing of voice groups through effects and sends to master
+/// - **Analysis**: Brightness and loudness measurements of rendered buffers
+/// - **FFT**: Shared transform behind analysis, spectrum displays, and the cab sim
+/// - **Repeat**: Tempo-synced note repeat for drum rolls
+/// - **Dither**: Bit reduction with TPDF dither and noise shaping for export
+/// - **Kick**: Synthesized kick drums used when no kick sample is loaded
+/// - **Scope**: Lock-free tap of the output mix for GUI scope and level displays
 ///
 /// # Design Principles
 ///
@@ -49,5 +79,15 @@ pub mod synthesis;
 
 // Re-export core types for convenient access
 pub use accents::AccentSoundGenerator;
-pub use stream::{AudioStream, PolyphonicaStreamConfig};
+pub use analysis::{integrated_loudness, spectral_centroid, LevelAnalysis};
+pub use cabsim::CabSim;
+pub use dither::{DitherMode, Ditherer};
+pub use drumsynth::{ClapSynth, HiHatSynth, SnareSynth, SynthDrumKit, SynthDrumPiece};
+pub use effects::{AudioEffect, AutoPan, Compressor, Exciter, Reverb, ToneControl};
+pub use filter::{Biquad, FilterMode};
+pub use graph::{AudioGraph, GraphError, GraphNode, NodeId};
+pub use kick::KickSynth;
+pub use repeat::NoteRepeat;
+pub use scope::{ScopeRead, ScopeTap};
+pub use stream::{check_output_channels, AudioStream, AudioStreamError, PolyphonicaStreamConfig};
 pub use synthesis::AudioSynthesis;
@@ -0,0 +1,285 @@
+/// Note repeat for sustained drum rolls
+///
+/// This module provides NoteRepeat, the drum machine "note repeat" control:
+/// while held, a drum sound is retriggered through the engine at a tempo-synced
+/// subdivision, optionally ramping its velocity for crescendo rolls.
+use crate::audio::synthesis::{get_calibrated_sound_params, AudioSampleAdapter};
+use crate::timing::{ClickType, Subdivision, LATE_TOLERANCE_MS};
+use crate::RealtimeEngine;
+use std::time::{Duration, Instant};
+
+/// Retriggers a drum sound at a subdivision of the beat while held
+///
+/// Hits are scheduled from the moment the repeat is pressed, one per
+/// subdivision pulse at the tempo passed to each poll, so tempo changes
+/// take effect from the next hit. Hits polled up to `LATE_TOLERANCE_MS` late
+/// still fire; hits missed by more, e.g. while the update loop stalled, are
+/// dropped and the roll resumes on its grid.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::audio::repeat::NoteRepeat;
+/// use polyphonica::audio::synthesis::AudioSampleAdapter;
+/// use polyphonica::timing::{ClickType, Subdivision};
+/// use polyphonica::RealtimeEngine;
+/// use std::time::Instant;
+///
+/// let mut engine = RealtimeEngine::new(44100.0);
+/// let adapter = AudioSampleAdapter::new();
+/// let mut roll = NoteRepeat::new(ClickType::AcousticSnare, Subdivision::Sixteenth)
+///     .with_velocity_ramp(0.3, 1.0, 4.0);
+///
+/// roll.press(Instant::now());
+/// // In the update loop while the pad is held
+/// roll.trigger_due(&mut engine, &adapter, 120.0, Instant::now());
+/// roll.release();
+/// ```
+#[derive(Debug, Clone)]
+pub struct NoteRepeat {
+    /// Drum sound to retrigger
+    click_type: ClickType,
+
+    /// Repeat rate as a subdivision of the beat
+    rate: Subdivision,
+
+    /// Velocity of the first hit
+    start_velocity: f32,
+
+    /// Velocity reached at the end of the ramp
+    end_velocity: f32,
+
+    /// Length of the velocity ramp in beats (0.0 = no ramp)
+    ramp_beats: f32,
+
+    /// Time of the next hit while held
+    next_hit: Option<Instant>,
+
+    /// Beats elapsed between the press and the next hit
+    beats_elapsed: f32,
+}
+
+impl NoteRepeat {
+    /// Create a note repeat for a drum sound at the given rate
+    pub fn new(click_type: ClickType, rate: Subdivision) -> Self {
+        Self {
+            click_type,
+            rate,
+            start_velocity: 1.0,
+            end_velocity: 1.0,
+            ramp_beats: 0.0,
+            next_hit: None,
+            beats_elapsed: 0.0,
+        }
+    }
+
+    /// Set a constant velocity for every hit
+    pub fn with_velocity(mut self, velocity: f32) -> Self {
+        self.start_velocity = velocity.clamp(0.0, 1.0);
+        self.end_velocity = self.start_velocity;
+        self.ramp_beats = 0.0;
+        self
+    }
+
+    /// Ramp the velocity from `start` to `end` over `ramp_beats` beats, then hold
+    pub fn with_velocity_ramp(mut self, start: f32, end: f32, ramp_beats: f32) -> Self {
+        self.start_velocity = start.clamp(0.0, 1.0);
+        self.end_velocity = end.clamp(0.0, 1.0);
+        self.ramp_beats = ramp_beats.max(0.0);
+        self
+    }
+
+    /// Set the repeat rate (takes effect after the next hit)
+    pub fn set_rate(&mut self, rate: Subdivision) {
+        self.rate = rate;
+    }
+
+    /// Get the repeat rate
+    pub fn rate(&self) -> Subdivision {
+        self.rate
+    }
+
+    /// Get the repeated drum sound
+    pub fn click_type(&self) -> ClickType {
+        self.click_type
+    }
+
+    /// Whether the repeat is currently held
+    pub fn is_held(&self) -> bool {
+        self.next_hit.is_some()
+    }
+
+    /// Start repeating; the first hit is due immediately
+    ///
+    /// Pressing while already held restarts the roll and its velocity ramp.
+    pub fn press(&mut self, now: Instant) {
+        self.next_hit = Some(now);
+        self.beats_elapsed = 0.0;
+    }
+
+    /// Stop repeating; no further hits are produced until pressed again
+    pub fn release(&mut self) {
+        self.next_hit = None;
+    }
+
+    /// Take the velocities of all hits due at `now` at the given tempo
+    pub fn poll(&mut self, tempo_bpm: f32, now: Instant) -> Vec<f32> {
+        let mut velocities = Vec::new();
+        if tempo_bpm <= 0.0 {
+            return velocities;
+        }
+
+        let pulse_beats = 1.0 / self.rate.pulses_per_beat() as f32;
+        let pulse = Duration::from_secs_f64(60.0 / tempo_bpm as f64 * pulse_beats as f64);
+        let tolerance = Duration::from_secs_f64(LATE_TOLERANCE_MS / 1000.0);
+
+        // Skip pulses missed by more than the tolerance, keeping the grid
+        if let Some(due) = self.next_hit {
+            let late = now.saturating_duration_since(due);
+            if late > tolerance {
+                let missed = ((late - tolerance).as_secs_f64() / pulse.as_secs_f64()).ceil();
+                self.next_hit = Some(due + pulse.mul_f64(missed));
+                self.beats_elapsed += pulse_beats * missed as f32;
+            }
+        }
+
+        while let Some(due) = self.next_hit.filter(|&due| due <= now) {
+            velocities.push(self.velocity_at(self.beats_elapsed));
+            self.next_hit = Some(due + pulse);
+            self.beats_elapsed += pulse_beats;
+        }
+        velocities
+    }
+
+    /// Trigger all due hits through the engine, returning their voice IDs
+    ///
+    /// Each hit's velocity is scaled by the click type's calibration gain.
+    pub fn trigger_due(
+        &mut self,
+        engine: &mut RealtimeEngine,
+        sample_adapter: &AudioSampleAdapter,
+        tempo_bpm: f32,
+        now: Instant,
+    ) -> Vec<u32> {
+        let velocities = self.poll(tempo_bpm, now);
+        if velocities.is_empty() {
+            return Vec::new();
+        }
+
+        let (waveform, frequency, envelope, gain) =
+            get_calibrated_sound_params(self.click_type, sample_adapter);
+        velocities
+            .into_iter()
+            .filter_map(|velocity| {
+                engine.trigger_note_with_volume(
+                    waveform.clone(),
+                    frequency,
+                    envelope.clone(),
+                    velocity * gain,
+                )
+            })
+            .collect()
+    }
+
+    /// Velocity of a hit the given number of beats into the roll
+    fn velocity_at(&self, beats: f32) -> f32 {
+        if self.ramp_beats <= 0.0 {
+            return self.end_velocity;
+        }
+        let progress = (beats / self.ramp_beats).min(1.0);
+        self.start_velocity + (self.end_velocity - self.start_velocity) * progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeats_at_subdivision_rate_until_released() {
+        let start = Instant::now();
+        let mut roll = NoteRepeat::new(ClickType::AcousticSnare, Subdivision::Sixteenth);
+        assert!(roll.poll(120.0, start).is_empty());
+
+        // At 120 BPM sixteenths fall every 125 ms: hits at 0, 125, ..., 875 ms
+        roll.press(start);
+        let mut hits = 0;
+        for ms in (0..=900).step_by(5) {
+            hits += roll.poll(120.0, start + Duration::from_millis(ms)).len();
+        }
+        assert_eq!(hits, 8);
+
+        roll.release();
+        assert!(!roll.is_held());
+        assert!(roll.poll(120.0, start + Duration::from_secs(10)).is_empty());
+
+        // A stalled poll drops the missed hits and resumes on the grid
+        roll.set_rate(Subdivision::Eighth);
+        roll.press(start);
+        let at = |ms| start + Duration::from_millis(ms);
+        assert!(roll.poll(60.0, at(1999)).is_empty());
+        assert!(roll.poll(60.0, at(1999)).is_empty());
+        assert_eq!(roll.poll(60.0, at(2010)).len(), 1);
+        assert!(roll.poll(60.0, at(2400)).is_empty());
+
+        // Hits within the tolerance still fire
+        assert_eq!(roll.poll(60.0, at(2540)).len(), 1);
+    }
+
+    #[test]
+    fn test_velocity_ramp_builds_crescendo() {
+        let start = Instant::now();
+        let mut roll = NoteRepeat::new(ClickType::AcousticSnare, Subdivision::Quarter)
+            .with_velocity_ramp(0.2, 1.0, 2.0);
+        roll.press(start);
+
+        let velocities: Vec<f32> = (0..4)
+            .flat_map(|beat| roll.poll(60.0, start + Duration::from_secs(beat)))
+            .collect();
+        assert_eq!(velocities.len(), 4);
+        assert!((velocities[0] - 0.2).abs() < 1e-6);
+        assert!((velocities[1] - 0.6).abs() < 1e-6);
+        assert_eq!(&velocities[2..], &[1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_trigger_due_starts_engine_voices() {
+        let start = Instant::now();
+        let mut engine = RealtimeEngine::new(44100.0);
+        let adapter = AudioSampleAdapter::new();
+        let mut roll =
+            NoteRepeat::new(ClickType::WoodBlock, Subdivision::Eighth).with_velocity(0.5);
+
+        roll.press(start);
+        let mut voices = roll.trigger_due(&mut engine, &adapter, 120.0, start);
+        voices.extend(roll.trigger_due(
+            &mut engine,
+            &adapter,
+            120.0,
+            start + Duration::from_millis(260),
+        ));
+        assert_eq!(voices.len(), 2);
+        assert_eq!(engine.get_active_voice_count(), 2);
+    }
+
+    #[test]
+    fn test_trigger_due_applies_calibration_gain() {
+        let peak = |gain: f32| {
+            let start = Instant::now();
+            let mut engine = RealtimeEngine::new(44100.0);
+            let mut adapter = AudioSampleAdapter::new();
+            adapter.set_click_type_gain(ClickType::WoodBlock, gain);
+            let mut roll =
+                NoteRepeat::new(ClickType::WoodBlock, Subdivision::Eighth).with_velocity(0.5);
+            roll.press(start);
+            let voices = roll.trigger_due(&mut engine, &adapter, 120.0, start);
+
+            let mut output = vec![0.0; 2048];
+            assert!(engine.process_voice_buffer(voices[0], &mut output));
+            output.iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+
+        let ratio = peak(2.0) / peak(0.5);
+        assert!((ratio - 4.0).abs() < 1e-3, "ratio {}", ratio);
+    }
+}
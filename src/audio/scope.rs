@@ -0,0 +1,201 @@
+/// Lock-free tap of the output buffer for oscilloscope and level displays
+///
+/// This module provides ScopeTap, a fixed-size ring of recent output samples
+/// shared between the audio callback and a GUI. The callback pushes every
+/// processed buffer without locking or allocating; the GUI pulls the most
+/// recent frame whenever it repaints and is told when it fell so far behind
+/// that audio went past unseen.
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Default number of samples a ScopeTap keeps (about 93 ms at 44.1kHz)
+pub const DEFAULT_SCOPE_CAPACITY: usize = 4096;
+
+/// Result of pulling a frame from a ScopeTap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopeRead {
+    /// Number of samples copied into the frame (fewer than requested early on)
+    pub samples: usize,
+
+    /// Whether samples were overwritten before this pull could see them
+    pub overrun: bool,
+}
+
+#[derive(Debug)]
+struct ScopeShared {
+    /// Ring of recent samples stored as f32 bits
+    ring: Box<[AtomicU32]>,
+
+    /// Total number of samples ever pushed
+    written: AtomicU64,
+
+    /// Value of `written` at the end of the previous pull
+    read: AtomicU64,
+}
+
+/// Single-producer, single-consumer tap of recent output samples
+///
+/// Clones share the same ring, so one clone can live in the stream callback
+/// and another in the GUI. `push` and `pull` never block each other; if the
+/// producer overwrites samples while a pull is copying them, that pull is
+/// reported as an overrun.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::audio::scope::ScopeTap;
+///
+/// let tap = ScopeTap::new(1024);
+/// let gui_tap = tap.clone();
+///
+/// // Audio callback
+/// tap.push(&[0.0, 0.5, 1.0, 0.5]);
+///
+/// // GUI repaint
+/// let mut frame = [0.0; 2];
+/// let read = gui_tap.pull(&mut frame);
+/// assert_eq!(read.samples, 2);
+/// assert_eq!(frame, [1.0, 0.5]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScopeTap {
+    shared: Arc<ScopeShared>,
+}
+
+impl ScopeTap {
+    /// Create a tap keeping the last `capacity` samples (at least one)
+    pub fn new(capacity: usize) -> Self {
+        let ring = (0..capacity.max(1)).map(|_| AtomicU32::new(0)).collect();
+        Self {
+            shared: Arc::new(ScopeShared {
+                ring,
+                written: AtomicU64::new(0),
+                read: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Number of samples kept
+    pub fn capacity(&self) -> usize {
+        self.shared.ring.len()
+    }
+
+    /// Append a buffer of samples (audio thread)
+    pub fn push(&self, buffer: &[f32]) {
+        self.push_samples(buffer.iter().copied());
+    }
+
+    /// Append the per-frame average of an interleaved buffer (audio thread)
+    pub fn push_interleaved(&self, buffer: &[f32], channels: usize) {
+        let channels = channels.max(1);
+        let scale = 1.0 / channels as f32;
+        self.push_samples(
+            buffer
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() * scale),
+        );
+    }
+
+    /// Copy the most recent samples into `frame`, oldest first (GUI thread)
+    ///
+    /// Fills at most `capacity` samples; when fewer have been pushed so far,
+    /// only the start of `frame` is written.
+    pub fn pull(&self, frame: &mut [f32]) -> ScopeRead {
+        let shared = &*self.shared;
+        let capacity = shared.ring.len() as u64;
+        let written = shared.written.load(Ordering::Acquire);
+        let previous = shared.read.swap(written, Ordering::Relaxed);
+
+        let count = (frame.len() as u64).min(capacity).min(written);
+        let start = written - count;
+        for (offset, sample) in frame.iter_mut().take(count as usize).enumerate() {
+            let index = ((start + offset as u64) % capacity) as usize;
+            *sample = f32::from_bits(shared.ring[index].load(Ordering::Relaxed));
+        }
+
+        // The producer may have lapped the oldest copied sample meanwhile
+        let torn = shared.written.load(Ordering::Acquire) - start > capacity;
+        ScopeRead {
+            samples: count as usize,
+            overrun: written - previous > capacity || torn,
+        }
+    }
+
+    /// Store samples in the ring, then publish the new write position
+    fn push_samples(&self, samples: impl Iterator<Item = f32>) {
+        let shared = &*self.shared;
+        let capacity = shared.ring.len() as u64;
+        let mut written = shared.written.load(Ordering::Relaxed);
+        for sample in samples {
+            shared.ring[(written % capacity) as usize].store(sample.to_bits(), Ordering::Relaxed);
+            written += 1;
+        }
+        shared.written.store(written, Ordering::Release);
+    }
+}
+
+impl Default for ScopeTap {
+    fn default() -> Self {
+        Self::new(DEFAULT_SCOPE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_returns_most_recent_frame() {
+        let tap = ScopeTap::new(8);
+        let gui = tap.clone();
+
+        let mut frame = [9.0; 4];
+        assert_eq!(
+            gui.pull(&mut frame),
+            ScopeRead {
+                samples: 0,
+                overrun: false
+            }
+        );
+
+        tap.push(&[1.0, 2.0, 3.0]);
+        let read = gui.pull(&mut frame);
+        assert_eq!(read.samples, 3);
+        assert!(!read.overrun);
+        assert_eq!(frame[..3], [1.0, 2.0, 3.0]);
+
+        // Wrapping around the ring keeps the newest samples in order
+        tap.push(&[4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        let read = gui.pull(&mut frame);
+        assert_eq!(read.samples, 4);
+        assert!(!read.overrun);
+        assert_eq!(frame, [6.0, 7.0, 8.0, 9.0]);
+
+        // Interleaved stereo is averaged to one sample per frame
+        tap.push_interleaved(&[1.0, 0.0, -1.0, -0.5], 2);
+        gui.pull(&mut frame);
+        assert_eq!(frame, [8.0, 9.0, 0.5, -0.75]);
+    }
+
+    #[test]
+    fn test_falling_behind_reports_overrun() {
+        let tap = ScopeTap::new(8);
+        let mut frame = [0.0; 8];
+
+        // Twelve samples since the last pull: four were never visible
+        for block in 0..3 {
+            let base = block as f32 * 4.0;
+            tap.push(&[base, base + 1.0, base + 2.0, base + 3.0]);
+        }
+        let read = tap.pull(&mut frame);
+        assert!(read.overrun);
+        assert_eq!(read.samples, 8);
+        assert_eq!(frame, [4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0]);
+
+        // Keeping up clears the flag again
+        tap.push(&[12.0, 13.0]);
+        let read = tap.pull(&mut frame[..2]);
+        assert!(!read.overrun);
+        assert_eq!(frame[..2], [12.0, 13.0]);
+    }
+}
@@ -3,20 +3,229 @@
 /// This module provides cross-platform audio stream setup and management
 /// using the CPAL library. It abstracts audio device selection, format
 /// negotiation, and real-time audio callback handling.
+use super::effects::ToneControl;
+use super::scope::ScopeTap;
 use crate::RealtimeEngine;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, Stream, StreamConfig as CpalStreamConfig};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Errors that can occur while opening or starting an audio output stream
+#[derive(Debug)]
+pub enum AudioStreamError {
+    /// The host has no output device
+    NoDevice,
+    /// The device's sample format has no stream implementation
+    UnsupportedFormat(cpal::SampleFormat),
+    /// Querying the device's default output configuration failed
+    DeviceConfig(cpal::DefaultStreamConfigError),
+    /// The routed output channels do not exist on the device
+    ChannelsUnavailable {
+        offset: usize,
+        device_channels: usize,
+    },
+    /// Reading the device name failed
+    DeviceName(cpal::DeviceNameError),
+    /// Enumerating output devices failed
+    Devices(cpal::DevicesError),
+    /// Building the output stream failed
+    BuildStream(cpal::BuildStreamError),
+    /// Starting playback on the built stream failed
+    PlayStream(cpal::PlayStreamError),
+    /// The requested stream kind is not implemented
+    NotImplemented(&'static str),
+}
+
+impl std::fmt::Display for AudioStreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioStreamError::NoDevice => write!(f, "No audio output device available"),
+            AudioStreamError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported audio format: {}", format)
+            }
+            AudioStreamError::DeviceConfig(e) => write!(f, "Device config error: {}", e),
+            AudioStreamError::ChannelsUnavailable {
+                offset,
+                device_channels,
+            } => write!(
+                f,
+                "Output channels {}/{} not available on a {}-channel device",
+                offset + 1,
+                offset + 2,
+                device_channels
+            ),
+            AudioStreamError::DeviceName(e) => write!(f, "Device name error: {}", e),
+            AudioStreamError::Devices(e) => write!(f, "Device enumeration error: {}", e),
+            AudioStreamError::BuildStream(e) => write!(f, "Stream build error: {}", e),
+            AudioStreamError::PlayStream(e) => write!(f, "Stream play error: {}", e),
+            AudioStreamError::NotImplemented(what) => write!(f, "{} not yet implemented", what),
+        }
+    }
+}
+
+impl std::error::Error for AudioStreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AudioStreamError::DeviceConfig(e) => Some(e),
+            AudioStreamError::DeviceName(e) => Some(e),
+            AudioStreamError::Devices(e) => Some(e),
+            AudioStreamError::BuildStream(e) => Some(e),
+            AudioStreamError::PlayStream(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<cpal::DefaultStreamConfigError> for AudioStreamError {
+    fn from(error: cpal::DefaultStreamConfigError) -> Self {
+        AudioStreamError::DeviceConfig(error)
+    }
+}
+
+impl From<cpal::DeviceNameError> for AudioStreamError {
+    fn from(error: cpal::DeviceNameError) -> Self {
+        AudioStreamError::DeviceName(error)
+    }
+}
+
+impl From<cpal::DevicesError> for AudioStreamError {
+    fn from(error: cpal::DevicesError) -> Self {
+        AudioStreamError::Devices(error)
+    }
+}
+
+impl From<cpal::BuildStreamError> for AudioStreamError {
+    fn from(error: cpal::BuildStreamError) -> Self {
+        AudioStreamError::BuildStream(error)
+    }
+}
+
+impl From<cpal::PlayStreamError> for AudioStreamError {
+    fn from(error: cpal::PlayStreamError) -> Self {
+        AudioStreamError::PlayStream(error)
+    }
+}
 
 /// Shared application state for audio processing
 #[derive(Clone)]
 pub struct AppState {
     pub engine: Arc<Mutex<RealtimeEngine>>,
+    /// Master tone control applied to the engine output (flat by default)
+    pub tone_control: Arc<Mutex<ToneControl>>,
+    /// First device channel of the stereo mix (0 = outputs 1/2)
+    pub output_channel_offset: usize,
+    /// Tap receiving the processed mix for scope and level displays
+    pub scope_tap: Option<ScopeTap>,
 }
 
 impl AppState {
     pub fn new(engine: Arc<Mutex<RealtimeEngine>>) -> Self {
-        Self { engine }
+        let sample_rate = engine.lock().unwrap().get_sample_rate();
+        Self {
+            engine,
+            tone_control: Arc::new(Mutex::new(ToneControl::new(sample_rate))),
+            output_channel_offset: 0,
+            scope_tap: None,
+        }
+    }
+
+    /// Route the stereo mix to device channels `offset` and `offset + 1`
+    ///
+    /// Other device channels are left silent.
+    pub fn with_output_channel_offset(mut self, offset: usize) -> Self {
+        self.output_channel_offset = offset;
+        self
+    }
+
+    /// Push the processed mix into `tap`, keeping a clone to pull from in the GUI
+    pub fn with_scope_tap(mut self, tap: ScopeTap) -> Self {
+        self.scope_tap = Some(tap);
+        self
+    }
+
+    /// Render one device buffer of interleaved `channels`-channel audio
+    ///
+    /// Mono devices get the mono mix. Wider devices get the stereo mix on the
+    /// configured channel pair and silence everywhere else.
+    fn render_output(&self, output: &mut [f32], channels: usize) {
+        output.fill(0.0);
+        if channels == 1 {
+            self.engine.lock().unwrap().process_buffer(output);
+            self.tone_control
+                .lock()
+                .unwrap()
+                .process_interleaved(output, 1);
+            if let Some(tap) = &self.scope_tap {
+                tap.push(output);
+            }
+            return;
+        }
+
+        let frames = output.len() / channels;
+        let mut stereo = vec![0.0f32; frames * 2];
+        self.engine
+            .lock()
+            .unwrap()
+            .process_stereo_buffer(&mut stereo);
+        self.tone_control
+            .lock()
+            .unwrap()
+            .process_interleaved(&mut stereo, 2);
+        if let Some(tap) = &self.scope_tap {
+            tap.push_interleaved(&stereo, 2);
+        }
+
+        let offset = self.output_channel_offset;
+        for (frame, mix) in output
+            .chunks_exact_mut(channels)
+            .zip(stereo.chunks_exact(2))
+        {
+            if let Some(pair) = frame.get_mut(offset..offset + 2) {
+                pair.copy_from_slice(mix);
+            }
+        }
+    }
+
+    /// Report how long rendering `frames` frames took to the engine's adaptive voice limit
+    fn report_render_time(&self, elapsed: Duration, frames: usize) {
+        let mut engine = self.engine.lock().unwrap();
+        let buffer_secs = frames as f32 / engine.get_sample_rate();
+        engine.report_processing_time(elapsed.as_secs_f32(), buffer_secs);
+    }
+}
+
+/// Check that a device has the channels the stereo mix is routed to
+pub fn check_output_channels(
+    offset: usize,
+    device_channels: usize,
+) -> Result<(), AudioStreamError> {
+    let valid = if device_channels == 1 {
+        offset == 0
+    } else {
+        offset + 2 <= device_channels
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AudioStreamError::ChannelsUnavailable {
+            offset,
+            device_channels,
+        })
+    }
+}
+
+/// Take the device the host offered, if any
+fn require_device<D>(device: Option<D>) -> Result<D, AudioStreamError> {
+    device.ok_or(AudioStreamError::NoDevice)
+}
+
+/// Check that a sample format has a stream implementation
+fn check_sample_format(format: cpal::SampleFormat) -> Result<(), AudioStreamError> {
+    match format {
+        cpal::SampleFormat::F32 | cpal::SampleFormat::I16 | cpal::SampleFormat::U16 => Ok(()),
+        other => Err(AudioStreamError::UnsupportedFormat(other)),
     }
 }
 
@@ -27,13 +236,9 @@ pub struct AudioStream {
 
 impl AudioStream {
     /// Setup CPAL audio stream for real-time metronome output
-    pub fn setup_audio_stream(
-        app_state: AppState,
-    ) -> Result<AudioStream, Box<dyn std::error::Error>> {
+    pub fn setup_audio_stream(app_state: AppState) -> Result<AudioStream, AudioStreamError> {
         let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or("No audio output device available")?;
+        let device = require_device(host.default_output_device())?;
 
         let config = device.default_output_config()?;
 
@@ -41,6 +246,8 @@ impl AudioStream {
         println!("Audio device: {}", device.name()?);
         println!("Sample rate: {} Hz", config.sample_rate().0);
         println!("Channels: {}", config.channels());
+        check_output_channels(app_state.output_channel_offset, config.channels() as usize)?;
+        check_sample_format(config.sample_format())?;
 
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => {
@@ -52,7 +259,7 @@ impl AudioStream {
             cpal::SampleFormat::U16 => {
                 Self::create_stream::<u16>(&device, &config.into(), app_state)
             }
-            _ => return Err("Unsupported audio format".into()),
+            other => return Err(AudioStreamError::UnsupportedFormat(other)),
         }?;
 
         stream.play()?;
@@ -64,26 +271,25 @@ impl AudioStream {
         device: &Device,
         config: &CpalStreamConfig,
         app_state: AppState,
-    ) -> Result<Stream, Box<dyn std::error::Error>>
+    ) -> Result<Stream, AudioStreamError>
     where
         T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32>,
     {
         let channels = config.channels as usize;
+        check_output_channels(app_state.output_channel_offset, channels)?;
+        app_state
+            .tone_control
+            .lock()
+            .unwrap()
+            .set_sample_rate(config.sample_rate.0 as f32);
 
         let stream = device.build_output_stream(
             config,
             move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let started = Instant::now();
                 let mut f32_buffer = vec![0.0f32; data.len()];
-
-                // Process audio with the engine
-                {
-                    let mut engine = app_state.engine.lock().unwrap();
-                    if channels == 1 {
-                        engine.process_buffer(&mut f32_buffer);
-                    } else {
-                        engine.process_stereo_buffer(&mut f32_buffer);
-                    }
-                }
+                app_state.render_output(&mut f32_buffer, channels);
+                app_state.report_render_time(started.elapsed(), data.len() / channels);
 
                 // Convert back to target format
                 for (dst, &src) in data.iter_mut().zip(f32_buffer.iter()) {
@@ -156,20 +362,17 @@ impl AudioStreamBuilder {
         self
     }
 
-    pub fn build_with_callback<F>(
-        self,
-        _callback: F,
-    ) -> Result<AudioStream, Box<dyn std::error::Error>>
+    pub fn build_with_callback<F>(self, _callback: F) -> Result<AudioStream, AudioStreamError>
     where
         F: FnMut(&mut [f32]) + Send + 'static,
     {
         // Implementation would create stream with custom callback
-        Err("Custom callback streams not yet implemented".into())
+        Err(AudioStreamError::NotImplemented("Custom callback streams"))
     }
 }
 
 /// Get available audio devices
-pub fn get_audio_devices() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+pub fn get_audio_devices() -> Result<Vec<String>, AudioStreamError> {
     let host = cpal::default_host();
     let mut devices = Vec::new();
 
@@ -183,14 +386,114 @@ pub fn get_audio_devices() -> Result<Vec<String>, Box<dyn std::error::Error>> {
 }
 
 /// Get default audio device information
-pub fn get_default_audio_device_info() -> Result<(String, u32, u16), Box<dyn std::error::Error>> {
+pub fn get_default_audio_device_info() -> Result<(String, u32, u16), AudioStreamError> {
     let host = cpal::default_host();
-    let device = host
-        .default_output_device()
-        .ok_or("No audio output device available")?;
+    let device = require_device(host.default_output_device())?;
 
     let name = device.name()?;
     let config = device.default_output_config()?;
 
     Ok((name, config.sample_rate().0, config.channels()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AdsrEnvelope, EnvelopeCurve, Waveform};
+
+    fn app_state_with_note(offset: usize) -> AppState {
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.trigger_note(
+            Waveform::Square,
+            441.0,
+            AdsrEnvelope {
+                attack_secs: 0.0,
+                hold_secs: 0.0,
+                decay_secs: 0.0,
+                sustain_level: 1.0,
+                release_secs: 0.1,
+                curve: EnvelopeCurve::LINEAR,
+            },
+        );
+        AppState::new(Arc::new(Mutex::new(engine))).with_output_channel_offset(offset)
+    }
+
+    #[test]
+    fn test_stereo_mix_lands_on_configured_channels() {
+        // Six-channel device with the mix routed to outputs 3/4
+        let app_state = app_state_with_note(2);
+        let mut output = vec![1.0; 6 * 64];
+        app_state.render_output(&mut output, 6);
+
+        for frame in output.chunks_exact(6) {
+            assert_eq!(frame[2], frame[3]);
+            for channel in [0, 1, 4, 5] {
+                assert_eq!(frame[channel], 0.0);
+            }
+        }
+        assert!(output.chunks_exact(6).any(|frame| frame[2].abs() > 0.1));
+    }
+
+    #[test]
+    fn test_scope_tap_sees_processed_mix() {
+        let tap = ScopeTap::new(256);
+        let app_state = app_state_with_note(0).with_scope_tap(tap.clone());
+        let mut output = vec![0.0; 2 * 64];
+        app_state.render_output(&mut output, 2);
+
+        let mut frame = [0.0; 64];
+        let read = tap.pull(&mut frame);
+        assert_eq!(read.samples, 64);
+        for (sample, mix) in frame.iter().zip(output.chunks_exact(2)) {
+            assert!((sample - mix[0]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_output_channels_validated_against_device() {
+        assert!(check_output_channels(0, 2).is_ok());
+        assert!(check_output_channels(2, 4).is_ok());
+        assert!(check_output_channels(3, 4).is_err());
+        assert!(check_output_channels(0, 1).is_ok());
+        assert!(check_output_channels(1, 1).is_err());
+    }
+
+    #[test]
+    fn test_stream_errors_identify_failure_mode() {
+        assert!(matches!(
+            require_device::<()>(None),
+            Err(AudioStreamError::NoDevice)
+        ));
+        assert!(require_device(Some(())).is_ok());
+
+        assert!(check_sample_format(cpal::SampleFormat::F32).is_ok());
+        assert!(matches!(
+            check_sample_format(cpal::SampleFormat::I8),
+            Err(AudioStreamError::UnsupportedFormat(cpal::SampleFormat::I8))
+        ));
+
+        assert!(matches!(
+            check_output_channels(3, 4),
+            Err(AudioStreamError::ChannelsUnavailable {
+                offset: 3,
+                device_channels: 4
+            })
+        ));
+
+        let config: AudioStreamError = cpal::DefaultStreamConfigError::DeviceNotAvailable.into();
+        assert!(matches!(config, AudioStreamError::DeviceConfig(_)));
+        let build: AudioStreamError = cpal::BuildStreamError::StreamConfigNotSupported.into();
+        assert!(matches!(build, AudioStreamError::BuildStream(_)));
+        assert!(std::error::Error::source(&build).is_some());
+
+        // Binaries returning Box<dyn Error> still propagate with `?`
+        fn boxed() -> Result<(), Box<dyn std::error::Error>> {
+            check_output_channels(1, 1)?;
+            Ok(())
+        }
+        assert_eq!(
+            boxed().unwrap_err().to_string(),
+            "Output channels 2/3 not available on a 1-channel device"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::audio::drumsynth::{HiHatSynth, SnareSynth};
+use crate::audio::kick::KickSynth;
 use crate::timing::ClickType;
 /// Audio synthesis and parameter generation for click types and musical elements
 ///
@@ -5,9 +7,10 @@ use crate::timing::ClickType;
 /// the monolithic guitar_buddy.rs implementation. It handles waveform generation,
 /// envelope configuration, and audio parameter mapping for different click types
 /// and musical notes/chords for the melody assistant.
-use crate::{AdsrEnvelope, SampleData, Waveform};
+use crate::{render_event, AdsrEnvelope, EnvelopeCurve, SampleData, SoundEvent, Waveform};
 use crate::melody::{Note, Chord};
 use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
 /// Audio synthesis capabilities for click types
 ///
@@ -42,17 +45,22 @@ impl AudioSynthesis for ClickType {
         &self,
         sample_adapter: &AudioSampleAdapter,
     ) -> (Waveform, f32, AdsrEnvelope) {
+        let tail = sample_adapter.click_tail_for(self);
+
         // Check if we have a sample for this click type
         if let Some(sample_data) = sample_adapter.get_sample(self) {
             return (
                 Waveform::DrumSample(sample_data.clone()),
                 440.0, // Frequency is ignored for drum samples
-                self.get_sample_envelope(),
+                scale_tail(self.get_sample_envelope(), tail),
             );
         }
 
-        // Fall back to synthetic sound
-        self.get_synthetic_params()
+        // Fall back to synthetic sound, reusing rendered drums
+        let (waveform, frequency, envelope) = sample_adapter
+            .synthesized_drum(self)
+            .unwrap_or_else(|| self.get_synthetic_params());
+        (waveform, frequency, scale_tail(envelope, tail))
     }
 
     /// Get ADSR envelope for sample-based sounds
@@ -61,89 +69,117 @@ impl AudioSynthesis for ClickType {
         match self {
             ClickType::AcousticKick => AdsrEnvelope {
                 attack_secs: 0.001,  // Instant attack
+                hold_secs: 0.0,
                 decay_secs: 1.0,     // Let natural sample decay
                 sustain_level: 0.0,  // No sustain - one-shot sample
                 release_secs: 0.001, // Minimal release
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::AcousticSnare => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.5, // Let natural snare ring
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::HiHatClosed => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.2, // Natural hi-hat decay
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::HiHatOpen => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 1.0, // Let open hi-hat ring naturally
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::RimShot => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.3, // Natural rim shot decay
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::Stick => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.1, // Short stick click
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             // Extended drum kit samples
             ClickType::KickTight => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.8, // Slightly shorter than regular kick
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::HiHatLoose => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.5, // Medium decay for loose hi-hat
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::HiHatVeryLoose => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 1.2, // Longer decay for very loose
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::CymbalSplash => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 1.5, // Long splash decay
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::CymbalRoll => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 2.0, // Extended roll decay
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::Ride => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.8, // Ride cymbal sustain
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             ClickType::RideBell => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.3, // Short bell ping
                 sustain_level: 0.0,
                 release_secs: 0.001,
+                curve: EnvelopeCurve::LINEAR,
             },
             // For synthetic sounds, use default
             _ => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.0,
                 release_secs: 0.05,
+                curve: EnvelopeCurve::LINEAR,
             },
         }
     }
@@ -156,9 +192,11 @@ impl AudioSynthesis for ClickType {
                 800.0, // High frequency for sharp click
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.05,
                     sustain_level: 0.0,
                     release_secs: 0.02,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::DigitalBeep => (
@@ -166,9 +204,11 @@ impl AudioSynthesis for ClickType {
                 1000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.08,
                     sustain_level: 0.0,
                     release_secs: 0.05,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::Cowbell => (
@@ -176,9 +216,11 @@ impl AudioSynthesis for ClickType {
                 800.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.15,
                     sustain_level: 0.0,
                     release_secs: 0.1,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::RimShot => (
@@ -186,9 +228,11 @@ impl AudioSynthesis for ClickType {
                 400.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.03,
                     sustain_level: 0.0,
                     release_secs: 0.02,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::Stick => (
@@ -196,9 +240,11 @@ impl AudioSynthesis for ClickType {
                 2000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.02,
                     sustain_level: 0.0,
                     release_secs: 0.01,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::ElectroClick => (
@@ -206,71 +252,30 @@ impl AudioSynthesis for ClickType {
                 1200.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.04,
                     sustain_level: 0.0,
                     release_secs: 0.03,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             // For drum samples without sample data, provide synthetic alternatives
-            ClickType::AcousticKick => (
-                Waveform::Sine,
-                60.0,
-                AdsrEnvelope {
-                    attack_secs: 0.001,
-                    decay_secs: 0.3,
-                    sustain_level: 0.0,
-                    release_secs: 0.1,
-                },
-            ),
-            ClickType::AcousticSnare => (
-                Waveform::Noise,
-                800.0,
-                AdsrEnvelope {
-                    attack_secs: 0.001,
-                    decay_secs: 0.15,
-                    sustain_level: 0.0,
-                    release_secs: 0.05,
-                },
-            ),
-            ClickType::HiHatClosed => (
-                Waveform::Pulse { duty_cycle: 0.1 },
-                8000.0,
-                AdsrEnvelope {
-                    attack_secs: 0.001,
-                    decay_secs: 0.08,
-                    sustain_level: 0.0,
-                    release_secs: 0.02,
-                },
-            ),
-            ClickType::HiHatOpen => (
-                Waveform::Pulse { duty_cycle: 0.1 },
-                6000.0,
-                AdsrEnvelope {
-                    attack_secs: 0.001,
-                    decay_secs: 0.25,
-                    sustain_level: 0.0,
-                    release_secs: 0.1,
-                },
-            ),
+            ClickType::AcousticKick => KickSynth::new().audio_params(),
+            ClickType::AcousticSnare => SnareSynth::new().audio_params(),
+            ClickType::HiHatClosed => HiHatSynth::closed().audio_params(),
+            ClickType::HiHatOpen => HiHatSynth::open().audio_params(),
             // Extended drum kit samples - synthetic fallbacks
-            ClickType::KickTight => (
-                Waveform::Sine,
-                80.0, // Slightly higher than regular kick
-                AdsrEnvelope {
-                    attack_secs: 0.001,
-                    decay_secs: 0.2, // Shorter decay for tight kick
-                    sustain_level: 0.0,
-                    release_secs: 0.05,
-                },
-            ),
+            ClickType::KickTight => KickSynth::tight().audio_params(),
             ClickType::HiHatLoose => (
                 Waveform::Pulse { duty_cycle: 0.2 },
                 5000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.4, // Medium decay
                     sustain_level: 0.0,
                     release_secs: 0.15,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::HiHatVeryLoose => (
@@ -278,9 +283,11 @@ impl AudioSynthesis for ClickType {
                 4000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.8, // Long decay
                     sustain_level: 0.0,
                     release_secs: 0.3,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::CymbalSplash => (
@@ -288,9 +295,11 @@ impl AudioSynthesis for ClickType {
                 4000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 1.0, // Splash decay
                     sustain_level: 0.0,
                     release_secs: 0.4,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::CymbalRoll => (
@@ -298,9 +307,11 @@ impl AudioSynthesis for ClickType {
                 3000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 1.5, // Extended roll
                     sustain_level: 0.0,
                     release_secs: 0.6,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::Ride => (
@@ -308,9 +319,11 @@ impl AudioSynthesis for ClickType {
                 2000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.5, // Ride sustain
                     sustain_level: 0.0,
                     release_secs: 0.2,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             ClickType::RideBell => (
@@ -318,23 +331,99 @@ impl AudioSynthesis for ClickType {
                 3000.0,
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.3, // Bell ping
                     sustain_level: 0.0,
                     release_secs: 0.1,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
         }
     }
 }
 
+/// Onset window over which click loudness is measured for calibration in seconds
+const CLICK_LOUDNESS_WINDOW_SECS: f32 = 0.05;
+
+/// RMS level over the onset window that calibrated clicks are matched to
+const CLICK_REFERENCE_RMS: f32 = 0.25;
+
+/// Sample rate click loudness is measured at
+const CLICK_MEASURE_SAMPLE_RATE: u32 = 44100;
+
+/// RMS level of a sound over its first `CLICK_LOUDNESS_WINDOW_SECS`
+///
+/// Clicks are short, so the onset is what sets their apparent loudness.
+pub fn click_loudness_rms(waveform: &Waveform, frequency: f32, envelope: &AdsrEnvelope) -> f32 {
+    let event = SoundEvent {
+        waveform: waveform.clone(),
+        start_frequency: frequency,
+        end_frequency: frequency,
+        duration_secs: CLICK_LOUDNESS_WINDOW_SECS,
+        envelope: envelope.clone(),
+    };
+    let samples = render_event(&event, CLICK_MEASURE_SAMPLE_RATE);
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Volume multiplier that brings a sound to the common click loudness
+///
+/// Measured from the sound itself, so sampled and synthesized versions of a
+/// click type each get their own gain. Silent sounds get unity gain.
+pub fn click_calibration_gain(waveform: &Waveform, frequency: f32, envelope: &AdsrEnvelope) -> f32 {
+    let rms = click_loudness_rms(waveform, frequency, envelope);
+    if rms <= f32::EPSILON {
+        return 1.0;
+    }
+    (CLICK_REFERENCE_RMS / rms).clamp(
+        AudioSampleAdapter::MIN_CLICK_GAIN,
+        AudioSampleAdapter::MAX_CLICK_GAIN,
+    )
+}
+
+/// Stretch or shorten the decay and release stages of a click envelope
+fn scale_tail(envelope: AdsrEnvelope, tail: f32) -> AdsrEnvelope {
+    AdsrEnvelope {
+        decay_secs: envelope.decay_secs * tail,
+        release_secs: envelope.release_secs * tail,
+        ..envelope
+    }
+}
+
 /// Audio sample adapter for ClickType sound generation
 ///
 /// Provides a convenient interface for loading and accessing drum samples
-/// for use with the AudioSynthesis trait and sound generation.
+/// for use with the AudioSynthesis trait and sound generation. It also holds
+/// the click tail settings: multipliers on the decay and release times of
+/// each click, so clicks can be made tighter (< 1.0) or more resonant (> 1.0),
+/// and any per-click-type gains that replace the measured loudness calibration.
 pub struct AudioSampleAdapter {
     samples: HashMap<ClickType, SampleData>,
+    /// Tail length multiplier for every click type (1.0 = built-in lengths)
+    click_tail: f32,
+    /// Per-click-type tail multipliers taking precedence over `click_tail`
+    click_tail_overrides: HashMap<ClickType, f32>,
+    /// Per-click-type volume gains used instead of the measured calibration
+    click_gain_overrides: HashMap<ClickType, f32>,
+    /// Synthesized drum fallbacks, rendered on first use
+    synth_drums: OnceLock<HashMap<ClickType, (Waveform, f32, AdsrEnvelope)>>,
+    /// Measured calibration gains by click type and accent, cleared whenever
+    /// a tail or sample change alters the sound being measured
+    calibration_gains: Mutex<HashMap<(ClickType, bool), f32>>,
 }
 
+/// Click types whose synthetic fallback is a rendered drum hit
+const SYNTHESIZED_DRUMS: [ClickType; 5] = [
+    ClickType::AcousticKick,
+    ClickType::KickTight,
+    ClickType::AcousticSnare,
+    ClickType::HiHatClosed,
+    ClickType::HiHatOpen,
+];
+
 impl Default for AudioSampleAdapter {
     fn default() -> Self {
         Self::new()
@@ -342,10 +431,126 @@ impl Default for AudioSampleAdapter {
 }
 
 impl AudioSampleAdapter {
+    /// Smallest allowed click tail multiplier
+    pub const MIN_CLICK_TAIL: f32 = 0.1;
+    /// Largest allowed click tail multiplier
+    pub const MAX_CLICK_TAIL: f32 = 4.0;
+    /// Smallest allowed click calibration gain
+    pub const MIN_CLICK_GAIN: f32 = 0.1;
+    /// Largest allowed click calibration gain
+    pub const MAX_CLICK_GAIN: f32 = 4.0;
+
     pub fn new() -> Self {
         Self {
             samples: HashMap::new(),
+            click_tail: 1.0,
+            click_tail_overrides: HashMap::new(),
+            click_gain_overrides: HashMap::new(),
+            synth_drums: OnceLock::new(),
+            calibration_gains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the tail length multiplier applied to all click types (clamped to 0.1-4.0)
+    pub fn set_click_tail(&mut self, tail: f32) {
+        self.click_tail = tail.clamp(Self::MIN_CLICK_TAIL, Self::MAX_CLICK_TAIL);
+        self.calibration_gains_mut().clear();
+    }
+
+    /// Get the tail length multiplier applied to all click types
+    pub fn click_tail(&self) -> f32 {
+        self.click_tail
+    }
+
+    /// Override the tail length multiplier for one click type
+    pub fn set_click_type_tail(&mut self, click_type: ClickType, tail: f32) {
+        self.click_tail_overrides.insert(
+            click_type,
+            tail.clamp(Self::MIN_CLICK_TAIL, Self::MAX_CLICK_TAIL),
+        );
+        self.forget_calibration(&click_type);
+    }
+
+    /// Remove a per-click-type override so the global tail applies again
+    pub fn clear_click_type_tail(&mut self, click_type: &ClickType) {
+        self.click_tail_overrides.remove(click_type);
+        self.forget_calibration(click_type);
+    }
+
+    /// Tail length multiplier in effect for a click type
+    pub fn click_tail_for(&self, click_type: &ClickType) -> f32 {
+        self.click_tail_overrides
+            .get(click_type)
+            .copied()
+            .unwrap_or(self.click_tail)
+    }
+
+    /// Use a fixed volume gain for one click type instead of its measured calibration
+    pub fn set_click_type_gain(&mut self, click_type: ClickType, gain: f32) {
+        self.click_gain_overrides.insert(
+            click_type,
+            gain.clamp(Self::MIN_CLICK_GAIN, Self::MAX_CLICK_GAIN),
+        );
+    }
+
+    /// Remove a per-click-type gain so the measured calibration applies again
+    pub fn clear_click_type_gain(&mut self, click_type: &ClickType) {
+        self.click_gain_overrides.remove(click_type);
+    }
+
+    /// Volume gain overriding the calibration for a click type, if one is set
+    pub fn click_gain_override(&self, click_type: &ClickType) -> Option<f32> {
+        self.click_gain_overrides.get(click_type).copied()
+    }
+
+    /// Volume gain for a click type's sound: its override, or else its calibration
+    ///
+    /// The calibration is measured from the given sound the first time it is
+    /// needed and cached, separately for the accent (`accent`) and regular
+    /// sounds, until a tail or sample change alters the click type's sound.
+    pub fn click_gain(
+        &self,
+        click_type: ClickType,
+        accent: bool,
+        waveform: &Waveform,
+        frequency: f32,
+        envelope: &AdsrEnvelope,
+    ) -> f32 {
+        if let Some(gain) = self.click_gain_override(&click_type) {
+            return gain;
         }
+        *self
+            .calibration_gains
+            .lock()
+            .unwrap()
+            .entry((click_type, accent))
+            .or_insert_with(|| click_calibration_gain(waveform, frequency, envelope))
+    }
+
+    fn calibration_gains_mut(&mut self) -> &mut HashMap<(ClickType, bool), f32> {
+        self.calibration_gains.get_mut().unwrap()
+    }
+
+    /// Drop the cached calibration of one click type's sounds
+    fn forget_calibration(&mut self, click_type: &ClickType) {
+        self.calibration_gains_mut()
+            .retain(|(cached, _), _| cached != click_type);
+    }
+
+    /// Synthetic fallback for a click type that is a rendered drum hit
+    ///
+    /// All of the synthesized drums are rendered the first time one is
+    /// needed and reused after that, so triggering them stays cheap.
+    fn synthesized_drum(&self, click_type: &ClickType) -> Option<(Waveform, f32, AdsrEnvelope)> {
+        self.synth_drums
+            .get_or_init(|| {
+                SYNTHESIZED_DRUMS
+                    .iter()
+                    .map(|drum| (*drum, drum.get_synthetic_params()))
+                    .collect()
+            })
+            .get(click_type)
+            .cloned()
     }
 
     pub fn load_drum_samples(&mut self) -> Result<(), Box<dyn std::error::Error>> {
@@ -411,6 +616,7 @@ impl AudioSampleAdapter {
                 Ok(sample_data) => {
                     println!("✅ Loaded drum sample: {} from {}", click_type.name(), path);
                     self.samples.insert(click_type, sample_data);
+                    self.forget_calibration(&click_type);
                 }
                 Err(e) => {
                     println!(
@@ -441,6 +647,21 @@ pub fn get_sound_params(
     click_type.get_audio_params(sample_adapter)
 }
 
+/// Get audio parameters for a click type along with its calibration gain
+///
+/// Returns (waveform, frequency, envelope, gain), where `gain` is the volume
+/// multiplier that makes every click type equally loud at the same volume
+/// setting: the adapter's override for the click type, or else the measured
+/// calibration of the chosen sound, measured once and cached by the adapter.
+pub fn get_calibrated_sound_params(
+    click_type: ClickType,
+    sample_adapter: &AudioSampleAdapter,
+) -> (Waveform, f32, AdsrEnvelope, f32) {
+    let (waveform, frequency, envelope) = get_sound_params(click_type, sample_adapter);
+    let gain = sample_adapter.click_gain(click_type, false, &waveform, frequency, &envelope);
+    (waveform, frequency, envelope, gain)
+}
+
 /// Musical note synthesis for melody assistant accompaniment
 impl AudioSynthesis for Note {
     fn get_audio_params(
@@ -451,9 +672,11 @@ impl AudioSynthesis for Note {
         let frequency = self.middle_frequency(); // Default to octave 4
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,   // Quick attack for responsiveness
+            hold_secs: 0.0,
             decay_secs: 0.1,     // Short decay for clarity
             sustain_level: 0.7,  // Moderate sustain for continuity
             release_secs: 0.5,   // Gradual release for musical quality
+            curve: EnvelopeCurve::LINEAR,
         };
         (Waveform::Sine, frequency, envelope)
     }
@@ -462,9 +685,11 @@ impl AudioSynthesis for Note {
         // Optimized for musical note playback
         AdsrEnvelope {
             attack_secs: 0.005,
+            hold_secs: 0.0,
             decay_secs: 0.05,
             sustain_level: 0.8,
             release_secs: 0.3,
+            curve: EnvelopeCurve::LINEAR,
         }
     }
 
@@ -484,9 +709,11 @@ impl AudioSynthesis for Chord {
         let frequency = self.root_frequency();
         let envelope = AdsrEnvelope {
             attack_secs: 0.02,  // Slightly slower attack for chords
+            hold_secs: 0.0,
             decay_secs: 0.15,   // Longer decay for richness
             sustain_level: 0.6, // Lower sustain to avoid muddiness
             release_secs: 0.8,  // Long release for chord resonance
+            curve: EnvelopeCurve::LINEAR,
         };
         (Waveform::Sine, frequency, envelope)
     }
@@ -495,9 +722,11 @@ impl AudioSynthesis for Chord {
         // Optimized for chord playback
         AdsrEnvelope {
             attack_secs: 0.015,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.7,
             release_secs: 0.6,
+            curve: EnvelopeCurve::LINEAR,
         }
     }
 
@@ -516,3 +745,119 @@ pub fn get_chord_audio_params(chord: &Chord) -> (Waveform, f32, AdsrEnvelope) {
     let adapter = AudioSampleAdapter::new();
     chord.get_audio_params(&adapter)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of audible samples in an offline render of a click
+    fn ringing_samples(adapter: &AudioSampleAdapter, click_type: ClickType) -> usize {
+        let (waveform, frequency, envelope) = get_sound_params(click_type, adapter);
+        let event = SoundEvent {
+            waveform,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration_secs: envelope.attack_secs
+                + envelope.hold_secs
+                + envelope.decay_secs
+                + envelope.release_secs,
+            envelope,
+        };
+        render_event(&event, 44100)
+            .iter()
+            .filter(|s| s.abs() > 1e-4)
+            .count()
+    }
+
+    #[test]
+    fn test_longer_click_tail_rings_longer() {
+        let mut adapter = AudioSampleAdapter::new();
+        let natural = ringing_samples(&adapter, ClickType::DigitalBeep);
+
+        adapter.set_click_tail(0.5);
+        let tight = ringing_samples(&adapter, ClickType::DigitalBeep);
+        adapter.set_click_tail(3.0);
+        let resonant = ringing_samples(&adapter, ClickType::DigitalBeep);
+
+        assert!(tight < natural && natural < resonant);
+        assert!(resonant > 2 * natural, "{} vs {}", resonant, natural);
+    }
+
+    #[test]
+    fn test_click_type_tail_override() {
+        let mut adapter = AudioSampleAdapter::new();
+        adapter.set_click_tail(2.0);
+        adapter.set_click_type_tail(ClickType::Cowbell, 0.5);
+
+        let (_, _, default_cowbell) = ClickType::Cowbell.get_synthetic_params();
+        let (_, _, cowbell) = get_sound_params(ClickType::Cowbell, &adapter);
+        let (_, _, beep) = get_sound_params(ClickType::DigitalBeep, &adapter);
+        assert_eq!(cowbell.decay_secs, default_cowbell.decay_secs * 0.5);
+        assert_eq!(cowbell.release_secs, default_cowbell.release_secs * 0.5);
+        assert_eq!(cowbell.attack_secs, default_cowbell.attack_secs);
+        assert_eq!(beep.decay_secs, 0.16);
+
+        adapter.clear_click_type_tail(&ClickType::Cowbell);
+        assert_eq!(adapter.click_tail_for(&ClickType::Cowbell), 2.0);
+        adapter.set_click_tail(100.0);
+        assert_eq!(adapter.click_tail(), AudioSampleAdapter::MAX_CLICK_TAIL);
+    }
+
+    #[test]
+    fn test_calibrated_gains_balance_click_loudness() {
+        let adapter = AudioSampleAdapter::new();
+        let level = |click_type: ClickType| {
+            let (waveform, frequency, envelope, gain) =
+                get_calibrated_sound_params(click_type, &adapter);
+            let rms = click_loudness_rms(&waveform, frequency, &envelope);
+            // Inherent level, and level when played at volume 0.8
+            (rms, rms * 0.8 * gain)
+        };
+
+        // A square cowbell is inherently much louder than a sine beep
+        let (cowbell_raw, cowbell) = level(ClickType::Cowbell);
+        let (beep_raw, beep) = level(ClickType::DigitalBeep);
+        assert!(cowbell_raw > 1.3 * beep_raw, "{} vs {}", cowbell_raw, beep_raw);
+
+        // At the same volume setting the calibrated clicks match within 0.5 dB
+        let difference_db = 20.0 * (cowbell / beep).log10();
+        assert!(difference_db.abs() < 0.5, "{} dB apart", difference_db);
+
+        // An override replaces the measured gain for its click type only
+        let mut adapter = AudioSampleAdapter::new();
+        adapter.set_click_type_gain(ClickType::Cowbell, 10.0);
+        assert_eq!(get_calibrated_sound_params(ClickType::Cowbell, &adapter).3, 4.0);
+        adapter.clear_click_type_gain(&ClickType::Cowbell);
+        assert_eq!(adapter.click_gain_override(&ClickType::Cowbell), None);
+    }
+
+    #[test]
+    fn test_calibration_gain_is_cached_until_the_tail_changes() {
+        let mut adapter = AudioSampleAdapter::new();
+        let gain = get_calibrated_sound_params(ClickType::DigitalBeep, &adapter).3;
+        assert_eq!(adapter.calibration_gains.lock().unwrap().len(), 1);
+        assert_eq!(get_calibrated_sound_params(ClickType::DigitalBeep, &adapter).3, gain);
+
+        // A much shorter tail leaves less onset energy, so the gain is remeasured
+        adapter.set_click_type_tail(ClickType::DigitalBeep, AudioSampleAdapter::MIN_CLICK_TAIL);
+        assert!(adapter.calibration_gains.lock().unwrap().is_empty());
+        assert!(get_calibrated_sound_params(ClickType::DigitalBeep, &adapter).3 > gain);
+    }
+
+    #[test]
+    fn test_synthesized_drums_render_once_with_gate_envelope() {
+        let adapter = AudioSampleAdapter::new();
+        let (first, _, envelope) = ClickType::AcousticSnare.get_audio_params(&adapter);
+        let (second, _, _) = ClickType::AcousticSnare.get_audio_params(&adapter);
+        assert_eq!(first, second);
+
+        // The hit is shaped in its samples; the envelope just gates its length
+        let Waveform::DrumSample(sample) = first else {
+            panic!("snare fallback should be a rendered drum sample");
+        };
+        assert_eq!(envelope.attack_secs, 0.0);
+        assert_eq!(envelope.hold_secs, sample.metadata.duration_secs);
+        assert_eq!(envelope.decay_secs, 0.0);
+        assert!(adapter.synthesized_drum(&ClickType::Cowbell).is_none());
+    }
+}
@@ -1,10 +1,11 @@
-use polyphonica::audio::accents::get_accent_sound;
-use polyphonica::audio::synthesis::{get_sound_params, AudioSampleAdapter, get_note_audio_params, get_chord_audio_params};
-use polyphonica::melody::{MelodyAssistantState, Note, KeySelection, GenerationParameters, TimelineConfig};
+use polyphonica::audio::accents::get_calibrated_accent_sound;
+use polyphonica::audio::synthesis::{get_calibrated_sound_params, AudioSampleAdapter, get_note_audio_params, get_chord_audio_params};
+use polyphonica::melody::{Chord, MelodyAssistantState, Note, KeySelection, GenerationParameters, TimelineConfig};
 use polyphonica::patterns::{DrumPattern, MasterCollection, PatternLibrary, PatternState};
 use polyphonica::patterns::types::PatternGenre;
 use polyphonica::timing::{
-    BeatClock, BeatEvent, BeatTracker, ClickType, Metronome as NewMetronome, TimeSignature,
+    BeatClock, BeatEvent, BeatTracker, ClickType, Metronome as NewMetronome, QuantizeGrid,
+    TimeSignature, TriggerQuantizer,
 };
 /// Guitar Buddy - Musical Practice Companion
 ///
@@ -46,6 +47,13 @@ use eframe::egui;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+/// Melody audition waiting to be played
+#[derive(Debug, Clone)]
+enum MelodyTrigger {
+    Note(Note),
+    Chord(Chord),
+}
+
 /// Metronome state and timing control
 struct MetronomeState {
     is_playing: bool,
@@ -68,6 +76,9 @@ struct MetronomeState {
     skill_level: f32, // 0.0 = beginner, 1.0 = expert
     melody_volume: f32, // Volume for melody assistant audio (0.0 to 1.0)
     auto_accompaniment: bool, // Whether to play automatic chord accompaniment
+    melody_quantizer: TriggerQuantizer<MelodyTrigger>, // Snaps auditions to the metronome grid
+    run_id: u32, // Transport run counter, incremented on every start
+    next_sequence: u64, // Sequence number for the next recorded beat event
 }
 
 impl MetronomeState {
@@ -99,6 +110,9 @@ impl MetronomeState {
             skill_level: 0.3, // Default to medium skill level
             melody_volume: 0.4, // Default melody volume
             auto_accompaniment: false, // Disabled by default
+            melody_quantizer: TriggerQuantizer::new(QuantizeGrid::Off),
+            run_id: 0,
+            next_sequence: 0,
         };
         // Sync initial settings to new metronome
         instance.sync_to_new_metronome();
@@ -113,27 +127,36 @@ impl MetronomeState {
             .set_accent_first_beat(self.accent_first_beat);
     }
 
+    /// Stamp a beat event with the current transport run and the next sequence number
+    fn stamp_beat(&mut self, beat_event: BeatEvent) -> BeatEvent {
+        let beat_event = beat_event.with_sequence(self.run_id, self.next_sequence);
+        self.next_sequence += 1;
+        beat_event
+    }
+
     /// Calculate time between beats in milliseconds
     fn beat_interval_ms(&self) -> f64 {
         60000.0 / self.tempo_bpm as f64
     }
 
     /// Check if it's time for the next beat (using new timing module)
-    fn should_trigger_beat(&mut self) -> bool {
+    ///
+    /// Returns the metronome's beat event, which may be silenced by a gap-click drill.
+    fn should_trigger_beat(&mut self) -> Option<BeatEvent> {
         if !self.is_playing {
-            return false;
+            return None;
         }
 
         // Use new metronome timing with discrete scheduling
-        let events = self.new_metronome.check_triggers(self.tempo_bpm);
-        if !events.is_empty() {
-            // Update current beat to match new metronome
-            self.current_beat = events[0].beat_number;
-            self.last_beat_time = Some(events[0].timestamp);
-            true
-        } else {
-            false
-        }
+        let event = self
+            .new_metronome
+            .check_triggers(self.tempo_bpm)
+            .into_iter()
+            .next()?;
+        // Update current beat to match new metronome
+        self.current_beat = event.beat_number;
+        self.last_beat_time = Some(event.timestamp);
+        Some(event)
     }
 
     /// Set drum pattern and switch to pattern mode
@@ -149,13 +172,13 @@ impl MetronomeState {
     }
 
     /// Check for pattern triggers and return samples to play
-    fn check_pattern_triggers(&mut self) -> Vec<(ClickType, bool)> {
+    fn check_pattern_triggers(&mut self) -> Vec<(ClickType, bool, f32)> {
         if self.pattern_mode && self.is_playing {
             // Get pattern triggers directly
             self.pattern_state
                 .check_pattern_triggers(self.tempo_bpm)
                 .into_iter()
-                .map(|trigger| (trigger.click_type, trigger.is_accent))
+                .map(|trigger| (trigger.click_type, trigger.is_accent, trigger.gain))
                 .collect()
         } else {
             vec![]
@@ -164,6 +187,7 @@ impl MetronomeState {
 
     fn start(&mut self) {
         self.is_playing = true;
+        self.run_id = self.run_id.wrapping_add(1);
         self.current_beat = 0;
         self.last_beat_time = None;
         // Start new metronome with updated settings
@@ -179,6 +203,7 @@ impl MetronomeState {
         self.last_beat_time = None;
         // Stop new metronome
         self.new_metronome.stop();
+        self.melody_quantizer.clear();
         if self.pattern_mode {
             self.pattern_state.stop();
         }
@@ -188,6 +213,7 @@ impl MetronomeState {
         self.is_playing = false;
         // Pause new metronome
         self.new_metronome.pause();
+        self.melody_quantizer.clear();
         if self.pattern_mode {
             self.pattern_state.stop();
         }
@@ -217,6 +243,31 @@ impl AppState {
     }
 }
 
+/// Play a melody audition immediately
+fn play_melody_trigger(app_state: &AppState, trigger: &MelodyTrigger) {
+    let (waveform, frequency, envelope) = match trigger {
+        MelodyTrigger::Note(note) => get_note_audio_params(note),
+        MelodyTrigger::Chord(chord) => get_chord_audio_params(chord),
+    };
+    let volume = {
+        let metronome = app_state.metronome.lock().unwrap();
+        metronome.melody_volume
+    }; // Release metronome lock before acquiring engine lock
+    let mut engine = app_state.engine.lock().unwrap();
+    engine.trigger_note_with_volume(waveform, frequency, envelope, volume);
+}
+
+/// Play a melody audition now, or defer it to the next beat when snapping is enabled
+fn queue_melody_trigger(app_state: &AppState, trigger: MelodyTrigger) {
+    let ready = {
+        let mut metronome = app_state.metronome.lock().unwrap();
+        metronome.melody_quantizer.request(trigger, Instant::now())
+    };
+    if let Some(trigger) = ready {
+        play_melody_trigger(app_state, &trigger);
+    }
+}
+
 /// GUI Components Module
 mod gui_components {
     use super::*;
@@ -383,9 +434,9 @@ mod gui_components {
             ui.horizontal(|ui| {
                 if ui.button("🔊 Test Click").clicked() {
                     let metronome = app_state.metronome.lock().unwrap();
-                    let (waveform, frequency, envelope) =
-                        get_sound_params(metronome.click_type, &metronome.audio_samples);
-                    let volume = metronome.volume;
+                    let (waveform, frequency, envelope, gain) =
+                        get_calibrated_sound_params(metronome.click_type, &metronome.audio_samples);
+                    let volume = metronome.volume * gain;
                     drop(metronome);
 
                     let mut engine = app_state.engine.lock().unwrap();
@@ -653,23 +704,11 @@ mod gui_components {
     impl MelodyAssistantPanel {
         // Helper function to play audio without lock conflicts
         fn play_note_audio(app_state: &AppState, note: &Note) {
-            let (waveform, frequency, envelope) = get_note_audio_params(note);
-            let volume = {
-                let metronome = app_state.metronome.lock().unwrap();
-                metronome.melody_volume
-            }; // Release metronome lock before acquiring engine lock
-            let mut engine = app_state.engine.lock().unwrap();
-            engine.trigger_note_with_volume(waveform, frequency, envelope, volume);
+            queue_melody_trigger(app_state, MelodyTrigger::Note(*note));
         }
 
-        fn play_chord_audio(app_state: &AppState, chord: &polyphonica::melody::Chord) {
-            let (waveform, frequency, envelope) = get_chord_audio_params(chord);
-            let volume = {
-                let metronome = app_state.metronome.lock().unwrap();
-                metronome.melody_volume
-            }; // Release metronome lock before acquiring engine lock
-            let mut engine = app_state.engine.lock().unwrap();
-            engine.trigger_note_with_volume(waveform, frequency, envelope, volume);
+        fn play_chord_audio(app_state: &AppState, chord: &Chord) {
+            queue_melody_trigger(app_state, MelodyTrigger::Chord(chord.clone()));
         }
 
         pub fn show(ui: &mut Ui, app_state: &AppState) {
@@ -688,6 +727,12 @@ mod gui_components {
                             }
                         }
                     }
+
+                    let mut snap_to_beat = metronome.melody_quantizer.grid() != QuantizeGrid::Off;
+                    if ui.checkbox(&mut snap_to_beat, "Snap to beat").changed() {
+                        let grid = if snap_to_beat { QuantizeGrid::Beat } else { QuantizeGrid::Off };
+                        metronome.melody_quantizer.set_grid(grid);
+                    }
                 });
 
                 if metronome.show_chord_progressions {
@@ -956,20 +1001,20 @@ impl GuitarBuddy {
         })
     }
 
-    fn trigger_click(&self, is_accent: bool, beat_number: u8) {
+    fn trigger_click(&self, is_accent: bool, beat_number: u8, silenced: bool) {
         let mut metronome = self.app_state.metronome.lock().unwrap();
         // drum_samples now accessed via metronome.audio_samples
 
         // Use different sound for accents to make them clearly distinct
-        let (waveform, frequency, envelope) = if is_accent && metronome.accent_first_beat {
+        let (waveform, frequency, envelope, gain) = if is_accent && metronome.accent_first_beat {
             // For accents, use a more prominent sound
-            get_accent_sound(metronome.click_type, &metronome.audio_samples)
+            get_calibrated_accent_sound(metronome.click_type, &metronome.audio_samples)
         } else {
             // Regular click
-            get_sound_params(metronome.click_type, &metronome.audio_samples)
+            get_calibrated_sound_params(metronome.click_type, &metronome.audio_samples)
         };
 
-        let volume = metronome.volume;
+        let volume = metronome.volume * gain;
         let click_type = metronome.click_type;
 
         // Record beat event for visualizer coupling
@@ -979,8 +1024,11 @@ impl GuitarBuddy {
             vec![click_type],
             metronome.tempo_bpm,
             metronome.time_signature,
-        );
+        )
+        .with_silenced(silenced);
+        let beat_event = metronome.stamp_beat(beat_event);
         metronome.beat_tracker.record_beat(beat_event.clone());
+        metronome.melody_quantizer.observe_beat(&beat_event);
 
         // Update melody assistant with beat event if chord progressions are enabled
         if metronome.show_chord_progressions {
@@ -1005,6 +1053,11 @@ impl GuitarBuddy {
         drop(metronome);
         // No longer need to drop drum_samples
 
+        // Gap-click drills keep counting silenced beats but leave them unplayed
+        if !beat_event.is_audible() {
+            return;
+        }
+
         let mut engine = self.app_state.engine.lock().unwrap();
         engine.trigger_note_with_volume(waveform, frequency, envelope, volume);
     }
@@ -1015,21 +1068,25 @@ impl GuitarBuddy {
         &self,
         click_type: ClickType,
         is_accent: bool,
+        gain: f32,
         beat_number: u8,
         samples: Vec<ClickType>,
     ) {
         let mut metronome = self.app_state.metronome.lock().unwrap();
         // drum_samples now accessed via metronome.audio_samples
 
-        let (waveform, frequency, envelope) =
-            get_sound_params(click_type, &metronome.audio_samples);
+        let (waveform, frequency, envelope, calibration_gain) =
+            get_calibrated_sound_params(click_type, &metronome.audio_samples);
+        let envelope = metronome
+            .pattern_state
+            .scale_envelope(&envelope, metronome.tempo_bpm);
 
         // Pattern accents need volume boost since they use same samples, unlike metronome which uses different sounds
         let volume = if is_accent {
             (metronome.volume * 1.5).min(1.0) // 50% louder for pattern accents
         } else {
             metronome.volume
-        };
+        } * gain * calibration_gain; // Per-pattern and per-kit-piece level, loudness-matched
 
         // Record beat event for visualizer coupling (only once per beat, not per sample)
         if click_type == samples[0] {
@@ -1041,6 +1098,7 @@ impl GuitarBuddy {
                 metronome.tempo_bpm,
                 metronome.time_signature,
             );
+            let beat_event = metronome.stamp_beat(beat_event);
             metronome.beat_tracker.record_beat(beat_event.clone());
 
             // Update melody assistant with beat event if chord progressions are enabled
@@ -1085,19 +1143,20 @@ impl eframe::App for GuitarBuddy {
                 let pattern_triggers = metronome.check_pattern_triggers();
                 if !pattern_triggers.is_empty() {
                     // Collect beat information for BeatTracker event
-                    let _has_accent = pattern_triggers.iter().any(|(_, is_accent)| *is_accent);
+                    let _has_accent = pattern_triggers.iter().any(|(_, is_accent, _)| *is_accent);
                     let all_samples: Vec<ClickType> = pattern_triggers
                         .iter()
-                        .map(|(click_type, _)| *click_type)
+                        .map(|(click_type, _, _)| *click_type)
                         .collect();
 
                     // Visualizer state is now handled by BeatTracker (no manual updates needed)
 
                     drop(metronome);
-                    for (click_type, is_accent) in pattern_triggers {
+                    for (click_type, is_accent, gain) in pattern_triggers {
                         self.trigger_pattern_sample(
                             click_type,
                             is_accent,
+                            gain,
                             beat_number,
                             all_samples.clone(),
                         );
@@ -1105,18 +1164,27 @@ impl eframe::App for GuitarBuddy {
                 }
             } else {
                 // Metronome mode: only play metronome clicks
-                if metronome.should_trigger_beat() {
-                    let is_accent = metronome.current_beat == 1;
-                    let beat_number = metronome.current_beat;
+                if let Some(beat_event) = metronome.should_trigger_beat() {
+                    let is_accent = beat_event.beat_number == 1;
+                    let beat_number = beat_event.beat_number;
 
                     // Visualizer state is now handled by BeatTracker (no manual updates needed)
 
                     drop(metronome);
-                    self.trigger_click(is_accent, beat_number);
+                    self.trigger_click(is_accent, beat_number, beat_event.silenced);
                 }
             }
         }
 
+        // Play melody auditions that were snapped to the beat grid
+        let due_triggers = {
+            let mut metronome = self.app_state.metronome.lock().unwrap();
+            metronome.melody_quantizer.poll(Instant::now())
+        };
+        for trigger in &due_triggers {
+            play_melody_trigger(&self.app_state, trigger);
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("🎸 Guitar Buddy - Practice Companion");
             ui.separator();
@@ -1153,7 +1221,7 @@ impl eframe::App for GuitarBuddy {
 
             // Test controls panel
             let trigger_click_fn = |is_accent: bool, beat_number: u8| {
-                self.trigger_click(is_accent, beat_number);
+                self.trigger_click(is_accent, beat_number, false);
             };
             TestControlsPanel::show(ui, &self.app_state, trigger_click_fn);
 
@@ -5,7 +5,7 @@ use eframe::egui;
 ///
 /// Interactive demonstration of the real-time synthesis engine with live audio output.
 /// Features GUI controls for real-time parameter manipulation and waveform selection.
-use polyphonica::{AdsrEnvelope, RealtimeEngine, Waveform, MAX_VOICES};
+use polyphonica::{AdsrEnvelope, EnvelopeCurve, RealtimeEngine, Waveform, MAX_VOICES};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -95,9 +95,11 @@ impl PolyphonicaDemo {
             master_volume: Arc::new(Mutex::new(0.5)),
             envelope: Arc::new(Mutex::new(AdsrEnvelope {
                 attack_secs: 0.1,
+                hold_secs: 0.0,
                 decay_secs: 0.2,
                 sustain_level: 0.6,
                 release_secs: 0.3,
+                curve: EnvelopeCurve::LINEAR,
             })),
             active_voices: Arc::new(Mutex::new(Vec::new())),
         };
@@ -1,6 +1,7 @@
 use clap::{Parser, Subcommand, ValueEnum};
 use hound::{WavSpec, WavWriter};
 use polyphonica::*;
+use polyphonica::audio::dither::{DitherMode, Ditherer};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
@@ -15,6 +16,9 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Dither applied when writing 16-bit WAV files
+    #[arg(long, value_enum, global = true, default_value = "off")]
+    dither: DitherArg,
 }
 
 #[derive(Subcommand)]
@@ -214,6 +218,23 @@ enum WaveformArg {
     Noise,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum DitherArg {
+    Off,
+    Tpdf,
+    NoiseShaped,
+}
+
+impl From<DitherArg> for DitherMode {
+    fn from(arg: DitherArg) -> Self {
+        match arg {
+            DitherArg::Off => DitherMode::Off,
+            DitherArg::Tpdf => DitherMode::Tpdf,
+            DitherArg::NoiseShaped => DitherMode::NoiseShaped,
+        }
+    }
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 enum CompositionType {
     Chord,
@@ -374,6 +395,7 @@ fn write_wav_file(
     samples: &[f32],
     sample_rate: u32,
     output_path: &PathBuf,
+    dither: DitherMode,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let spec = WavSpec {
         channels: 1,
@@ -383,10 +405,10 @@ fn write_wav_file(
     };
 
     let mut writer = WavWriter::create(output_path, spec)?;
+    let mut ditherer = Ditherer::new(dither);
 
     for &sample in samples {
-        let amplitude = (sample * i16::MAX as f32) as i16;
-        writer.write_sample(amplitude)?;
+        writer.write_sample(ditherer.quantize_i16(sample))?;
     }
 
     writer.finalize()?;
@@ -405,9 +427,11 @@ fn create_polyphonic_composition(
     // Standard envelope for all voices
     let envelope = AdsrEnvelope {
         attack_secs: 0.1,
+        hold_secs: 0.0,
         decay_secs: 0.2,
         sustain_level: 0.6,
         release_secs: 0.4,
+        curve: EnvelopeCurve::LINEAR,
     };
 
     match composition_type {
@@ -511,6 +535,7 @@ fn run_test_suite(
     sample_rate: u32,
     play: bool,
     volume: f32,
+    dither: DitherMode,
 ) -> Result<(), Box<dyn std::error::Error>> {
     fs::create_dir_all(output_dir)?;
 
@@ -530,18 +555,12 @@ fn run_test_suite(
     for waveform in &waveforms {
         let samples = generate_wave(waveform.clone(), 440.0, 1.0, sample_rate);
         let filename = match waveform {
-            Waveform::Sine => "sine_440hz.wav".to_string(),
-            Waveform::Square => "square_440hz.wav".to_string(),
-            Waveform::Sawtooth => "sawtooth_440hz.wav".to_string(),
-            Waveform::Triangle => "triangle_440hz.wav".to_string(),
             Waveform::Pulse { duty_cycle } => {
                 format!("pulse_{:.0}pct_440hz.wav", duty_cycle * 100.0)
             }
-            Waveform::Noise => "noise_440hz.wav".to_string(),
-            Waveform::Sample(_) => "sample_440hz.wav".to_string(),
-            Waveform::DrumSample(_) => "drum_sample_440hz.wav".to_string(),
+            _ => format!("{}_440hz.wav", waveform.kind_name()),
         };
-        write_wav_file(&samples, sample_rate, &output_dir.join(filename))?;
+        write_wav_file(&samples, sample_rate, &output_dir.join(filename), dither)?;
     }
 
     // Test 2: ADSR Envelope variations
@@ -551,27 +570,33 @@ fn run_test_suite(
             "piano",
             AdsrEnvelope {
                 attack_secs: 0.01,
+                hold_secs: 0.0,
                 decay_secs: 0.3,
                 sustain_level: 0.3,
                 release_secs: 1.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         ),
         (
             "organ",
             AdsrEnvelope {
                 attack_secs: 0.1,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 0.8,
                 release_secs: 0.1,
+                curve: EnvelopeCurve::LINEAR,
             },
         ),
         (
             "pluck",
             AdsrEnvelope {
                 attack_secs: 0.01,
+                hold_secs: 0.0,
                 decay_secs: 0.5,
                 sustain_level: 0.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         ),
     ];
@@ -589,6 +614,7 @@ fn run_test_suite(
             &samples,
             sample_rate,
             &output_dir.join(format!("envelope_{}.wav", name)),
+            dither,
         )?;
     }
 
@@ -601,9 +627,11 @@ fn run_test_suite(
         duration_secs: 3.0,
         envelope: AdsrEnvelope {
             attack_secs: 0.0,
+            hold_secs: 0.0,
             decay_secs: 0.0,
             sustain_level: 1.0,
             release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
         },
     };
     let sweep_samples = render_event(&sweep_event, sample_rate);
@@ -611,6 +639,7 @@ fn run_test_suite(
         &sweep_samples,
         sample_rate,
         &output_dir.join("frequency_sweep.wav"),
+        dither,
     )?;
 
     // Test 4: Polyphonic compositions
@@ -628,6 +657,7 @@ fn run_test_suite(
             &timeline,
             sample_rate,
             &output_dir.join(format!("polyphonic_{}.wav", name)),
+            dither,
         )?;
     }
 
@@ -686,6 +716,7 @@ fn report_issue(
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let dither = DitherMode::from(cli.dither);
 
     match cli.command {
         Commands::Generate {
@@ -705,7 +736,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let wave = waveform_from_arg(waveform, duty_cycle);
             let samples = generate_wave(wave, frequency, duration, sample_rate);
 
-            write_wav_file(&samples, sample_rate, &output)?;
+            write_wav_file(&samples, sample_rate, &output, dither)?;
 
             if play {
                 play_audio(&samples, sample_rate, volume)?;
@@ -733,9 +764,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             let envelope = AdsrEnvelope {
                 attack_secs: attack,
+                hold_secs: 0.0,
                 decay_secs: decay,
                 sustain_level: sustain,
                 release_secs: release,
+                curve: EnvelopeCurve::LINEAR,
             };
 
             let event = SoundEvent {
@@ -747,7 +780,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
 
             let samples = render_event(&event, sample_rate);
-            write_wav_file(&samples, sample_rate, &output)?;
+            write_wav_file(&samples, sample_rate, &output, dither)?;
 
             if play {
                 play_audio(&samples, sample_rate, volume)?;
@@ -769,7 +802,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             );
             let events = create_polyphonic_composition(composition, voices, duration);
             let timeline = render_timeline(&events, duration, sample_rate);
-            write_wav_file(&timeline, sample_rate, &output)?;
+            write_wav_file(&timeline, sample_rate, &output, dither)?;
 
             println!("Composition details:");
             for (i, (start_time, event)) in events.iter().enumerate() {
@@ -824,7 +857,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let waveform = Waveform::Sample(sample_data);
             let samples = generate_wave(waveform, target_frequency, duration, sample_rate);
 
-            write_wav_file(&samples, sample_rate, &output)?;
+            write_wav_file(&samples, sample_rate, &output, dither)?;
 
             if play {
                 play_audio(&samples, sample_rate, volume)?;
@@ -871,9 +904,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Create ADSR envelope
             let envelope = AdsrEnvelope {
                 attack_secs: attack,
+                hold_secs: 0.0,
                 decay_secs: decay,
                 sustain_level: sustain,
                 release_secs: release,
+                curve: EnvelopeCurve::LINEAR,
             };
 
             println!(
@@ -891,7 +926,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
 
             let samples = render_event(&event, sample_rate);
-            write_wav_file(&samples, sample_rate, &output)?;
+            write_wav_file(&samples, sample_rate, &output, dither)?;
 
             if play {
                 play_audio(&samples, sample_rate, volume)?;
@@ -904,7 +939,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             play,
             volume,
         } => {
-            run_test_suite(&output_dir, sample_rate, play, volume)?;
+            run_test_suite(&output_dir, sample_rate, play, volume, dither)?;
         }
 
         Commands::ReportIssue {
@@ -3,8 +3,10 @@
 /// This module contains the configuration structures extracted from the
 /// scattered settings throughout guitar_buddy.rs, providing a centralized
 /// and type-safe configuration management system.
+use crate::audio::synthesis::AudioSampleAdapter;
 use crate::timing::{ClickType, TimeSignature};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fs;
@@ -44,6 +46,18 @@ pub struct MetronomeConfig {
     /// Metronome volume (0.0-1.0)
     #[serde(default = "default_volume")]
     pub volume: f32,
+    /// Click decay/release length multiplier (0.1-4.0, 1.0 = built-in)
+    #[serde(default = "default_click_tail")]
+    pub click_tail: f32,
+    /// Per-click-type tail multipliers overriding `click_tail`
+    #[serde(default)]
+    pub click_tail_overrides: HashMap<ClickType, f32>,
+    /// Per-click-type volume gains replacing the measured loudness calibration (0.1-4.0)
+    #[serde(default)]
+    pub click_gain_overrides: HashMap<ClickType, f32>,
+    /// Delay applied to the visual beat flash in milliseconds (-500-500, from calibration)
+    #[serde(default)]
+    pub visual_offset_ms: f32,
 }
 
 /// Audio system configuration
@@ -198,6 +212,42 @@ impl AppConfig {
             )));
         }
 
+        // Validate click tail lengths
+        for tail in std::iter::once(&self.metronome.click_tail)
+            .chain(self.metronome.click_tail_overrides.values())
+        {
+            if !(AudioSampleAdapter::MIN_CLICK_TAIL..=AudioSampleAdapter::MAX_CLICK_TAIL)
+                .contains(tail)
+            {
+                return Err(ConfigError::ValidationError(format!(
+                    "Click tail {} is out of range ({}-{})",
+                    tail,
+                    AudioSampleAdapter::MIN_CLICK_TAIL,
+                    AudioSampleAdapter::MAX_CLICK_TAIL
+                )));
+            }
+        }
+
+        for gain in self.metronome.click_gain_overrides.values() {
+            if !(AudioSampleAdapter::MIN_CLICK_GAIN..=AudioSampleAdapter::MAX_CLICK_GAIN)
+                .contains(gain)
+            {
+                return Err(ConfigError::ValidationError(format!(
+                    "Click gain {} is out of range ({}-{})",
+                    gain,
+                    AudioSampleAdapter::MIN_CLICK_GAIN,
+                    AudioSampleAdapter::MAX_CLICK_GAIN
+                )));
+            }
+        }
+
+        if !(-500.0..=500.0).contains(&self.metronome.visual_offset_ms) {
+            return Err(ConfigError::ValidationError(format!(
+                "Visual offset {} ms is out of range (-500-500)",
+                self.metronome.visual_offset_ms
+            )));
+        }
+
         if self.audio.master_volume < 0.0 || self.audio.master_volume > 1.0 {
             return Err(ConfigError::ValidationError(format!(
                 "Master volume {} is out of range (0.0-1.0)",
@@ -286,6 +336,10 @@ impl Default for MetronomeConfig {
             click_type: default_click_type(),
             accent_first_beat: default_accent_first_beat(),
             volume: default_volume(),
+            click_tail: default_click_tail(),
+            click_tail_overrides: HashMap::new(),
+            click_gain_overrides: HashMap::new(),
+            visual_offset_ms: 0.0,
         }
     }
 }
@@ -343,6 +397,9 @@ fn default_accent_first_beat() -> bool {
 fn default_volume() -> f32 {
     0.7
 }
+fn default_click_tail() -> f32 {
+    1.0
+}
 fn default_master_volume() -> f32 {
     1.0
 }
@@ -471,6 +528,26 @@ mod tests {
         config.metronome.tempo_bpm = 120.0;
         config.metronome.volume = 2.0;
         assert!(config.validate().is_err());
+
+        // Click tails outside 0.1-4.0 are rejected
+        config.metronome.volume = 0.7;
+        config.metronome.click_tail = 5.0;
+        assert!(config.validate().is_err());
+
+        // Calibrated visual offsets are limited to half a second either way
+        config.metronome.click_tail = 1.0;
+        config.metronome.visual_offset_ms = -35.0;
+        assert!(config.validate().is_ok());
+        config.metronome.visual_offset_ms = 900.0;
+        assert!(config.validate().is_err());
+
+        // Click gain overrides share the tail range
+        config.metronome.visual_offset_ms = 0.0;
+        config
+            .metronome
+            .click_gain_overrides
+            .insert(ClickType::WoodBlock, 6.0);
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -481,6 +558,15 @@ mod tests {
         let mut original_config = AppConfig::default();
         original_config.metronome.tempo_bpm = 140.0;
         original_config.metronome.click_type = ClickType::Cowbell;
+        original_config.metronome.click_tail = 1.5;
+        original_config
+            .metronome
+            .click_tail_overrides
+            .insert(ClickType::WoodBlock, 0.5);
+        original_config
+            .metronome
+            .click_gain_overrides
+            .insert(ClickType::Cowbell, 0.7);
 
         // Save config
         assert!(original_config.save_to_file(&config_path).is_ok());
@@ -489,6 +575,15 @@ mod tests {
         let loaded_config = AppConfig::load_from_file(&config_path).unwrap();
         assert_eq!(loaded_config.metronome.tempo_bpm, 140.0);
         assert_eq!(loaded_config.metronome.click_type, ClickType::Cowbell);
+        assert_eq!(loaded_config.metronome.click_tail, 1.5);
+        assert_eq!(
+            loaded_config.metronome.click_tail_overrides[&ClickType::WoodBlock],
+            0.5
+        );
+        assert_eq!(
+            loaded_config.metronome.click_gain_overrides[&ClickType::Cowbell],
+            0.7
+        );
     }
 
     #[test]
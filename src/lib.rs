@@ -20,7 +20,7 @@
 //! ## Quick Start
 //!
 //! ```rust
-//! use polyphonica::{RealtimeEngine, Waveform, AdsrEnvelope};
+//! use polyphonica::{RealtimeEngine, Waveform, AdsrEnvelope, EnvelopeCurve};
 //!
 //! // Create a real-time synthesis engine
 //! let mut engine = RealtimeEngine::new(44100.0);
@@ -28,9 +28,11 @@
 //! // Define an envelope
 //! let envelope = AdsrEnvelope {
 //!     attack_secs: 0.1,
+//!     hold_secs: 0.0,
 //!     decay_secs: 0.1,
 //!     sustain_level: 0.7,
 //!     release_secs: 0.3,
+//!     curve: EnvelopeCurve::LINEAR,
 //! };
 //!
 //! // Trigger a note
@@ -45,7 +47,8 @@
 //!
 //! ### Core Audio Engine (`lib.rs`)
 //! - Real-time polyphonic synthesis engine
-//! - Waveform generation (sine, square, sawtooth, triangle, pulse, noise, samples)
+//! - Waveform generation (sine, square, sawtooth, triangle, pulse, noise, plucked strings,
+//!   samples, custom oscillators)
 //! - ADSR envelope processing
 //! - Audio timeline rendering
 //! - Voice management and allocation
@@ -77,8 +80,8 @@
 //!
 //! ### Visualization (`visualization::`)
 //! - Beat visualization components
-//! - Real-time audio spectrum analysis (planned)
-//! - Waveform display utilities (planned)
+//! - Real-time audio spectrum analysis
+//! - Waveform display utilities
 //!
 //! ### Configuration (`config::`)
 //! - Application settings and preferences
@@ -93,6 +96,11 @@
 //! - Configurable complexity levels and musical style presets
 //! - Beat-synchronized chord changes for practice sessions
 //!
+//! ### MIDI (`midi::`)
+//! - Standard MIDI File export of sound event timelines
+//! - Optional pitch-bend messages for pitch sweeps
+//! - Live note input from MIDI ports with per-key voice tracking
+//!
 //! ## Performance Characteristics
 //!
 //! - **Voice Polyphony**: Up to 32 simultaneous voices
@@ -112,9 +120,8 @@
 //!
 //! - Sample loading limited to WAV format only
 //! - No built-in audio effects or filtering
-//! - Pattern system doesn't support real-time editing during playback
 //! - Visualization module is minimal (mainly beat indicators)
-//! - No MIDI input/output support
+//! - No real-time MIDI output; MIDI input requires the `midi-input` feature
 //! - Configuration system is basic
 //!
 //! ## Examples and Applications
@@ -125,6 +132,9 @@
 //! - Various test applications demonstrating specific features
 //!
 
+use audio::effects::flush_denormal;
+use samples::VelocityCurve;
+use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
@@ -147,11 +157,47 @@ fn validate_inputs(
     Ok(())
 }
 
-fn generate_sample(waveform: &Waveform, phase: f32, time_secs: f32, target_frequency: f32) -> f32 {
+/// PolyBLEP residual for a unit step at phase 0 of a waveform
+///
+/// `phase` is the normalized phase (0.0-1.0) and `phase_increment` the
+/// normalized frequency (cycles per sample). The residual is non-zero only
+/// within one sample either side of the discontinuity; subtracting it from a
+/// falling edge (or adding it to a rising one) rounds off the step so it no
+/// longer aliases.
+fn poly_blep(phase: f32, phase_increment: f32) -> f32 {
+    let dt = phase_increment.clamp(0.0, 0.5);
+    if dt <= 0.0 {
+        0.0
+    } else if phase < dt {
+        let x = phase / dt;
+        x + x - x * x - 1.0
+    } else if phase > 1.0 - dt {
+        let x = (phase - 1.0) / dt;
+        x * x + x + x + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Generate one sample of `waveform`
+///
+/// `phase_increment` is the normalized frequency (`frequency / sample_rate`),
+/// used by the band-limited waveforms to correct their discontinuities; other
+/// waveforms ignore it.
+fn generate_sample(
+    waveform: &Waveform,
+    phase: f32,
+    time_secs: f32,
+    target_frequency: f32,
+    phase_increment: f32,
+    noise: &mut NoiseFilter,
+    sample_rate: f32,
+) -> f32 {
     match waveform {
         Waveform::Sine => phase.sin(),
         Waveform::Square => {
-            if (phase % (2.0 * PI)).sin() >= 0.0 {
+            let normalized_phase = (phase / (2.0 * PI)) % 1.0;
+            if normalized_phase < 0.5 {
                 1.0
             } else {
                 -1.0
@@ -161,6 +207,17 @@ fn generate_sample(waveform: &Waveform, phase: f32, time_secs: f32, target_frequ
             let normalized_phase = (phase / (2.0 * PI)) % 1.0;
             2.0 * normalized_phase - 1.0
         }
+        Waveform::BandLimitedSquare => {
+            let normalized_phase = (phase / (2.0 * PI)) % 1.0;
+            let naive = if normalized_phase < 0.5 { 1.0 } else { -1.0 };
+            // Rising edge at the wrap, falling edge half a cycle later
+            naive + poly_blep(normalized_phase, phase_increment)
+                - poly_blep((normalized_phase + 0.5) % 1.0, phase_increment)
+        }
+        Waveform::BandLimitedSawtooth => {
+            let normalized_phase = (phase / (2.0 * PI)) % 1.0;
+            2.0 * normalized_phase - 1.0 - poly_blep(normalized_phase, phase_increment)
+        }
         Waveform::Triangle => {
             let normalized_phase = (phase / (2.0 * PI)) % 1.0;
             if normalized_phase < 0.5 {
@@ -177,18 +234,69 @@ fn generate_sample(waveform: &Waveform, phase: f32, time_secs: f32, target_frequ
                 -1.0
             }
         }
-        Waveform::Noise => {
-            // Linear congruential generator for deterministic noise
-            let seed = ((time_secs * 1000.0) as u32)
-                .wrapping_mul(1103515245)
-                .wrapping_add(12345);
-            
-            (seed % 32768) as f32 / 16384.0 - 1.0
-        }
+        Waveform::Noise => noise.next_white(),
+        Waveform::PluckedString { damping } => noise.pluck(target_frequency, *damping, sample_rate),
+        Waveform::FilteredNoise {
+            cutoff_hz,
+            resonance,
+        } => noise.process(*cutoff_hz, *resonance, sample_rate),
         Waveform::Sample(sample_data) => {
             sample_data.get_sample_at_time(time_secs, target_frequency)
         }
         Waveform::DrumSample(sample_data) => sample_data.get_natural_sample_at_time(time_secs),
+        Waveform::Custom(source) => source.sample(phase, time_secs, target_frequency),
+    }
+}
+
+/// User-supplied oscillator for [`Waveform::Custom`]
+///
+/// Implementations are called once per sample from the audio thread, so
+/// `sample` should be cheap and must not block or allocate. Custom
+/// waveforms are always treated as pitched.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::{generate_wave, OscillatorSource, Waveform};
+/// use std::sync::Arc;
+///
+/// struct HalfSine;
+///
+/// impl OscillatorSource for HalfSine {
+///     fn sample(&self, phase: f32, _time_secs: f32, _frequency: f32) -> f32 {
+///         phase.sin().max(0.0)
+///     }
+/// }
+///
+/// let samples = generate_wave(Waveform::Custom(Arc::new(HalfSine)), 440.0, 0.01, 44100);
+/// assert!(samples.iter().all(|&s| s >= 0.0));
+/// ```
+pub trait OscillatorSource: Send + Sync {
+    /// Produce one sample, nominally in [-1.0, 1.0]
+    ///
+    /// `phase` is in radians and keeps growing with time (the built-in sine
+    /// is `phase.sin()`), `time_secs` is the time since the note started and
+    /// `frequency` the current target frequency in Hz.
+    fn sample(&self, phase: f32, time_secs: f32, frequency: f32) -> f32;
+
+    /// Name shown in debug output
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+impl std::fmt::Debug for dyn OscillatorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OscillatorSource")
+            .field(&self.name())
+            .finish()
+    }
+}
+
+/// Custom oscillators compare equal only when they are the same instance
+impl PartialEq for dyn OscillatorSource {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self, other)
     }
 }
 
@@ -210,14 +318,24 @@ fn generate_sample(waveform: &Waveform, phase: f32, time_secs: f32, target_frequ
 /// // Pulse wave with 25% duty cycle
 /// let pulse = Waveform::Pulse { duty_cycle: 0.25 };
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Waveform {
     /// Pure sine wave - smooth, fundamental tone
     Sine,
     /// Square wave - rich in odd harmonics, classic electronic sound
+    ///
+    /// High (1.0) for the first half of each cycle and low (-1.0) from the
+    /// half-cycle point on, matching `Pulse { duty_cycle: 0.5 }`.
     Square,
     /// Sawtooth wave - rich in all harmonics, classic synth lead sound
     Sawtooth,
+    /// Square wave with PolyBLEP-corrected edges to suppress aliasing
+    ///
+    /// Sounds like `Square` at low pitches but stays clean for high notes,
+    /// where the naive waveform folds harmonics back below Nyquist.
+    BandLimitedSquare,
+    /// Sawtooth wave with a PolyBLEP-corrected reset to suppress aliasing
+    BandLimitedSawtooth,
     /// Triangle wave - similar to sine but with more harmonics
     Triangle,
     /// Pulse wave with configurable duty cycle
@@ -229,6 +347,26 @@ pub enum Waveform {
     },
     /// White noise - random values for percussion and sound effects
     Noise,
+    /// Low-pass filtered white noise for hi-hats, cymbals, and brushes
+    ///
+    /// Each voice keeps its own filter state. A cutoff at or above Nyquist
+    /// with no resonance passes plain white noise.
+    FilteredNoise {
+        /// Filter cutoff frequency in Hz
+        cutoff_hz: f32,
+        /// Resonance at the cutoff (0.0 = flat, approaching 1.0 = ringing)
+        resonance: f32,
+    },
+    /// Karplus-Strong plucked string for guitar and bass tones
+    ///
+    /// A noise burst circulates through a tuned delay line and low-pass
+    /// filter, so the note decays on its own like a picked string; hold it
+    /// with a full sustain to let it ring. The pitch is fixed when the note
+    /// starts, so glides and pitch bends do not affect it.
+    PluckedString {
+        /// Extra loss per period (0.0 = rings longest, 1.0 = heavily muted)
+        damping: f32,
+    },
     /// Audio sample with pitch shifting capability
     ///
     /// Used for melodic instruments where pitch shifting is desired
@@ -237,6 +375,295 @@ pub enum Waveform {
     ///
     /// Optimized for percussive sounds where natural timbre is important
     DrumSample(SampleData),
+    /// User-supplied oscillator (see [`OscillatorSource`])
+    ///
+    /// Clones share the same source, and two custom waveforms are equal only
+    /// when they share it. Custom waveforms cannot be serialized.
+    #[serde(skip)]
+    Custom(Arc<dyn OscillatorSource>),
+}
+
+impl Waveform {
+    /// Short snake_case name of the waveform variant, suitable for labels and filenames
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Waveform::Sine => "sine",
+            Waveform::Square => "square",
+            Waveform::Sawtooth => "sawtooth",
+            Waveform::BandLimitedSquare => "band_limited_square",
+            Waveform::BandLimitedSawtooth => "band_limited_sawtooth",
+            Waveform::Triangle => "triangle",
+            Waveform::Pulse { .. } => "pulse",
+            Waveform::Noise => "noise",
+            Waveform::FilteredNoise { .. } => "filtered_noise",
+            Waveform::PluckedString { .. } => "plucked_string",
+            Waveform::Sample(_) => "sample",
+            Waveform::DrumSample(_) => "drum_sample",
+            Waveform::Custom(_) => "custom",
+        }
+    }
+
+    /// Whether the waveform follows the voice frequency
+    ///
+    /// Noise waveforms and `DrumSample` ignore the requested frequency, so
+    /// pitch controls have no effect on them.
+    pub fn is_pitched(&self) -> bool {
+        !matches!(
+            self,
+            Waveform::Noise | Waveform::FilteredNoise { .. } | Waveform::DrumSample(_)
+        )
+    }
+}
+
+/// Small, fast, seedable pseudo-random generator for synthesis
+///
+/// A xorshift64* generator: cheap enough for per-sample use in the audio
+/// thread and fully reproducible from its seed. There is no shared global
+/// state; each subsystem that needs randomness (noise voices, melody
+/// generation) owns its own instance.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::SynthRng;
+///
+/// let mut a = SynthRng::new(42);
+/// let mut b = SynthRng::new(42);
+/// assert_eq!(a.next_u32(), b.next_u32());
+///
+/// let value = a.next_f32();
+/// assert!((0.0..1.0).contains(&value));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SynthRng {
+    state: u64,
+}
+
+impl SynthRng {
+    /// Seed used by `default`
+    pub const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Create a generator from a seed (any value, including 0, is valid)
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: 0 };
+        rng.seed(seed);
+        rng
+    }
+
+    /// Restart the sequence from a seed
+    pub fn seed(&mut self, seed: u64) {
+        // SplitMix64 scrambles the seed so similar seeds give unrelated
+        // sequences and the xorshift state is never zero
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        self.state = if z == 0 { Self::DEFAULT_SEED } else { z };
+    }
+
+    /// Next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Next 32 random bits
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Uniformly distributed value in [0.0, 1.0)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniformly distributed value in [-1.0, 1.0)
+    pub fn next_bipolar(&mut self) -> f32 {
+        self.next_f32() * 2.0 - 1.0
+    }
+
+    /// Uniformly distributed index in 0..max (0 when `max` is 0)
+    pub fn next_usize(&mut self, max: usize) -> usize {
+        if max == 0 {
+            0
+        } else {
+            (self.next_u64() % max as u64) as usize
+        }
+    }
+}
+
+impl Default for SynthRng {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+/// Karplus-Strong delay line behind `Waveform::PluckedString`
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct StringState {
+    /// One period of the string, excited with a noise burst (empty = not plucked)
+    delay_line: Vec<f32>,
+    position: usize,
+    /// Previous delay line output, averaged with the current one
+    last_output: f32,
+    /// First-order allpass tuning the fractional part of the period
+    allpass_coefficient: f32,
+    allpass_input: f32,
+    allpass_output: f32,
+}
+
+impl StringState {
+    /// Clear the string, keeping the delay line allocation for the next pluck
+    fn clear(&mut self) {
+        self.delay_line.clear();
+        self.position = 0;
+        self.last_output = 0.0;
+        self.allpass_input = 0.0;
+        self.allpass_output = 0.0;
+    }
+
+    /// Advance one sample, feeding the averaged output back with `feedback` gain
+    fn process(&mut self, feedback: f32) -> f32 {
+        let output = self.delay_line[self.position];
+        let averaged = 0.5 * (output + self.last_output);
+        self.last_output = output;
+
+        let c = self.allpass_coefficient;
+        let tuned = flush_denormal(c * averaged + self.allpass_input - c * self.allpass_output);
+        self.allpass_input = averaged;
+        self.allpass_output = tuned;
+
+        self.delay_line[self.position] = flush_denormal(feedback * tuned);
+        self.position = (self.position + 1) % self.delay_line.len();
+        output
+    }
+}
+
+/// Noise source and filter state for the noise and plucked-string waveforms
+///
+/// White noise from a `SynthRng` is optionally shaped by a two-pole
+/// state-variable low-pass filter, or used to excite a Karplus-Strong string.
+/// The generator starts from a fixed seed, so a retriggered voice produces the
+/// same noise burst every time.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NoiseFilter {
+    rng: SynthRng,
+    /// Seed restored by `reset`
+    seed: u64,
+    /// Integrator states of the state-variable filter
+    ic1eq: f32,
+    ic2eq: f32,
+    /// Delay line for `Waveform::PluckedString`
+    string: StringState,
+}
+
+impl NoiseFilter {
+    /// Seed used by `new` and by offline renders that do not pick one
+    pub const DEFAULT_SEED: u64 = 0x9E37_79B9;
+
+    /// Lowest frequency a plucked string is tuned to in Hz
+    pub const MIN_PLUCK_FREQUENCY: f32 = 20.0;
+
+    /// Create a filter with cleared state
+    pub fn new() -> Self {
+        Self::with_seed(Self::DEFAULT_SEED)
+    }
+
+    /// Create a filter whose noise sequence starts from `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: SynthRng::new(seed),
+            seed,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+            string: StringState::default(),
+        }
+    }
+
+    /// Restore the initial seed and clear the filter and string
+    pub fn reset(&mut self) {
+        let mut string = std::mem::take(&mut self.string);
+        string.clear();
+        *self = Self::with_seed(self.seed);
+        self.string = string;
+    }
+
+    /// Generate the next filtered noise sample
+    pub fn process(&mut self, cutoff_hz: f32, resonance: f32, sample_rate: f32) -> f32 {
+        let input = self.next_white();
+        let nyquist = sample_rate * 0.5;
+        let resonance = resonance.clamp(0.0, 0.95);
+        if cutoff_hz >= nyquist && resonance == 0.0 {
+            return input;
+        }
+
+        // Trapezoidal state-variable filter (stable up to Nyquist)
+        let g = (PI * cutoff_hz.clamp(10.0, nyquist * 0.99) / sample_rate).tan();
+        let k = 2.0 - 2.0 * resonance;
+        let a1 = 1.0 / (1.0 + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+
+        let v3 = input - self.ic2eq;
+        let v1 = a1 * self.ic1eq + a2 * v3;
+        let v2 = self.ic2eq + a2 * self.ic1eq + a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+        v2
+    }
+
+    /// Next uniformly distributed white noise sample in [-1.0, 1.0)
+    pub fn next_white(&mut self) -> f32 {
+        self.rng.next_bipolar()
+    }
+
+    /// Reserve a delay line long enough for any pluck at `sample_rate`
+    ///
+    /// Plucking then fills the line without allocating, which keeps the first
+    /// sample of a note safe to generate inside an audio callback.
+    pub fn reserve_pluck(&mut self, sample_rate: f32) {
+        let longest = (sample_rate / Self::MIN_PLUCK_FREQUENCY).ceil() as usize;
+        let delay_line = &mut self.string.delay_line;
+        delay_line.reserve(longest.saturating_sub(delay_line.len()));
+    }
+
+    /// Generate the next sample of a Karplus-Strong plucked string
+    ///
+    /// The first call after `new` or `reset` plucks the string: a delay line
+    /// one period long is filled with a noise burst, which then circulates
+    /// through an averaging low-pass filter. The pitch is fixed at the pluck,
+    /// no lower than `MIN_PLUCK_FREQUENCY`; `damping` (0.0-1.0) removes up to
+    /// 5% of the level on every period.
+    pub fn pluck(&mut self, frequency: f32, damping: f32, sample_rate: f32) -> f32 {
+        if self.string.delay_line.is_empty() {
+            // The averaging filter delays by half a sample and the allpass by
+            // the remaining fraction (kept at 0.1 or more for stability)
+            let period = (sample_rate / frequency.max(Self::MIN_PLUCK_FREQUENCY)).max(2.6) - 0.5;
+            let length = ((period - 0.1).floor() as usize).max(2);
+            let fraction = period - length as f32;
+            self.string.allpass_coefficient = (1.0 - fraction) / (1.0 + fraction);
+
+            let rng = &mut self.rng;
+            self.string
+                .delay_line
+                .extend((0..length).map(|_| rng.next_bipolar()));
+            let mean = self.string.delay_line.iter().sum::<f32>() / length as f32;
+            for sample in &mut self.string.delay_line {
+                *sample -= mean;
+            }
+        }
+        self.string.process(1.0 - 0.05 * damping.clamp(0.0, 1.0))
+    }
+}
+
+impl Default for NoiseFilter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Audio sample data container
@@ -259,7 +686,7 @@ pub enum Waveform {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SampleData {
     /// Raw audio samples as f32 values in [-1.0, 1.0] range
     pub samples: Vec<f32>,
@@ -271,16 +698,74 @@ pub struct SampleData {
     pub loop_start: Option<usize>,
     /// Loop end point in samples (None for one-shot playback)
     pub loop_end: Option<usize>,
+    /// Interpolation used when reading between stored sample frames
+    pub interpolation: Interpolation,
+    /// Left and right channels of a stereo sample (None for mono); `samples` holds their mix
+    pub stereo_channels: Option<(Vec<f32>, Vec<f32>)>,
     /// Sample metadata and file information
     pub metadata: SampleMetadata,
 }
 
+/// Interpolation algorithm used when reading sample data at fractional positions
+///
+/// Pitch shifting reads the stored samples at non-integer positions, so the
+/// choice of interpolator trades CPU cost against playback fidelity.
+///
+/// # Real-time Safety
+///
+/// `Nearest`, `Linear`, and `Hermite` are cheap and suitable for the audio
+/// callback; the real-time engine can select among them per voice or for all
+/// new notes (see `RealtimeEngine::set_sample_interpolation`).
+/// `Sinc` evaluates a windowed-sinc kernel of `taps` points for every output
+/// sample. It never allocates, but with large tap counts (e.g. 32+) per voice it
+/// is intended for offline or high-quality rendering rather than real-time use.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::{Interpolation, SampleData};
+///
+/// # fn example() -> Result<(), polyphonica::SampleError> {
+/// // Render a sample two octaves down with a 32-tap windowed-sinc kernel
+/// let sample_data = SampleData::from_file("piano_c4.wav", 261.63)?
+///     .with_interpolation(Interpolation::Sinc { taps: 32 });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub enum Interpolation {
+    /// Nearest stored frame, no interpolation (cheapest, most aliasing)
+    Nearest,
+    /// Two-point linear interpolation (default)
+    #[default]
+    Linear,
+    /// Four-point cubic Hermite (Catmull-Rom) interpolation
+    Hermite,
+    /// Blackman-windowed sinc interpolation
+    ///
+    /// Not real-time-safe for large tap counts; see the type-level docs.
+    Sinc {
+        /// Kernel width in source samples (clamped to at least 2)
+        taps: usize,
+    },
+}
+
+/// How [`SampleData::fit_to_bars`] matches a sample to the bar length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    /// Resample the audio to the bar length; pitch follows the stretch
+    #[default]
+    Stretch,
+    /// Keep the audio untouched: repeat it (honouring loop points) and trim
+    Loop,
+}
+
 /// Metadata associated with audio samples
 ///
 /// Contains file information and audio characteristics for loaded samples.
 /// This metadata is preserved when samples are loaded and can be used for
 /// display purposes or audio processing decisions.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SampleMetadata {
     /// Original filename (without path)
     pub filename: String,
@@ -292,6 +777,441 @@ pub struct SampleMetadata {
     pub bits_per_sample: u16,
 }
 
+/// Optional post-load sanitation for WAV samples
+///
+/// All checks are off by default, so [`SampleData::from_file`] returns the
+/// samples exactly as decoded. Use [`SampleData::from_file_with_options`] to
+/// measure levels and correct problem files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadOptions {
+    /// Subtract the mean value so the waveform is centred on zero
+    pub remove_dc_offset: bool,
+    /// Clamp values outside -1.0..=1.0 and replace NaN/infinite values with silence
+    pub clamp_out_of_range: bool,
+    /// Estimate peaks between samples and report them when above full scale
+    pub detect_inter_sample_peaks: bool,
+    /// Absolute mean value above which a DC offset is reported
+    pub dc_offset_threshold: f32,
+    /// Fail on any header problem instead of retrying with the lenient parser
+    pub strict_header: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            remove_dc_offset: false,
+            clamp_out_of_range: false,
+            detect_inter_sample_peaks: false,
+            dc_offset_threshold: 0.001, // ~-60 dBFS
+            strict_header: false,
+        }
+    }
+}
+
+/// Level analysis produced when loading a sample with [`LoadOptions`]
+///
+/// Measurements describe the file as decoded, before any correction.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LoadReport {
+    /// Largest absolute sample value
+    pub peak_level: f32,
+    /// Mean sample value
+    pub dc_offset: f32,
+    /// Whether the DC offset exceeded the configured threshold
+    pub dc_offset_detected: bool,
+    /// Finite samples outside -1.0..=1.0
+    pub out_of_range_samples: usize,
+    /// NaN or infinite samples
+    pub non_finite_samples: usize,
+    /// Estimated inter-sample peak, when detection was requested
+    pub inter_sample_peak: Option<f32>,
+    /// Whether the DC offset was removed
+    pub dc_removed: bool,
+    /// Whether out-of-range or non-finite samples were corrected
+    pub clamped: bool,
+    /// Header problems worked around by the lenient parser (empty for clean files)
+    pub header_repairs: Vec<String>,
+}
+
+impl LoadReport {
+    /// Measure a decoded buffer
+    fn analyze(samples: &[f32], options: &LoadOptions) -> Self {
+        let mut report = LoadReport::default();
+        let mut sum = 0.0_f64;
+        let mut finite_count = 0usize;
+
+        for &sample in samples {
+            if !sample.is_finite() {
+                report.non_finite_samples += 1;
+                continue;
+            }
+            report.peak_level = report.peak_level.max(sample.abs());
+            if sample.abs() > 1.0 {
+                report.out_of_range_samples += 1;
+            }
+            sum += sample as f64;
+            finite_count += 1;
+        }
+
+        if finite_count > 0 {
+            report.dc_offset = (sum / finite_count as f64) as f32;
+        }
+        report.dc_offset_detected = report.dc_offset.abs() > options.dc_offset_threshold;
+
+        if options.detect_inter_sample_peaks {
+            report.inter_sample_peak = Some(estimate_inter_sample_peak(samples));
+        }
+
+        report
+    }
+
+    /// Whether any clipping, invalid data, or inter-sample overs were found
+    pub fn has_clipping(&self) -> bool {
+        self.out_of_range_samples > 0
+            || self.non_finite_samples > 0
+            || self.inter_sample_peak.is_some_and(|peak| peak > 1.0)
+    }
+
+    /// Human-readable warnings for any problems detected
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.non_finite_samples > 0 {
+            warnings.push(format!("{} NaN/infinite samples", self.non_finite_samples));
+        }
+        if self.out_of_range_samples > 0 {
+            warnings.push(format!(
+                "{} samples outside full scale (peak {:.3})",
+                self.out_of_range_samples, self.peak_level
+            ));
+        }
+        if self.dc_offset_detected {
+            warnings.push(format!("DC offset of {:.4}", self.dc_offset));
+        }
+        if let Some(peak) = self.inter_sample_peak.filter(|&peak| peak > 1.0) {
+            warnings.push(format!(
+                "inter-sample peak of {:.3} exceeds full scale",
+                peak
+            ));
+        }
+        warnings.extend(
+            self.header_repairs
+                .iter()
+                .map(|repair| format!("recovered from header issue: {}", repair)),
+        );
+        warnings
+    }
+}
+
+/// Estimate the true peak by evaluating a Catmull-Rom curve between samples
+fn estimate_inter_sample_peak(samples: &[f32]) -> f32 {
+    (0..samples.len())
+        .map(|index| inter_sample_peak_at(samples, index))
+        .fold(0.0, f32::max)
+}
+
+/// Largest absolute value of a sample and the Catmull-Rom curve toward the next one
+///
+/// Non-finite samples are treated as silence.
+pub(crate) fn inter_sample_peak_at(samples: &[f32], index: usize) -> f32 {
+    const OVERSAMPLING: usize = 4;
+
+    let at = |index: isize| -> f32 {
+        let clamped = index.clamp(0, samples.len() as isize - 1) as usize;
+        let value = samples[clamped];
+        if value.is_finite() {
+            value
+        } else {
+            0.0
+        }
+    };
+
+    let index = index as isize;
+    let (y0, y1, y2, y3) = (at(index - 1), at(index), at(index + 1), at(index + 2));
+    let mut peak = y1.abs();
+    for step in 1..OVERSAMPLING {
+        let t = step as f32 / OVERSAMPLING as f32;
+        let value = y1
+            + 0.5
+                * t
+                * (y2 - y0
+                    + t * (2.0 * y0 - 5.0 * y1 + 4.0 * y2 - y3 + t * (3.0 * (y1 - y2) + y3 - y0)));
+        peak = peak.max(value.abs());
+    }
+    peak
+}
+
+/// Convert an integer PCM sample as decoded by `hound` to f32
+fn int_sample_to_f32(sample: i32, bits_per_sample: u16) -> f32 {
+    match bits_per_sample {
+        16 => sample as f32 / i16::MAX as f32,
+        24 => (sample >> 8) as f32 / i32::MAX as f32,
+        _ => sample as f32 / i32::MAX as f32,
+    }
+}
+
+/// Decode a WAV file with `hound`, rejecting any header inconsistency
+pub(crate) fn read_wav_strict(path: &Path) -> Result<(hound::WavSpec, Vec<f32>), SampleError> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+
+    // Read samples and convert to f32
+    let samples: Result<Vec<f32>, _> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect(),
+        hound::SampleFormat::Int => match spec.bits_per_sample {
+            16 | 24 | 32 => reader
+                .samples::<i32>()
+                .map(|s| s.map(|sample| int_sample_to_f32(sample, spec.bits_per_sample)))
+                .collect(),
+            _ => {
+                return Err(SampleError::UnsupportedFormat(format!(
+                    "Unsupported bit depth: {}",
+                    spec.bits_per_sample
+                )))
+            }
+        },
+    };
+
+    let samples = samples.map_err(|e| SampleError::FormatError(e.to_string()))?;
+    Ok((spec, samples))
+}
+
+/// Decode a FLAC file with `claxon`
+fn read_flac(path: &Path) -> Result<(hound::WavSpec, Vec<f32>), SampleError> {
+    let mut reader = claxon::FlacReader::open(path)
+        .map_err(|e| SampleError::FormatError(format!("FLAC error: {}", e)))?;
+    let info = reader.streaminfo();
+    let scale = (1i64 << (info.bits_per_sample - 1)) as f32;
+    let samples = reader
+        .samples()
+        .map(|sample| sample.map(|sample| sample as f32 / scale))
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|e| SampleError::FormatError(format!("FLAC error: {}", e)))?;
+
+    let spec = hound::WavSpec {
+        channels: info.channels as u16,
+        sample_rate: info.sample_rate,
+        bits_per_sample: info.bits_per_sample as u16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    Ok((spec, samples))
+}
+
+/// Decode an Ogg Vorbis file with `lewton`
+fn read_ogg_vorbis(path: &Path) -> Result<(hound::WavSpec, Vec<f32>), SampleError> {
+    let vorbis_error =
+        |e: lewton::VorbisError| SampleError::FormatError(format!("Vorbis error: {}", e));
+    let file = std::fs::File::open(path)?;
+    let mut reader = lewton::inside_ogg::OggStreamReader::new(std::io::BufReader::new(file))
+        .map_err(vorbis_error)?;
+
+    let mut samples = Vec::new();
+    while let Some(packet) = reader
+        .read_dec_packet_generic::<lewton::samples::InterleavedSamples<f32>>()
+        .map_err(vorbis_error)?
+    {
+        samples.extend(packet.samples);
+    }
+
+    // Vorbis decodes to floats; there is no source bit depth
+    let spec = hound::WavSpec {
+        channels: reader.ident_hdr.audio_channels as u16,
+        sample_rate: reader.ident_hdr.audio_sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    Ok((spec, samples))
+}
+
+/// Audio file formats `SampleData::from_file` can decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AudioFileFormat {
+    Wav,
+    Flac,
+    OggVorbis,
+}
+
+impl AudioFileFormat {
+    /// Identify a file by its magic bytes, falling back to its extension
+    ///
+    /// The extension fallback lets damaged WAV files reach the lenient parser.
+    fn detect(path: &Path) -> Result<Self, SampleError> {
+        let mut magic = [0u8; 4];
+        let read = std::io::Read::read(&mut std::fs::File::open(path)?, &mut magic)?;
+        match &magic[..read] {
+            b"RIFF" => return Ok(Self::Wav),
+            b"fLaC" => return Ok(Self::Flac),
+            b"OggS" => return Ok(Self::OggVorbis),
+            _ => {}
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("wav" | "wave") => Ok(Self::Wav),
+            Some("flac") => Ok(Self::Flac),
+            Some("ogg" | "oga") => Ok(Self::OggVorbis),
+            _ => Err(SampleError::UnsupportedFormat(format!(
+                "Unrecognized audio file: {}",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Read a little-endian u16 at `offset`
+fn le_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+/// Read a little-endian u32 at `offset`
+fn le_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// Whether four bytes look like a RIFF chunk ID (printable ASCII)
+fn is_chunk_id(bytes: &[u8]) -> bool {
+    bytes.len() >= 4 && bytes[..4].iter().all(|b| (0x20..0x7f).contains(b))
+}
+
+/// Decode a WAV file by walking its chunks directly, tolerating header quirks
+///
+/// Derived fields (byte rate, block align, RIFF length) are recomputed, a data
+/// chunk that overruns the file or ends mid-frame is cut to whole frames, and
+/// chunks may appear in any order. Every fix is described in the returned
+/// repair list. Files without RIFF/WAVE tags, a usable fmt chunk, or a data
+/// chunk are still rejected.
+fn read_wav_lenient(path: &Path) -> Result<(hound::WavSpec, Vec<f32>, Vec<String>), SampleError> {
+    let bytes = std::fs::read(path)?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(SampleError::FormatError(
+            "missing RIFF/WAVE tags".to_string(),
+        ));
+    }
+
+    let mut repairs = Vec::new();
+    let riff_len = le_u32(&bytes, 4) as usize;
+    if riff_len != bytes.len() - 8 {
+        repairs.push(format!(
+            "RIFF length {} does not match file length {}",
+            riff_len,
+            bytes.len() - 8
+        ));
+    }
+
+    let mut fmt = None;
+    let mut data = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() && is_chunk_id(&bytes[offset..]) {
+        let declared = le_u32(&bytes, offset + 4) as usize;
+        let body_start = offset + 8;
+        let len = declared.min(bytes.len() - body_start);
+        let body = &bytes[body_start..body_start + len];
+
+        match &bytes[offset..offset + 4] {
+            b"fmt " if fmt.is_none() => fmt = Some(body),
+            b"data" if data.is_none() => {
+                if len < declared {
+                    repairs.push(format!(
+                        "data chunk declares {} bytes but only {} are present",
+                        declared, len
+                    ));
+                }
+                data = Some(body);
+            }
+            _ => {}
+        }
+
+        // Odd chunks are padded to an even length, but some writers skip the pad
+        let next = body_start + len;
+        offset = if len % 2 == 1 && !is_chunk_id(bytes.get(next..).unwrap_or(&[])) {
+            next + 1
+        } else {
+            next
+        };
+    }
+
+    let fmt = fmt.ok_or_else(|| SampleError::FormatError("missing fmt chunk".to_string()))?;
+    let data = data.ok_or_else(|| SampleError::FormatError("missing data chunk".to_string()))?;
+    if fmt.len() < 16 {
+        return Err(SampleError::FormatError("fmt chunk too short".to_string()));
+    }
+
+    let mut format_tag = le_u16(fmt, 0);
+    if format_tag == 0xFFFE && fmt.len() >= 26 {
+        // WAVE_FORMAT_EXTENSIBLE: the sub-format GUID starts with the real tag
+        format_tag = le_u16(fmt, 24);
+    }
+    let channels = le_u16(fmt, 2);
+    let sample_rate = le_u32(fmt, 4);
+    let bits_per_sample = le_u16(fmt, 14);
+    if channels == 0 || sample_rate == 0 {
+        return Err(SampleError::FormatError(
+            "fmt chunk has zero channels or sample rate".to_string(),
+        ));
+    }
+
+    let sample_format = match (format_tag, bits_per_sample) {
+        (1, 16 | 24 | 32) => hound::SampleFormat::Int,
+        (3, 32) => hound::SampleFormat::Float,
+        _ => {
+            return Err(SampleError::UnsupportedFormat(format!(
+                "format tag {} with {} bits per sample",
+                format_tag, bits_per_sample
+            )))
+        }
+    };
+
+    let bytes_per_sample = bits_per_sample as usize / 8;
+    let block_align = channels as usize * bytes_per_sample;
+    if le_u16(fmt, 12) as usize != block_align {
+        repairs.push(format!(
+            "block align {} corrected to {}",
+            le_u16(fmt, 12),
+            block_align
+        ));
+    }
+    if le_u32(fmt, 8) as usize != block_align * sample_rate as usize {
+        repairs.push(format!(
+            "byte rate {} corrected to {}",
+            le_u32(fmt, 8),
+            block_align * sample_rate as usize
+        ));
+    }
+
+    let frames = data.len() / block_align;
+    if frames * block_align != data.len() {
+        repairs.push(format!(
+            "dropped {} trailing bytes of a partial frame",
+            data.len() - frames * block_align
+        ));
+    }
+
+    let samples = data[..frames * block_align]
+        .chunks_exact(bytes_per_sample)
+        .map(|raw| match (sample_format, bits_per_sample) {
+            (hound::SampleFormat::Float, _) => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]),
+            (_, 16) => int_sample_to_f32(i16::from_le_bytes([raw[0], raw[1]]) as i32, 16),
+            (_, 24) => int_sample_to_f32(i32::from_le_bytes([0, raw[0], raw[1], raw[2]]) >> 8, 24),
+            _ => int_sample_to_f32(i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]), 32),
+        })
+        .collect();
+
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample,
+        sample_format,
+    };
+    Ok((spec, samples, repairs))
+}
+
 /// Errors that can occur during sample loading and processing
 ///
 /// This enum covers all error conditions that may arise when working with
@@ -330,55 +1250,100 @@ impl From<hound::Error> for SampleError {
     }
 }
 
-impl SampleData {
-    /// Load a WAV file as sample data
-    pub fn from_file<P: AsRef<Path>>(path: P, base_frequency: f32) -> Result<Self, SampleError> {
-        let path = path.as_ref();
-        let mut reader = hound::WavReader::open(path)?;
+/// Lowest fundamental `SampleData::detect_base_frequency` searches for, in Hz
+pub const MIN_DETECTED_FREQUENCY: f32 = 30.0;
 
-        let spec = reader.spec();
+/// Highest fundamental `SampleData::detect_base_frequency` searches for, in Hz
+pub const MAX_DETECTED_FREQUENCY: f32 = 4000.0;
 
-        // Validate format
-        if base_frequency <= 0.0 || base_frequency > 20000.0 {
-            return Err(SampleError::FormatError(
-                "Base frequency must be between 0 and 20000 Hz".to_string(),
-            ));
-        }
+/// Confidence below which a detected pitch should not be used as a base frequency
+pub const MIN_PITCH_CONFIDENCE: f32 = 0.8;
 
-        // Read samples and convert to f32
-        let samples: Result<Vec<f32>, _> = match spec.sample_format {
-            hound::SampleFormat::Float => reader.samples::<f32>().collect(),
-            hound::SampleFormat::Int => match spec.bits_per_sample {
-                16 => reader
-                    .samples::<i16>()
-                    .map(|s| s.map(|sample| sample as f32 / i16::MAX as f32))
-                    .collect(),
-                24 => reader
-                    .samples::<i32>()
-                    .map(|s| s.map(|sample| (sample >> 8) as f32 / i32::MAX as f32))
-                    .collect(),
-                32 => reader
-                    .samples::<i32>()
-                    .map(|s| s.map(|sample| sample as f32 / i32::MAX as f32))
-                    .collect(),
-                _ => {
-                    return Err(SampleError::UnsupportedFormat(format!(
-                        "Unsupported bit depth: {}",
-                        spec.bits_per_sample
-                    )))
-                }
+/// Frames compared at each lag when detecting pitch
+const PITCH_WINDOW_FRAMES: usize = 4096;
+
+/// Normalised difference below which the first dip is taken as the period
+const PITCH_DIP_THRESHOLD: f32 = 0.15;
+
+/// Fundamental frequency estimated from a sample's audio
+///
+/// `confidence` runs from 0.0 for unpitched material such as noise or
+/// cymbals to 1.0 for a perfectly periodic tone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchEstimate {
+    /// Detected fundamental in Hz
+    pub frequency_hz: f32,
+    /// How periodic the audio is at the detected frequency (0.0-1.0)
+    pub confidence: f32,
+}
+
+impl PitchEstimate {
+    /// Whether the estimate is reliable enough to tune the sample by
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= MIN_PITCH_CONFIDENCE
+    }
+}
+
+impl SampleData {
+    /// Load a WAV, FLAC, or Ogg Vorbis file as sample data
+    pub fn from_file<P: AsRef<Path>>(path: P, base_frequency: f32) -> Result<Self, SampleError> {
+        Self::from_file_with_options(path, base_frequency, &LoadOptions::default())
+            .map(|(sample, _report)| sample)
+    }
+
+    /// Load an audio file, analysing and optionally correcting its levels
+    ///
+    /// Returns the sample together with a [`LoadReport`] describing the peak
+    /// level, DC offset, and any out-of-range values found in the file.
+    ///
+    /// The format is detected from the file's magic bytes (or its extension):
+    /// WAV is read with `hound`, FLAC with `claxon`, and Ogg Vorbis with
+    /// `lewton`. Other files return [`SampleError::UnsupportedFormat`].
+    ///
+    /// WAV files that `hound` rejects for header inconsistencies (a wrong byte
+    /// rate, a data chunk that overruns the file or ends mid-frame) are
+    /// retried with a lenient parser unless `options.strict_header` is set;
+    /// the fixes applied are listed in `LoadReport::header_repairs`. If the
+    /// lenient parser cannot recover the audio either, the original error is
+    /// returned.
+    pub fn from_file_with_options<P: AsRef<Path>>(
+        path: P,
+        base_frequency: f32,
+        options: &LoadOptions,
+    ) -> Result<(Self, LoadReport), SampleError> {
+        let path = path.as_ref();
+        let (spec, mut samples, header_repairs) = match AudioFileFormat::detect(path)? {
+            AudioFileFormat::Flac => {
+                read_flac(path).map(|(spec, samples)| (spec, samples, Vec::new()))?
+            }
+            AudioFileFormat::OggVorbis => {
+                read_ogg_vorbis(path).map(|(spec, samples)| (spec, samples, Vec::new()))?
+            }
+            AudioFileFormat::Wav => match read_wav_strict(path) {
+                Ok((spec, samples)) => (spec, samples, Vec::new()),
+                Err(error) if options.strict_header => return Err(error),
+                Err(error) => read_wav_lenient(path).map_err(|_| error)?,
             },
         };
 
-        let mut samples = samples.map_err(|e| SampleError::FormatError(e.to_string()))?;
+        // Validate format
+        if base_frequency <= 0.0 || base_frequency > 20000.0 {
+            return Err(SampleError::FormatError(
+                "Base frequency must be between 0 and 20000 Hz".to_string(),
+            ));
+        }
 
-        // Convert stereo to mono by averaging channels
+        // Keep both channels of stereo files and their mono mix for pitched playback
+        let mut stereo_channels = None;
         if spec.channels == 2 {
-            let mono_samples: Vec<f32> = samples
-                .chunks_exact(2)
-                .map(|stereo| (stereo[0] + stereo[1]) / 2.0)
+            let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+            let right: Vec<f32> = samples.iter().skip(1).step_by(2).copied().collect();
+            samples = left
+                .iter()
+                .zip(&right)
+                .map(|(left, right)| (left + right) / 2.0)
                 .collect();
-            samples = mono_samples;
+            stereo_channels = Some((left, right));
         } else if spec.channels > 2 {
             return Err(SampleError::UnsupportedFormat(format!(
                 "Unsupported channel count: {}",
@@ -386,6 +1351,35 @@ impl SampleData {
             )));
         }
 
+        let mut report = LoadReport::analyze(&samples, options);
+        report.header_repairs = header_repairs;
+
+        // Repairs measured on the mix apply to the stereo channels as well
+        let channels = stereo_channels
+            .iter_mut()
+            .flat_map(|(left, right)| [left, right]);
+        for buffer in std::iter::once(&mut samples).chain(channels) {
+            if options.remove_dc_offset && report.dc_offset_detected {
+                let offset = report.dc_offset;
+                for sample in buffer.iter_mut().filter(|s| s.is_finite()) {
+                    *sample -= offset;
+                }
+                report.dc_removed = true;
+            }
+
+            if options.clamp_out_of_range {
+                for sample in buffer.iter_mut() {
+                    if !sample.is_finite() {
+                        *sample = 0.0;
+                        report.clamped = true;
+                    } else if sample.abs() > 1.0 {
+                        *sample = sample.clamp(-1.0, 1.0);
+                        report.clamped = true;
+                    }
+                }
+            }
+        }
+
         let duration_secs = samples.len() as f32 / spec.sample_rate as f32;
 
         let metadata = SampleMetadata {
@@ -399,14 +1393,18 @@ impl SampleData {
             bits_per_sample: spec.bits_per_sample,
         };
 
-        Ok(SampleData {
+        let sample = SampleData {
             samples,
             sample_rate: spec.sample_rate,
             base_frequency,
             loop_start: None,
             loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels,
             metadata,
-        })
+        };
+
+        Ok((sample, report))
     }
 
     /// Add loop points to the sample for sustained playback
@@ -422,6 +1420,176 @@ impl SampleData {
         Ok(self)
     }
 
+    /// Select the interpolation algorithm used for fractional sample reads
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Fit the sample to a whole number of bars at the given tempo
+    ///
+    /// Returns a new one-shot sample lasting exactly `bars` bars of
+    /// `beats_per_bar` beats, so a loop used as a pattern element neither runs
+    /// over nor falls short of the bar line. With [`FitMode::Stretch`] the
+    /// base frequency is adjusted to match the transposed audio.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use polyphonica::{FitMode, SampleData};
+    ///
+    /// # fn example() -> Result<(), polyphonica::SampleError> {
+    /// let groove = SampleData::from_file("groove.wav", 440.0)?;
+    ///
+    /// // Two bars of 4/4 at 100 BPM, 4.8 seconds
+    /// let fitted = groove.fit_to_bars(2, 4, 100.0, FitMode::Stretch)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fit_to_bars(
+        &self,
+        bars: u32,
+        beats_per_bar: u8,
+        tempo_bpm: f32,
+        mode: FitMode,
+    ) -> Result<Self, SampleError> {
+        if self.samples.is_empty() {
+            return Err(SampleError::FormatError(
+                "Cannot fit an empty sample".to_string(),
+            ));
+        }
+        if bars == 0 || beats_per_bar == 0 || !(tempo_bpm > 0.0 && tempo_bpm.is_finite()) {
+            return Err(SampleError::FormatError(
+                "Bar count, beats per bar and tempo must be positive".to_string(),
+            ));
+        }
+
+        let duration_secs = bars as f32 * beats_per_bar as f32 * 60.0 / tempo_bpm;
+        let target_len = (duration_secs * self.sample_rate as f32).round() as usize;
+        let source_len = self.samples.len();
+
+        let ratio = source_len as f32 / target_len.max(1) as f32;
+        let loop_region = self.loop_region(true);
+        let fit = |channel: &[f32]| -> Vec<f32> {
+            (0..target_len)
+                .map(|i| match mode {
+                    FitMode::Stretch => {
+                        self.interpolate_channel(channel, i as f32 * ratio, ratio, None)
+                    }
+                    FitMode::Loop => self
+                        .frame_index(i as isize, loop_region)
+                        .map_or(0.0, |index| channel[index]),
+                })
+                .collect()
+        };
+        let base_frequency = match mode {
+            FitMode::Stretch => self.base_frequency * ratio,
+            FitMode::Loop => self.base_frequency,
+        };
+
+        Ok(SampleData {
+            samples: fit(&self.samples),
+            sample_rate: self.sample_rate,
+            base_frequency,
+            loop_start: None,
+            loop_end: None,
+            interpolation: self.interpolation,
+            stereo_channels: self
+                .stereo_channels()
+                .map(|(left, right)| (fit(left), fit(right))),
+            metadata: SampleMetadata {
+                duration_secs: target_len as f32 / self.sample_rate as f32,
+                ..self.metadata.clone()
+            },
+        })
+    }
+
+    /// Estimate the sample's fundamental frequency for use as its base frequency
+    ///
+    /// Uses the YIN method: a window from the middle of the sample, past the
+    /// attack, is compared with delayed copies of itself, and the shortest
+    /// delay at which it nearly repeats is taken as the period, refined
+    /// between frames by parabolic interpolation. Fundamentals between
+    /// `MIN_DETECTED_FREQUENCY` and `MAX_DETECTED_FREQUENCY` are found.
+    ///
+    /// Short samples are searched down to the lowest fundamental that repeats
+    /// twice within them. Returns `None` for silent samples and samples too
+    /// short to hold two periods of `MAX_DETECTED_FREQUENCY`. Check
+    /// [`PitchEstimate::is_confident`] before retuning, as unpitched material
+    /// still yields a (meaningless) frequency.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use polyphonica::SampleData;
+    ///
+    /// # fn example() -> Result<(), polyphonica::SampleError> {
+    /// let mut piano = SampleData::from_file("piano_c4.wav", 440.0)?;
+    /// if let Some(pitch) = piano.detect_base_frequency().filter(|p| p.is_confident()) {
+    ///     piano.base_frequency = pitch.frequency_hz;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn detect_base_frequency(&self) -> Option<PitchEstimate> {
+        let sample_rate = self.sample_rate as f32;
+        let min_lag = ((sample_rate / MAX_DETECTED_FREQUENCY) as usize).max(2);
+        let max_lag =
+            ((sample_rate / MIN_DETECTED_FREQUENCY).ceil() as usize).min(self.samples.len() / 2);
+        if max_lag <= min_lag + 1 {
+            return None;
+        }
+
+        let window = PITCH_WINDOW_FRAMES.min(self.samples.len() - max_lag);
+        let start = (self.samples.len() - window - max_lag) / 2;
+        let frames = &self.samples[start..start + window + max_lag];
+        if frames.iter().all(|sample| sample.abs() < 1e-6) {
+            return None;
+        }
+
+        // Cumulative mean normalised difference: near zero where the audio repeats
+        let mut difference = vec![1.0f32; max_lag + 1];
+        let mut running_sum = 0.0f64;
+        for (lag, normalised) in difference.iter_mut().enumerate().skip(1) {
+            let sum: f64 = (0..window)
+                .map(|i| {
+                    let delta = (frames[i] - frames[i + lag]) as f64;
+                    delta * delta
+                })
+                .sum();
+            running_sum += sum;
+            if running_sum > 0.0 {
+                *normalised = (sum * lag as f64 / running_sum) as f32;
+            }
+        }
+
+        // Take the first dip under the threshold, followed down to its minimum,
+        // so multiples of the period are not mistaken for it
+        let lag = match (min_lag..=max_lag).find(|&lag| difference[lag] < PITCH_DIP_THRESHOLD) {
+            Some(mut lag) => {
+                while lag < max_lag && difference[lag + 1] < difference[lag] {
+                    lag += 1;
+                }
+                lag
+            }
+            None => (min_lag..=max_lag).min_by(|&a, &b| difference[a].total_cmp(&difference[b]))?,
+        };
+
+        let mut period = lag as f32;
+        if lag < max_lag {
+            let (before, at, after) = (difference[lag - 1], difference[lag], difference[lag + 1]);
+            let curvature = before - 2.0 * at + after;
+            if curvature > 0.0 {
+                period += 0.5 * (before - after) / curvature;
+            }
+        }
+
+        Some(PitchEstimate {
+            frequency_hz: sample_rate / period,
+            confidence: (1.0 - difference[lag]).clamp(0.0, 1.0),
+        })
+    }
+
     /// Get a sample at a specific time position with pitch shifting
     /// Get sample at natural playback speed (no pitch shifting) - ideal for drums
     pub fn get_natural_sample_at_time(&self, time_secs: f32) -> f32 {
@@ -432,45 +1600,62 @@ impl SampleData {
         // Play at natural speed - no frequency-based pitch shifting
         let sample_pos = time_secs * self.sample_rate as f32;
 
-        // Handle looping
-        let (loop_start, loop_end) = match (self.loop_start, self.loop_end) {
-            (Some(start), Some(end)) => (start as f32, end as f32),
-            _ => (0.0, self.samples.len() as f32),
-        };
-
-        let effective_pos = if sample_pos >= loop_end {
-            // For drums, often we want one-shot playback, so return 0 after sample ends
-            if self.loop_start.is_none() && self.loop_end.is_none() {
-                return 0.0; // Sample has ended naturally
-            }
-            // Loop back to start
-            let loop_length = loop_end - loop_start;
-            let overflow = sample_pos - loop_end;
-            loop_start + (overflow % loop_length)
-        } else {
-            sample_pos
-        };
+        // For drums, often we want one-shot playback, so only explicit loops wrap
+        let loop_region = self.loop_region(false);
 
-        // Linear interpolation between samples
-        let floor_pos = effective_pos.floor() as usize;
-        let ceil_pos = (floor_pos + 1).min(self.samples.len() - 1);
-        let fraction = effective_pos - floor_pos as f32;
+        match self.wrap_position(sample_pos, loop_region) {
+            Some(position) => self.interpolate(position, 1.0, loop_region),
+            None => 0.0, // Sample has ended naturally
+        }
+    }
 
-        if floor_pos >= self.samples.len() {
-            return 0.0;
+    /// Left and right channels of a stereo sample
+    ///
+    /// Returns None for mono samples, and for channels whose length does not
+    /// match `samples`, which are then played as mono.
+    pub fn stereo_channels(&self) -> Option<(&[f32], &[f32])> {
+        match &self.stereo_channels {
+            Some((left, right))
+                if left.len() == self.samples.len() && right.len() == self.samples.len() =>
+            {
+                Some((left, right))
+            }
+            _ => None,
         }
+    }
 
-        let sample_low = self.samples[floor_pos];
-        let sample_high = self.samples[ceil_pos];
+    /// Get a (left, right) frame at natural playback speed - keeps the image of stereo drums
+    ///
+    /// Each channel is read with the same loop points and interpolation as
+    /// `get_natural_sample_at_time`. Mono samples return their single channel
+    /// on both sides.
+    pub fn get_natural_stereo_sample_at_time(&self, time_secs: f32) -> (f32, f32) {
+        let Some((left, right)) = self.stereo_channels() else {
+            let sample = self.get_natural_sample_at_time(time_secs);
+            return (sample, sample);
+        };
+        if self.samples.is_empty() {
+            return (0.0, 0.0);
+        }
 
-        sample_low + fraction * (sample_high - sample_low)
+        let sample_pos = time_secs * self.sample_rate as f32;
+        let loop_region = self.loop_region(false);
+
+        match self.wrap_position(sample_pos, loop_region) {
+            Some(position) => (
+                self.interpolate_channel(left, position, 1.0, loop_region),
+                self.interpolate_channel(right, position, 1.0, loop_region),
+            ),
+            None => (0.0, 0.0),
+        }
     }
 
     /// Gets a sample value at a specific time with frequency adjustment.
     ///
     /// Calculates the sample value at the given time, adjusting playback speed
     /// based on the target frequency relative to the sample's base frequency.
-    /// Supports looping and uses linear interpolation for smooth playback.
+    /// Supports looping and reads between frames using the sample's configured
+    /// [`Interpolation`] mode (linear by default).
     ///
     /// # Arguments
     /// * `time_secs` - The time position in seconds
@@ -496,37 +1681,189 @@ impl SampleData {
         let speed_ratio = target_frequency / self.base_frequency;
         let sample_pos = time_secs * self.sample_rate as f32 * speed_ratio;
 
-        // Handle looping
-        let (loop_start, loop_end) = match (self.loop_start, self.loop_end) {
-            (Some(start), Some(end)) => (start as f32, end as f32),
-            _ => (0.0, self.samples.len() as f32),
+        // Pitched playback loops the whole sample when no loop points are set
+        let loop_region = self.loop_region(true);
+
+        match self.wrap_position(sample_pos, loop_region) {
+            Some(position) => self.interpolate(position, speed_ratio, loop_region),
+            None => 0.0,
+        }
+    }
+
+    /// Gets a (left, right) frame at a specific time with frequency adjustment
+    ///
+    /// Stereo samples read each channel exactly as `get_sample_at_time` reads
+    /// the mono mix; mono samples return that value on both sides.
+    pub fn get_stereo_sample_at_time(&self, time_secs: f32, target_frequency: f32) -> (f32, f32) {
+        let Some((left, right)) = self.stereo_channels() else {
+            let sample = self.get_sample_at_time(time_secs, target_frequency);
+            return (sample, sample);
         };
+        if self.samples.is_empty() {
+            return (0.0, 0.0);
+        }
 
-        let effective_pos = if sample_pos >= loop_end {
-            // Loop back to start
-            let loop_length = loop_end - loop_start;
-            let overflow = sample_pos - loop_end;
-            loop_start + (overflow % loop_length)
-        } else {
-            sample_pos
+        let speed_ratio = target_frequency / self.base_frequency;
+        let sample_pos = time_secs * self.sample_rate as f32 * speed_ratio;
+        let loop_region = self.loop_region(true);
+
+        match self.wrap_position(sample_pos, loop_region) {
+            Some(position) => (
+                self.interpolate_channel(left, position, speed_ratio, loop_region),
+                self.interpolate_channel(right, position, speed_ratio, loop_region),
+            ),
+            None => (0.0, 0.0),
+        }
+    }
+
+    /// Loop region `(start, end)` that playback wraps within, if any
+    ///
+    /// Frames from `start` up to (not including) `end` repeat; reading past
+    /// `end` continues from `start`.
+    fn loop_region(&self, wrap_whole_sample: bool) -> Option<(usize, usize)> {
+        match (self.loop_start, self.loop_end) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ if wrap_whole_sample => Some((0, self.samples.len())),
+            _ => None,
+        }
+    }
+
+    /// Map a playback position into the buffer, wrapping around the loop region
+    fn wrap_position(&self, position: f32, loop_region: Option<(usize, usize)>) -> Option<f32> {
+        let position = match loop_region {
+            Some((start, end)) if position >= end as f32 => {
+                let loop_length = (end - start) as f32;
+                start as f32 + (position - end as f32) % loop_length
+            }
+            _ => position,
         };
 
-        // Bounds check
-        if effective_pos < 0.0 || effective_pos >= self.samples.len() as f32 {
-            return 0.0;
+        if position < 0.0 || position >= self.samples.len() as f32 {
+            None
+        } else {
+            Some(position)
+        }
+    }
+
+    /// Resolve a neighbouring frame index, following the loop seam back to its start
+    ///
+    /// Returns `None` for frames outside the buffer that playback never wraps to.
+    fn frame_index(&self, index: isize, loop_region: Option<(usize, usize)>) -> Option<usize> {
+        if let Some((start, end)) = loop_region {
+            if index >= end as isize && end > start {
+                return Some(start + (index as usize - end) % (end - start));
+            }
+        }
+
+        if index < 0 || index as usize >= self.samples.len() {
+            None
+        } else {
+            Some(index as usize)
         }
+    }
+
+    /// Read the sample buffer at a fractional position using the configured interpolation
+    ///
+    /// `speed_ratio` is the playback rate relative to the stored rate; the sinc
+    /// kernel narrows its cutoff when reading faster than 1.0 to avoid aliasing.
+    /// Frames after the end of `loop_region` are read from its start, so the
+    /// loop seam is interpolated like any other pair of frames.
+    fn interpolate(
+        &self,
+        position: f32,
+        speed_ratio: f32,
+        loop_region: Option<(usize, usize)>,
+    ) -> f32 {
+        self.interpolate_channel(&self.samples, position, speed_ratio, loop_region)
+    }
+
+    /// Read one channel buffer, laid out like `samples`, at a fractional position
+    fn interpolate_channel(
+        &self,
+        channel: &[f32],
+        position: f32,
+        speed_ratio: f32,
+        loop_region: Option<(usize, usize)>,
+    ) -> f32 {
+        let index = position as usize;
+        let fraction = position - index as f32;
+        let last = channel.len() - 1;
+        let frame = |offset: isize, fallback: usize| {
+            let resolved = self.frame_index(index as isize + offset, loop_region);
+            channel[resolved.unwrap_or(fallback)]
+        };
 
-        // Linear interpolation between samples
-        let index = effective_pos as usize;
-        let fraction = effective_pos - index as f32;
+        match self.interpolation {
+            Interpolation::Nearest => {
+                if fraction < 0.5 {
+                    channel[index]
+                } else {
+                    frame(1, index)
+                }
+            }
+            Interpolation::Linear => {
+                let sample1 = channel[index];
+                let sample2 = frame(1, index);
+                sample1 + (sample2 - sample1) * fraction
+            }
+            Interpolation::Hermite => {
+                let y0 = frame(-1, index);
+                let y1 = channel[index];
+                let y2 = frame(1, last);
+                let y3 = frame(2, last);
+
+                // Catmull-Rom spline through the four neighbouring frames
+                let c1 = 0.5 * (y2 - y0);
+                let c2 = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                let c3 = 0.5 * (y3 - y0) + 1.5 * (y1 - y2);
+                ((c3 * fraction + c2) * fraction + c1) * fraction + y1
+            }
+            Interpolation::Sinc { taps } => {
+                self.sinc_interpolate(channel, index, fraction, taps, speed_ratio, loop_region)
+            }
+        }
+    }
 
-        if index + 1 >= self.samples.len() {
-            self.samples[index]
+    /// Blackman-windowed sinc interpolation around `index + fraction`
+    fn sinc_interpolate(
+        &self,
+        channel: &[f32],
+        index: usize,
+        fraction: f32,
+        taps: usize,
+        speed_ratio: f32,
+        loop_region: Option<(usize, usize)>,
+    ) -> f32 {
+        let half_width = (taps.max(2) / 2) as isize;
+        let half_width_f = half_width as f32;
+
+        // Lower the cutoff when reading faster than the stored rate (pitching up)
+        let cutoff = if speed_ratio > 1.0 {
+            1.0 / speed_ratio
         } else {
-            let sample1 = self.samples[index];
-            let sample2 = self.samples[index + 1];
-            sample1 + (sample2 - sample1) * fraction
+            1.0
+        };
+
+        let mut sum = 0.0;
+        for offset in (1 - half_width)..=half_width {
+            let Some(source_index) = self.frame_index(index as isize + offset, loop_region) else {
+                continue;
+            };
+
+            let distance = offset as f32 - fraction;
+            if distance.abs() >= half_width_f {
+                continue;
+            }
+
+            let x = PI * cutoff * distance;
+            let sinc = if x.abs() < 1e-6 { 1.0 } else { x.sin() / x };
+            let window_phase = PI * distance / half_width_f;
+            let window = 0.42 + 0.5 * window_phase.cos() + 0.08 * (2.0 * window_phase).cos();
+
+            sum += channel[source_index] * cutoff * sinc * window;
         }
+
+        sum
     }
 }
 
@@ -545,34 +1882,186 @@ impl SampleData {
 /// # Examples
 ///
 /// ```rust
-/// use polyphonica::AdsrEnvelope;
+/// use polyphonica::{AdsrEnvelope, EnvelopeCurve};
 ///
 /// // Piano-like envelope (quick attack, gradual decay)
 /// let piano = AdsrEnvelope {
 ///     attack_secs: 0.01,
+///     hold_secs: 0.0,
 ///     decay_secs: 0.3,
 ///     sustain_level: 0.4,
 ///     release_secs: 0.8,
+///     curve: EnvelopeCurve::LINEAR,
 /// };
 ///
 /// // Organ-like envelope (no decay, full sustain)
 /// let organ = AdsrEnvelope {
 ///     attack_secs: 0.1,
+///     hold_secs: 0.0,
 ///     decay_secs: 0.0,
 ///     sustain_level: 1.0,
 ///     release_secs: 0.2,
+///     curve: EnvelopeCurve::LINEAR,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct AdsrEnvelope {
     /// Time in seconds to reach peak amplitude from zero
     pub attack_secs: f32,
+    /// Time in seconds to stay at peak amplitude before decaying
+    #[serde(default)]
+    pub hold_secs: f32,
     /// Time in seconds to decay from peak to sustain level
     pub decay_secs: f32,
     /// Sustain amplitude level (0.0 to 1.0)
     pub sustain_level: f32,
     /// Time in seconds to fade from sustain level to zero
     pub release_secs: f32,
+    /// Curve shapes of the attack, decay, and release stages
+    #[serde(default)]
+    pub curve: EnvelopeCurve,
+}
+
+/// How sharply the curved stage shapes bend (higher is more pronounced)
+const CURVE_STEEPNESS: f32 = 5.0;
+
+/// Shape of the transition an envelope stage makes between its start and end levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum CurveShape {
+    /// Constant rate of change
+    #[default]
+    Linear,
+    /// Fast at first, easing into the target level like an analog RC envelope
+    ///
+    /// Gives the natural fast-then-slow decay of plucked and struck sounds.
+    Exponential,
+    /// Slow at first, accelerating into the target level
+    Logarithmic,
+}
+
+impl CurveShape {
+    /// Fraction of a stage's transition completed `progress` (0.0-1.0) of the way through it
+    ///
+    /// Every shape starts at 0.0 and ends at exactly 1.0, so stage lengths
+    /// are unaffected by the choice of curve.
+    pub fn apply(self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            CurveShape::Linear => progress,
+            CurveShape::Exponential => {
+                (1.0 - (-CURVE_STEEPNESS * progress).exp()) / (1.0 - (-CURVE_STEEPNESS).exp())
+            }
+            CurveShape::Logarithmic => {
+                ((CURVE_STEEPNESS * progress).exp() - 1.0) / (CURVE_STEEPNESS.exp() - 1.0)
+            }
+        }
+    }
+}
+
+/// Curve shapes for the attack, decay, and release stages of an [`AdsrEnvelope`]
+///
+/// Missing stages default to [`CurveShape::Linear`] when deserializing, so
+/// envelopes saved before curves existed load unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct EnvelopeCurve {
+    /// Shape of the rise from silence to peak
+    #[serde(default)]
+    pub attack: CurveShape,
+    /// Shape of the fall from peak to the sustain level
+    #[serde(default)]
+    pub decay: CurveShape,
+    /// Shape of the fade from the release level to silence
+    #[serde(default)]
+    pub release: CurveShape,
+}
+
+impl EnvelopeCurve {
+    /// Straight-line ramps for every stage, the classic ADSR
+    pub const LINEAR: Self = Self::uniform(CurveShape::Linear);
+
+    /// The same shape for every stage
+    pub const fn uniform(shape: CurveShape) -> Self {
+        Self {
+            attack: shape,
+            decay: shape,
+            release: shape,
+        }
+    }
+}
+
+/// Errors reported by [`AdsrEnvelope::validated`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvelopeError {
+    /// A stage time is negative or not finite
+    InvalidTime {
+        /// Envelope stage name ("attack", "hold", "decay", or "release")
+        stage: &'static str,
+        /// Offending value in seconds
+        value: f32,
+    },
+    /// Sustain level is outside 0.0 to 1.0
+    InvalidSustain(f32),
+}
+
+impl std::fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::InvalidTime { stage, value } => {
+                write!(f, "Invalid {} time: {} seconds", stage, value)
+            }
+            EnvelopeError::InvalidSustain(level) => {
+                write!(f, "Sustain level {} is outside 0.0 to 1.0", level)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+impl AdsrEnvelope {
+    /// Check that stage times are non-negative and sustain is within 0.0 to 1.0
+    ///
+    /// Returns the envelope unchanged when valid. Use this for user-facing
+    /// configs where bad values should be reported rather than fixed.
+    pub fn validated(self) -> Result<Self, EnvelopeError> {
+        for (stage, value) in [
+            ("attack", self.attack_secs),
+            ("hold", self.hold_secs),
+            ("decay", self.decay_secs),
+            ("release", self.release_secs),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(EnvelopeError::InvalidTime { stage, value });
+            }
+        }
+        if !(0.0..=1.0).contains(&self.sustain_level) {
+            return Err(EnvelopeError::InvalidSustain(self.sustain_level));
+        }
+        Ok(self)
+    }
+
+    /// Copy of the envelope with every value forced into its valid range
+    ///
+    /// Negative or non-finite times become 0.0 and the sustain level is
+    /// clamped to 0.0 to 1.0 (NaN becomes 0.0). Playback paths apply this to
+    /// every envelope they receive.
+    pub fn clamp(&self) -> Self {
+        let time = |secs: f32| if secs.is_finite() { secs.max(0.0) } else { 0.0 };
+        let sustain_level = if self.sustain_level.is_nan() {
+            0.0
+        } else {
+            self.sustain_level.clamp(0.0, 1.0)
+        };
+
+        Self {
+            attack_secs: time(self.attack_secs),
+            hold_secs: time(self.hold_secs),
+            decay_secs: time(self.decay_secs),
+            sustain_level,
+            release_secs: time(self.release_secs),
+            curve: self.curve,
+        }
+    }
 }
 
 /// A scheduled sound event with waveform, frequency sweep, and envelope
@@ -584,7 +2073,7 @@ pub struct AdsrEnvelope {
 /// # Examples
 ///
 /// ```rust
-/// use polyphonica::{SoundEvent, Waveform, AdsrEnvelope};
+/// use polyphonica::{SoundEvent, Waveform, AdsrEnvelope, EnvelopeCurve};
 ///
 /// // Simple constant-frequency tone
 /// let beep = SoundEvent {
@@ -594,9 +2083,11 @@ pub struct AdsrEnvelope {
 ///     duration_secs: 0.5,
 ///     envelope: AdsrEnvelope {
 ///         attack_secs: 0.1,
+///         hold_secs: 0.0,
 ///         decay_secs: 0.1,
 ///         sustain_level: 0.7,
 ///         release_secs: 0.3,
+///         curve: EnvelopeCurve::LINEAR,
 ///     },
 /// };
 ///
@@ -623,6 +2114,43 @@ pub struct SoundEvent {
     pub envelope: AdsrEnvelope,
 }
 
+impl SoundEvent {
+    /// Shift the event's pitch by a number of equal-tempered semitones
+    ///
+    /// Both the start and end frequency are scaled, so sweeps keep their
+    /// interval. Unpitched events (noise and drum samples) are left untouched.
+    pub fn transpose(&mut self, semitones: i8) {
+        if !self.waveform.is_pitched() {
+            return;
+        }
+        let ratio = 2.0_f32.powf(semitones as f32 / 12.0);
+        self.start_frequency *= ratio;
+        self.end_frequency *= ratio;
+    }
+}
+
+/// Shift every pitched event of a timeline by a number of semitones
+///
+/// Event start times are unchanged; see `SoundEvent::transpose`.
+pub fn transpose_timeline(events: &mut [(f32, SoundEvent)], semitones: i8) {
+    for (_, event) in events.iter_mut() {
+        event.transpose(semitones);
+    }
+}
+
+/// Reference pitch for MIDI note 69 (A4)
+pub(crate) const A4_FREQUENCY: f32 = 440.0;
+
+/// Convert a frequency in Hz to a fractional MIDI note number
+pub(crate) fn frequency_to_midi(frequency: f32) -> f32 {
+    69.0 + 12.0 * (frequency / A4_FREQUENCY).log2()
+}
+
+/// Convert a fractional MIDI note number to a frequency in Hz
+pub(crate) fn midi_to_frequency(midi_note: f32) -> f32 {
+    A4_FREQUENCY * 2.0_f32.powf((midi_note - 69.0) / 12.0)
+}
+
 /// Generate audio samples for a single waveform
 ///
 /// Creates a buffer of audio samples for the specified waveform, frequency, and duration.
@@ -638,7 +2166,8 @@ pub struct SoundEvent {
 ///
 /// # Returns
 ///
-/// Vector of f32 samples in the range [-1.0, 1.0]. Returns empty vector if parameters are invalid.
+/// Vector of f32 samples clamped to the range [-1.0, 1.0]. Returns empty vector if parameters are invalid.
+/// Use [`generate_wave_raw`] to get the unclamped signal.
 ///
 /// # Examples
 ///
@@ -666,46 +2195,126 @@ pub fn generate_wave(
     if validate_inputs(frequency, duration_secs, sample_rate).is_err() {
         return Vec::new();
     }
-    let total_samples = (duration_secs * sample_rate as f32) as usize;
-    let mut samples = Vec::with_capacity(total_samples);
-
-    for i in 0..total_samples {
-        let t = i as f32 / sample_rate as f32;
-        let phase = 2.0 * PI * frequency * t;
-        let sample = generate_sample(&waveform, phase, t, frequency);
-        samples.push(sample);
+    let mut samples = generate_wave_raw(waveform, frequency, duration_secs, sample_rate);
+    for sample in &mut samples {
+        *sample = sample.clamp(-1.0, 1.0);
     }
 
     samples
 }
 
-/// Applies an ADSR envelope to a buffer of audio samples in-place.
+/// Generate audio samples for a single waveform without clamping
 ///
-/// Modifies the provided sample buffer by applying the four-stage ADSR
-/// (Attack, Decay, Sustain, Release) envelope. This is essential for
-/// natural-sounding audio synthesis and sample playback.
+/// Same as [`generate_wave`], but the output is left exactly as the waveform
+/// produced it. Oscillators stay within [-1.0, 1.0], but sample waveforms
+/// with hot or summed content can exceed it, so callers that normalize or
+/// manage gain downstream are responsible for the range themselves.
 ///
-/// # Arguments
-/// * `samples` - Mutable slice of audio samples to be processed
-/// * `envelope` - ADSR envelope parameters defining the amplitude shape
-/// * `sample_rate` - Sample rate in Hz for timing calculations
+/// # Examples
 ///
-/// # ADSR Stages
-/// - **Attack**: Linear ramp from 0 to peak amplitude
-/// - **Decay**: Linear ramp from peak to sustain level
-/// - **Sustain**: Constant amplitude at sustain level
-/// - **Release**: Linear ramp from sustain level to 0
+/// ```rust
+/// use polyphonica::{generate_wave_raw, Waveform};
 ///
-/// # Examples
+/// let samples = generate_wave_raw(Waveform::Sine, 440.0, 0.1, 44100);
+/// assert_eq!(samples.len(), 4410);
 /// ```
-/// use polyphonica::{apply_envelope, AdsrEnvelope};
+pub fn generate_wave_raw(
+    waveform: Waveform,
+    frequency: f32,
+    duration_secs: f32,
+    sample_rate: u32,
+) -> Vec<f32> {
+    if validate_inputs(frequency, duration_secs, sample_rate).is_err() {
+        return Vec::new();
+    }
+    let total_samples = (duration_secs * sample_rate as f32) as usize;
+    let mut samples = vec![0.0; total_samples];
+    generate_wave_into(&waveform, frequency, sample_rate, &mut samples);
+
+    samples
+}
+
+/// Generate audio samples for a single waveform into an existing buffer
+///
+/// Non-allocating counterpart of [`generate_wave`]: the length of `output`
+/// determines the duration, and every sample is overwritten starting at time
+/// zero. Useful in tight loops that generate many short tones. Like
+/// [`generate_wave_raw`], the output is not clamped.
+///
+/// # Returns
+///
+/// Number of samples written. Returns 0 and leaves `output` untouched if
+/// parameters are invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::{generate_wave_into, Waveform};
+///
+/// let mut buffer = [0.0f32; 512];
+/// let written = generate_wave_into(&Waveform::Sine, 440.0, 44100, &mut buffer);
+/// assert_eq!(written, 512);
+/// ```
+pub fn generate_wave_into(
+    waveform: &Waveform,
+    frequency: f32,
+    sample_rate: u32,
+    output: &mut [f32],
+) -> usize {
+    if validate_inputs(frequency, 0.0, sample_rate).is_err() {
+        return 0;
+    }
+
+    let mut noise = NoiseFilter::new();
+    for (i, sample) in output.iter_mut().enumerate() {
+        let t = i as f32 / sample_rate as f32;
+        let phase = 2.0 * PI * frequency * t;
+        *sample = generate_sample(
+            waveform,
+            phase,
+            t,
+            frequency,
+            frequency / sample_rate as f32,
+            &mut noise,
+            sample_rate as f32,
+        );
+    }
+
+    output.len()
+}
+
+/// Applies an ADSR envelope to a buffer of audio samples in-place.
+///
+/// Modifies the provided sample buffer by applying the ADSR envelope
+/// (Attack, Hold, Decay, Sustain, Release). This is essential for
+/// natural-sounding audio synthesis and sample playback.
+///
+/// # Arguments
+/// * `samples` - Mutable slice of audio samples to be processed
+/// * `envelope` - ADSR envelope parameters defining the amplitude shape
+/// * `sample_rate` - Sample rate in Hz for timing calculations
+///
+/// # ADSR Stages
+/// - **Attack**: Ramp from 0 to peak amplitude
+/// - **Hold**: Peak amplitude held for `hold_secs`
+/// - **Decay**: Ramp from peak to sustain level
+/// - **Sustain**: Constant amplitude at sustain level
+/// - **Release**: Ramp from sustain level to 0
+///
+/// The attack, decay, and release ramps follow `envelope.curve`.
+///
+/// # Examples
+/// ```
+/// use polyphonica::{apply_envelope, AdsrEnvelope, EnvelopeCurve};
 ///
 /// let mut samples = vec![1.0; 1000]; // 1000 samples at full amplitude
 /// let envelope = AdsrEnvelope {
 ///     attack_secs: 0.1,
+///     hold_secs: 0.0,
 ///     decay_secs: 0.1,
 ///     sustain_level: 0.7,
 ///     release_secs: 0.2,
+///     curve: EnvelopeCurve::LINEAR,
 /// };
 /// apply_envelope(&mut samples, &envelope, 44100);
 /// ```
@@ -716,28 +2325,37 @@ pub fn apply_envelope(samples: &mut [f32], envelope: &AdsrEnvelope, sample_rate:
     }
 
     let attack_samples = (envelope.attack_secs * sample_rate as f32) as usize;
+    let hold_samples = (envelope.hold_secs * sample_rate as f32) as usize;
     let decay_samples = (envelope.decay_secs * sample_rate as f32) as usize;
     let release_samples = (envelope.release_secs * sample_rate as f32) as usize;
 
     // Ensure we don't exceed the total sample count
     let attack_end = attack_samples.min(total_samples);
-    let decay_end = (attack_samples + decay_samples).min(total_samples);
+    let decay_start = attack_samples + hold_samples;
+    let hold_end = decay_start.min(total_samples);
+    let decay_end = (decay_start + decay_samples).min(total_samples);
     let sustain_end = total_samples.saturating_sub(release_samples);
     let release_start = sustain_end;
 
     for (i, sample) in samples.iter_mut().enumerate() {
         let envelope_value = if i < attack_end {
-            // Attack phase: linear ramp from 0 to 1
+            // Attack phase: ramp from 0 to 1
             if attack_samples > 0 {
-                i as f32 / attack_samples as f32
+                envelope
+                    .curve
+                    .attack
+                    .apply(i as f32 / attack_samples as f32)
             } else {
                 1.0
             }
+        } else if i < hold_end {
+            // Hold phase: constant at peak
+            1.0
         } else if i < decay_end {
-            // Decay phase: linear ramp from 1 to sustain_level
+            // Decay phase: ramp from 1 to sustain_level
             if decay_samples > 0 {
-                let decay_progress = (i - attack_samples) as f32 / decay_samples as f32;
-                1.0 - decay_progress * (1.0 - envelope.sustain_level)
+                let decay_progress = (i - decay_start) as f32 / decay_samples as f32;
+                1.0 - envelope.curve.decay.apply(decay_progress) * (1.0 - envelope.sustain_level)
             } else {
                 envelope.sustain_level
             }
@@ -745,10 +2363,10 @@ pub fn apply_envelope(samples: &mut [f32], envelope: &AdsrEnvelope, sample_rate:
             // Sustain phase: constant at sustain_level
             envelope.sustain_level
         } else {
-            // Release phase: linear ramp from sustain_level to 0
+            // Release phase: ramp from sustain_level to 0
             if release_samples > 0 {
                 let release_progress = (i - release_start) as f32 / release_samples as f32;
-                envelope.sustain_level * (1.0 - release_progress)
+                envelope.sustain_level * (1.0 - envelope.curve.release.apply(release_progress))
             } else {
                 0.0
             }
@@ -780,13 +2398,15 @@ pub fn apply_envelope(samples: &mut [f32], envelope: &AdsrEnvelope, sample_rate:
 ///
 /// # Examples
 /// ```
-/// use polyphonica::{render_event, SoundEvent, Waveform, AdsrEnvelope};
+/// use polyphonica::{render_event, SoundEvent, Waveform, AdsrEnvelope, EnvelopeCurve};
 ///
 /// let envelope = AdsrEnvelope {
 ///     attack_secs: 0.01,
+///     hold_secs: 0.0,
 ///     decay_secs: 0.1,
 ///     sustain_level: 0.8,
 ///     release_secs: 0.2,
+///     curve: EnvelopeCurve::LINEAR,
 /// };
 ///
 /// let event = SoundEvent {
@@ -799,7 +2419,47 @@ pub fn apply_envelope(samples: &mut [f32], envelope: &AdsrEnvelope, sample_rate:
 ///
 /// let samples = render_event(&event, 44100);
 /// ```
+///
+/// # Determinism
+/// Output is bit-identical for identical inputs. Noise waveforms use
+/// [`NoiseFilter::DEFAULT_SEED`]; see [`render_event_seeded`] to choose it.
 pub fn render_event(event: &SoundEvent, sample_rate: u32) -> Vec<f32> {
+    render_event_seeded(event, sample_rate, NoiseFilter::DEFAULT_SEED)
+}
+
+/// Render a sound event with an explicit noise seed
+///
+/// Identical to [`render_event`] except that noise waveforms draw from a
+/// generator seeded with `seed`. The same event, sample rate, and seed always
+/// produce bit-identical output, which makes the result usable as golden
+/// reference data for exports and regression tests. Events without noise
+/// render the same for every seed.
+///
+/// # Examples
+/// ```
+/// use polyphonica::{render_event_seeded, AdsrEnvelope, EnvelopeCurve, SoundEvent, Waveform};
+///
+/// let hiss = SoundEvent {
+///     waveform: Waveform::Noise,
+///     start_frequency: 440.0,
+///     end_frequency: 440.0,
+///     duration_secs: 0.1,
+///     envelope: AdsrEnvelope {
+///         attack_secs: 0.0,
+///         hold_secs: 0.0,
+///         decay_secs: 0.05,
+///         sustain_level: 0.5,
+///         release_secs: 0.02,
+///         curve: EnvelopeCurve::LINEAR,
+///     },
+/// };
+///
+/// assert_eq!(
+///     render_event_seeded(&hiss, 44100, 7),
+///     render_event_seeded(&hiss, 44100, 7)
+/// );
+/// ```
+pub fn render_event_seeded(event: &SoundEvent, sample_rate: u32, seed: u64) -> Vec<f32> {
     if validate_inputs(event.start_frequency, event.duration_secs, sample_rate).is_err() {
         return Vec::new();
     }
@@ -808,6 +2468,7 @@ pub fn render_event(event: &SoundEvent, sample_rate: u32) -> Vec<f32> {
     }
     let total_samples = (event.duration_secs * sample_rate as f32) as usize;
     let mut samples = Vec::with_capacity(total_samples);
+    let mut noise = NoiseFilter::with_seed(seed);
 
     for i in 0..total_samples {
         let t = i as f32 / sample_rate as f32;
@@ -818,16 +2479,59 @@ pub fn render_event(event: &SoundEvent, sample_rate: u32) -> Vec<f32> {
             event.start_frequency + (event.end_frequency - event.start_frequency) * progress;
 
         let phase = 2.0 * PI * current_frequency * t;
-        let sample = generate_sample(&event.waveform, phase, t, current_frequency);
+        let sample = generate_sample(
+            &event.waveform,
+            phase,
+            t,
+            current_frequency,
+            current_frequency / sample_rate as f32,
+            &mut noise,
+            sample_rate as f32,
+        );
         samples.push(sample);
     }
 
     // Apply the ADSR envelope
-    apply_envelope(&mut samples, &event.envelope, sample_rate);
+    apply_envelope(&mut samples, &event.envelope.clamp(), sample_rate);
 
     samples
 }
 
+/// Render a sound event as interleaved stereo (left, right)
+///
+/// Stereo drum samples keep their own left and right channels, read at
+/// natural speed and shaped by the event's envelope. Every other event is
+/// rendered with [`render_event`] and placed equally on both sides.
+pub fn render_event_stereo(event: &SoundEvent, sample_rate: u32) -> Vec<f32> {
+    let stereo_sample = match &event.waveform {
+        Waveform::DrumSample(sample_data) if sample_data.stereo_channels().is_some() => sample_data,
+        _ => {
+            return render_event(event, sample_rate)
+                .into_iter()
+                .flat_map(|sample| [sample, sample])
+                .collect();
+        }
+    };
+    if validate_inputs(event.start_frequency, event.duration_secs, sample_rate).is_err() {
+        return Vec::new();
+    }
+
+    // The envelope shapes both channels alike
+    let total_samples = (event.duration_secs * sample_rate as f32) as usize;
+    let mut gains = vec![1.0; total_samples];
+    apply_envelope(&mut gains, &event.envelope.clamp(), sample_rate);
+
+    gains
+        .iter()
+        .enumerate()
+        .flat_map(|(i, gain)| {
+            let t = i as f32 / sample_rate as f32;
+            let (left, right) = stereo_sample.get_natural_stereo_sample_at_time(t);
+            [left * gain, right * gain]
+        })
+        .collect()
+}
+
 /// Renders a timeline of multiple sound events into a single audio buffer.
 ///
 /// Combines multiple sound events positioned at specific times into a single
@@ -851,13 +2555,15 @@ pub fn render_event(event: &SoundEvent, sample_rate: u32) -> Vec<f32> {
 ///
 /// # Examples
 /// ```
-/// use polyphonica::{render_timeline, SoundEvent, Waveform, AdsrEnvelope};
+/// use polyphonica::{render_timeline, SoundEvent, Waveform, AdsrEnvelope, EnvelopeCurve};
 ///
 /// let envelope = AdsrEnvelope {
 ///     attack_secs: 0.01,
+///     hold_secs: 0.0,
 ///     decay_secs: 0.1,
 ///     sustain_level: 0.8,
 ///     release_secs: 0.2,
+///     curve: EnvelopeCurve::LINEAR,
 /// };
 ///
 /// let events = vec![
@@ -866,7 +2572,7 @@ pub fn render_event(event: &SoundEvent, sample_rate: u32) -> Vec<f32> {
 ///         start_frequency: 440.0,
 ///         end_frequency: 440.0,
 ///         duration_secs: 0.5,
-///         envelope,
+///         envelope: envelope.clone(),
 ///     }),
 ///     (0.5, SoundEvent {
 ///         waveform: Waveform::Sine,
@@ -891,37 +2597,309 @@ pub fn render_timeline(
     let mut master_buffer = vec![0.0; total_samples];
 
     for (start_time, event) in events {
-        // Calculate the start sample index
-        let start_sample_index = (*start_time * sample_rate as f32) as usize;
-
-        // Skip events that start after the total duration
-        if start_sample_index >= total_samples {
-            continue;
-        }
+        mix_event_into(&mut master_buffer, 0, *start_time, event, sample_rate);
+    }
 
-        // Render the event's audio samples
-        let event_samples = render_event(event, sample_rate);
+    // Clamp all samples to prevent clipping
+    for sample in master_buffer.iter_mut() {
+        *sample = sample.clamp(-1.0, 1.0);
+    }
 
-        // Mix the event samples into the master buffer
-        for (i, sample) in event_samples.iter().enumerate() {
-            let buffer_index = start_sample_index + i;
+    master_buffer
+}
 
-            // Stop if we exceed the master buffer length
-            if buffer_index >= total_samples {
-                break;
-            }
+/// Renders only a window of a timeline, for punching in on part of a mix
+///
+/// Returns the samples from `region_start_secs` up to `region_end_secs`,
+/// identical to the same slice of `render_timeline` over the same events.
+/// Only events sounding inside the window are synthesized; events that
+/// straddle either edge are rendered in full and trimmed, so their phase,
+/// envelope, and noise match the full render exactly.
+///
+/// Returns an empty vector if the region is negative, reversed, or not
+/// finite, or the sample rate is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::{render_timeline_region, AdsrEnvelope, EnvelopeCurve, SoundEvent, Waveform};
+///
+/// let note = SoundEvent {
+///     waveform: Waveform::Sine,
+///     start_frequency: 440.0,
+///     end_frequency: 440.0,
+///     duration_secs: 1.0,
+///     envelope: AdsrEnvelope {
+///         attack_secs: 0.01,
+///         hold_secs: 0.0,
+///         decay_secs: 0.1,
+///         sustain_level: 0.7,
+///         release_secs: 0.2,
+///         curve: EnvelopeCurve::LINEAR,
+///     },
+/// };
+/// let song: Vec<(f32, SoundEvent)> = (0..60).map(|bar| (bar as f32 * 2.0, note.clone())).collect();
+///
+/// // Re-render just the bridge, from 40 to 48 seconds
+/// let bridge = render_timeline_region(&song, 40.0, 48.0, 44100);
+/// assert_eq!(bridge.len(), 8 * 44100);
+/// ```
+pub fn render_timeline_region(
+    events: &[(f32, SoundEvent)],
+    region_start_secs: f32,
+    region_end_secs: f32,
+    sample_rate: u32,
+) -> Vec<f32> {
+    let region_valid = region_start_secs >= 0.0
+        && region_end_secs >= region_start_secs
+        && region_end_secs.is_finite();
+    if !region_valid || sample_rate == 0 || sample_rate > 192000 {
+        return Vec::new();
+    }
+    let region_start = (region_start_secs * sample_rate as f32) as usize;
+    let region_end = (region_end_secs * sample_rate as f32) as usize;
+    let mut region_buffer = vec![0.0; region_end - region_start];
 
-            // Add the sample to the master buffer
-            master_buffer[buffer_index] += sample;
-        }
+    for (start_time, event) in events {
+        mix_event_into(
+            &mut region_buffer,
+            region_start,
+            *start_time,
+            event,
+            sample_rate,
+        );
     }
 
-    // Clamp all samples to prevent clipping
-    for sample in master_buffer.iter_mut() {
+    for sample in region_buffer.iter_mut() {
         *sample = sample.clamp(-1.0, 1.0);
     }
 
-    master_buffer
+    region_buffer
+}
+
+/// Identifier of a voice group or output bus
+pub type GroupId = u32;
+
+/// Renders a timeline into one stem buffer per group for stems export.
+///
+/// Each event carries the group it is routed to. Every group present in
+/// `events` gets a buffer of the full timeline length, so all stems line up.
+/// Stems are not clamped: they sum sample-for-sample to the mix, and
+/// `render_timeline` over the same events returns that sum clamped.
+///
+/// Returns an empty map if input validation fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::{render_stems, AdsrEnvelope, EnvelopeCurve, SoundEvent, Waveform};
+///
+/// let envelope = AdsrEnvelope {
+///     attack_secs: 0.01,
+///     hold_secs: 0.0,
+///     decay_secs: 0.1,
+///     sustain_level: 0.5,
+///     release_secs: 0.2,
+///     curve: EnvelopeCurve::LINEAR,
+/// };
+/// let note = |frequency: f32| SoundEvent {
+///     waveform: Waveform::Sine,
+///     start_frequency: frequency,
+///     end_frequency: frequency,
+///     duration_secs: 0.5,
+///     envelope: envelope.clone(),
+/// };
+///
+/// const BASS: u32 = 1;
+/// const LEAD: u32 = 2;
+/// let events = vec![(0.0, BASS, note(55.0)), (0.25, LEAD, note(880.0))];
+/// let stems = render_stems(&events, 1.0, 44100);
+/// assert_eq!(stems[&BASS].len(), stems[&LEAD].len());
+/// ```
+pub fn render_stems(
+    events: &[(f32, GroupId, SoundEvent)],
+    total_duration_secs: f32,
+    sample_rate: u32,
+) -> HashMap<GroupId, Vec<f32>> {
+    let mut stems = HashMap::new();
+    if total_duration_secs < 0.0 || sample_rate == 0 || sample_rate > 192000 {
+        return stems;
+    }
+    let total_samples = (total_duration_secs * sample_rate as f32) as usize;
+
+    for (start_time, group, event) in events {
+        let stem = stems
+            .entry(*group)
+            .or_insert_with(|| vec![0.0; total_samples]);
+        mix_event_into(stem, 0, *start_time, event, sample_rate);
+    }
+
+    stems
+}
+
+/// Render an event and add it into `buffer` starting at `start_time` seconds
+///
+/// `buffer_start` is the timeline sample the buffer begins at. Events that
+/// end before the buffer or start after it are skipped without rendering,
+/// and samples outside the buffer are dropped.
+fn mix_event_into(
+    buffer: &mut [f32],
+    buffer_start: usize,
+    start_time: f32,
+    event: &SoundEvent,
+    sample_rate: u32,
+) {
+    let start_sample_index = (start_time * sample_rate as f32) as usize;
+    let event_length = (event.duration_secs * sample_rate as f32) as usize;
+
+    if start_sample_index >= buffer_start + buffer.len()
+        || start_sample_index + event_length <= buffer_start
+    {
+        return;
+    }
+
+    let event_samples = render_event(event, sample_rate);
+    let skipped = buffer_start.saturating_sub(start_sample_index);
+    let offset = start_sample_index.saturating_sub(buffer_start);
+    for (output, sample) in buffer[offset..]
+        .iter_mut()
+        .zip(event_samples.iter().skip(skipped))
+    {
+        *output += sample;
+    }
+}
+
+/// Join two rendered buffers with an equal-power crossfade
+///
+/// The last `overlap_secs` of `a` is faded out while the first `overlap_secs`
+/// of `b` is faded in, using cosine/sine gains whose squares sum to one so
+/// uncorrelated material keeps a constant loudness through the transition.
+/// The non-overlapping parts are copied unchanged, so the result is
+/// `a.len() + b.len() - overlap` samples long.
+///
+/// Returns an empty buffer if the overlap is negative, longer than either
+/// buffer, or the sample rate is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::crossfade_buffers;
+///
+/// let intro = vec![0.5; 44100];
+/// let groove = vec![0.25; 88200];
+/// let song = crossfade_buffers(&intro, &groove, 0.5, 44100);
+/// assert_eq!(song.len(), 44100 + 88200 - 22050);
+/// ```
+pub fn crossfade_buffers(a: &[f32], b: &[f32], overlap_secs: f32, sample_rate: u32) -> Vec<f32> {
+    if !(0.0..f32::INFINITY).contains(&overlap_secs) || sample_rate == 0 || sample_rate > 192000 {
+        return Vec::new();
+    }
+    let overlap = (overlap_secs * sample_rate as f32).round() as usize;
+    if overlap > a.len() || overlap > b.len() {
+        return Vec::new();
+    }
+
+    let fade_start = a.len() - overlap;
+    let mut output = Vec::with_capacity(a.len() + b.len() - overlap);
+    output.extend_from_slice(&a[..fade_start]);
+
+    for i in 0..overlap {
+        let (gain_out, gain_in) = equal_power_gains(i, overlap);
+        output.push(a[fade_start + i] * gain_out + b[i] * gain_in);
+    }
+
+    output.extend_from_slice(&b[overlap..]);
+    output
+}
+
+/// Outgoing and incoming gains at step `i` of an `overlap`-step equal-power crossfade
+fn equal_power_gains(i: usize, overlap: usize) -> (f32, f32) {
+    // Sample-centred position keeps the fade symmetric around its midpoint
+    let progress = (i as f32 + 0.5) / overlap as f32;
+    let angle = progress * PI / 2.0;
+    (angle.cos(), angle.sin())
+}
+
+/// Build a loop by repeating one rendered bar `repeats` times
+///
+/// The bar is rendered once and copied, so long loops cost no more synthesis
+/// than a single bar. Identical tiles join without any processing; use
+/// `render_loop_tiled_with_fills` to swap in fills or variations.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::render_loop_tiled;
+///
+/// let bar = vec![0.5; 1000];
+/// let song = render_loop_tiled(&bar, 8);
+/// assert_eq!(song.len(), 8000);
+/// ```
+pub fn render_loop_tiled(bar: &[f32], repeats: usize) -> Vec<f32> {
+    bar.repeat(repeats)
+}
+
+/// Build a loop from one rendered bar, replacing chosen repeats with fills
+///
+/// `fills` pairs a repeat index (counting from zero) with the buffer played
+/// in place of the bar on that repeat; later entries win if an index is
+/// listed twice. Every tile keeps the bar's length, so a fill is truncated or
+/// padded with silence to fit and the loop stays on the grid. Wherever the
+/// material changes between tiles, the outgoing tile carries on for
+/// `crossfade_secs` as if it had looped once more and is faded out with the
+/// same equal-power curve as `crossfade_buffers`, so fills enter and leave
+/// without clicks. Buffers are interleaved with `channels` channels and the
+/// fade advances per frame.
+///
+/// Returns an empty buffer if `channels` is zero, the crossfade is negative,
+/// or the sample rate is invalid. A crossfade longer than a bar is shortened
+/// to one bar.
+pub fn render_loop_tiled_with_fills(
+    bar: &[f32],
+    repeats: usize,
+    fills: &[(usize, &[f32])],
+    crossfade_secs: f32,
+    sample_rate: u32,
+    channels: usize,
+) -> Vec<f32> {
+    if channels == 0
+        || !(0.0..f32::INFINITY).contains(&crossfade_secs)
+        || sample_rate == 0
+        || sample_rate > 192000
+    {
+        return Vec::new();
+    }
+    let overlap =
+        ((crossfade_secs * sample_rate as f32).round() as usize).min(bar.len() / channels);
+    let tile_for = |repeat: usize| -> &[f32] {
+        fills
+            .iter()
+            .rev()
+            .find(|(index, _)| *index == repeat)
+            .map_or(bar, |(_, fill)| fill)
+    };
+    let sample_at = |tile: &[f32], index: usize| tile.get(index).copied().unwrap_or(0.0);
+
+    let mut output = Vec::with_capacity(bar.len() * repeats);
+    let mut previous: Option<&[f32]> = None;
+    for repeat in 0..repeats {
+        let tile = tile_for(repeat);
+        // Repeats of the same buffer already join seamlessly
+        let outgoing = previous.filter(|previous| !std::ptr::eq(*previous, tile));
+        for index in 0..bar.len() {
+            let frame = index / channels;
+            let sample = match outgoing {
+                Some(outgoing) if frame < overlap => {
+                    let (gain_out, gain_in) = equal_power_gains(frame, overlap);
+                    sample_at(outgoing, index) * gain_out + sample_at(tile, index) * gain_in
+                }
+                _ => sample_at(tile, index),
+            };
+            output.push(sample);
+        }
+        previous = Some(tile);
+    }
+    output
 }
 
 // ============================================================================
@@ -931,6 +2909,47 @@ pub fn render_timeline(
 /// Maximum number of simultaneous voices for polyphonic playback
 pub const MAX_VOICES: usize = 32;
 
+/// Highest sample rate accepted by the real-time engine
+pub const MAX_SAMPLE_RATE: f32 = 192000.0;
+
+/// Sample rate used when the engine is created with an invalid rate
+pub const DEFAULT_SAMPLE_RATE: f32 = 44100.0;
+
+/// Lowest mix headroom accepted by `RealtimeEngine::set_mix_headroom_db`
+pub const MIN_MIX_HEADROOM_DB: f32 = -60.0;
+
+/// Fade-out time for notes cut off by their choke group
+pub const CHOKE_FADE_SECS: f32 = 0.005;
+
+/// Fraction of the buffer time budget above which the adaptive voice limit sheds voices
+pub const CPU_LOAD_HIGH: f32 = 0.8;
+
+/// Fraction of the buffer time budget below which the adaptive voice limit recovers
+pub const CPU_LOAD_LOW: f32 = 0.5;
+
+/// Fewest voices the adaptive voice limit will shed down to
+pub const MIN_ADAPTIVE_VOICES: usize = 4;
+
+/// Highest MIDI note velocity, which plays at the velocity curve's full gain
+pub const MAX_MIDI_VELOCITY: u8 = 127;
+
+/// Default shortest attack the engine plays, a declick ramp for instant attacks
+pub const DEFAULT_MIN_ATTACK_SECS: f32 = 0.0005;
+
+/// Default shortest decay and release the engine plays, declicking instant cutoffs
+pub const DEFAULT_MIN_RELEASE_SECS: f32 = 0.0005;
+
+/// Validate a real-time sample rate, clamping rates above `MAX_SAMPLE_RATE`
+///
+/// Returns `None` for zero, negative, or non-finite rates.
+fn checked_sample_rate(sample_rate: f32) -> Option<f32> {
+    if sample_rate.is_finite() && sample_rate > 0.0 {
+        Some(sample_rate.min(MAX_SAMPLE_RATE))
+    } else {
+        None
+    }
+}
+
 /// Atomic f32 wrapper for lock-free parameter updates
 #[derive(Debug)]
 pub struct AtomicF32 {
@@ -975,8 +2994,87 @@ impl AtomicF32 {
     }
 }
 
+/// Shape of a low-frequency oscillator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum LfoShape {
+    /// Smooth sine sweep
+    #[default]
+    Sine,
+    /// Linear sweep between the extremes
+    Triangle,
+    /// Alternates between the extremes (trills and choppy tremolo)
+    Square,
+}
+
+/// Voice parameter modulated by an [`Lfo`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LfoTarget {
+    /// Vibrato: `depth` is the pitch swing in semitones either side of the note
+    Frequency,
+    /// Tremolo: `depth` (0.0-1.0) is how far the level dips below full
+    Amplitude,
+}
+
+/// Low-frequency oscillator for vibrato and tremolo
+///
+/// Attached to a playing voice with [`RealtimeEngine::set_voice_lfo`]; its
+/// phase starts at zero and advances once per processed sample.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::{Lfo, LfoShape};
+///
+/// let mut lfo = Lfo::new(LfoShape::Square, 5.0, 0.5);
+/// assert_eq!(lfo.next_value(1000.0), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Lfo {
+    /// Waveform of the modulation
+    pub shape: LfoShape,
+    /// Modulation rate in Hz
+    pub rate_hz: f32,
+    /// Modulation depth (meaning depends on the [`LfoTarget`])
+    pub depth: f32,
+    /// Normalized phase (0.0-1.0)
+    phase: f32,
+}
+
+impl Lfo {
+    /// Create an LFO starting at the beginning of its cycle
+    pub fn new(shape: LfoShape, rate_hz: f32, depth: f32) -> Self {
+        Self {
+            shape,
+            rate_hz,
+            depth,
+            phase: 0.0,
+        }
+    }
+
+    /// Current value in [-1.0, 1.0], then advance by one sample
+    pub fn next_value(&mut self, sample_rate: f32) -> f32 {
+        let value = match self.shape {
+            LfoShape::Sine => (2.0 * PI * self.phase).sin(),
+            LfoShape::Triangle => {
+                // Peaks a quarter cycle in, like the sine
+                let offset = (self.phase - 0.25).rem_euclid(1.0);
+                1.0 - 4.0 * offset.min(1.0 - offset)
+            }
+            LfoShape::Square => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        };
+        self.phase = (self.phase + self.rate_hz.max(0.0) / sample_rate).fract();
+        value
+    }
+}
+
 /// Real-time voice state for polyphonic synthesis
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Voice {
     /// Current waveform for this voice
     pub waveform: Waveform,
@@ -986,8 +3084,18 @@ pub struct Voice {
     pub frequency: f32,
     /// Target frequency for sweeps
     pub target_frequency: f32,
+    /// Per-sample frequency multiplier while gliding
+    pub glide_ratio: f32,
+    /// Samples left in the current glide (0 = not gliding)
+    pub glide_samples: u32,
     /// Voice amplitude (0.0 to 1.0)
     pub amplitude: f32,
+    /// Amplitude the voice is ramping toward
+    pub target_amplitude: f32,
+    /// Per-sample amplitude increment while ramping
+    pub amplitude_step: f32,
+    /// Samples left in the current amplitude ramp (0 = not ramping)
+    pub amplitude_ramp_samples: u32,
     /// Current envelope state
     pub envelope_state: EnvelopeState,
     /// ADSR envelope parameters
@@ -996,17 +3104,33 @@ pub struct Voice {
     pub active: AtomicBool,
     /// Voice ID for tracking
     pub voice_id: u32,
+    /// Allocation order of the current note (higher = triggered later)
+    pub generation: u64,
+    /// Voice group the current note was triggered in, if any
+    pub group: Option<u32>,
+    /// Choke group the current note was triggered in, if any
+    pub choke_group: Option<u32>,
+    /// Noise generator, filter and string state for the noise and plucked-string waveforms
+    pub noise: NoiseFilter,
     /// Sample time offset for samples
     pub sample_time: f32,
     /// Volume scaling (0.0 to 1.0)
     pub volume: f32,
+    /// Sample started on this voice when its one-shot sample ends
+    pub follow_up: Option<Waveform>,
+    /// Frequency modulation for the current note
+    pub vibrato: Option<Lfo>,
+    /// Amplitude modulation for the current note
+    pub tremolo: Option<Lfo>,
 }
 
 /// Current state within ADSR envelope
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum EnvelopePhase {
     /// Attack phase: linearly ramping from 0 to peak amplitude
     Attack,
+    /// Hold phase: staying at peak amplitude for `hold_secs`
+    Hold,
     /// Decay phase: linearly ramping from peak to sustain level
     Decay,
     /// Sustain phase: holding constant amplitude at sustain level
@@ -1018,7 +3142,7 @@ pub enum EnvelopePhase {
 }
 
 /// Running envelope state for real-time processing
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EnvelopeState {
     /// Current phase of the ADSR envelope
     pub phase: EnvelopePhase,
@@ -1066,16 +3190,24 @@ impl EnvelopeState {
 
         match self.phase {
             EnvelopePhase::Attack => {
-                if envelope.attack_secs <= 0.0 {
+                if envelope.attack_secs <= 0.0 || self.phase_time >= envelope.attack_secs {
                     self.current_level = 1.0;
-                    self.phase = EnvelopePhase::Decay;
+                    self.phase = if envelope.hold_secs > 0.0 {
+                        EnvelopePhase::Hold
+                    } else {
+                        EnvelopePhase::Decay
+                    };
                     self.phase_time = 0.0;
-                } else if self.phase_time >= envelope.attack_secs {
-                    self.current_level = 1.0;
+                } else {
+                    let progress = self.phase_time / envelope.attack_secs;
+                    self.current_level = envelope.curve.attack.apply(progress);
+                }
+            }
+            EnvelopePhase::Hold => {
+                self.current_level = 1.0;
+                if self.phase_time >= envelope.hold_secs {
                     self.phase = EnvelopePhase::Decay;
                     self.phase_time = 0.0;
-                } else {
-                    self.current_level = self.phase_time / envelope.attack_secs;
                 }
             }
             EnvelopePhase::Decay => {
@@ -1088,7 +3220,10 @@ impl EnvelopeState {
                     self.phase = EnvelopePhase::Sustain;
                     self.phase_time = 0.0;
                 } else {
-                    let progress = self.phase_time / envelope.decay_secs;
+                    let progress = envelope
+                        .curve
+                        .decay
+                        .apply(self.phase_time / envelope.decay_secs);
                     self.current_level = 1.0 - progress * (1.0 - envelope.sustain_level);
                 }
             }
@@ -1104,7 +3239,10 @@ impl EnvelopeState {
                     self.current_level = 0.0;
                     self.phase = EnvelopePhase::Finished;
                 } else {
-                    let progress = self.phase_time / envelope.release_secs;
+                    let progress = envelope
+                        .curve
+                        .release
+                        .apply(self.phase_time / envelope.release_secs);
                     self.current_level = self.release_level * (1.0 - progress);
                 }
             }
@@ -1148,18 +3286,32 @@ impl Voice {
             phase: 0.0,
             frequency: 440.0,
             target_frequency: 440.0,
+            glide_ratio: 1.0,
+            glide_samples: 0,
             amplitude: 1.0,
+            target_amplitude: 1.0,
+            amplitude_step: 0.0,
+            amplitude_ramp_samples: 0,
             envelope_state: EnvelopeState::new(),
             envelope: AdsrEnvelope {
                 attack_secs: 0.1,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.7,
                 release_secs: 0.3,
+                curve: EnvelopeCurve::LINEAR,
             },
             active: AtomicBool::new(false),
             voice_id,
+            generation: 0,
+            group: None,
+            choke_group: None,
+            noise: NoiseFilter::new(),
             sample_time: 0.0,
             volume: 1.0,
+            follow_up: None,
+            vibrato: None,
+            tremolo: None,
         }
     }
 
@@ -1170,6 +3322,9 @@ impl Voice {
         self.sample_time = 0.0;
         self.envelope_state = EnvelopeState::new();
         self.volume = 1.0;
+        self.follow_up = None;
+        self.vibrato = None;
+        self.tremolo = None;
     }
 
     /// Trigger a note with the given parameters
@@ -1177,11 +3332,17 @@ impl Voice {
         self.waveform = waveform;
         self.frequency = frequency;
         self.target_frequency = frequency;
-        self.envelope = envelope;
+        self.glide_samples = 0;
+        self.envelope = envelope.clamp();
         self.envelope_state = EnvelopeState::new();
         self.phase = 0.0;
         self.sample_time = 0.0;
+        self.noise.reset();
+        self.finish_amplitude_ramp();
         self.volume = 1.0;
+        self.follow_up = None;
+        self.vibrato = None;
+        self.tremolo = None;
         self.active.store(true, Ordering::Relaxed);
     }
 
@@ -1196,11 +3357,17 @@ impl Voice {
         self.waveform = waveform;
         self.frequency = frequency;
         self.target_frequency = frequency;
-        self.envelope = envelope;
+        self.glide_samples = 0;
+        self.envelope = envelope.clamp();
         self.envelope_state = EnvelopeState::new();
         self.phase = 0.0;
         self.sample_time = 0.0;
+        self.noise.reset();
+        self.finish_amplitude_ramp();
         self.volume = volume; // Store volume for use during sample generation
+        self.follow_up = None;
+        self.vibrato = None;
+        self.tremolo = None;
         self.active.store(true, Ordering::Relaxed);
     }
 
@@ -1209,36 +3376,196 @@ impl Voice {
         self.envelope_state.release();
     }
 
-    /// Generate the next audio sample
-    pub fn process_sample(&mut self, sample_rate: f32) -> f32 {
-        if !self.active.load(Ordering::Relaxed) {
-            return 0.0;
+    /// Set the interpolation used to read a sample-based waveform
+    ///
+    /// Has no effect on oscillator and noise waveforms.
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        if let Waveform::Sample(sample_data) | Waveform::DrumSample(sample_data) =
+            &mut self.waveform
+        {
+            sample_data.interpolation = interpolation;
         }
+    }
 
-        let dt = 1.0 / sample_rate;
+    /// Start `follow_up` once the voice's one-shot sample has played out
+    ///
+    /// A `DrumSample` ends after its natural length; a `Sample` without loop
+    /// points ends after one pass at its pitched speed instead of wrapping.
+    fn advance_sample_chain(&mut self) {
+        let (sample_data, speed_ratio) = match &self.waveform {
+            Waveform::DrumSample(sample_data) => (sample_data, 1.0),
+            Waveform::Sample(sample_data) => {
+                (sample_data, self.frequency / sample_data.base_frequency)
+            }
+            _ => return,
+        };
+        if self.follow_up.is_none() || sample_data.loop_region(false).is_some() {
+            return;
+        }
 
-        // Update envelope
-        let envelope_amplitude = self.envelope_state.update(&self.envelope, dt, false);
+        // Same end test as `get_natural_sample_at_time`, keeping the overshoot
+        // so the follow-up starts exactly where the first sample stopped
+        let length = sample_data.samples.len() as f32;
+        let frames_per_sec = sample_data.sample_rate as f32 * speed_ratio;
+        if self.sample_time * frames_per_sec < length {
+            return;
+        }
+        if let Some(follow_up) = self.follow_up.take() {
+            self.sample_time -= length / frames_per_sec;
+            self.phase = 0.0;
+            self.waveform = follow_up;
+        }
+    }
 
-        // If envelope is finished, deactivate voice
-        if self.envelope_state.is_finished() {
-            self.active.store(false, Ordering::Relaxed);
-            return 0.0;
+    /// Fade the current note out within `fade_secs`, even if already releasing
+    pub fn choke(&mut self, fade_secs: f32) {
+        let state = &mut self.envelope_state;
+        if state.phase == EnvelopePhase::Finished {
+            return;
         }
 
-        // Generate waveform sample
-        let waveform_sample =
-            generate_sample(&self.waveform, self.phase, self.sample_time, self.frequency);
+        self.envelope.release_secs = self.envelope.release_secs.min(fade_secs.max(0.0));
+        state.phase = EnvelopePhase::Release;
+        state.phase_time = 0.0;
+        state.release_level = state.current_level;
+    }
+
+    /// Current output gain of the voice (envelope, amplitude, and volume)
+    pub fn output_level(&self) -> f32 {
+        self.envelope_state.current_level * self.amplitude * self.volume
+    }
+
+    /// Set the voice amplitude, ramping linearly over `ramp_samples` samples
+    ///
+    /// A ramp of zero samples applies the new amplitude immediately.
+    pub fn set_amplitude(&mut self, amplitude: f32, ramp_samples: u32) {
+        self.target_amplitude = amplitude;
+        if ramp_samples == 0 {
+            self.finish_amplitude_ramp();
+        } else {
+            self.amplitude_step = (amplitude - self.amplitude) / ramp_samples as f32;
+            self.amplitude_ramp_samples = ramp_samples;
+        }
+    }
+
+    /// Glide exponentially to `frequency` over `glide_samples` samples
+    ///
+    /// A glide of zero samples changes the frequency immediately. The note's
+    /// envelope is not retriggered.
+    pub fn glide_to(&mut self, frequency: f32, glide_samples: u32) {
+        self.target_frequency = frequency;
+        if glide_samples == 0 || self.frequency <= 0.0 || frequency <= 0.0 {
+            self.frequency = frequency;
+            self.glide_samples = 0;
+        } else {
+            self.glide_ratio = (frequency / self.frequency).powf(1.0 / glide_samples as f32);
+            self.glide_samples = glide_samples;
+        }
+    }
+
+    /// Jump to the target amplitude and stop any ramp in progress
+    fn finish_amplitude_ramp(&mut self) {
+        self.amplitude = self.target_amplitude;
+        self.amplitude_step = 0.0;
+        self.amplitude_ramp_samples = 0;
+    }
+
+    /// Generate the next audio sample
+    pub fn process_sample(&mut self, sample_rate: f32) -> f32 {
+        self.process_frame(sample_rate, false).0
+    }
+
+    /// Generate the next (left, right) audio frame
+    ///
+    /// Stereo `Sample` and `DrumSample` waveforms play their own left and
+    /// right channels; every other waveform is the same on both sides.
+    pub fn process_stereo_sample(&mut self, sample_rate: f32) -> (f32, f32) {
+        self.process_frame(sample_rate, true)
+    }
+
+    /// Advance the voice by one frame, reading stereo sample channels if `stereo`
+    fn process_frame(&mut self, sample_rate: f32, stereo: bool) -> (f32, f32) {
+        if !self.active.load(Ordering::Relaxed) || checked_sample_rate(sample_rate).is_none() {
+            return (0.0, 0.0);
+        }
+
+        let dt = 1.0 / sample_rate;
+
+        // Update envelope
+        let envelope_amplitude = self.envelope_state.update(&self.envelope, dt, false);
+
+        // If envelope is finished, deactivate voice
+        if self.envelope_state.is_finished() {
+            self.active.store(false, Ordering::Relaxed);
+            return (0.0, 0.0);
+        }
+
+        self.advance_sample_chain();
+
+        // Apply LFO modulation around the glided frequency and full level
+        let frequency = match &mut self.vibrato {
+            Some(lfo) => self.frequency * (lfo.depth * lfo.next_value(sample_rate) / 12.0).exp2(),
+            None => self.frequency,
+        };
+        let tremolo_gain = match &mut self.tremolo {
+            Some(lfo) => {
+                1.0 - lfo.depth.clamp(0.0, 1.0) * 0.5 * (1.0 - lfo.next_value(sample_rate))
+            }
+            None => 1.0,
+        };
+
+        // Generate waveform sample
+        let (left, right) = match &self.waveform {
+            Waveform::Sample(sample_data) if stereo => {
+                sample_data.get_stereo_sample_at_time(self.sample_time, frequency)
+            }
+            Waveform::DrumSample(sample_data) if stereo => {
+                sample_data.get_natural_stereo_sample_at_time(self.sample_time)
+            }
+            waveform => {
+                let sample = generate_sample(
+                    waveform,
+                    self.phase,
+                    self.sample_time,
+                    frequency,
+                    frequency / sample_rate,
+                    &mut self.noise,
+                    sample_rate,
+                );
+                (sample, sample)
+            }
+        };
 
         // Update phase for next sample
-        self.phase += 2.0 * PI * self.frequency / sample_rate;
+        self.phase += 2.0 * PI * frequency / sample_rate;
         self.phase %= 2.0 * PI;
 
         // Update sample time for sample-based waveforms
         self.sample_time += dt;
 
-        // Apply envelope, amplitude, and volume
-        waveform_sample * envelope_amplitude * self.amplitude * self.volume
+        // Advance pitch glide
+        if self.glide_samples > 0 {
+            self.glide_samples -= 1;
+            if self.glide_samples == 0 {
+                self.frequency = self.target_frequency;
+            } else {
+                self.frequency *= self.glide_ratio;
+            }
+        }
+
+        // Advance amplitude smoothing
+        if self.amplitude_ramp_samples > 0 {
+            self.amplitude_ramp_samples -= 1;
+            if self.amplitude_ramp_samples == 0 {
+                self.amplitude = self.target_amplitude;
+            } else {
+                self.amplitude += self.amplitude_step;
+            }
+        }
+
+        // Apply envelope, amplitude, tremolo, and volume
+        let gain = envelope_amplitude * self.amplitude * tremolo_gain * self.volume;
+        (left * gain, right * gain)
     }
 
     /// Checks if this voice is currently active.
@@ -1257,17 +3584,127 @@ impl Clone for Voice {
             phase: self.phase,
             frequency: self.frequency,
             target_frequency: self.target_frequency,
+            glide_ratio: self.glide_ratio,
+            glide_samples: self.glide_samples,
             amplitude: self.amplitude,
+            target_amplitude: self.target_amplitude,
+            amplitude_step: self.amplitude_step,
+            amplitude_ramp_samples: self.amplitude_ramp_samples,
             envelope_state: self.envelope_state.clone(),
             envelope: self.envelope.clone(),
             active: AtomicBool::new(self.active.load(Ordering::Relaxed)),
             voice_id: self.voice_id,
+            generation: self.generation,
+            group: self.group,
+            choke_group: self.choke_group,
+            noise: self.noise.clone(),
             sample_time: self.sample_time,
             volume: self.volume,
+            follow_up: self.follow_up.clone(),
+            vibrato: self.vibrato,
+            tremolo: self.tremolo,
         }
     }
 }
 
+/// Which held note sounds on the monophonic voice when several are pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum NotePriority {
+    /// The most recently pressed note
+    #[default]
+    Last,
+    /// The highest held note
+    High,
+    /// The lowest held note
+    Low,
+}
+
+/// When the monophonic voice glides to a new note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum GlideMode {
+    /// Only between overlapping notes (fingered portamento); a note after a gap starts at its pitch
+    #[default]
+    Legato,
+    /// On every new note, sliding from the previous note even after a gap
+    Always,
+}
+
+/// A note held down on the monophonic voice
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct HeldMonoNote {
+    waveform: Waveform,
+    frequency: f32,
+    envelope: AdsrEnvelope,
+    volume: f32,
+}
+
+/// Frozen playback state of a `RealtimeEngine`
+///
+/// Captures every voice (waveform, phase, envelope state, sample position,
+/// noise generator), the master volume, voice allocation bookkeeping and the
+/// engine settings, so `RealtimeEngine::restore` continues rendering exactly
+/// where the snapshot was taken. Snapshots serialize with serde, e.g. to JSON
+/// for attaching to a bug report.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EngineSnapshot {
+    voices: Vec<Voice>,
+    master_volume: f32,
+    sample_rate: f32,
+    next_voice_id: u32,
+    next_generation: u64,
+    amplitude_smoothing_ms: f32,
+    phase_reset: bool,
+    sample_clock: u64,
+    group_max_voices: HashMap<u32, usize>,
+    mix_gain: f32,
+    active_slots: Vec<usize>,
+    slot_by_id: HashMap<u32, usize>,
+    sample_interpolation: Option<Interpolation>,
+    adaptive_voice_limit: bool,
+    effective_max_voices: usize,
+    note_priority: NotePriority,
+    mono_glide_secs: f32,
+    mono_glide_mode: GlideMode,
+    last_mono_frequency: Option<f32>,
+    held_mono_notes: Vec<HeldMonoNote>,
+    mono_voice: Option<u32>,
+    velocity_curve: VelocityCurve,
+    velocity_attack_scale: f32,
+    min_attack_secs: f32,
+    min_release_secs: f32,
+}
+
+impl EngineSnapshot {
+    /// Master volume when the snapshot was taken
+    pub fn master_volume(&self) -> f32 {
+        self.master_volume
+    }
+
+    /// Sample rate the snapshot was rendering at
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Number of voices that were playing
+    pub fn active_voice_count(&self) -> usize {
+        self.active_slots.len()
+    }
+
+    /// Frames the engine had rendered when the snapshot was taken
+    pub fn sample_clock(&self) -> u64 {
+        self.sample_clock
+    }
+
+    /// Whether the snapshot's voice pool and slot bookkeeping fit this engine
+    fn is_consistent(&self) -> bool {
+        self.voices.len() == MAX_VOICES
+            && self.active_slots.iter().all(|&slot| slot < MAX_VOICES)
+            && self.slot_by_id.values().all(|&slot| slot < MAX_VOICES)
+            && self.effective_max_voices <= MAX_VOICES
+            && checked_sample_rate(self.sample_rate).is_some()
+    }
+}
+
 /// Real-time polyphonic synthesis engine
 ///
 /// The core engine for real-time audio synthesis supporting up to 32 simultaneous voices.
@@ -1278,8 +3715,9 @@ impl Clone for Voice {
 ///
 /// - **Polyphonic synthesis**: Up to 32 simultaneous voices
 /// - **Voice allocation**: Automatic voice stealing when all voices are in use
-/// - **Multiple waveforms**: Sine, square, sawtooth, triangle, pulse, noise, and samples
+/// - **Multiple waveforms**: Sine, square, sawtooth, triangle, pulse, noise, plucked strings, and samples
 /// - **ADSR envelopes**: Per-voice envelope processing
+/// - **LFO modulation**: Per-voice vibrato and tremolo
 /// - **Real-time safe**: Zero-allocation audio processing
 /// - **Master volume**: Global volume control with atomic updates
 /// - **Stereo output**: Supports both mono and stereo buffer processing
@@ -1295,7 +3733,7 @@ impl Clone for Voice {
 /// # Examples
 ///
 /// ```rust
-/// use polyphonica::{RealtimeEngine, Waveform, AdsrEnvelope};
+/// use polyphonica::{RealtimeEngine, Waveform, AdsrEnvelope, EnvelopeCurve};
 ///
 /// // Create engine for 44.1kHz audio
 /// let mut engine = RealtimeEngine::new(44100.0);
@@ -1306,9 +3744,11 @@ impl Clone for Voice {
 /// // Define a piano-like envelope
 /// let envelope = AdsrEnvelope {
 ///     attack_secs: 0.01,
+///     hold_secs: 0.0,
 ///     decay_secs: 0.3,
 ///     sustain_level: 0.4,
 ///     release_secs: 0.8,
+///     curve: EnvelopeCurve::LINEAR,
 /// };
 ///
 /// // Trigger a note (returns voice ID for later control)
@@ -1334,6 +3774,14 @@ impl Clone for Voice {
 ///
 /// - Audio processing is allocation-free once voices are allocated
 /// - Voice stealing uses a simple oldest-voice strategy
+///
+/// # Voice IDs
+///
+/// Each trigger returns a voice ID for later control. IDs start at 1 and
+/// increase by one per note, wrapping from `u32::MAX` back to 1; 0 is never
+/// issued and IDs still held by active voices are skipped. Stealing follows
+/// a separate 64-bit allocation generation, so the genuinely oldest voice is
+/// stolen even after IDs wrap around.
 /// - All samples are clamped to [-1.0, 1.0] to prevent clipping
 /// - Inactive voices are automatically detected and recycled
 pub struct RealtimeEngine {
@@ -1343,12 +3791,59 @@ pub struct RealtimeEngine {
     master_volume: AtomicF32,
     /// Current sample rate
     sample_rate: f32,
-    /// Next voice ID for allocation
+    /// Most recently issued public voice ID
     next_voice_id: u32,
+    /// Allocation counter ordering voices by trigger time
+    next_generation: u64,
+    /// Ramp time for `set_voice_amplitude` changes (0.0 = instant)
+    amplitude_smoothing_ms: f32,
+    /// Whether new notes start at phase 0 (otherwise at the running phase)
+    phase_reset: bool,
+    /// Frames rendered since creation, the running phase reference
+    sample_clock: u64,
+    /// Per-group voice limits set with `set_group_max_voices`
+    group_max_voices: HashMap<u32, usize>,
+    /// Linear gain applied to the voice sum before master volume (from headroom dB)
+    mix_gain: f32,
+    /// Slots of active voices in ascending order, so mixing skips idle slots
+    active_slots: Vec<usize>,
+    /// Slot of each active voice by public voice ID
+    slot_by_id: HashMap<u32, usize>,
+    /// Voices that finished during the last processed buffer, with the frame offset
+    finished_events: Vec<(u32, usize)>,
+    /// Interpolation forced on sample-based notes (None = use each sample's own)
+    sample_interpolation: Option<Interpolation>,
+    /// Whether measured processing load lowers the voice cap
+    adaptive_voice_limit: bool,
+    /// Voice cap currently enforced by the adaptive limit (`MAX_VOICES` when idle)
+    effective_max_voices: usize,
+    /// Which held note the monophonic voice plays
+    note_priority: NotePriority,
+    /// Glide time between monophonic notes (0.0 = retrigger instead)
+    mono_glide_secs: f32,
+    /// Whether the monophonic glide needs overlapping notes
+    mono_glide_mode: GlideMode,
+    /// Pitch of the last note the monophonic voice played, the start of an `Always` glide
+    last_mono_frequency: Option<f32>,
+    /// Notes held on the monophonic voice, in press order
+    held_mono_notes: Vec<HeldMonoNote>,
+    /// Voice ID of the monophonic voice, if one has been triggered
+    mono_voice: Option<u32>,
+    /// Response curve mapping note velocity to amplitude
+    velocity_curve: VelocityCurve,
+    /// How much softer notes lengthen their attack (0.0 = velocity leaves attack alone)
+    velocity_attack_scale: f32,
+    /// Shortest attack applied to every triggered note
+    min_attack_secs: f32,
+    /// Shortest decay and release applied to every triggered note
+    min_release_secs: f32,
 }
 
 impl RealtimeEngine {
     /// Create a new real-time synthesis engine
+    ///
+    /// Zero, negative, or non-finite sample rates fall back to
+    /// `DEFAULT_SAMPLE_RATE`; rates above `MAX_SAMPLE_RATE` are clamped.
     pub fn new(sample_rate: f32) -> Self {
         // Initialize voice array
         let mut voices = Vec::with_capacity(MAX_VOICES);
@@ -1359,17 +3854,63 @@ impl RealtimeEngine {
         // Convert to fixed-size array
         let voices: [Voice; MAX_VOICES] = voices.try_into().unwrap();
 
-        RealtimeEngine {
+        let mut engine = RealtimeEngine {
             voices,
             master_volume: AtomicF32::new(1.0),
-            sample_rate,
+            sample_rate: checked_sample_rate(sample_rate).unwrap_or(DEFAULT_SAMPLE_RATE),
             next_voice_id: 0,
+            next_generation: 0,
+            amplitude_smoothing_ms: 0.0,
+            phase_reset: true,
+            sample_clock: 0,
+            group_max_voices: HashMap::new(),
+            mix_gain: 1.0,
+            active_slots: Vec::with_capacity(MAX_VOICES),
+            slot_by_id: HashMap::with_capacity(MAX_VOICES),
+            finished_events: Vec::with_capacity(MAX_VOICES),
+            sample_interpolation: None,
+            adaptive_voice_limit: false,
+            effective_max_voices: MAX_VOICES,
+            note_priority: NotePriority::default(),
+            mono_glide_secs: 0.0,
+            mono_glide_mode: GlideMode::default(),
+            last_mono_frequency: None,
+            held_mono_notes: Vec::new(),
+            mono_voice: None,
+            velocity_curve: VelocityCurve::Linear,
+            velocity_attack_scale: 0.0,
+            min_attack_secs: DEFAULT_MIN_ATTACK_SECS,
+            min_release_secs: DEFAULT_MIN_RELEASE_SECS,
+        };
+        engine.reserve_plucks();
+        engine
+    }
+
+    /// Size every voice's string delay line for the current sample rate
+    fn reserve_plucks(&mut self) {
+        for voice in &mut self.voices {
+            voice.noise.reserve_pluck(self.sample_rate);
         }
     }
 
     /// Set the sample rate (call this when audio device sample rate changes)
-    pub fn set_sample_rate(&mut self, sample_rate: f32) {
-        self.sample_rate = sample_rate;
+    ///
+    /// Returns `false` and keeps the current rate if `sample_rate` is zero,
+    /// negative, or non-finite. Rates above `MAX_SAMPLE_RATE` are clamped.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) -> bool {
+        match checked_sample_rate(sample_rate) {
+            Some(sample_rate) => {
+                self.sample_rate = sample_rate;
+                self.reserve_plucks();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the current sample rate
+    pub fn get_sample_rate(&self) -> f32 {
+        self.sample_rate
     }
 
     /// Set master volume (0.0 to 1.0)
@@ -1383,6 +3924,156 @@ impl RealtimeEngine {
         self.master_volume.load(Ordering::Relaxed)
     }
 
+    /// Set the gain applied to the summed voices before master volume and clamping
+    ///
+    /// Negative values leave headroom for dense polyphony (-6 dB halves the
+    /// mix); 0 dB, the default, leaves the mix untouched. Values are clamped
+    /// to `MIN_MIX_HEADROOM_DB..=0.0`; non-finite values are ignored.
+    pub fn set_mix_headroom_db(&mut self, headroom_db: f32) {
+        if headroom_db.is_finite() {
+            let headroom_db = headroom_db.clamp(MIN_MIX_HEADROOM_DB, 0.0);
+            self.mix_gain = 10.0_f32.powf(headroom_db / 20.0);
+        }
+    }
+
+    /// Get the mix headroom in dB
+    pub fn get_mix_headroom_db(&self) -> f32 {
+        20.0 * self.mix_gain.log10()
+    }
+
+    /// Set the interpolation used by sample-based notes triggered from now on
+    ///
+    /// `Some(mode)` overrides the interpolation configured on each note's
+    /// `SampleData`, trading CPU for pitch-shifting quality engine-wide;
+    /// `None` (the default) leaves each sample's own setting in place. Notes
+    /// already playing are unaffected; use `set_voice_interpolation` for those.
+    pub fn set_sample_interpolation(&mut self, interpolation: Option<Interpolation>) {
+        self.sample_interpolation = interpolation;
+    }
+
+    /// Get the interpolation override for new sample-based notes
+    pub fn get_sample_interpolation(&self) -> Option<Interpolation> {
+        self.sample_interpolation
+    }
+
+    /// Capture the engine's complete playback state
+    ///
+    /// Restoring the snapshot later, on this or another engine, makes the
+    /// following buffers render bit-for-bit as they would have from here.
+    /// Finished voices are stored with a plain sine waveform, so a custom
+    /// oscillator that has stopped playing does not keep the snapshot from
+    /// serializing. Allocates; call it outside the audio callback.
+    pub fn snapshot(&self) -> EngineSnapshot {
+        let voices = self
+            .voices
+            .iter()
+            .map(|voice| {
+                let mut voice = voice.clone();
+                if !voice.is_active() {
+                    voice.waveform = Waveform::Sine;
+                    voice.follow_up = None;
+                }
+                voice
+            })
+            .collect();
+        EngineSnapshot {
+            voices,
+            master_volume: self.get_master_volume(),
+            sample_rate: self.sample_rate,
+            next_voice_id: self.next_voice_id,
+            next_generation: self.next_generation,
+            amplitude_smoothing_ms: self.amplitude_smoothing_ms,
+            phase_reset: self.phase_reset,
+            sample_clock: self.sample_clock,
+            group_max_voices: self.group_max_voices.clone(),
+            mix_gain: self.mix_gain,
+            active_slots: self.active_slots.clone(),
+            slot_by_id: self.slot_by_id.clone(),
+            sample_interpolation: self.sample_interpolation,
+            adaptive_voice_limit: self.adaptive_voice_limit,
+            effective_max_voices: self.effective_max_voices,
+            note_priority: self.note_priority,
+            mono_glide_secs: self.mono_glide_secs,
+            mono_glide_mode: self.mono_glide_mode,
+            last_mono_frequency: self.last_mono_frequency,
+            held_mono_notes: self.held_mono_notes.clone(),
+            mono_voice: self.mono_voice,
+            velocity_curve: self.velocity_curve.clone(),
+            velocity_attack_scale: self.velocity_attack_scale,
+            min_attack_secs: self.min_attack_secs,
+            min_release_secs: self.min_release_secs,
+        }
+    }
+
+    /// Reinstate a state captured with `snapshot`
+    ///
+    /// Returns `false` and leaves the engine unchanged if the snapshot does
+    /// not describe a valid engine (e.g. a hand-edited or truncated file).
+    pub fn restore(&mut self, snapshot: &EngineSnapshot) -> bool {
+        if !snapshot.is_consistent() {
+            return false;
+        }
+
+        for (voice, saved) in self.voices.iter_mut().zip(&snapshot.voices) {
+            *voice = saved.clone();
+        }
+        self.set_master_volume(snapshot.master_volume);
+        self.sample_rate = snapshot.sample_rate;
+        self.next_voice_id = snapshot.next_voice_id;
+        self.next_generation = snapshot.next_generation;
+        self.amplitude_smoothing_ms = snapshot.amplitude_smoothing_ms;
+        self.phase_reset = snapshot.phase_reset;
+        self.sample_clock = snapshot.sample_clock;
+        self.group_max_voices = snapshot.group_max_voices.clone();
+        self.mix_gain = snapshot.mix_gain;
+        self.active_slots.clone_from(&snapshot.active_slots);
+        self.slot_by_id.clone_from(&snapshot.slot_by_id);
+        self.finished_events.clear();
+        self.sample_interpolation = snapshot.sample_interpolation;
+        self.adaptive_voice_limit = snapshot.adaptive_voice_limit;
+        self.effective_max_voices = snapshot.effective_max_voices;
+        self.note_priority = snapshot.note_priority;
+        self.mono_glide_secs = snapshot.mono_glide_secs;
+        self.mono_glide_mode = snapshot.mono_glide_mode;
+        self.last_mono_frequency = snapshot.last_mono_frequency;
+        self.held_mono_notes.clone_from(&snapshot.held_mono_notes);
+        self.mono_voice = snapshot.mono_voice;
+        self.velocity_curve = snapshot.velocity_curve.clone();
+        self.velocity_attack_scale = snapshot.velocity_attack_scale;
+        self.min_attack_secs = snapshot.min_attack_secs;
+        self.min_release_secs = snapshot.min_release_secs;
+        self.reserve_plucks();
+        true
+    }
+
+    /// Change the interpolation of a playing sample-based voice
+    ///
+    /// Takes effect from the next processed sample. Oscillator and noise
+    /// voices are unaffected.
+    pub fn set_voice_interpolation(&mut self, voice_id: u32, interpolation: Interpolation) {
+        if let Some(index) = self.active_slot(voice_id) {
+            self.voices[index].set_interpolation(interpolation);
+        }
+    }
+
+    /// Chain a sample to start on a playing voice when its one-shot sample ends
+    ///
+    /// Once the voice's `DrumSample` or pitched `Sample` (without loop points)
+    /// plays past its last frame, `follow_up` takes over on the same voice and
+    /// envelope from the very next output sample, so an attack sample can run
+    /// straight into a looping body. A follow-up may itself be a one-shot
+    /// sample, but only one follow-up is held per voice. Returns false if the
+    /// voice is not playing.
+    pub fn chain_sample(&mut self, voice_id: u32, follow_up: Waveform) -> bool {
+        match self.active_slot(voice_id) {
+            Some(index) => {
+                self.voices[index].follow_up = Some(follow_up);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Trigger a new note (finds an available voice)
     pub fn trigger_note(
         &mut self,
@@ -1390,25 +4081,10 @@ impl RealtimeEngine {
         frequency: f32,
         envelope: AdsrEnvelope,
     ) -> Option<u32> {
-        // First, try to find an inactive voice
-        for voice in &mut self.voices {
-            if !voice.is_active() {
-                voice.trigger_note(waveform, frequency, envelope);
-                self.next_voice_id += 1;
-                voice.voice_id = self.next_voice_id;
-                return Some(voice.voice_id);
-            }
-        }
-
-        // If no inactive voice found, steal the oldest voice (voice stealing)
-        if let Some(oldest_voice) = self.voices.iter_mut().min_by_key(|v| v.voice_id) {
-            oldest_voice.trigger_note(waveform, frequency, envelope);
-            self.next_voice_id += 1;
-            oldest_voice.voice_id = self.next_voice_id;
-            Some(oldest_voice.voice_id)
-        } else {
-            None
-        }
+        let index = self.allocate_voice(None);
+        self.voices[index].trigger_note(waveform, frequency, envelope);
+        self.apply_start_phase(index);
+        Some(self.assign_voice_id(index, None))
     }
 
     /// Trigger a new note with volume control (finds an available voice)
@@ -1419,148 +4095,793 @@ impl RealtimeEngine {
         envelope: AdsrEnvelope,
         volume: f32,
     ) -> Option<u32> {
-        // First, try to find an inactive voice
-        for voice in &mut self.voices {
-            if !voice.is_active() {
-                voice.trigger_note_with_volume(waveform, frequency, envelope, volume);
-                self.next_voice_id += 1;
-                voice.voice_id = self.next_voice_id;
-                return Some(voice.voice_id);
-            }
-        }
+        let index = self.allocate_voice(None);
+        self.voices[index].trigger_note_with_volume(waveform, frequency, envelope, volume);
+        self.apply_start_phase(index);
+        Some(self.assign_voice_id(index, None))
+    }
 
-        // If no inactive voice found, steal the oldest voice (voice stealing)
-        if let Some(oldest_voice) = self.voices.iter_mut().min_by_key(|v| v.voice_id) {
-            oldest_voice.trigger_note_with_volume(waveform, frequency, envelope, volume);
-            self.next_voice_id += 1;
-            oldest_voice.voice_id = self.next_voice_id;
-            Some(oldest_voice.voice_id)
-        } else {
-            None
-        }
+    /// Trigger a new note with a MIDI-style velocity (0-127)
+    ///
+    /// The velocity is mapped through the engine's velocity curve (see
+    /// `set_velocity_curve`) to the note's volume, so 127 plays at unity gain.
+    /// With a velocity attack scale set, softer notes also get a longer attack.
+    pub fn trigger_note_with_velocity(
+        &mut self,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        velocity: u8,
+    ) -> Option<u32> {
+        let velocity = velocity.min(MAX_MIDI_VELOCITY) as f32 / MAX_MIDI_VELOCITY as f32;
+        let volume = self.velocity_curve.apply(velocity);
+        let envelope = AdsrEnvelope {
+            attack_secs: envelope.attack_secs * (1.0 + self.velocity_attack_scale * (1.0 - volume)),
+            ..envelope
+        };
+        self.trigger_note_with_volume(waveform, frequency, envelope, volume)
     }
 
-    /// Release a specific note by voice ID
-    pub fn release_note(&mut self, voice_id: u32) {
-        for voice in &mut self.voices {
-            if voice.voice_id == voice_id && voice.is_active() {
-                voice.release_note();
-                break;
-            }
-        }
+    /// Set the response curve used by `trigger_note_with_velocity`
+    pub fn set_velocity_curve(&mut self, curve: VelocityCurve) {
+        self.velocity_curve = curve;
     }
 
-    /// Release all currently active notes
-    pub fn release_all_notes(&mut self) {
-        for voice in &mut self.voices {
-            if voice.is_active() {
-                voice.release_note();
-            }
-        }
+    /// Get the response curve used by `trigger_note_with_velocity`
+    pub fn get_velocity_curve(&self) -> &VelocityCurve {
+        &self.velocity_curve
     }
 
-    /// Stop all notes immediately (for panic button)
-    pub fn stop_all_notes(&mut self) {
-        for voice in &mut self.voices {
-            voice.reset();
-        }
+    /// Set how much softer notes lengthen their attack
+    ///
+    /// A silent-velocity note's attack is stretched by `1.0 + scale`, scaling
+    /// down to no change at full gain. Negative values are treated as 0.0.
+    pub fn set_velocity_attack_scale(&mut self, scale: f32) {
+        self.velocity_attack_scale = scale.max(0.0);
     }
 
-    /// Get number of currently active voices
-    pub fn get_active_voice_count(&self) -> usize {
-        self.voices.iter().filter(|v| v.is_active()).count()
+    /// Get how much softer notes lengthen their attack
+    pub fn get_velocity_attack_scale(&self) -> f32 {
+        self.velocity_attack_scale
     }
 
-    /// Process a buffer of audio samples (CPAL-compatible interface)
-    pub fn process_buffer(&mut self, output: &mut [f32]) {
-        let master_vol = self.master_volume.load(Ordering::Relaxed);
+    /// Set the shortest attack of notes triggered from now on
+    ///
+    /// Shorter attacks, including instant ones, are lengthened to this so a
+    /// note never starts with a full-scale step. The default is
+    /// `DEFAULT_MIN_ATTACK_SECS`; 0.0 plays envelopes exactly as given.
+    /// Negative or non-finite values are treated as 0.0.
+    pub fn set_min_attack_secs(&mut self, secs: f32) {
+        self.min_attack_secs = if secs.is_finite() { secs.max(0.0) } else { 0.0 };
+    }
 
-        for sample in output.iter_mut() {
-            let mut mixed_sample = 0.0;
+    /// Get the shortest attack of triggered notes
+    pub fn get_min_attack_secs(&self) -> f32 {
+        self.min_attack_secs
+    }
 
-            // Mix all active voices
-            for voice in &mut self.voices {
-                if voice.is_active() {
-                    mixed_sample += voice.process_sample(self.sample_rate);
-                }
-            }
+    /// Set the shortest decay and release of notes triggered from now on
+    ///
+    /// Like `set_min_attack_secs`, but for the falling edges: the release,
+    /// and the decay, which ends notes with a low sustain level. The default
+    /// is `DEFAULT_MIN_RELEASE_SECS`.
+    pub fn set_min_release_secs(&mut self, secs: f32) {
+        self.min_release_secs = if secs.is_finite() { secs.max(0.0) } else { 0.0 };
+    }
 
-            // Apply master volume and clipping prevention
-            *sample = (mixed_sample * master_vol).clamp(-1.0, 1.0);
-        }
+    /// Get the shortest decay and release of triggered notes
+    pub fn get_min_release_secs(&self) -> f32 {
+        self.min_release_secs
     }
 
-    /// Process interleaved stereo buffer (common CPAL format)
-    pub fn process_stereo_buffer(&mut self, output: &mut [f32]) {
-        assert!(output.len() % 2 == 0, "Stereo buffer must have even length");
+    /// Trigger a new note starting at an explicit oscillator phase
+    ///
+    /// `phase` is in radians and wrapped to 0..2π. It overrides the engine's
+    /// phase reset setting for this note, so phase-offset oscillators can be
+    /// layered into a single tone.
+    pub fn trigger_note_with_phase(
+        &mut self,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        phase: f32,
+    ) -> Option<u32> {
+        let index = self.allocate_voice(None);
+        self.voices[index].trigger_note(waveform, frequency, envelope);
+        self.voices[index].phase = phase.rem_euclid(2.0 * PI);
+        Some(self.assign_voice_id(index, None))
+    }
 
-        let master_vol = self.master_volume.load(Ordering::Relaxed);
+    /// Trigger a note in a voice group
+    ///
+    /// When the group is at its limit (see `set_group_max_voices`), the
+    /// group's oldest note is choked to make room instead of stealing from
+    /// the whole engine.
+    pub fn trigger_note_in_group(
+        &mut self,
+        group: u32,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        volume: f32,
+    ) -> Option<u32> {
+        let index = self.allocate_voice(Some(group));
+        self.voices[index].trigger_note_with_volume(waveform, frequency, envelope, volume);
+        self.apply_start_phase(index);
+        Some(self.assign_voice_id(index, Some(group)))
+    }
 
-        for chunk in output.chunks_exact_mut(2) {
-            let mut mixed_sample = 0.0;
+    /// Trigger a note in a choke group
+    ///
+    /// Any note still sounding in the same choke group is faded out over
+    /// `CHOKE_FADE_SECS` first, the way closing a hi-hat cuts off the open
+    /// hat. Unlike group voice limits, this applies whatever the count.
+    pub fn trigger_note_in_choke_group(
+        &mut self,
+        choke_group: u32,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        volume: f32,
+    ) -> Option<u32> {
+        self.choke(choke_group);
+        let index = self.allocate_voice(None);
+        self.voices[index].trigger_note_with_volume(waveform, frequency, envelope, volume);
+        self.apply_start_phase(index);
+        let voice_id = self.assign_voice_id(index, None);
+        self.voices[index].choke_group = Some(choke_group);
+        Some(voice_id)
+    }
 
-            // Mix all active voices
-            for voice in &mut self.voices {
-                if voice.is_active() {
-                    mixed_sample += voice.process_sample(self.sample_rate);
-                }
+    /// Fade out every note playing in a choke group
+    pub fn choke(&mut self, choke_group: u32) {
+        for &index in &self.active_slots {
+            let voice = &mut self.voices[index];
+            if voice.choke_group == Some(choke_group) {
+                voice.choke(CHOKE_FADE_SECS);
             }
+        }
+    }
 
-            // Apply master volume and clipping prevention
-            let final_sample = (mixed_sample * master_vol).clamp(-1.0, 1.0);
-
-            // Copy mono signal to both stereo channels
-            chunk[0] = final_sample; // Left
-            chunk[1] = final_sample; // Right
+    /// Limit how many notes a voice group can play at once (0 = no limit)
+    ///
+    /// Notes already playing beyond a lowered limit keep ringing; the next
+    /// note triggered in the group chokes the oldest one.
+    pub fn set_group_max_voices(&mut self, group: u32, max_voices: usize) {
+        if max_voices == 0 {
+            self.group_max_voices.remove(&group);
+        } else {
+            self.group_max_voices.insert(group, max_voices);
         }
     }
 
-    /// Convenience method for triggering multiple notes at once (chords)
-    pub fn trigger_chord(&mut self, notes: &[(Waveform, f32)], envelope: AdsrEnvelope) -> Vec<u32> {
-        let mut voice_ids = Vec::new();
-        for (waveform, frequency) in notes {
-            if let Some(voice_id) =
-                self.trigger_note(waveform.clone(), *frequency, envelope.clone())
-            {
-                voice_ids.push(voice_id);
-            }
+    /// Get the voice limit of a group, if one is set
+    pub fn get_group_max_voices(&self, group: u32) -> Option<usize> {
+        self.group_max_voices.get(&group).copied()
+    }
+
+    /// Enable or disable the CPU-budget voice limiter
+    ///
+    /// When enabled, `report_processing_time` lowers the number of voices
+    /// the engine will play while processing approaches the buffer's time
+    /// budget, and raises it again as load falls. Disabling restores the full
+    /// `MAX_VOICES` immediately.
+    pub fn set_adaptive_voice_limit(&mut self, enabled: bool) {
+        self.adaptive_voice_limit = enabled;
+        if !enabled {
+            self.effective_max_voices = MAX_VOICES;
         }
-        voice_ids
     }
 
-    /// Update voice parameters for real-time modulation
-    pub fn set_voice_frequency(&mut self, voice_id: u32, frequency: f32) {
-        for voice in &mut self.voices {
-            if voice.voice_id == voice_id && voice.is_active() {
-                voice.frequency = frequency;
-                break;
-            }
+    /// Whether the CPU-budget voice limiter is enabled
+    pub fn get_adaptive_voice_limit(&self) -> bool {
+        self.adaptive_voice_limit
+    }
+
+    /// Get the voice cap currently enforced by the adaptive limit
+    pub fn get_effective_max_voices(&self) -> usize {
+        self.effective_max_voices
+    }
+
+    /// Feed the time spent rendering one buffer to the adaptive voice limit
+    ///
+    /// `processing_secs` is how long the audio callback took and `buffer_secs`
+    /// how much audio it produced. Above `CPU_LOAD_HIGH` of the budget the cap
+    /// drops to three quarters of the voices sounding (never below
+    /// `MIN_ADAPTIVE_VOICES`) and the quietest voices beyond it fade out over
+    /// `CHOKE_FADE_SECS`; below `CPU_LOAD_LOW` the cap recovers by one voice
+    /// per report. Ignored while the limiter is disabled or the times are invalid.
+    pub fn report_processing_time(&mut self, processing_secs: f32, buffer_secs: f32) {
+        let valid = buffer_secs > 0.0 && processing_secs.is_finite();
+        if !self.adaptive_voice_limit || !valid {
+            return;
+        }
+
+        let load = processing_secs / buffer_secs;
+        if load > CPU_LOAD_HIGH {
+            let sounding = self.active_slots.len().min(self.effective_max_voices);
+            self.effective_max_voices = (sounding * 3 / 4).max(MIN_ADAPTIVE_VOICES);
+            self.shed_voices();
+        } else if load < CPU_LOAD_LOW {
+            self.effective_max_voices = (self.effective_max_voices + 1).min(MAX_VOICES);
         }
     }
 
-    /// Set voice amplitude for real-time volume control
-    pub fn set_voice_amplitude(&mut self, voice_id: u32, amplitude: f32) {
-        for voice in &mut self.voices {
-            if voice.voice_id == voice_id && voice.is_active() {
-                voice.amplitude = amplitude.clamp(0.0, 1.0);
-                break;
+    /// Fade out the quietest voices until no more than the effective cap remain
+    fn shed_voices(&mut self) {
+        let is_fading = |voice: &Voice| {
+            voice.envelope_state.phase == EnvelopePhase::Release
+                && voice.envelope.release_secs <= CHOKE_FADE_SECS
+        };
+
+        let sounding = self
+            .active_voices()
+            .filter(|(_, voice)| !is_fading(voice))
+            .count();
+        for _ in self.effective_max_voices..sounding {
+            let quietest = self
+                .active_voices()
+                .filter(|(_, voice)| !is_fading(voice))
+                .min_by(|(_, a), (_, b)| a.output_level().total_cmp(&b.output_level()))
+                .map(|(index, _)| index);
+            if let Some(index) = quietest {
+                self.voices[index].choke(CHOKE_FADE_SECS);
             }
         }
     }
-}
 
-/// Thread-safe wrapper for shared access to RealtimeEngine.
-///
-/// Provides mutex-protected access to a RealtimeEngine instance for
-/// multi-threaded audio applications. Use this when the engine needs
-/// to be shared between audio and UI threads.
-pub type SharedRealtimeEngine = Arc<std::sync::Mutex<RealtimeEngine>>;
+    /// Get number of currently active voices in a group
+    pub fn get_group_voice_count(&self, group: u32) -> usize {
+        self.active_voices()
+            .filter(|(_, voice)| voice.group == Some(group))
+            .count()
+    }
 
-impl Default for RealtimeEngine {
-    fn default() -> Self {
-        Self::new(44100.0)
+    /// Choose whether new notes start at phase 0 (the default)
+    ///
+    /// With phase reset off, a note starts at the phase a free-running
+    /// oscillator at its frequency would have reached since the engine was
+    /// created. Notes of the same frequency then keep a fixed phase
+    /// relationship however they are triggered, so layered or unison voices
+    /// sum the same way every time.
+    pub fn set_phase_reset(&mut self, phase_reset: bool) {
+        self.phase_reset = phase_reset;
     }
-}
+
+    /// Whether new notes start at phase 0
+    pub fn get_phase_reset(&self) -> bool {
+        self.phase_reset
+    }
+
+    /// Start a freshly triggered voice at the running phase when phase reset is off
+    fn apply_start_phase(&mut self, index: usize) {
+        if self.phase_reset {
+            return;
+        }
+
+        // f64 keeps the product exact enough over hours of running time
+        let voice = &mut self.voices[index];
+        let cycles = voice.frequency as f64 * self.sample_clock as f64 / self.sample_rate as f64;
+        voice.phase = (cycles.fract() * 2.0 * std::f64::consts::PI) as f32;
+    }
+
+    /// Pick the voice slot for a new note
+    ///
+    /// Prefers an inactive voice; otherwise steals the voice with the lowest
+    /// allocation generation (the oldest note). A group at its voice limit
+    /// reuses its own oldest voice instead.
+    fn allocate_voice(&self, group: Option<u32>) -> usize {
+        if let Some(group) = group {
+            let limit = self.get_group_max_voices(group).unwrap_or(MAX_VOICES);
+            if self.get_group_voice_count(group) >= limit {
+                if let Some((index, _)) = self
+                    .active_voices()
+                    .filter(|(_, voice)| voice.group == Some(group))
+                    .min_by_key(|(_, voice)| voice.generation)
+                {
+                    return index;
+                }
+            }
+        }
+
+        // Under the adaptive limit, the quietest voice makes room
+        if self.effective_max_voices < MAX_VOICES
+            && self.active_slots.len() >= self.effective_max_voices
+        {
+            if let Some((index, _)) = self
+                .active_voices()
+                .min_by(|(_, a), (_, b)| a.output_level().total_cmp(&b.output_level()))
+            {
+                return index;
+            }
+        }
+
+        // Active slots are sorted, so the first gap is the lowest free slot
+        if let Some(index) =
+            (0..MAX_VOICES).find(|&index| self.active_slots.get(index) != Some(&index))
+        {
+            return index;
+        }
+
+        self.active_voices()
+            .min_by_key(|(_, voice)| voice.generation)
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// Active voices with their slot index, in slot order
+    fn active_voices(&self) -> impl Iterator<Item = (usize, &Voice)> + '_ {
+        self.active_slots
+            .iter()
+            .map(|&index| (index, &self.voices[index]))
+    }
+
+    /// Slot of the active voice with a public ID
+    fn active_slot(&self, voice_id: u32) -> Option<usize> {
+        self.slot_by_id
+            .get(&voice_id)
+            .copied()
+            .filter(|&index| self.voices[index].is_active())
+    }
+
+    /// Drop voices that finished during processing from the active bookkeeping
+    fn prune_finished_voices(&mut self) {
+        let voices = &self.voices;
+        let slot_by_id = &mut self.slot_by_id;
+        self.active_slots.retain(|&index| {
+            let active = voices[index].is_active();
+            if !active {
+                slot_by_id.remove(&voices[index].voice_id);
+            }
+            active
+        });
+    }
+
+    /// Apply engine note settings to a freshly triggered voice and give it a new public ID
+    fn assign_voice_id(&mut self, index: usize, group: Option<u32>) -> u32 {
+        if let Some(interpolation) = self.sample_interpolation {
+            self.voices[index].set_interpolation(interpolation);
+        }
+        let envelope = &mut self.voices[index].envelope;
+        envelope.attack_secs = envelope.attack_secs.max(self.min_attack_secs);
+        envelope.decay_secs = envelope.decay_secs.max(self.min_release_secs);
+        envelope.release_secs = envelope.release_secs.max(self.min_release_secs);
+        self.next_generation += 1;
+        self.voices[index].generation = self.next_generation;
+        self.voices[index].group = group;
+        self.voices[index].choke_group = None;
+
+        // A stolen voice gives up its old ID
+        let previous_id = self.voices[index].voice_id;
+        if self.slot_by_id.get(&previous_id) == Some(&index) {
+            self.slot_by_id.remove(&previous_id);
+        }
+        if let Err(position) = self.active_slots.binary_search(&index) {
+            self.active_slots.insert(position, index);
+        }
+
+        let mut voice_id = self.next_voice_id;
+        loop {
+            voice_id = voice_id.wrapping_add(1).max(1);
+            let in_use = self.active_slot(voice_id).is_some_and(|slot| slot != index);
+            if !in_use {
+                break;
+            }
+        }
+
+        self.next_voice_id = voice_id;
+        self.voices[index].voice_id = voice_id;
+        self.slot_by_id.insert(voice_id, index);
+        voice_id
+    }
+
+    /// Release a specific note by voice ID
+    pub fn release_note(&mut self, voice_id: u32) {
+        if let Some(index) = self.active_slot(voice_id) {
+            self.voices[index].release_note();
+        }
+    }
+
+    /// Release all currently active notes
+    pub fn release_all_notes(&mut self) {
+        for &index in &self.active_slots {
+            self.voices[index].release_note();
+        }
+    }
+
+    /// Stop all notes immediately (for panic button)
+    pub fn stop_all_notes(&mut self) {
+        for voice in &mut self.voices {
+            voice.reset();
+        }
+        self.active_slots.clear();
+        self.slot_by_id.clear();
+        self.held_mono_notes.clear();
+        self.mono_voice = None;
+        self.last_mono_frequency = None;
+    }
+
+    /// Return the engine to the state of a freshly created one
+    ///
+    /// Clears all playback state: every voice is silenced immediately and held
+    /// monophonic notes are dropped, voice IDs restart at 1, the running phase
+    /// clock and finished-voice events are cleared, master volume returns to
+    /// 1.0, and the adaptive voice cap is lifted. Configuration is preserved:
+    /// the sample rate, the voice capacity (`MAX_VOICES`), mix headroom,
+    /// amplitude smoothing, phase reset mode, per-group voice limits, the
+    /// sample interpolation override, whether the adaptive voice limit is
+    /// enabled, the monophonic priority and glide time and mode, the velocity
+    /// curve and velocity attack scale, and the minimum attack and release.
+    pub fn reset(&mut self) {
+        self.stop_all_notes();
+        self.next_voice_id = 0;
+        self.next_generation = 0;
+        self.sample_clock = 0;
+        self.finished_events.clear();
+        self.set_master_volume(1.0);
+        self.effective_max_voices = MAX_VOICES;
+    }
+
+    /// Get number of currently active voices
+    pub fn get_active_voice_count(&self) -> usize {
+        self.active_slots.len()
+    }
+
+    /// Voices that finished during the last processed buffer
+    ///
+    /// Each entry is a voice ID and the frame offset within that buffer of the
+    /// first silent sample, so hosts can chain follow-on events sample-accurately.
+    /// The list is replaced on every `process_buffer` or `process_stereo_buffer`
+    /// call. Voices cut off by `stop_all_notes` or stolen for a new note are not
+    /// reported.
+    pub fn finished_events(&self) -> Vec<(u32, usize)> {
+        self.finished_events.clone()
+    }
+
+    /// Advance every active voice by one sample and return the clamped mix
+    ///
+    /// Voices that finish on this sample are recorded in `finished_events`
+    /// at the given frame offset.
+    fn mix_next_sample(&mut self, frame: usize, master_vol: f32) -> f32 {
+        let mut mixed_sample = 0.0;
+
+        // Mix all active voices
+        for &index in &self.active_slots {
+            let voice = &mut self.voices[index];
+            if voice.is_active() {
+                mixed_sample += voice.process_sample(self.sample_rate);
+                if !voice.is_active() {
+                    self.finished_events.push((voice.voice_id, frame));
+                }
+            }
+        }
+
+        // Apply master volume and clipping prevention
+        (mixed_sample * self.mix_gain * master_vol).clamp(-1.0, 1.0)
+    }
+
+    /// Mix the next (left, right) frame of all active voices, recording voices that finish
+    fn mix_next_frame(&mut self, frame: usize, master_vol: f32) -> (f32, f32) {
+        let mut left = 0.0;
+        let mut right = 0.0;
+
+        for &index in &self.active_slots {
+            let voice = &mut self.voices[index];
+            if voice.is_active() {
+                let (voice_left, voice_right) = voice.process_stereo_sample(self.sample_rate);
+                left += voice_left;
+                right += voice_right;
+                if !voice.is_active() {
+                    self.finished_events.push((voice.voice_id, frame));
+                }
+            }
+        }
+
+        let gain = self.mix_gain * master_vol;
+        (
+            (left * gain).clamp(-1.0, 1.0),
+            (right * gain).clamp(-1.0, 1.0),
+        )
+    }
+
+    /// Advance the engine by exactly one sample and return the mixed output
+    ///
+    /// Equivalent to `process_buffer` on a one-sample buffer, so N calls
+    /// produce the same audio as one N-sample buffer. Intended for tests and
+    /// sample-accurate algorithms; use the buffer methods for playback.
+    pub fn process_sample_mixed(&mut self) -> f32 {
+        let mut output = [0.0];
+        self.process_buffer(&mut output);
+        output[0]
+    }
+
+    /// Advance the engine by exactly one sample and return the (left, right) output
+    ///
+    /// Equivalent to `process_stereo_buffer` on a one-frame buffer.
+    pub fn process_sample_stereo(&mut self) -> (f32, f32) {
+        let mut output = [0.0; 2];
+        self.process_stereo_buffer(&mut output);
+        (output[0], output[1])
+    }
+
+    /// Process a buffer of audio samples (CPAL-compatible interface)
+    pub fn process_buffer(&mut self, output: &mut [f32]) {
+        if output.is_empty() {
+            return;
+        }
+
+        let master_vol = self.master_volume.load(Ordering::Relaxed);
+        self.finished_events.clear();
+
+        for (frame, sample) in output.iter_mut().enumerate() {
+            *sample = self.mix_next_sample(frame, master_vol);
+        }
+
+        self.sample_clock += output.len() as u64;
+        self.prune_finished_voices();
+    }
+
+    /// Render a single voice in isolation for analysis or debugging
+    ///
+    /// Works on a copy of the voice, so the engine's playback state is not
+    /// advanced: the buffer previews what the voice will contribute to the
+    /// next `output.len()` samples of the mix (including mix headroom and master
+    /// volume, before the final clamp). Returns `false` and leaves `output` untouched if no
+    /// active voice has the given ID.
+    pub fn process_voice_buffer(&self, voice_id: u32, output: &mut [f32]) -> bool {
+        let Some(index) = self.active_slot(voice_id) else {
+            return false;
+        };
+
+        let master_vol = self.master_volume.load(Ordering::Relaxed);
+        let mut voice = self.voices[index].clone();
+        for sample in output.iter_mut() {
+            *sample = voice.process_sample(self.sample_rate) * self.mix_gain * master_vol;
+        }
+        true
+    }
+
+    /// Process interleaved stereo buffer (common CPAL format)
+    ///
+    /// Voices playing stereo samples feed the left and right channels
+    /// independently; all other voices are centred.
+    pub fn process_stereo_buffer(&mut self, output: &mut [f32]) {
+        assert!(output.len() % 2 == 0, "Stereo buffer must have even length");
+        if output.is_empty() {
+            return;
+        }
+
+        let master_vol = self.master_volume.load(Ordering::Relaxed);
+        self.finished_events.clear();
+
+        for (frame, chunk) in output.chunks_exact_mut(2).enumerate() {
+            let (left, right) = self.mix_next_frame(frame, master_vol);
+            chunk[0] = left;
+            chunk[1] = right;
+        }
+
+        self.sample_clock += (output.len() / 2) as u64;
+        self.prune_finished_voices();
+    }
+
+    /// Convenience method for triggering multiple notes at once (chords)
+    pub fn trigger_chord(&mut self, notes: &[(Waveform, f32)], envelope: AdsrEnvelope) -> Vec<u32> {
+        let mut voice_ids = Vec::new();
+        for (waveform, frequency) in notes {
+            if let Some(voice_id) =
+                self.trigger_note(waveform.clone(), *frequency, envelope.clone())
+            {
+                voice_ids.push(voice_id);
+            }
+        }
+        voice_ids
+    }
+
+    /// Set which held note the monophonic voice plays
+    pub fn set_note_priority(&mut self, priority: NotePriority) {
+        self.note_priority = priority;
+        self.update_mono_voice();
+    }
+
+    /// Get the monophonic note priority
+    pub fn get_note_priority(&self) -> NotePriority {
+        self.note_priority
+    }
+
+    /// Set the monophonic glide time (0.0 = retrigger each new note)
+    ///
+    /// With a glide, a change of held note slides the sounding voice to the
+    /// new pitch without restarting its envelope.
+    pub fn set_mono_glide_secs(&mut self, glide_secs: f32) {
+        self.mono_glide_secs = glide_secs.max(0.0);
+    }
+
+    /// Get the monophonic glide time in seconds
+    pub fn get_mono_glide_secs(&self) -> f32 {
+        self.mono_glide_secs
+    }
+
+    /// Set whether the monophonic glide needs overlapping notes
+    ///
+    /// `Legato` (the default) glides only while a previous note is still
+    /// held, like a mono synth's fingered portamento; `Always` also slides
+    /// into a note played after a gap.
+    pub fn set_mono_glide_mode(&mut self, mode: GlideMode) {
+        self.mono_glide_mode = mode;
+    }
+
+    /// Get the monophonic glide mode
+    pub fn get_mono_glide_mode(&self) -> GlideMode {
+        self.mono_glide_mode
+    }
+
+    /// Press a note on the monophonic voice
+    ///
+    /// The note joins the held notes and the note priority decides which of
+    /// them sounds. Returns the ID of the monophonic voice.
+    pub fn press_mono_note(
+        &mut self,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        volume: f32,
+    ) -> Option<u32> {
+        self.held_mono_notes.retain(|n| n.frequency != frequency);
+        self.held_mono_notes.push(HeldMonoNote {
+            waveform,
+            frequency,
+            envelope,
+            volume,
+        });
+        self.update_mono_voice();
+        self.mono_voice
+    }
+
+    /// Release a held monophonic note by frequency
+    ///
+    /// If other notes are still held the voice moves to the one the priority
+    /// selects; releasing the last held note releases the voice.
+    pub fn release_mono_note(&mut self, frequency: f32) {
+        let held = self.held_mono_notes.len();
+        self.held_mono_notes.retain(|n| n.frequency != frequency);
+        if self.held_mono_notes.len() == held {
+            return;
+        }
+
+        if self.held_mono_notes.is_empty() {
+            if let Some(voice_id) = self.mono_voice {
+                self.release_note(voice_id);
+            }
+        } else {
+            self.update_mono_voice();
+        }
+    }
+
+    /// Frequency of the held note the monophonic voice plays, if any
+    pub fn get_mono_note(&self) -> Option<f32> {
+        self.selected_mono_note().map(|note| note.frequency)
+    }
+
+    /// Held note chosen by the note priority
+    fn selected_mono_note(&self) -> Option<&HeldMonoNote> {
+        let notes = self.held_mono_notes.iter();
+        match self.note_priority {
+            NotePriority::Last => notes.last(),
+            NotePriority::High => notes.max_by(|a, b| a.frequency.total_cmp(&b.frequency)),
+            NotePriority::Low => notes.min_by(|a, b| a.frequency.total_cmp(&b.frequency)),
+        }
+    }
+
+    /// Move the monophonic voice to the selected held note
+    ///
+    /// Glides when a glide time is set and the voice is still held,
+    /// otherwise retriggers the note on the voice's slot. In `Always` glide
+    /// mode the retriggered note slides in from the previous note's pitch.
+    fn update_mono_voice(&mut self) {
+        let Some(note) = self.selected_mono_note().cloned() else {
+            return;
+        };
+        let previous_frequency = self.last_mono_frequency.replace(note.frequency);
+        let glide_samples = (self.mono_glide_secs * self.sample_rate) as u32;
+
+        let slot = self
+            .mono_voice
+            .and_then(|voice_id| self.active_slot(voice_id));
+        let held_slot =
+            slot.filter(|&index| self.voices[index].envelope_state.phase != EnvelopePhase::Release);
+        if let Some(index) = held_slot {
+            if self.voices[index].target_frequency == note.frequency {
+                return;
+            }
+            if self.mono_glide_secs > 0.0 {
+                self.voices[index].glide_to(note.frequency, glide_samples);
+                return;
+            }
+        }
+
+        let index = slot.unwrap_or_else(|| self.allocate_voice(None));
+        self.voices[index].trigger_note_with_volume(
+            note.waveform,
+            note.frequency,
+            note.envelope,
+            note.volume,
+        );
+        if self.mono_glide_mode == GlideMode::Always && self.mono_glide_secs > 0.0 {
+            if let Some(previous) = previous_frequency {
+                self.voices[index].frequency = previous;
+                self.voices[index].glide_to(note.frequency, glide_samples);
+            }
+        }
+        self.apply_start_phase(index);
+        self.mono_voice = Some(self.assign_voice_id(index, None));
+    }
+
+    /// Update voice parameters for real-time modulation
+    pub fn set_voice_frequency(&mut self, voice_id: u32, frequency: f32) {
+        if let Some(index) = self.active_slot(voice_id) {
+            self.voices[index].glide_to(frequency, 0);
+        }
+    }
+
+    /// Attach an LFO to a playing voice for vibrato or tremolo
+    ///
+    /// Replaces any LFO already on that target; the modulation lasts until
+    /// the note ends or the voice is retriggered.
+    pub fn set_voice_lfo(&mut self, voice_id: u32, target: LfoTarget, lfo: Lfo) {
+        if let Some(index) = self.active_slot(voice_id) {
+            let voice = &mut self.voices[index];
+            match target {
+                LfoTarget::Frequency => voice.vibrato = Some(lfo),
+                LfoTarget::Amplitude => voice.tremolo = Some(lfo),
+            }
+        }
+    }
+
+    /// Remove the LFO on `target` from a playing voice
+    pub fn clear_voice_lfo(&mut self, voice_id: u32, target: LfoTarget) {
+        if let Some(index) = self.active_slot(voice_id) {
+            let voice = &mut self.voices[index];
+            match target {
+                LfoTarget::Frequency => voice.vibrato = None,
+                LfoTarget::Amplitude => voice.tremolo = None,
+            }
+        }
+    }
+
+    /// Set voice amplitude for real-time volume control
+    ///
+    /// Ramps toward the new amplitude when smoothing is enabled via
+    /// `set_voice_amplitude_smoothing`, otherwise applies it instantly.
+    pub fn set_voice_amplitude(&mut self, voice_id: u32, amplitude: f32) {
+        let ramp_samples = (self.amplitude_smoothing_ms * 0.001 * self.sample_rate) as u32;
+        if let Some(index) = self.active_slot(voice_id) {
+            self.voices[index].set_amplitude(amplitude.clamp(0.0, 1.0), ramp_samples);
+        }
+    }
+
+    /// Set the ramp time used by `set_voice_amplitude` to avoid clicks (0.0 = instant)
+    pub fn set_voice_amplitude_smoothing(&mut self, smoothing_ms: f32) {
+        self.amplitude_smoothing_ms = smoothing_ms.max(0.0);
+    }
+
+    /// Get the amplitude ramp time in milliseconds
+    pub fn get_voice_amplitude_smoothing(&self) -> f32 {
+        self.amplitude_smoothing_ms
+    }
+}
+
+/// Thread-safe wrapper for shared access to RealtimeEngine.
+///
+/// Provides mutex-protected access to a RealtimeEngine instance for
+/// multi-threaded audio applications. Use this when the engine needs
+/// to be shared between audio and UI threads.
+pub type SharedRealtimeEngine = Arc<std::sync::Mutex<RealtimeEngine>>;
+
+impl Default for RealtimeEngine {
+    fn default() -> Self {
+        Self::new(44100.0)
+    }
+}
 
 /// Pattern management subsystem for drum patterns and musical sequences.
 pub mod patterns;
@@ -1581,6 +4902,9 @@ pub mod config;
 /// Melody assistant subsystem for intelligent chord progression generation.
 pub mod melody;
 
+/// MIDI subsystem for Standard MIDI File export and live note input.
+pub mod midi;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1617,6 +4941,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_square_wave_transition_points() {
+        let mut noise = NoiseFilter::new();
+        let mut square = |phase: f32| {
+            generate_sample(&Waveform::Square, phase, 0.0, 0.0, 0.0, &mut noise, 44100.0)
+        };
+
+        // Transitions at exactly half a cycle and at the wrap
+        assert_eq!(square(0.0), 1.0);
+        assert_eq!(square(PI - 1e-4), 1.0);
+        assert_eq!(square(PI), -1.0);
+        assert_eq!(square(2.0 * PI - 1e-4), -1.0);
+        assert_eq!(square(2.0 * PI), 1.0);
+        assert_eq!(square(3.0 * PI), -1.0);
+
+        // Same output as the sign of the sine it replaces, and as a 50% pulse
+        let mut pulse_noise = NoiseFilter::new();
+        let pulse = Waveform::Pulse { duty_cycle: 0.5 };
+        for step in 0..20_000 {
+            let phase = step as f32 * 0.001;
+            let expected = if (phase % (2.0 * PI)).sin() >= 0.0 {
+                1.0
+            } else {
+                -1.0
+            };
+            assert_eq!(square(phase), expected, "phase {}", phase);
+            let pulse_value =
+                generate_sample(&pulse, phase, 0.0, 0.0, 0.0, &mut pulse_noise, 44100.0);
+            assert_eq!(square(phase), pulse_value, "phase {}", phase);
+        }
+    }
+
+    #[test]
+    fn test_generate_wave_raw_skips_clamping() {
+        // Two summed partials peak well above full scale
+        let sample_rate = 1000;
+        let partials: Vec<f32> = (0..sample_rate)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                (2.0 * PI * 5.0 * t).sin() + (2.0 * PI * 10.0 * t).sin()
+            })
+            .collect();
+        let additive = Waveform::DrumSample(SampleData {
+            samples: partials.clone(),
+            sample_rate: sample_rate as u32,
+            base_frequency: 5.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "partials.wav".to_string(),
+                duration_secs: 1.0,
+                channels: 1,
+                bits_per_sample: 32,
+            },
+        });
+
+        let raw = generate_wave_raw(additive.clone(), 5.0, 1.0, sample_rate as u32);
+        let clamped = generate_wave(additive, 5.0, 1.0, sample_rate as u32);
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+        assert!(peak(&raw) > 1.5);
+        assert_eq!(peak(&clamped), 1.0);
+        for (raw_sample, partial) in raw.iter().zip(&partials) {
+            assert!((raw_sample - partial).abs() < 1e-5);
+        }
+
+        // In-range waveforms are identical either way
+        assert_eq!(
+            generate_wave_raw(Waveform::Sine, 440.0, 0.01, 44100),
+            generate_wave(Waveform::Sine, 440.0, 0.01, 44100)
+        );
+    }
+
     #[test]
     fn test_sawtooth_wave() {
         let samples = generate_wave(Waveform::Sawtooth, 1.0, 1.0, 4);
@@ -1632,6 +5031,44 @@ mod tests {
         assert!((samples[3] - 0.5).abs() < TOLERANCE);
     }
 
+    #[test]
+    fn test_band_limited_sawtooth_reduces_aliasing() {
+        let sample_rate = 44100;
+        let naive = generate_wave(Waveform::Sawtooth, 5000.0, 0.1, sample_rate);
+        let band_limited = generate_wave(Waveform::BandLimitedSawtooth, 5000.0, 0.1, sample_rate);
+
+        // Goertzel power at one frequency; 0.1 s puts every target on a bin
+        let power_at = |samples: &[f32], frequency: f32| {
+            let coefficient = 2.0 * (2.0 * PI * frequency / sample_rate as f32).cos();
+            let (mut s1, mut s2) = (0.0f32, 0.0f32);
+            for &sample in samples {
+                let s0 = sample + coefficient * s1 - s2;
+                s2 = s1;
+                s1 = s0;
+            }
+            s1 * s1 + s2 * s2 - coefficient * s1 * s2
+        };
+        // The 5th and 6th harmonics (25 and 30 kHz) fold back above Nyquist/2
+        let alias_power = |samples: &[f32]| power_at(samples, 19100.0) + power_at(samples, 14100.0);
+
+        let reduction = alias_power(&band_limited) / alias_power(&naive);
+        assert!(reduction < 0.25, "alias power ratio {}", reduction);
+
+        // The fundamental is barely touched
+        let fundamental = power_at(&band_limited, 5000.0) / power_at(&naive, 5000.0);
+        assert!(
+            (0.8..1.2).contains(&fundamental),
+            "fundamental ratio {}",
+            fundamental
+        );
+
+        // At low pitches the corrected square matches the naive one away from its edges
+        let naive = generate_wave(Waveform::Square, 100.0, 0.01, sample_rate);
+        let band_limited = generate_wave(Waveform::BandLimitedSquare, 100.0, 0.01, sample_rate);
+        assert_eq!(naive[5..215], band_limited[5..215]);
+        assert!(band_limited[221].abs() < 1.0);
+    }
+
     #[test]
     fn test_triangle_wave() {
         let samples = generate_wave(Waveform::Triangle, 1.0, 1.0, 8);
@@ -1691,9 +5128,11 @@ mod tests {
     fn test_adsr_envelope_basic() {
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.5,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let mut samples = vec![1.0; 40]; // 0.4 seconds at 100 samples/sec
@@ -1724,9 +5163,11 @@ mod tests {
     fn test_adsr_envelope_empty_samples() {
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.5,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let mut samples: Vec<f32> = vec![];
@@ -1738,9 +5179,11 @@ mod tests {
     fn test_adsr_envelope_attack_only() {
         let envelope = AdsrEnvelope {
             attack_secs: 1.0,
+            hold_secs: 0.0,
             decay_secs: 0.0,
             sustain_level: 1.0,
             release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let mut samples = vec![1.0; 10]; // 0.1 seconds at 100 samples/sec
@@ -1758,9 +5201,11 @@ mod tests {
     fn test_adsr_envelope_sustain_only() {
         let envelope = AdsrEnvelope {
             attack_secs: 0.0,
+            hold_secs: 0.0,
             decay_secs: 0.0,
             sustain_level: 0.7,
             release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let mut samples = vec![1.0; 10];
@@ -1780,9 +5225,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.5,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         apply_envelope(&mut samples, &envelope, 100);
@@ -1801,9 +5248,11 @@ mod tests {
     fn test_adsr_envelope_zero_sustain() {
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.0,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let mut samples = vec![1.0; 40]; // 0.4 seconds at 100 samples/sec
@@ -1819,9 +5268,11 @@ mod tests {
     fn test_adsr_envelope_bounds() {
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.5,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let mut samples = vec![2.0; 40]; // Start with values > 1.0
@@ -1842,9 +5293,11 @@ mod tests {
             duration_secs: 1.0,
             envelope: AdsrEnvelope {
                 attack_secs: 0.1,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.5,
                 release_secs: 0.1,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -1858,17 +5311,69 @@ mod tests {
     }
 
     #[test]
-    fn test_sound_event_frequency_sweep() {
-        let event = SoundEvent {
+    fn test_transpose_timeline_up_a_fifth() {
+        let note = |frequency: f32| SoundEvent {
             waveform: Waveform::Sine,
-            start_frequency: 100.0,
-            end_frequency: 200.0,
-            duration_secs: 1.0,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration_secs: 0.5,
+            envelope: AdsrEnvelope {
+                attack_secs: 0.01,
+                hold_secs: 0.0,
+                decay_secs: 0.1,
+                sustain_level: 0.7,
+                release_secs: 0.2,
+                curve: EnvelopeCurve::LINEAR,
+            },
+        };
+        let drum = SoundEvent {
+            waveform: Waveform::FilteredNoise {
+                cutoff_hz: 8000.0,
+                resonance: 0.0,
+            },
+            ..note(1000.0)
+        };
+        let mut timeline = vec![
+            (0.0, note(220.0)),
+            (
+                0.5,
+                SoundEvent {
+                    end_frequency: 440.0,
+                    ..note(330.0)
+                },
+            ),
+            (0.5, drum.clone()),
+        ];
+        transpose_timeline(&mut timeline, 7);
+
+        let fifth = 2.0_f32.powf(7.0 / 12.0);
+        assert!((timeline[0].1.start_frequency - 220.0 * fifth).abs() < 1e-3);
+        assert!((timeline[0].1.end_frequency - 220.0 * fifth).abs() < 1e-3);
+        assert!((timeline[1].1.start_frequency - 330.0 * fifth).abs() < 1e-3);
+        assert!((timeline[1].1.end_frequency - 440.0 * fifth).abs() < 1e-3);
+        assert_eq!(timeline[1].0, 0.5);
+        assert_eq!(timeline[2].1, drum);
+
+        // Down an octave is an exact halving
+        let mut event = note(440.0);
+        event.transpose(-12);
+        assert!((event.start_frequency - 220.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sound_event_frequency_sweep() {
+        let event = SoundEvent {
+            waveform: Waveform::Sine,
+            start_frequency: 100.0,
+            end_frequency: 200.0,
+            duration_secs: 1.0,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -1884,9 +5389,11 @@ mod tests {
             duration_secs: 1.0,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -1913,9 +5420,11 @@ mod tests {
             duration_secs: 0.1,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -1937,9 +5446,11 @@ mod tests {
             duration_secs: 0.1,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -1978,9 +5489,11 @@ mod tests {
             duration_secs: 0.0,
             envelope: AdsrEnvelope {
                 attack_secs: 0.1,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.5,
                 release_secs: 0.1,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -1997,9 +5510,11 @@ mod tests {
             duration_secs: 0.8,
             envelope: AdsrEnvelope {
                 attack_secs: 0.2,
+                hold_secs: 0.0,
                 decay_secs: 0.2,
                 sustain_level: 0.3,
                 release_secs: 0.2,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2023,9 +5538,11 @@ mod tests {
             duration_secs: 0.1,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2062,9 +5579,11 @@ mod tests {
             duration_secs: 0.5,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2094,9 +5613,11 @@ mod tests {
             duration_secs: 0.3,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2107,9 +5628,11 @@ mod tests {
             duration_secs: 0.3,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2137,9 +5660,11 @@ mod tests {
             duration_secs: 0.6,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 0.5,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2150,9 +5675,11 @@ mod tests {
             duration_secs: 0.6,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 0.5,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2194,9 +5721,11 @@ mod tests {
             duration_secs: 0.5,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2230,9 +5759,11 @@ mod tests {
             duration_secs: 0.5,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2255,9 +5786,11 @@ mod tests {
             duration_secs: 0.6,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2286,9 +5819,11 @@ mod tests {
             duration_secs: 0.3,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2299,9 +5834,11 @@ mod tests {
             duration_secs: 0.3,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2334,6 +5871,110 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_stems_sum_to_mix() {
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs: 0.0,
+            decay_secs: 0.05,
+            sustain_level: 0.6,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let note = |waveform: Waveform, frequency: f32| SoundEvent {
+            waveform,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration_secs: 0.4,
+            envelope: envelope.clone(),
+        };
+        let (drums, lead) = (1, 2);
+        let arrangement = vec![
+            (0.0, drums, note(Waveform::Noise, 100.0)),
+            (0.1, lead, note(Waveform::Sawtooth, 440.0)),
+            (0.5, drums, note(Waveform::Noise, 100.0)),
+            (0.6, lead, note(Waveform::Triangle, 660.0)),
+        ];
+
+        let stems = render_stems(&arrangement, 1.0, 44100);
+        assert_eq!(stems.len(), 2);
+        assert_eq!(stems[&drums].len(), 44100);
+        assert_eq!(stems[&lead].len(), 44100);
+
+        // The lead stem is silent until its first note
+        assert!(stems[&lead][..4410].iter().all(|&s| s == 0.0));
+        assert!(stems[&drums][..4410].iter().any(|&s| s != 0.0));
+
+        let events: Vec<(f32, SoundEvent)> = arrangement
+            .iter()
+            .map(|(start, _, event)| (*start, event.clone()))
+            .collect();
+        let mix = render_timeline(&events, 1.0, 44100);
+        for (i, mixed) in mix.iter().enumerate() {
+            let stem_sum = stems[&drums][i] + stems[&lead][i];
+            assert!(
+                (stem_sum.clamp(-1.0, 1.0) - mixed).abs() < 1e-6,
+                "sample {}",
+                i
+            );
+        }
+
+        assert!(render_stems(&arrangement, 1.0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_render_timeline_region_matches_full_render() {
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs: 0.0,
+            decay_secs: 0.05,
+            sustain_level: 0.6,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let note = |waveform: Waveform, frequency: f32, duration_secs: f32| SoundEvent {
+            waveform,
+            start_frequency: frequency,
+            end_frequency: frequency * 1.5,
+            duration_secs,
+            envelope: envelope.clone(),
+        };
+        let events = vec![
+            // Ends before the region
+            (0.0, note(Waveform::Sine, 220.0, 0.2)),
+            // Straddles the region start
+            (0.3, note(Waveform::Noise, 100.0, 0.4)),
+            // Wholly inside
+            (0.55, note(Waveform::Sawtooth, 330.0, 0.1)),
+            // Straddles the region end
+            (0.8, note(Waveform::Square, 440.0, 0.5)),
+            // Spans the whole region
+            (0.1, note(Waveform::Triangle, 110.0, 1.5)),
+            // Starts after the region
+            (1.2, note(Waveform::Sine, 880.0, 0.3)),
+        ];
+        let sample_rate = 8000;
+        let full = render_timeline(&events, 2.0, sample_rate);
+
+        let region = render_timeline_region(&events, 0.5, 1.0, sample_rate);
+        assert_eq!(region.len(), 4000);
+        assert_eq!(region, full[4000..8000]);
+
+        // Edges that fall between samples round down, as in the full render
+        let region = render_timeline_region(&events, 0.33333, 0.91234, sample_rate);
+        let (start, end) = ((0.33333 * 8000.0) as usize, (0.91234 * 8000.0) as usize);
+        assert_eq!(region, full[start..end]);
+
+        // Regions running past the last event are padded with silence
+        let tail = render_timeline_region(&events, 1.9, 2.5, sample_rate);
+        assert_eq!(tail[..800], full[15200..16000]);
+        assert!(tail[800..].iter().all(|&s| s == 0.0));
+
+        assert!(render_timeline_region(&events, 1.0, 0.5, sample_rate).is_empty());
+        assert!(render_timeline_region(&events, -0.5, 0.5, sample_rate).is_empty());
+        assert!(render_timeline_region(&events, 0.0, 0.5, 0).is_empty());
+    }
+
     #[test]
     fn test_render_timeline_zero_duration() {
         let event = SoundEvent {
@@ -2343,9 +5984,11 @@ mod tests {
             duration_secs: 0.5,
             envelope: AdsrEnvelope {
                 attack_secs: 0.0,
+                hold_secs: 0.0,
                 decay_secs: 0.0,
                 sustain_level: 1.0,
                 release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -2382,6 +6025,8 @@ mod tests {
             base_frequency: frequency,
             loop_start: None,
             loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
             metadata,
         };
 
@@ -2419,6 +6064,8 @@ mod tests {
             base_frequency: base_freq,
             loop_start: None,
             loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
             metadata: SampleMetadata {
                 filename: "test.wav".to_string(),
                 duration_secs: 1.0,
@@ -2446,6 +6093,8 @@ mod tests {
             base_frequency: 1.0, // 1Hz
             loop_start: None,
             loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
             metadata: SampleMetadata {
                 filename: "test.wav".to_string(),
                 duration_secs: 1.0,
@@ -2466,6 +6115,99 @@ mod tests {
         }
     }
 
+    /// Build a sine sample near the source Nyquist limit, where linear
+    /// interpolation error is most audible
+    fn high_frequency_sine_sample(interpolation: Interpolation) -> SampleData {
+        let sample_rate = 8000;
+        let sine_frequency = 3000.0;
+        let samples: Vec<f32> = (0..sample_rate)
+            .map(|i| (2.0 * PI * sine_frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        SampleData {
+            samples,
+            sample_rate,
+            base_frequency: 440.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation,
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "hf_sine.wav".to_string(),
+                duration_secs: 1.0,
+                channels: 1,
+                bits_per_sample: 16,
+            },
+        }
+    }
+
+    /// Residual energy relative to the ideal sine when playing two octaves down
+    fn downshift_distortion(sample_data: &SampleData) -> f32 {
+        let sample_rate = sample_data.sample_rate as f32;
+        let speed_ratio = 0.25;
+        let mut error_energy = 0.0;
+        let mut signal_energy = 0.0;
+
+        // Skip the edges where the kernel would read past the buffer
+        for i in 400..3600 {
+            let t = i as f32 / sample_rate;
+            let source_pos = t * sample_rate * speed_ratio;
+            let ideal = (2.0 * PI * 3000.0 * source_pos / sample_rate).sin();
+            let actual = sample_data.get_sample_at_time(t, 440.0 * speed_ratio);
+            error_energy += (actual - ideal).powi(2);
+            signal_energy += ideal.powi(2);
+        }
+
+        (error_energy / signal_energy).sqrt()
+    }
+
+    #[test]
+    fn test_sample_interpolation_default_is_linear() {
+        let sample_data = high_frequency_sine_sample(Interpolation::default());
+        assert_eq!(sample_data.interpolation, Interpolation::Linear);
+
+        // Exact frame positions are unaffected by the interpolator choice
+        let sinc = sample_data
+            .clone()
+            .with_interpolation(Interpolation::Sinc { taps: 32 });
+        let t = 1000.0 / 8000.0;
+        let linear_value = sample_data.get_sample_at_time(t, 440.0);
+        let sinc_value = sinc.get_sample_at_time(t, 440.0);
+        assert!((linear_value - sinc_value).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sinc_interpolation_reduces_downshift_distortion() {
+        let linear = downshift_distortion(&high_frequency_sine_sample(Interpolation::Linear));
+        let hermite = downshift_distortion(&high_frequency_sine_sample(Interpolation::Hermite));
+        let sinc = downshift_distortion(&high_frequency_sine_sample(Interpolation::Sinc {
+            taps: 32,
+        }));
+
+        // Linear interpolation of a 0.375*fs sine is badly distorted
+        assert!(
+            linear > 0.1,
+            "linear distortion unexpectedly low: {}",
+            linear
+        );
+        assert!(hermite < linear);
+        assert!(
+            sinc < linear * 0.1,
+            "sinc distortion {} not well below linear {}",
+            sinc,
+            linear
+        );
+    }
+
+    #[test]
+    fn test_sinc_interpolation_small_tap_count() {
+        // Degenerate tap counts are clamped rather than producing silence
+        let sample_data = high_frequency_sine_sample(Interpolation::Sinc { taps: 0 });
+        let value = sample_data.get_sample_at_time(0.5 / 3000.0 / 4.0, 440.0);
+        assert!(value.is_finite());
+        assert!(value.abs() <= 1.0 + TOLERANCE);
+    }
+
     // ======================================================================
     // REAL-TIME ENGINE TESTS
     // ======================================================================
@@ -2492,9 +6234,11 @@ mod tests {
         let mut envelope_state = EnvelopeState::new();
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.7,
             release_secs: 0.2,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // At start, should be in attack phase
@@ -2517,9 +6261,11 @@ mod tests {
         let mut envelope_state = EnvelopeState::new();
         let envelope = AdsrEnvelope {
             attack_secs: 0.1,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.6,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         let dt = 0.01; // 10ms steps
@@ -2567,9 +6313,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.01,
             sustain_level: 0.8,
             release_secs: 0.01,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger a note
@@ -2609,9 +6357,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.01,
             sustain_level: 0.8,
             release_secs: 0.01,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger a note
@@ -2652,9 +6402,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.6,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger multiple notes
@@ -2697,9 +6449,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.5,
             sustain_level: 0.8,
             release_secs: 0.5,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Fill all voices
@@ -2727,15 +6481,235 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trigger_note_with_velocity_follows_curve() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs: 0.0,
+            decay_secs: 0.1,
+            sustain_level: 0.8,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        fn voice(engine: &RealtimeEngine, id: u32) -> &Voice {
+            &engine.voices[engine.active_slot(id).unwrap()]
+        }
+
+        engine.set_velocity_curve(VelocityCurve::Exponential(2.0));
+        let full = engine
+            .trigger_note_with_velocity(Waveform::Sine, 440.0, envelope.clone(), 127)
+            .unwrap();
+        assert!((voice(&engine, full).volume - 1.0).abs() < 1e-6);
+
+        // Half velocity plays at the curve's value, not half volume
+        let half = engine
+            .trigger_note_with_velocity(Waveform::Sine, 440.0, envelope.clone(), 64)
+            .unwrap();
+        let expected = (64.0f32 / 127.0).powi(2);
+        assert!((voice(&engine, half).volume - expected).abs() < 1e-6);
+        assert!((voice(&engine, half).volume - 0.254).abs() < 1e-3);
+
+        // Softer notes can also ease in more slowly
+        engine.set_velocity_attack_scale(2.0);
+        let soft = engine
+            .trigger_note_with_velocity(Waveform::Sine, 440.0, envelope.clone(), 64)
+            .unwrap();
+        let attack = voice(&engine, soft).envelope.attack_secs;
+        assert!((attack - 0.01 * (1.0 + 2.0 * (1.0 - expected))).abs() < 1e-6);
+        let loud = engine
+            .trigger_note_with_velocity(Waveform::Sine, 440.0, envelope, 127)
+            .unwrap();
+        assert!((voice(&engine, loud).envelope.attack_secs - 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_instant_envelope_gets_declick_ramps() {
+        let instant = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let largest_step = |samples: &[f32]| {
+            samples
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .fold(0.0, f32::max)
+        };
+
+        // A 100 Hz square stays at +1 for 220 samples, so any step is the envelope's
+        let mut engine = RealtimeEngine::new(44100.0);
+        assert_eq!(engine.get_min_attack_secs(), DEFAULT_MIN_ATTACK_SECS);
+        let voice_id = engine
+            .trigger_note(Waveform::Square, 100.0, instant.clone())
+            .unwrap();
+        let mut onset = vec![0.0; 100];
+        engine.process_buffer(&mut onset);
+        assert!(onset[0] < 0.1, "first sample {}", onset[0]);
+        assert!(largest_step(&onset) < 0.1);
+        assert!((onset[30] - 1.0).abs() < 1e-6);
+
+        engine.release_note(voice_id);
+        let mut release = vec![0.0; 100];
+        engine.process_buffer(&mut release);
+        assert!(release[0] > 0.9, "first released sample {}", release[0]);
+        assert!(largest_step(&release) < 0.1);
+        assert_eq!(release[30], 0.0);
+        assert_eq!(engine.get_active_voice_count(), 0);
+
+        // Without the floors the envelope steps straight to full scale and back
+        engine.set_min_attack_secs(0.0);
+        engine.set_min_release_secs(-1.0);
+        assert_eq!(engine.get_min_release_secs(), 0.0);
+        let voice_id = engine
+            .trigger_note(Waveform::Square, 100.0, instant)
+            .unwrap();
+        let mut onset = vec![0.0; 10];
+        engine.process_buffer(&mut onset);
+        assert_eq!(onset[0], 1.0);
+        engine.release_note(voice_id);
+        let mut release = vec![0.0; 10];
+        engine.process_buffer(&mut release);
+        assert_eq!(release[0], 0.0);
+    }
+
+    #[test]
+    fn test_snapshot_restore_replays_bit_for_bit() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs: 0.0,
+            decay_secs: 0.05,
+            sustain_level: 0.6,
+            release_secs: 0.05,
+            curve: EnvelopeCurve::uniform(CurveShape::Exponential),
+        };
+        let ramp = SampleData {
+            samples: (0..4410).map(|i| (i as f32 * 0.37).sin()).collect(),
+            sample_rate: 44100,
+            base_frequency: 440.0,
+            loop_start: Some(1000),
+            loop_end: Some(3000),
+            interpolation: Interpolation::Hermite,
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "ramp".to_string(),
+                duration_secs: 0.1,
+                channels: 1,
+                bits_per_sample: 32,
+            },
+        };
+
+        engine.set_master_volume(0.7);
+        engine.trigger_note(Waveform::Sawtooth, 220.0, envelope.clone());
+        engine.trigger_note(Waveform::Noise, 440.0, envelope.clone());
+        let sample_voice = engine
+            .trigger_note(Waveform::Sample(ramp), 523.25, envelope.clone())
+            .unwrap();
+        let mut buffer = vec![0.0; 700];
+        engine.process_buffer(&mut buffer);
+        engine.release_note(sample_voice);
+
+        // Mid-playback: one note releasing, two sustaining
+        let snapshot = engine.snapshot();
+        assert_eq!(snapshot.active_voice_count(), 3);
+        assert_eq!(snapshot.master_volume(), 0.7);
+
+        let mut original = vec![0.0; 4096];
+        engine.process_buffer(&mut original);
+        engine.trigger_note(Waveform::Square, 330.0, envelope);
+
+        assert!(engine.restore(&snapshot));
+        let mut replay = vec![0.0; 4096];
+        engine.process_buffer(&mut replay);
+        assert!(original.iter().any(|&sample| sample != 0.0));
+        assert_eq!(original, replay);
+
+        // A serialized snapshot replays identically on a fresh engine
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let mut fresh = RealtimeEngine::new(48000.0);
+        assert!(fresh.restore(&serde_json::from_str(&json).unwrap()));
+        let mut from_json = vec![0.0; 4096];
+        fresh.process_buffer(&mut from_json);
+        assert_eq!(original, from_json);
+
+        // Malformed snapshots are rejected
+        let mut broken = snapshot;
+        broken.voices.truncate(4);
+        assert!(!fresh.restore(&broken));
+    }
+
+    #[test]
+    fn test_stereo_sample_voice_feeds_channels_independently() {
+        // Tone on the left, silence on the right
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ambience.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..4410 {
+            writer
+                .write_sample(0.5 * (2.0 * PI * 441.0 * i as f32 / 44100.0).sin())
+                .unwrap();
+            writer.write_sample(0.0f32).unwrap();
+        }
+        writer.finalize().unwrap();
+        let sample = SampleData::from_file(&path, 441.0).unwrap();
+
+        // Pitched reads keep the channels apart, an octave up too
+        let (left, right) = sample.get_stereo_sample_at_time(0.0005, 882.0);
+        assert!(left.abs() > 0.1);
+        assert_eq!(right, 0.0);
+
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.001,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.01,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.trigger_note(Waveform::Sample(sample.clone()), 441.0, envelope.clone());
+        let mut stereo = vec![0.0; 2048];
+        engine.process_stereo_buffer(&mut stereo);
+        let energy = |channel: usize| -> f32 {
+            stereo
+                .chunks_exact(2)
+                .map(|frame| frame[channel] * frame[channel])
+                .sum()
+        };
+        assert!(energy(0) > 1.0);
+        assert_eq!(energy(1), 0.0);
+
+        // The mono output still plays the downmix
+        let mut mono_engine = RealtimeEngine::new(44100.0);
+        mono_engine.trigger_note(Waveform::Sample(sample), 441.0, envelope);
+        let mut mono = vec![0.0; 1024];
+        mono_engine.process_buffer(&mut mono);
+        for (mixed, frame) in mono.iter().zip(stereo.chunks_exact(2)) {
+            assert!((mixed - frame[0] / 2.0).abs() < 1e-6);
+        }
+    }
+
     #[test]
     fn test_realtime_engine_stereo_buffer() {
         let mut engine = RealtimeEngine::new(44100.0);
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.8,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger a note
@@ -2764,9 +6738,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.0,
+            hold_secs: 0.0,
             decay_secs: 0.0,
             sustain_level: 1.0,
             release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger a note
@@ -2807,9 +6783,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.7,
             release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger a C Major chord
@@ -2843,9 +6821,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 0.5,
             sustain_level: 0.8,
             release_secs: 0.5,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger a note
@@ -2876,9 +6856,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.01,
+            hold_secs: 0.0,
             decay_secs: 1.0,
             sustain_level: 0.8,
             release_secs: 1.0,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Trigger multiple notes
@@ -2905,9 +6887,11 @@ mod tests {
 
         let envelope = AdsrEnvelope {
             attack_secs: 0.0,
+            hold_secs: 0.0,
             decay_secs: 0.0,
             sustain_level: 1.0,
             release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
         };
 
         // Test all waveform types
@@ -2940,4 +6924,2192 @@ mod tests {
             }
         }
     }
+
+    fn write_test_wav(path: &Path, samples: &[f32]) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_stereo_drum_sample_keeps_its_image() {
+        // A 441 Hz tone on the left and a quieter 882 Hz tone on the right
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("overheads.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let tone = |frequency: f32, i: usize| (2.0 * PI * frequency * i as f32 / 44100.0).sin();
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..4410 {
+            writer.write_sample(0.8 * tone(441.0, i)).unwrap();
+            writer.write_sample(0.2 * tone(882.0, i)).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let sample = SampleData::from_file(&path, 60.0).unwrap();
+        let (left, right) = sample.stereo_channels().unwrap();
+        assert_eq!(left.len(), 4410);
+        assert!((left[25] - 0.8 * tone(441.0, 25)).abs() < 1e-6);
+        assert!((right[25] - 0.2 * tone(882.0, 25)).abs() < 1e-6);
+        assert!((sample.samples[25] - (left[25] + right[25]) / 2.0).abs() < 1e-6);
+
+        // Natural-speed playback reads each channel unchanged
+        let event = SoundEvent {
+            waveform: Waveform::DrumSample(sample.clone()),
+            start_frequency: 60.0,
+            end_frequency: 60.0,
+            duration_secs: 0.1,
+            envelope: AdsrEnvelope {
+                attack_secs: 0.0,
+                hold_secs: 0.1,
+                decay_secs: 0.0,
+                sustain_level: 1.0,
+                release_secs: 0.0,
+                curve: EnvelopeCurve::LINEAR,
+            },
+        };
+        let stereo = render_event_stereo(&event, 44100);
+        assert_eq!(stereo.len(), 2 * 4410);
+        for (i, frame) in stereo.chunks_exact(2).enumerate().skip(1).step_by(97) {
+            assert!((frame[0] - left[i]).abs() < 1e-4, "left frame {}", i);
+            assert!((frame[1] - right[i]).abs() < 1e-4, "right frame {}", i);
+        }
+
+        // Mono data plays identically on both sides
+        let mono = SampleData {
+            stereo_channels: None,
+            ..sample
+        };
+        let (mono_left, mono_right) = mono.get_natural_stereo_sample_at_time(0.01);
+        assert_eq!(mono_left, mono_right);
+        assert!((mono_left - mono.samples[441]).abs() < 1e-3);
+    }
+
+    /// Write interleaved 16-bit audio as a FLAC file of uncompressed (verbatim) frames
+    fn write_test_flac(path: &Path, samples: &[i16], channels: usize, sample_rate: u32) {
+        const BLOCK: usize = 4096;
+        fn crc8(bytes: &[u8]) -> u8 {
+            bytes.iter().fold(0u8, |crc, &byte| {
+                (0..8).fold(crc ^ byte, |crc, _| {
+                    if crc & 0x80 != 0 {
+                        (crc << 1) ^ 0x07
+                    } else {
+                        crc << 1
+                    }
+                })
+            })
+        }
+        fn crc16(bytes: &[u8]) -> u16 {
+            bytes.iter().fold(0u16, |crc, &byte| {
+                (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+                    if crc & 0x8000 != 0 {
+                        (crc << 1) ^ 0x8005
+                    } else {
+                        crc << 1
+                    }
+                })
+            })
+        }
+
+        let frames = samples.len() / channels;
+        let mut bytes = b"fLaC".to_vec();
+        // Last metadata block: STREAMINFO, 34 bytes
+        bytes.extend([0x80, 0, 0, 34]);
+        bytes.extend((BLOCK as u16).to_be_bytes());
+        bytes.extend((BLOCK as u16).to_be_bytes());
+        bytes.extend([0; 6]);
+        let packed = (sample_rate as u64) << 44
+            | ((channels as u64 - 1) << 41)
+            | (15u64 << 36)
+            | frames as u64;
+        bytes.extend(packed.to_be_bytes());
+        bytes.extend([0; 16]);
+
+        for (index, block) in samples.chunks(BLOCK * channels).enumerate() {
+            let block_frames = block.len() / channels;
+            let mut frame = vec![0xFF, 0xF8, 0x70, ((channels as u8 - 1) << 4) | 0x08];
+            frame.push(index as u8);
+            frame.extend((block_frames as u16 - 1).to_be_bytes());
+            frame.push(crc8(&frame));
+            for channel in 0..channels {
+                frame.push(0x02);
+                for sample in block.iter().skip(channel).step_by(channels) {
+                    frame.extend(sample.to_be_bytes());
+                }
+            }
+            frame.extend(crc16(&frame).to_be_bytes());
+            bytes.extend(frame);
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_from_file_decodes_flac() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a440.flac");
+        let tone: Vec<i16> = (0..11025)
+            .map(|i| (16384.0 * (2.0 * PI * 440.0 * i as f32 / 44100.0).sin()) as i16)
+            .collect();
+        write_test_flac(&path, &tone, 1, 44100);
+
+        let sample = SampleData::from_file(&path, 440.0).unwrap();
+        assert_eq!(sample.samples.len(), 11025);
+        assert_eq!(sample.sample_rate, 44100);
+        assert_eq!(sample.metadata.bits_per_sample, 16);
+        let peak = sample
+            .samples
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 1e-3, "peak {}", peak);
+
+        // Stereo FLAC is mixed to mono like stereo WAV, keeping both channels
+        let path = dir.path().join("left_only.flac");
+        let stereo: Vec<i16> = tone.iter().flat_map(|&sample| [sample, 0]).collect();
+        write_test_flac(&path, &stereo, 2, 44100);
+        let sample = SampleData::from_file(&path, 440.0).unwrap();
+        assert_eq!(sample.samples.len(), 11025);
+        assert!((sample.samples[25] - tone[25] as f32 / 65536.0).abs() < 1e-6);
+        let (left, right) = sample.stereo_channels().unwrap();
+        assert!((left[25] - tone[25] as f32 / 32768.0).abs() < 1e-6);
+        assert!(right.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_from_file_rejects_unknown_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "not audio").unwrap();
+        assert!(matches!(
+            SampleData::from_file(&path, 440.0),
+            Err(SampleError::UnsupportedFormat(_))
+        ));
+
+        // A file claiming to be Ogg that is not is a format error
+        let path = dir.path().join("broken.ogg");
+        std::fs::write(&path, "OggS and then nothing").unwrap();
+        assert!(matches!(
+            SampleData::from_file(&path, 440.0),
+            Err(SampleError::FormatError(_))
+        ));
+    }
+
+    fn offset_sine(offset: f32, amplitude: f32) -> Vec<f32> {
+        (0..4410)
+            .map(|i| offset + amplitude * (2.0 * PI * 441.0 * i as f32 / 44100.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_base_frequency_of_loaded_sine() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a3.wav");
+        let sine: Vec<f32> = (0..22050)
+            .map(|i| 0.6 * (2.0 * PI * 220.0 * i as f32 / 44100.0).sin())
+            .collect();
+        write_test_wav(&path, &sine);
+
+        // Loaded with the wrong base frequency, as a user might
+        let sample = SampleData::from_file(&path, 440.0).unwrap();
+        let pitch = sample.detect_base_frequency().unwrap();
+        let cents = 1200.0 * (pitch.frequency_hz / 220.0).log2();
+        assert!(cents.abs() < 3.0, "{} Hz", pitch.frequency_hz);
+        assert!(pitch.is_confident(), "confidence {}", pitch.confidence);
+
+        // A harmonic-rich tone is not mistaken for one of its overtones
+        let saw: Vec<f32> = (0..22050)
+            .map(|i| {
+                (1..8)
+                    .map(|h| (2.0 * PI * 110.0 * h as f32 * i as f32 / 44100.0).sin() / h as f32)
+                    .sum::<f32>()
+                    * 0.3
+            })
+            .collect();
+        let sample = SampleData {
+            samples: saw,
+            ..sample
+        };
+        let pitch = sample.detect_base_frequency().unwrap();
+        assert!(
+            (1200.0 * (pitch.frequency_hz / 110.0).log2()).abs() < 3.0,
+            "{} Hz",
+            pitch.frequency_hz
+        );
+    }
+
+    #[test]
+    fn test_detect_base_frequency_of_noise_has_low_confidence() {
+        let mut rng = SynthRng::new(7);
+        let noise: Vec<f32> = (0..22050).map(|_| 0.5 * rng.next_bipolar()).collect();
+        let mut sample = SampleData {
+            samples: noise,
+            sample_rate: 44100,
+            base_frequency: 440.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "noise".to_string(),
+                duration_secs: 0.5,
+                channels: 1,
+                bits_per_sample: 32,
+            },
+        };
+        let pitch = sample.detect_base_frequency().unwrap();
+        assert!(!pitch.is_confident());
+        assert!(pitch.confidence < 0.5, "confidence {}", pitch.confidence);
+
+        // Silence and very short samples give no estimate at all
+        sample.samples = vec![0.0; 22050];
+        assert_eq!(sample.detect_base_frequency(), None);
+        sample.samples = vec![0.5; 20];
+        assert_eq!(sample.detect_base_frequency(), None);
+    }
+
+    #[test]
+    fn test_load_options_default_leaves_samples_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("offset.wav");
+        let original = offset_sine(0.2, 0.5);
+        write_test_wav(&path, &original);
+
+        let sample = SampleData::from_file(&path, 440.0).unwrap();
+        assert_eq!(sample.samples, original);
+
+        let (sample, report) =
+            SampleData::from_file_with_options(&path, 440.0, &LoadOptions::default()).unwrap();
+        assert_eq!(sample.samples, original);
+        assert!(!report.dc_removed);
+        assert!(report.inter_sample_peak.is_none());
+    }
+
+    #[test]
+    fn test_load_options_detects_and_removes_dc_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("offset.wav");
+        write_test_wav(&path, &offset_sine(0.2, 0.5));
+
+        let options = LoadOptions {
+            remove_dc_offset: true,
+            ..LoadOptions::default()
+        };
+        let (sample, report) = SampleData::from_file_with_options(&path, 440.0, &options).unwrap();
+
+        // 4410 samples cover 44 full cycles, so the mean is exactly the offset
+        assert!((report.dc_offset - 0.2).abs() < 1e-3);
+        assert!(report.dc_offset_detected);
+        assert!(report.dc_removed);
+        assert!((report.peak_level - 0.7).abs() < 1e-3);
+        assert!(!report.warnings().is_empty());
+
+        let mean: f32 = sample.samples.iter().sum::<f32>() / sample.samples.len() as f32;
+        assert!(mean.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_load_options_clamps_and_reports_overs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hot.wav");
+        let mut original = offset_sine(0.0, 1.05);
+        original[10] = f32::NAN;
+        write_test_wav(&path, &original);
+
+        let options = LoadOptions {
+            clamp_out_of_range: true,
+            detect_inter_sample_peaks: true,
+            ..LoadOptions::default()
+        };
+        let (sample, report) = SampleData::from_file_with_options(&path, 440.0, &options).unwrap();
+
+        assert!(report.out_of_range_samples > 0);
+        assert_eq!(report.non_finite_samples, 1);
+        assert!(report.inter_sample_peak.unwrap() > 1.0);
+        assert!(report.has_clipping());
+        assert!(report.clamped);
+        assert!(sample
+            .samples
+            .iter()
+            .all(|s| s.is_finite() && s.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_lenient_loader_recovers_header_quirks() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = offset_sine(0.0, 0.5);
+        let strict = LoadOptions {
+            strict_header: true,
+            ..LoadOptions::default()
+        };
+
+        // A stale byte rate in the fmt chunk
+        let byte_rate_path = dir.path().join("byte_rate.wav");
+        write_test_wav(&byte_rate_path, &original);
+        let mut bytes = std::fs::read(&byte_rate_path).unwrap();
+        bytes[28..32].copy_from_slice(&44100u32.to_le_bytes());
+        std::fs::write(&byte_rate_path, bytes).unwrap();
+
+        // A data chunk ending in a stray byte
+        let partial_path = dir.path().join("partial.wav");
+        write_test_wav(&partial_path, &original);
+        let mut bytes = std::fs::read(&partial_path).unwrap();
+        let len_at = bytes.windows(4).position(|id| id == b"data").unwrap() + 4;
+        let data_len = le_u32(&bytes, len_at) + 1;
+        bytes[len_at..len_at + 4].copy_from_slice(&data_len.to_le_bytes());
+        bytes.push(0x7f);
+        std::fs::write(&partial_path, bytes).unwrap();
+
+        for path in [&byte_rate_path, &partial_path] {
+            assert!(hound::WavReader::open(path)
+                .and_then(|mut reader| reader.samples::<f32>().collect::<Result<Vec<_>, _>>())
+                .is_err());
+            assert!(SampleData::from_file_with_options(path, 440.0, &strict).is_err());
+
+            let (sample, report) =
+                SampleData::from_file_with_options(path, 440.0, &LoadOptions::default()).unwrap();
+            assert_eq!(sample.samples, original);
+            assert_eq!(sample.sample_rate, 44100);
+            assert!(!report.header_repairs.is_empty());
+            assert!(report
+                .warnings()
+                .iter()
+                .any(|warning| warning.contains("header")));
+        }
+
+        // Clean files report no repairs, and garbage still fails
+        let clean_path = dir.path().join("clean.wav");
+        write_test_wav(&clean_path, &original);
+        let (_, report) =
+            SampleData::from_file_with_options(&clean_path, 440.0, &LoadOptions::default())
+                .unwrap();
+        assert!(report.header_repairs.is_empty());
+
+        let garbage_path = dir.path().join("garbage.wav");
+        std::fs::write(&garbage_path, b"RIFF\x10\0\0\0WAVEjunkjunk").unwrap();
+        assert!(SampleData::from_file(&garbage_path, 440.0).is_err());
+    }
+
+    #[test]
+    fn test_inter_sample_peak_exceeds_sample_peak() {
+        // A quarter-rate sine sampled off its crests hides the true peak
+        let samples: Vec<f32> = (0..64)
+            .map(|i| (PI / 2.0 * i as f32 + PI / 4.0).sin())
+            .collect();
+        let sample_peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+
+        assert!(estimate_inter_sample_peak(&samples) > sample_peak + 0.1);
+    }
+
+    fn sustained_voice(engine: &mut RealtimeEngine) -> u32 {
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        engine
+            .trigger_note(Waveform::Sine, 440.0, envelope)
+            .unwrap()
+    }
+
+    fn voice_amplitude(engine: &RealtimeEngine, voice_id: u32) -> f32 {
+        engine
+            .voices
+            .iter()
+            .find(|voice| voice.voice_id == voice_id)
+            .unwrap()
+            .amplitude
+    }
+
+    #[test]
+    fn test_voice_amplitude_unsmoothed_is_instant() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        assert_eq!(engine.get_voice_amplitude_smoothing(), 0.0);
+        let voice_id = sustained_voice(&mut engine);
+
+        engine.set_voice_amplitude(voice_id, 0.2);
+        assert!((voice_amplitude(&engine, voice_id) - 0.2).abs() < TOLERANCE);
+
+        let mut buffer = vec![0.0; 64];
+        engine.process_buffer(&mut buffer);
+        assert!((voice_amplitude(&engine, voice_id) - 0.2).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_voice_amplitude_smoothing_ramps() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.set_voice_amplitude_smoothing(10.0); // 441 samples
+        let voice_id = sustained_voice(&mut engine);
+
+        engine.set_voice_amplitude(voice_id, 0.0);
+        assert_eq!(voice_amplitude(&engine, voice_id), 1.0);
+
+        // Halfway through the ramp the amplitude is halfway to the target
+        let mut buffer = vec![0.0; 220];
+        engine.process_buffer(&mut buffer);
+        let halfway = voice_amplitude(&engine, voice_id);
+        assert!((halfway - 0.5).abs() < 0.01, "amplitude {}", halfway);
+
+        // The ramp lands exactly on the target after the configured time
+        let mut buffer = vec![0.0; 221];
+        engine.process_buffer(&mut buffer);
+        assert_eq!(voice_amplitude(&engine, voice_id), 0.0);
+    }
+
+    #[test]
+    fn test_vibrato_lfo_swings_pitch_at_its_rate() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let voice_id = sustained_voice(&mut engine);
+        engine.set_voice_lfo(
+            voice_id,
+            LfoTarget::Frequency,
+            Lfo::new(LfoShape::Sine, 5.0, 1.0),
+        );
+
+        let mut buffer = vec![0.0; 44100];
+        engine.process_buffer(&mut buffer);
+
+        // Instantaneous frequency from interpolated upward zero crossings
+        let crossings: Vec<f32> = buffer
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] < 0.0 && pair[1] >= 0.0)
+            .map(|(i, pair)| i as f32 + pair[0] / (pair[0] - pair[1]))
+            .collect();
+        let frequencies: Vec<f32> = crossings
+            .windows(2)
+            .map(|t| 44100.0 / (t[1] - t[0]))
+            .collect();
+
+        let highest = frequencies.iter().cloned().fold(0.0, f32::max);
+        let lowest = frequencies.iter().cloned().fold(f32::INFINITY, f32::min);
+        assert!(highest > 460.0 && highest < 470.0, "highest {}", highest);
+        assert!(lowest > 411.0 && lowest < 420.0, "lowest {}", lowest);
+
+        // Five cycles starting at the centre pitch cross it nine more times in a second
+        let centre_crossings = frequencies
+            .windows(2)
+            .filter(|f| (f[0] - 440.0).signum() != (f[1] - 440.0).signum())
+            .count();
+        assert_eq!(centre_crossings, 9);
+    }
+
+    #[test]
+    fn test_tremolo_lfo_dips_level_until_retrigger() {
+        let mut lfo = Lfo::new(LfoShape::Triangle, 1.0, 0.0);
+        let shape: Vec<f32> = (0..4).map(|_| lfo.next_value(4.0)).collect();
+        assert_eq!(shape, vec![0.0, 1.0, 0.0, -1.0]);
+
+        let mut voice = Voice::new(0);
+        voice.trigger_note(Waveform::Sine, 441.0, held_envelope());
+        voice.tremolo = Some(Lfo::new(LfoShape::Square, 10.0, 0.75));
+        let output: Vec<f32> = (0..4410).map(|_| voice.process_sample(44100.0)).collect();
+        let peak = |range: std::ops::Range<usize>| {
+            output[range]
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+        assert!((peak(200..2205) - 1.0).abs() < 0.01);
+        assert!((peak(2205..4410) - 0.25).abs() < 0.01);
+
+        voice.trigger_note(Waveform::Sine, 441.0, held_envelope());
+        assert_eq!(voice.tremolo, None);
+    }
+
+    #[test]
+    fn test_snapshot_serializes_after_custom_voice_finishes() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let voice_id = engine
+            .trigger_note(Waveform::Custom(Arc::new(HalfSine)), 440.0, envelope)
+            .unwrap();
+        let mut buffer = vec![0.0; 256];
+        engine.process_buffer(&mut buffer);
+        assert!(serde_json::to_string(&engine.snapshot()).is_err());
+
+        engine.release_note(voice_id);
+        engine.process_buffer(&mut buffer);
+        let snapshot = engine.snapshot();
+        assert_eq!(snapshot.active_voice_count(), 0);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let mut fresh = RealtimeEngine::new(44100.0);
+        assert!(fresh.restore(&serde_json::from_str(&json).unwrap()));
+    }
+
+    struct HalfSine;
+
+    impl OscillatorSource for HalfSine {
+        fn sample(&self, phase: f32, _time_secs: f32, _frequency: f32) -> f32 {
+            phase.sin().max(0.0)
+        }
+
+        fn name(&self) -> &str {
+            "half_sine"
+        }
+    }
+
+    #[test]
+    fn test_custom_waveform_generates_offline_and_realtime() {
+        let waveform = Waveform::Custom(Arc::new(HalfSine));
+
+        let offline = generate_wave(waveform.clone(), 440.0, 0.01, 44100);
+        assert_eq!(offline.len(), 441);
+        assert!(offline.iter().all(|&s| s >= 0.0));
+        assert!(offline.iter().any(|&s| s > 0.9));
+
+        let mut engine = RealtimeEngine::new(44100.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        assert!(engine.trigger_note(waveform, 440.0, envelope).is_some());
+        let mut buffer = vec![0.0; 256];
+        engine.process_buffer(&mut buffer);
+        assert!(buffer.iter().any(|&s| s > 0.01));
+        assert!(buffer.iter().all(|&s| s >= -1e-6));
+    }
+
+    #[test]
+    fn test_custom_waveform_compares_by_identity() {
+        let source: Arc<dyn OscillatorSource> = Arc::new(HalfSine);
+        let a = Waveform::Custom(source.clone());
+        let b = a.clone();
+        assert_eq!(a, b);
+        assert_eq!(a, Waveform::Custom(source));
+        assert_ne!(a, Waveform::Custom(Arc::new(HalfSine)));
+        assert_ne!(a, Waveform::Sine);
+
+        assert_eq!(a.kind_name(), "custom");
+        assert!(a.is_pitched());
+        assert!(format!("{:?}", a).contains("half_sine"));
+        assert!(serde_json::to_string(&a).is_err());
+    }
+
+    #[test]
+    fn test_waveform_kind_names() {
+        let sample = SampleData {
+            samples: vec![0.0; 4],
+            sample_rate: 44100,
+            base_frequency: 440.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "test.wav".to_string(),
+                duration_secs: 0.0,
+                channels: 1,
+                bits_per_sample: 16,
+            },
+        };
+
+        let expected = [
+            (Waveform::Sine, "sine", true),
+            (Waveform::Square, "square", true),
+            (Waveform::Sawtooth, "sawtooth", true),
+            (Waveform::BandLimitedSquare, "band_limited_square", true),
+            (Waveform::BandLimitedSawtooth, "band_limited_sawtooth", true),
+            (Waveform::Triangle, "triangle", true),
+            (Waveform::Pulse { duty_cycle: 0.25 }, "pulse", true),
+            (Waveform::Noise, "noise", false),
+            (Waveform::Sample(sample.clone()), "sample", true),
+            (Waveform::DrumSample(sample), "drum_sample", false),
+        ];
+
+        for (waveform, name, pitched) in expected {
+            assert_eq!(waveform.kind_name(), name);
+            assert_eq!(waveform.is_pitched(), pitched, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_engine_rejects_invalid_sample_rate() {
+        for invalid in [0.0, -44100.0, f32::NAN, f32::INFINITY] {
+            let engine = RealtimeEngine::new(invalid);
+            assert_eq!(engine.get_sample_rate(), DEFAULT_SAMPLE_RATE);
+        }
+
+        let mut engine = RealtimeEngine::new(48000.0);
+        assert!(!engine.set_sample_rate(0.0));
+        assert!(!engine.set_sample_rate(-1.0));
+        assert_eq!(engine.get_sample_rate(), 48000.0);
+
+        assert!(engine.set_sample_rate(384000.0));
+        assert_eq!(engine.get_sample_rate(), MAX_SAMPLE_RATE);
+        assert_eq!(
+            RealtimeEngine::new(384000.0).get_sample_rate(),
+            MAX_SAMPLE_RATE
+        );
+    }
+
+    #[test]
+    fn test_engine_output_stays_finite_after_invalid_rate() {
+        let mut engine = RealtimeEngine::new(0.0);
+        engine.set_sample_rate(-1.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs: 0.0,
+            decay_secs: 0.1,
+            sustain_level: 0.7,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        engine.trigger_note(Waveform::Sawtooth, 440.0, envelope.clone());
+
+        let mut buffer = vec![0.0; 512];
+        engine.process_buffer(&mut buffer);
+        assert!(buffer.iter().all(|s| s.is_finite()));
+        assert!(buffer.iter().any(|s| s.abs() > 0.0));
+
+        // A voice driven with a bad rate directly stays silent instead of producing NaN
+        let mut voice = Voice::new(0);
+        voice.trigger_note(Waveform::Sine, 440.0, envelope);
+        assert_eq!(voice.process_sample(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_engine_empty_buffers_are_noops() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        engine.trigger_note(Waveform::Sine, 440.0, envelope);
+
+        engine.process_buffer(&mut []);
+        engine.process_stereo_buffer(&mut []);
+        assert_eq!(engine.get_active_voice_count(), 1);
+    }
+
+    #[test]
+    fn test_crossfade_equal_power() {
+        let sample_rate = 1000;
+        let a = vec![1.0; 500];
+        let b = vec![0.5; 800];
+        let output = crossfade_buffers(&a, &b, 0.1, sample_rate);
+
+        // Non-overlapping parts are untouched
+        assert_eq!(output.len(), 500 + 800 - 100);
+        assert!(output[..400].iter().all(|&s| s == 1.0));
+        assert!(output[500..].iter().all(|&s| s == 0.5));
+
+        // Inside the overlap the gains follow cos/sin with unit power
+        for i in 0..100 {
+            let angle = (i as f32 + 0.5) / 100.0 * PI / 2.0;
+            let (gain_a, gain_b) = (angle.cos(), angle.sin());
+            assert!((gain_a * gain_a + gain_b * gain_b - 1.0).abs() < TOLERANCE);
+            assert!((output[400 + i] - (gain_a + 0.5 * gain_b)).abs() < TOLERANCE);
+        }
+
+        // The transition starts near `a`, ends near `b`, and never jumps
+        assert!((output[400] - 1.0).abs() < 0.02);
+        assert!((output[499] - 0.5).abs() < 0.02);
+        for window in output.windows(2) {
+            assert!((window[1] - window[0]).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_tiled_loop_matches_concatenated_bars() {
+        let sample_rate = 1000;
+        let bar: Vec<f32> = (0..500)
+            .map(|i| (2.0 * PI * 4.0 * i as f32 / sample_rate as f32).sin() * 0.8)
+            .collect();
+
+        let tiled = render_loop_tiled(&bar, 4);
+        assert_eq!(tiled, bar.repeat(4));
+
+        // Without fills nothing is crossfaded, however long the fade
+        let plain = render_loop_tiled_with_fills(&bar, 4, &[], 0.05, sample_rate, 1);
+        assert_eq!(plain, tiled);
+        assert!(render_loop_tiled(&bar, 0).is_empty());
+        assert!(render_loop_tiled_with_fills(&bar, 4, &[], 0.05, sample_rate, 0).is_empty());
+        assert!(render_loop_tiled_with_fills(&bar, 4, &[], -0.1, sample_rate, 1).is_empty());
+    }
+
+    #[test]
+    fn test_tiled_loop_fill_boundaries_are_click_free() {
+        let sample_rate = 1000;
+        let bar = vec![0.8; 500];
+        let fill = vec![-0.8; 500];
+        let fills: [(usize, &[f32]); 1] = [(2, &fill)];
+
+        let hard = render_loop_tiled_with_fills(&bar, 4, &fills, 0.0, sample_rate, 1);
+        let smooth = render_loop_tiled_with_fills(&bar, 4, &fills, 0.02, sample_rate, 1);
+        assert_eq!(smooth.len(), 2000);
+
+        // The fill replaces the third bar
+        assert_eq!(hard[..1000], bar.repeat(2)[..]);
+        assert_eq!(hard[1000..1500], fill[..]);
+        assert_eq!(hard[1500..], bar[..]);
+        let largest_step = |buffer: &[f32]| {
+            buffer
+                .windows(2)
+                .fold(0.0f32, |step, pair| step.max((pair[1] - pair[0]).abs()))
+        };
+        assert!(largest_step(&hard) > 1.5);
+
+        // Crossfaded, each change of material eases in over 20 ms
+        assert!(largest_step(&smooth) < 0.2, "{}", largest_step(&smooth));
+        assert_eq!(smooth[1020..1500], fill[20..]);
+        assert_eq!(smooth[1520..], bar[20..]);
+
+        // Short fills are padded with silence to keep the loop on the grid
+        let short_fill = [0.3; 100];
+        let fills: [(usize, &[f32]); 1] = [(1, &short_fill)];
+        let padded = render_loop_tiled_with_fills(&bar, 2, &fills, 0.0, sample_rate, 1);
+        assert_eq!(padded.len(), 1000);
+        assert_eq!(padded[500..600], short_fill[..]);
+        assert!(padded[600..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_crossfade_rejects_oversized_overlap() {
+        let a = vec![1.0; 100];
+        let b = vec![0.5; 1000];
+        assert!(crossfade_buffers(&a, &b, 0.2, 1000).is_empty());
+        assert!(crossfade_buffers(&b, &a, 0.2, 1000).is_empty());
+        assert!(crossfade_buffers(&a, &b, -0.1, 1000).is_empty());
+        assert!(crossfade_buffers(&a, &b, 0.05, 0).is_empty());
+
+        // Zero overlap is a plain concatenation
+        let joined = crossfade_buffers(&a, &b, 0.0, 1000);
+        assert_eq!(joined.len(), 1100);
+        assert_eq!(joined[99], 1.0);
+        assert_eq!(joined[100], 0.5);
+    }
+
+    #[test]
+    fn test_voice_buffer_matches_mix_contribution() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.set_master_volume(0.5);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.001,
+            hold_secs: 0.0,
+            decay_secs: 0.05,
+            sustain_level: 0.6,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let low = engine
+            .trigger_note(Waveform::Sine, 220.0, envelope.clone())
+            .unwrap();
+        let high = engine
+            .trigger_note(Waveform::Triangle, 660.0, envelope)
+            .unwrap();
+
+        let mut low_tap = vec![0.0; 512];
+        let mut high_tap = vec![0.0; 512];
+        assert!(engine.process_voice_buffer(low, &mut low_tap));
+        assert!(engine.process_voice_buffer(high, &mut high_tap));
+
+        // Tapping twice gives the same result because the engine is not advanced
+        let mut low_again = vec![0.0; 512];
+        engine.process_voice_buffer(low, &mut low_again);
+        assert_eq!(low_tap, low_again);
+
+        let mut mix = vec![0.0; 512];
+        engine.process_buffer(&mut mix);
+        for i in 0..mix.len() {
+            assert!((mix[i] - (low_tap[i] + high_tap[i])).abs() < TOLERANCE);
+        }
+
+        // Unknown voices are reported and leave the buffer alone
+        let mut untouched = vec![0.25; 16];
+        assert!(!engine.process_voice_buffer(9999, &mut untouched));
+        assert!(untouched.iter().all(|&s| s == 0.25));
+    }
+
+    fn ramp_sample_with_loop() -> SampleData {
+        SampleData {
+            samples: vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0, 1.0],
+            sample_rate: 10,
+            base_frequency: 440.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "ramp.wav".to_string(),
+                duration_secs: 0.7,
+                channels: 1,
+                bits_per_sample: 16,
+            },
+        }
+        .with_loop_points(1, 5)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_loop_seam_interpolates_into_loop_start() {
+        let sample = ramp_sample_with_loop();
+
+        // Halfway between the last loop frame (0.8) and the loop start (0.2)
+        let natural = sample.get_natural_sample_at_time(0.45);
+        let pitched = sample.get_sample_at_time(0.45, 440.0);
+        assert!((natural - 0.5).abs() < 1e-4, "natural {}", natural);
+        assert!((pitched - 0.5).abs() < 1e-4, "pitched {}", pitched);
+
+        // After wrapping, playback continues between loop frames 1 and 2
+        let wrapped = sample.get_natural_sample_at_time(0.55);
+        assert!((wrapped - 0.3).abs() < 1e-4, "wrapped {}", wrapped);
+        assert!((sample.get_sample_at_time(0.55, 440.0) - wrapped).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_loop_seam_with_hermite_and_sinc() {
+        for interpolation in [Interpolation::Hermite, Interpolation::Sinc { taps: 8 }] {
+            let sample = ramp_sample_with_loop().with_interpolation(interpolation);
+
+            // Neither mode should read the post-loop frames (1.0) at the seam
+            let seam = sample.get_natural_sample_at_time(0.45);
+            assert!(seam < 0.8, "{:?} seam {}", interpolation, seam);
+            assert!((seam - sample.get_sample_at_time(0.45, 440.0)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_one_shot_drum_sample_end() {
+        let mut sample = ramp_sample_with_loop();
+        sample.loop_start = None;
+        sample.loop_end = None;
+
+        // One-shot playback holds the final frame, then falls silent
+        assert!((sample.get_natural_sample_at_time(0.65) - 1.0).abs() < 1e-6);
+        assert_eq!(sample.get_natural_sample_at_time(0.75), 0.0);
+    }
+
+    #[test]
+    fn test_chained_sample_starts_when_one_shot_ends() {
+        let constant = |value: f32, frames: usize| SampleData {
+            samples: vec![value; frames],
+            sample_rate: 128,
+            base_frequency: 440.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::Nearest,
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "constant.wav".to_string(),
+                duration_secs: frames as f32 / 128.0,
+                channels: 1,
+                bits_per_sample: 16,
+            },
+        };
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+
+        let mut engine = RealtimeEngine::new(128.0);
+        let attack = Waveform::DrumSample(constant(0.5, 10));
+        let id = engine
+            .trigger_note(attack, 440.0, envelope.clone())
+            .unwrap();
+        assert!(engine.chain_sample(id, Waveform::DrumSample(constant(-0.25, 6))));
+
+        let mut output = vec![0.0; 20];
+        engine.process_buffer(&mut output);
+
+        // The body follows the attack on the very next sample, then ends too
+        let master = engine.get_master_volume();
+        assert!(output[..10]
+            .iter()
+            .all(|&s| (s - 0.5 * master).abs() < 1e-6));
+        assert!(output[10..16]
+            .iter()
+            .all(|&s| (s + 0.25 * master).abs() < 1e-6));
+        assert!(output[16..].iter().all(|&s| s == 0.0));
+        assert_eq!(engine.get_active_voice_count(), 1);
+
+        // Stopped or unknown voices cannot be chained
+        engine.stop_all_notes();
+        assert!(!engine.chain_sample(id, Waveform::Sine));
+
+        // A loop-less pitched sample ends after one pass at its playback speed
+        let mut engine = RealtimeEngine::new(128.0);
+        let attack = Waveform::Sample(constant(0.5, 10));
+        let id = engine.trigger_note(attack, 880.0, envelope).unwrap();
+        assert!(engine.chain_sample(id, Waveform::DrumSample(constant(-0.25, 6))));
+
+        engine.process_buffer(&mut output);
+        assert!(output[..5].iter().all(|&s| (s - 0.5 * master).abs() < 1e-6));
+        assert!(output[5..11]
+            .iter()
+            .all(|&s| (s + 0.25 * master).abs() < 1e-6));
+        assert!(output[11..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_generate_wave_into_matches_generate_wave() {
+        let waveforms = [
+            Waveform::Sine,
+            Waveform::Square,
+            Waveform::Sawtooth,
+            Waveform::Triangle,
+            Waveform::Pulse { duty_cycle: 0.25 },
+        ];
+
+        for waveform in waveforms {
+            let expected = generate_wave(waveform.clone(), 330.0, 0.01, 44100);
+            let mut buffer = vec![0.0; expected.len()];
+            let written = generate_wave_into(&waveform, 330.0, 44100, &mut buffer);
+
+            assert_eq!(written, expected.len());
+            assert_eq!(buffer, expected, "{:?}", waveform);
+        }
+    }
+
+    #[test]
+    fn test_generate_wave_into_respects_buffer_length() {
+        let sine = Waveform::Sine;
+        let mut buffer = [7.0f32; 100];
+        let written = generate_wave_into(&sine, 440.0, 44100, &mut buffer[..40]);
+        assert_eq!(written, 40);
+        assert_eq!(buffer[0], 0.0);
+        assert!(buffer[40..].iter().all(|&s| s == 7.0));
+
+        // Invalid parameters write nothing
+        assert_eq!(generate_wave_into(&sine, 0.0, 44100, &mut buffer), 0);
+        assert_eq!(generate_wave_into(&sine, 440.0, 0, &mut buffer), 0);
+        assert!(buffer[40..].iter().all(|&s| s == 7.0));
+        assert_eq!(generate_wave_into(&sine, 440.0, 44100, &mut []), 0);
+    }
+
+    #[test]
+    fn test_fit_to_bars_stretches_to_exact_length() {
+        let mut sample = SampleData {
+            samples: (0..1000).map(|i| i as f32 / 1000.0).collect(),
+            sample_rate: 1000,
+            ..ramp_sample_with_loop()
+        };
+        sample.loop_start = None;
+        sample.loop_end = None;
+
+        // Two bars of 4/4 at 120 BPM last 4 seconds
+        let fitted = sample.fit_to_bars(2, 4, 120.0, FitMode::Stretch).unwrap();
+        assert_eq!(fitted.samples.len(), 4000);
+        assert!((fitted.metadata.duration_secs - 4.0).abs() < 1e-6);
+        assert!((fitted.base_frequency - 110.0).abs() < 1e-3);
+
+        // The ramp is spread over the whole two bars
+        assert!((fitted.samples[2000] - 0.5).abs() < 1e-3);
+        assert_eq!(fitted.get_natural_sample_at_time(4.0), 0.0);
+    }
+
+    #[test]
+    fn test_fit_to_bars_loops_and_trims() {
+        let sample = ramp_sample_with_loop();
+
+        // One bar of 3/4 at 600 BPM is 3 frames at 10 Hz: trimmed
+        let short = sample.fit_to_bars(1, 3, 600.0, FitMode::Loop).unwrap();
+        assert_eq!(short.samples, vec![0.0, 0.2, 0.4]);
+
+        // Two bars of 4/4 at 480 BPM is 10 frames: the loop region repeats
+        let long = sample.fit_to_bars(2, 4, 480.0, FitMode::Loop).unwrap();
+        assert_eq!(long.samples.len(), 10);
+        assert!((long.metadata.duration_secs - 1.0).abs() < 1e-6);
+        assert_eq!(&long.samples[4..7], &[0.8, 0.2, 0.4]);
+        assert_eq!(long.loop_start, None);
+
+        assert!(sample.fit_to_bars(0, 4, 120.0, FitMode::Loop).is_err());
+        assert!(sample.fit_to_bars(1, 4, 0.0, FitMode::Stretch).is_err());
+    }
+
+    fn held_envelope() -> AdsrEnvelope {
+        AdsrEnvelope {
+            attack_secs: 0.001,
+            hold_secs: 0.0,
+            decay_secs: 0.001,
+            sustain_level: 1.0,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        }
+    }
+
+    #[test]
+    fn test_voice_ids_are_sequential_from_one() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let first = engine.trigger_note(Waveform::Sine, 220.0, held_envelope());
+        let second = engine.trigger_note(Waveform::Sine, 330.0, held_envelope());
+        assert_eq!(first, Some(1));
+        assert_eq!(second, Some(2));
+    }
+
+    #[test]
+    fn test_voice_stealing_survives_id_wraparound() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.next_voice_id = u32::MAX - 3;
+
+        // IDs run up to u32::MAX, then wrap to 1, 2, ... skipping 0
+        let ids: Vec<u32> = (0..MAX_VOICES)
+            .map(|i| {
+                let frequency = 100.0 + i as f32;
+                engine
+                    .trigger_note(Waveform::Sine, frequency, held_envelope())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(&ids[..5], &[u32::MAX - 2, u32::MAX - 1, u32::MAX, 1, 2]);
+        assert!(!ids.contains(&0));
+
+        // The pool is full: the first note is stolen, not the lowest ID
+        let stolen = engine
+            .trigger_note(Waveform::Sine, 1000.0, held_envelope())
+            .unwrap();
+        let active_ids: Vec<u32> = engine
+            .voices
+            .iter()
+            .filter(|voice| voice.is_active())
+            .map(|voice| voice.voice_id)
+            .collect();
+        assert!(!active_ids.contains(&ids[0]));
+        assert!(active_ids.contains(&1));
+        assert!(active_ids.contains(&stolen));
+
+        // Stealing again takes the second-oldest note
+        engine.trigger_note(Waveform::Sine, 1100.0, held_envelope());
+        assert!(!engine.voices.iter().any(|voice| voice.voice_id == ids[1]));
+    }
+
+    #[test]
+    fn test_group_voice_limit_chokes_oldest_in_group() {
+        const HI_HAT: u32 = 1;
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.set_group_max_voices(HI_HAT, 2);
+        assert_eq!(engine.get_group_max_voices(HI_HAT), Some(2));
+
+        // An ungrouped pad is left alone by the hi-hat choke
+        let pad = engine
+            .trigger_note(Waveform::Sine, 220.0, held_envelope())
+            .unwrap();
+        let hits: Vec<u32> = (0..3)
+            .map(|i| {
+                engine
+                    .trigger_note_in_group(
+                        HI_HAT,
+                        Waveform::Noise,
+                        8000.0 + i as f32,
+                        held_envelope(),
+                        1.0,
+                    )
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(engine.get_group_voice_count(HI_HAT), 2);
+        assert_eq!(engine.get_active_voice_count(), 3);
+        let active_ids: Vec<u32> = engine
+            .voices
+            .iter()
+            .filter(|voice| voice.is_active())
+            .map(|voice| voice.voice_id)
+            .collect();
+        assert!(!active_ids.contains(&hits[0]));
+        assert!(active_ids.contains(&hits[1]));
+        assert!(active_ids.contains(&hits[2]));
+        assert!(active_ids.contains(&pad));
+
+        // Removing the limit lets the group grow again
+        engine.set_group_max_voices(HI_HAT, 0);
+        assert_eq!(engine.get_group_max_voices(HI_HAT), None);
+        engine.trigger_note_in_group(HI_HAT, Waveform::Noise, 9000.0, held_envelope(), 1.0);
+        assert_eq!(engine.get_group_voice_count(HI_HAT), 3);
+    }
+
+    #[test]
+    fn test_choke_group_silences_open_hat() {
+        const HI_HAT: u32 = 7;
+        let mut engine = RealtimeEngine::new(1000.0);
+        let open_hat = engine
+            .trigger_note_in_choke_group(HI_HAT, Waveform::Noise, 8000.0, held_envelope(), 1.0)
+            .unwrap();
+        let ride = engine
+            .trigger_note(Waveform::Sine, 400.0, held_envelope())
+            .unwrap();
+        let mut buffer = vec![0.0; 100];
+        engine.process_buffer(&mut buffer);
+
+        // Closing the hat fades the open hat out; the ride is unaffected
+        let closed_hat = engine
+            .trigger_note_in_choke_group(HI_HAT, Waveform::Noise, 9000.0, held_envelope(), 1.0)
+            .unwrap();
+        let mut buffer = vec![0.0; 10];
+        engine.process_buffer(&mut buffer);
+
+        let is_active = |engine: &RealtimeEngine, id: u32| {
+            engine
+                .voices
+                .iter()
+                .any(|voice| voice.voice_id == id && voice.is_active())
+        };
+        assert!(!is_active(&engine, open_hat));
+        assert!(is_active(&engine, closed_hat));
+        assert!(is_active(&engine, ride));
+
+        // Plain triggers never choke
+        engine.trigger_note(Waveform::Noise, 8000.0, held_envelope());
+        assert!(is_active(&engine, closed_hat));
+    }
+
+    /// Check the active-slot index and ID map against a full voice scan
+    fn assert_voice_bookkeeping(engine: &RealtimeEngine) {
+        let scanned: Vec<usize> = (0..MAX_VOICES)
+            .filter(|&index| engine.voices[index].is_active())
+            .collect();
+        assert_eq!(engine.active_slots, scanned);
+        assert_eq!(engine.slot_by_id.len(), scanned.len());
+        for &index in &scanned {
+            let voice_id = engine.voices[index].voice_id;
+            assert_eq!(engine.slot_by_id.get(&voice_id), Some(&index));
+        }
+        assert_eq!(engine.get_active_voice_count(), scanned.len());
+    }
+
+    #[test]
+    fn test_active_voice_bookkeeping_stays_consistent() {
+        let mut engine = RealtimeEngine::new(1000.0);
+        let short = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.01,
+            curve: EnvelopeCurve::LINEAR,
+        };
+
+        // Triggers fill slots in order
+        let ids: Vec<u32> = (0..4)
+            .map(|i| {
+                engine
+                    .trigger_note(Waveform::Sine, 200.0 + i as f32, held_envelope())
+                    .unwrap()
+            })
+            .collect();
+        assert_voice_bookkeeping(&engine);
+
+        // A released note stays active until its release finishes
+        engine.release_note(ids[1]);
+        let mut buffer = vec![0.0; 50];
+        engine.process_buffer(&mut buffer);
+        assert_voice_bookkeeping(&engine);
+        engine.release_note(ids[2]);
+        engine.set_voice_frequency(ids[2], 300.0);
+        let mut buffer = vec![0.0; 200];
+        engine.process_buffer(&mut buffer);
+        assert_voice_bookkeeping(&engine);
+        assert_eq!(engine.get_active_voice_count(), 2);
+
+        // Finished slots are recycled, lowest first
+        let reused = engine
+            .trigger_note(Waveform::Sine, 500.0, short.clone())
+            .unwrap();
+        assert_eq!(engine.slot_by_id[&reused], 1);
+        assert_voice_bookkeeping(&engine);
+
+        // Stealing from a full pool hands the slot to the new ID
+        while engine.get_active_voice_count() < MAX_VOICES {
+            engine.trigger_note(Waveform::Sine, 600.0, held_envelope());
+        }
+        let stolen_slot = engine.slot_by_id[&ids[0]];
+        let thief = engine
+            .trigger_note(Waveform::Sine, 700.0, held_envelope())
+            .unwrap();
+        assert_eq!(engine.slot_by_id[&thief], stolen_slot);
+        assert!(!engine.slot_by_id.contains_key(&ids[0]));
+        assert_voice_bookkeeping(&engine);
+
+        // Stereo processing recycles finished voices too
+        engine.release_note(reused);
+        let mut stereo = vec![0.0; 100];
+        engine.process_stereo_buffer(&mut stereo);
+        assert_voice_bookkeeping(&engine);
+        assert_eq!(engine.get_active_voice_count(), MAX_VOICES - 1);
+
+        engine.stop_all_notes();
+        assert_voice_bookkeeping(&engine);
+        assert_eq!(engine.get_active_voice_count(), 0);
+    }
+
+    #[test]
+    fn test_trigger_note_with_phase_offsets_oscillator() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+
+        // Two sines half a cycle apart cancel
+        engine.trigger_note_with_phase(Waveform::Sine, 440.0, envelope.clone(), 0.0);
+        engine.trigger_note_with_phase(Waveform::Sine, 440.0, envelope.clone(), PI);
+        let mut buffer = vec![0.0; 512];
+        engine.process_buffer(&mut buffer);
+        let peak = buffer
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        assert!(peak < 1e-3, "peak {}", peak);
+
+        // The phase is taken modulo 2π
+        engine.stop_all_notes();
+        let id = engine
+            .trigger_note_with_phase(Waveform::Sine, 440.0, envelope, 5.0 * PI / 2.0)
+            .unwrap();
+        let index = engine.slot_by_id[&id];
+        assert!((engine.voices[index].phase - PI / 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_mix_headroom_scales_sum_before_master_and_clamp() {
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 0.1,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let render = |headroom_db: f32, master_volume: f32| {
+            let mut engine = RealtimeEngine::new(44100.0);
+            engine.set_mix_headroom_db(headroom_db);
+            engine.set_master_volume(master_volume);
+            // Two in-phase squares sum to twice full scale
+            engine.trigger_note(Waveform::Square, 441.0, envelope.clone());
+            engine.trigger_note(Waveform::Square, 441.0, envelope.clone());
+            let mut buffer = vec![0.0; 200];
+            engine.process_buffer(&mut buffer);
+            buffer[100..]
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+
+        // 0 dB: the sum clips at full scale
+        assert_eq!(render(0.0, 1.0), 1.0);
+
+        // -6 dB halves the pre-master mix, and master volume applies on top
+        let expected = 2.0 * 10.0_f32.powf(-6.0 / 20.0) * 0.5;
+        assert!((render(-6.0, 0.5) - expected).abs() < 1e-3);
+        assert!((render(-6.0, 0.25) - expected / 2.0).abs() < 1e-3);
+        assert!((render(0.0, 0.25) - 0.5).abs() < 1e-3);
+
+        // Headroom is attenuation only, and survives a round trip
+        let mut engine = RealtimeEngine::new(44100.0);
+        assert_eq!(engine.get_mix_headroom_db(), 0.0);
+        engine.set_mix_headroom_db(-6.0);
+        assert!((engine.get_mix_headroom_db() + 6.0).abs() < 1e-4);
+        engine.set_mix_headroom_db(12.0);
+        assert_eq!(engine.get_mix_headroom_db(), 0.0);
+        engine.set_mix_headroom_db(f32::NAN);
+        assert_eq!(engine.get_mix_headroom_db(), 0.0);
+    }
+
+    #[test]
+    fn test_finished_events_report_frame_offset() {
+        // Power-of-two rate keeps the envelope clock exact: release lasts 8 frames
+        let mut engine = RealtimeEngine::new(1024.0);
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.0,
+            sustain_level: 1.0,
+            release_secs: 8.0 / 1024.0,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let held = engine
+            .trigger_note(Waveform::Sine, 100.0, held_envelope())
+            .unwrap();
+        let short = engine
+            .trigger_note(Waveform::Sine, 100.0, envelope)
+            .unwrap();
+
+        let mut buffer = vec![0.0; 16];
+        engine.process_buffer(&mut buffer);
+        assert!(engine.finished_events().is_empty());
+
+        engine.release_note(short);
+        engine.process_buffer(&mut buffer);
+        assert_eq!(engine.finished_events(), vec![(short, 7)]);
+
+        // Stereo buffers report frame offsets, and each buffer starts a new list
+        engine.release_note(held);
+        let mut stereo = vec![0.0; 512];
+        engine.process_stereo_buffer(&mut stereo);
+        let events = engine.finished_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, held);
+        assert!(events[0].1 > 90 && events[0].1 < 110);
+
+        engine.process_buffer(&mut buffer);
+        assert!(engine.finished_events().is_empty());
+    }
+
+    #[test]
+    fn test_realtime_interpolation_matches_offline_path() {
+        let sample = high_frequency_sine_sample(Interpolation::Linear);
+        let frequency = 110.0; // Two octaves below the sample's base
+        let offline = |interpolation: Interpolation| {
+            let waveform = Waveform::Sample(sample.clone().with_interpolation(interpolation));
+            generate_wave_raw(waveform, frequency, 0.1, 44100)
+        };
+        let max_difference = |realtime: &[f32], offline: &[f32]| {
+            realtime
+                .iter()
+                .zip(offline)
+                .skip(200) // Past the envelope attack and decay
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0f32, f32::max)
+        };
+
+        // Engine-wide override applies to new notes
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.set_sample_interpolation(Some(Interpolation::Hermite));
+        assert_eq!(
+            engine.get_sample_interpolation(),
+            Some(Interpolation::Hermite)
+        );
+        engine.trigger_note(Waveform::Sample(sample.clone()), frequency, held_envelope());
+        let mut buffer = vec![0.0; 4096];
+        engine.process_buffer(&mut buffer);
+
+        // Identical to a note whose sample is configured for Hermite
+        let mut reference = RealtimeEngine::new(44100.0);
+        let hermite_sample = sample.clone().with_interpolation(Interpolation::Hermite);
+        reference.trigger_note(Waveform::Sample(hermite_sample), frequency, held_envelope());
+        let mut expected = vec![0.0; 4096];
+        reference.process_buffer(&mut expected);
+        assert_eq!(buffer, expected);
+
+        // The voice clock accumulates f32 rounding, so compare with the offline
+        // render loosely: far closer to Hermite than to linear
+        let hermite_error = max_difference(&buffer, &offline(Interpolation::Hermite));
+        let linear_error = max_difference(&buffer, &offline(Interpolation::Linear));
+        assert!(hermite_error * 4.0 < linear_error);
+
+        // Per-voice selection on a playing note
+        let mut engine = RealtimeEngine::new(44100.0);
+        let id = engine
+            .trigger_note(Waveform::Sample(sample.clone()), frequency, held_envelope())
+            .unwrap();
+        engine.set_voice_interpolation(id, Interpolation::Nearest);
+        engine.process_buffer(&mut buffer);
+
+        let mut reference = RealtimeEngine::new(44100.0);
+        let nearest_sample = sample.clone().with_interpolation(Interpolation::Nearest);
+        reference.trigger_note(Waveform::Sample(nearest_sample), frequency, held_envelope());
+        reference.process_buffer(&mut expected);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn test_adaptive_voice_limit_sheds_quietest_and_recovers() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let mut ids = Vec::new();
+        for i in 1..=16 {
+            let volume = i as f32 / 16.0;
+            ids.push(
+                engine
+                    .trigger_note_with_volume(Waveform::Sine, 220.0, held_envelope(), volume)
+                    .unwrap(),
+            );
+        }
+        let mut buffer = vec![0.0; 512];
+        engine.process_buffer(&mut buffer);
+
+        // Reports are ignored until the limiter is enabled
+        engine.report_processing_time(0.011, 0.0116);
+        assert_eq!(engine.get_effective_max_voices(), MAX_VOICES);
+
+        // Processing at 95% of the budget drops the cap and fades the quietest
+        engine.set_adaptive_voice_limit(true);
+        engine.report_processing_time(0.011, 0.0116);
+        assert_eq!(engine.get_effective_max_voices(), 12);
+        engine.process_buffer(&mut buffer);
+        assert_eq!(engine.get_active_voice_count(), 12);
+        assert!(ids[..4].iter().all(|&id| engine.active_slot(id).is_none()));
+        assert!(ids[4..].iter().all(|&id| engine.active_slot(id).is_some()));
+
+        // New notes over the cap replace the quietest voice
+        let loud = engine
+            .trigger_note_with_volume(Waveform::Sine, 330.0, held_envelope(), 1.0)
+            .unwrap();
+        assert_eq!(engine.get_active_voice_count(), 12);
+        assert!(engine.active_slot(ids[4]).is_none());
+        assert!(engine.active_slot(loud).is_some());
+
+        // Moderate load holds the cap, light load restores it a voice at a time
+        engine.report_processing_time(0.007, 0.0116);
+        assert_eq!(engine.get_effective_max_voices(), 12);
+        engine.report_processing_time(0.002, 0.0116);
+        assert_eq!(engine.get_effective_max_voices(), 13);
+        for _ in 0..MAX_VOICES {
+            engine.report_processing_time(0.002, 0.0116);
+        }
+        assert_eq!(engine.get_effective_max_voices(), MAX_VOICES);
+
+        engine.report_processing_time(0.011, 0.0116);
+        engine.set_adaptive_voice_limit(false);
+        assert_eq!(engine.get_effective_max_voices(), MAX_VOICES);
+    }
+
+    #[test]
+    fn test_reset_restores_pristine_state() {
+        let mut engine = RealtimeEngine::new(48000.0);
+        engine.set_mix_headroom_db(-6.0);
+        engine.set_master_volume(0.3);
+        for _ in 0..5 {
+            engine.trigger_note(Waveform::Sine, 440.0, held_envelope());
+        }
+        let mut buffer = vec![0.0; 256];
+        engine.process_buffer(&mut buffer);
+
+        engine.reset();
+        assert_eq!(engine.get_active_voice_count(), 0);
+        assert_eq!(engine.get_master_volume(), 1.0);
+        assert!(engine.finished_events().is_empty());
+
+        // Configuration survives
+        assert_eq!(engine.get_sample_rate(), 48000.0);
+        assert!((engine.get_mix_headroom_db() + 6.0).abs() < 1e-4);
+
+        // IDs start over, and playback matches a fresh engine
+        let mut fresh = RealtimeEngine::new(48000.0);
+        fresh.set_mix_headroom_db(-6.0);
+        let id = engine
+            .trigger_note(Waveform::Sine, 440.0, held_envelope())
+            .unwrap();
+        assert_eq!(id, 1);
+        fresh.trigger_note(Waveform::Sine, 440.0, held_envelope());
+
+        let mut expected = vec![0.0; 256];
+        engine.process_buffer(&mut buffer);
+        fresh.process_buffer(&mut expected);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn test_mono_note_priority_selects_held_note() {
+        use NotePriority::*;
+        // Pressed 220, 440, 330; then the sounding note is released
+        for (priority, sounding, after_release) in [
+            (Last, 330.0, 440.0),
+            (High, 440.0, 330.0),
+            (Low, 220.0, 330.0),
+        ] {
+            let mut engine = RealtimeEngine::new(44100.0);
+            engine.set_note_priority(priority);
+            let mut voice_id = None;
+            for frequency in [220.0, 440.0, 330.0] {
+                voice_id = engine.press_mono_note(Waveform::Sine, frequency, held_envelope(), 1.0);
+            }
+            let frequency_of = |engine: &RealtimeEngine, id| {
+                engine.voices[engine.active_slot(id).unwrap()].frequency
+            };
+
+            assert_eq!(engine.get_mono_note(), Some(sounding), "{:?}", priority);
+            assert_eq!(frequency_of(&engine, voice_id.unwrap()), sounding);
+            assert_eq!(engine.get_active_voice_count(), 1);
+
+            engine.release_mono_note(sounding);
+            let voice_id = engine.mono_voice.unwrap();
+            assert_eq!(
+                engine.get_mono_note(),
+                Some(after_release),
+                "{:?}",
+                priority
+            );
+            assert_eq!(frequency_of(&engine, voice_id), after_release);
+            assert_eq!(engine.get_active_voice_count(), 1);
+
+            // Releasing a note that is not sounding leaves the voice alone
+            let silent = [220.0, 330.0, 440.0]
+                .into_iter()
+                .find(|&f| f != sounding && f != after_release)
+                .unwrap();
+            engine.release_mono_note(silent);
+            assert_eq!(frequency_of(&engine, voice_id), after_release);
+
+            // Releasing the last held note releases the voice
+            engine.release_mono_note(after_release);
+            assert_eq!(engine.get_mono_note(), None);
+            let index = engine.active_slot(voice_id).unwrap();
+            assert_eq!(
+                engine.voices[index].envelope_state.phase,
+                EnvelopePhase::Release
+            );
+        }
+    }
+
+    #[test]
+    fn test_mono_glide_slides_without_retrigger() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let first = engine.press_mono_note(Waveform::Sine, 220.0, held_envelope(), 1.0);
+
+        // Without glide a new note retriggers the voice at the new pitch
+        let second = engine.press_mono_note(Waveform::Sine, 440.0, held_envelope(), 1.0);
+        assert_ne!(first, second);
+        let index = engine.active_slot(second.unwrap()).unwrap();
+        assert_eq!(engine.voices[index].frequency, 440.0);
+
+        // With glide the same note slides down over 10 ms
+        engine.set_mono_glide_secs(0.01);
+        engine.process_buffer(&mut vec![0.0; 441]);
+        let third = engine.press_mono_note(Waveform::Sine, 220.0, held_envelope(), 1.0);
+        assert_eq!(third, second);
+        assert_eq!(engine.voices[index].frequency, 440.0);
+
+        engine.process_buffer(&mut vec![0.0; 220]);
+        let midway = engine.voices[index].frequency;
+        assert!((midway - 220.0 * 2.0f32.sqrt()).abs() < 2.0, "{}", midway);
+        engine.process_buffer(&mut vec![0.0; 300]);
+        assert_eq!(engine.voices[index].frequency, 220.0);
+        assert_eq!(
+            engine.voices[index].envelope_state.phase,
+            EnvelopePhase::Sustain
+        );
+    }
+
+    #[test]
+    fn test_legato_glide_only_between_overlapping_notes() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        engine.set_mono_glide_secs(0.01);
+        assert_eq!(engine.get_mono_glide_mode(), GlideMode::Legato);
+        let frequency = |engine: &RealtimeEngine, id: Option<u32>| {
+            engine.voices[engine.active_slot(id.unwrap()).unwrap()].frequency
+        };
+
+        // Overlapping notes glide to the new pitch
+        let first = engine.press_mono_note(Waveform::Sine, 220.0, held_envelope(), 1.0);
+        engine.process_buffer(&mut vec![0.0; 441]);
+        let second = engine.press_mono_note(Waveform::Sine, 440.0, held_envelope(), 1.0);
+        assert_eq!(first, second);
+        assert_eq!(frequency(&engine, second), 220.0);
+        engine.process_buffer(&mut vec![0.0; 600]);
+        assert_eq!(frequency(&engine, second), 440.0);
+
+        // After a gap the next note starts at its own pitch
+        engine.release_mono_note(220.0);
+        engine.release_mono_note(440.0);
+        engine.process_buffer(&mut vec![0.0; 441]);
+        let after_gap = engine.press_mono_note(Waveform::Sine, 330.0, held_envelope(), 1.0);
+        assert_eq!(frequency(&engine, after_gap), 330.0);
+
+        // In Always mode the same gap is bridged by a glide from the last pitch
+        engine.set_mono_glide_mode(GlideMode::Always);
+        engine.release_mono_note(330.0);
+        engine.process_buffer(&mut vec![0.0; 441]);
+        let glided = engine.press_mono_note(Waveform::Sine, 440.0, held_envelope(), 1.0);
+        assert_eq!(frequency(&engine, glided), 330.0);
+        engine.process_buffer(&mut vec![0.0; 600]);
+        assert_eq!(frequency(&engine, glided), 440.0);
+    }
+
+    #[test]
+    fn test_single_sample_steps_match_buffer() {
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs: 0.0,
+            decay_secs: 0.02,
+            sustain_level: 0.5,
+            release_secs: 0.01,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let start = |engine: &mut RealtimeEngine| {
+            engine.trigger_note(Waveform::Sawtooth, 220.0, envelope.clone());
+            engine.trigger_note(Waveform::Sine, 330.0, envelope.clone());
+        };
+
+        let mut buffered = RealtimeEngine::new(44100.0);
+        let mut stepped = RealtimeEngine::new(44100.0);
+        start(&mut buffered);
+        start(&mut stepped);
+
+        let mut buffer = vec![0.0; 4096];
+        buffered.process_buffer(&mut buffer);
+        let steps: Vec<f32> = (0..4096).map(|_| stepped.process_sample_mixed()).collect();
+        assert_eq!(steps, buffer);
+
+        let mut stereo = vec![0.0; 512];
+        buffered.process_stereo_buffer(&mut stereo);
+        let stereo_steps: Vec<f32> = (0..256)
+            .flat_map(|_| {
+                let (left, right) = stepped.process_sample_stereo();
+                [left, right]
+            })
+            .collect();
+        assert_eq!(stereo_steps, stereo);
+        assert_eq!(
+            stepped.get_active_voice_count(),
+            buffered.get_active_voice_count()
+        );
+    }
+
+    #[test]
+    fn test_wrapped_voice_ids_skip_active_voices() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let held = engine
+            .trigger_note(Waveform::Sine, 220.0, held_envelope())
+            .unwrap();
+        assert_eq!(held, 1);
+
+        // After a full wrap, ID 1 is still held and must not be reissued
+        engine.next_voice_id = u32::MAX;
+        let next = engine.trigger_note(Waveform::Sine, 330.0, held_envelope());
+        assert_eq!(next, Some(2));
+    }
+
+    /// Average Goertzel power over evenly spaced bins in a frequency band
+    fn band_power(buffer: &[f32], low_hz: f32, high_hz: f32, sample_rate: f32) -> f32 {
+        let bins = 16;
+        let mut total = 0.0;
+        for bin in 0..bins {
+            let frequency = low_hz + (high_hz - low_hz) * bin as f32 / (bins - 1) as f32;
+            let coefficient = 2.0 * (2.0 * PI * frequency / sample_rate).cos();
+            let (mut previous, mut before_previous) = (0.0f32, 0.0f32);
+            for &sample in buffer {
+                let current = sample + coefficient * previous - before_previous;
+                before_previous = previous;
+                previous = current;
+            }
+            total += previous * previous + before_previous * before_previous
+                - coefficient * previous * before_previous;
+        }
+        total / bins as f32
+    }
+
+    #[test]
+    fn test_filtered_noise_attenuates_above_cutoff() {
+        let raw = Waveform::FilteredNoise {
+            cutoff_hz: 22050.0,
+            resonance: 0.0,
+        };
+        let filtered = Waveform::FilteredNoise {
+            cutoff_hz: 2000.0,
+            resonance: 0.0,
+        };
+        let raw = generate_wave(raw, 440.0, 0.5, 44100);
+        let filtered = generate_wave(filtered, 440.0, 0.5, 44100);
+        assert!(filtered.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+
+        // Pass band is mostly kept, two octaves and more above the cutoff is not
+        let pass_ratio = band_power(&filtered, 200.0, 1000.0, 44100.0)
+            / band_power(&raw, 200.0, 1000.0, 44100.0);
+        let stop_ratio = band_power(&filtered, 8000.0, 16000.0, 44100.0)
+            / band_power(&raw, 8000.0, 16000.0, 44100.0);
+        assert!(pass_ratio > 0.5, "pass band ratio {}", pass_ratio);
+        assert!(stop_ratio < 0.01, "stop band ratio {}", stop_ratio);
+    }
+
+    #[test]
+    fn test_plucked_string_pitch_and_decay() {
+        let rms = |samples: &[f32]| {
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+        let pluck =
+            |damping: f32| generate_wave(Waveform::PluckedString { damping }, 196.0, 1.0, 44100);
+
+        let ringing = pluck(0.0);
+        let muted = pluck(1.0);
+        assert!(ringing.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+
+        let sample = SampleData {
+            samples: ringing[4410..26460].to_vec(),
+            sample_rate: 44100,
+            base_frequency: 440.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: None,
+            metadata: SampleMetadata {
+                filename: "pluck.wav".to_string(),
+                duration_secs: 0.5,
+                channels: 1,
+                bits_per_sample: 32,
+            },
+        };
+        let pitch = sample.detect_base_frequency().unwrap();
+        let cents = 1200.0 * (pitch.frequency_hz / 196.0).log2();
+        assert!(cents.abs() < 5.0, "{} Hz", pitch.frequency_hz);
+
+        // Both decay on their own, the damped string much faster
+        let ringing_decay = rms(&ringing[39690..]) / rms(&ringing[..4410]);
+        let muted_decay = rms(&muted[39690..]) / rms(&muted[..4410]);
+        assert!(ringing_decay < 0.9, "ringing decay {}", ringing_decay);
+        assert!(
+            muted_decay < ringing_decay * 0.01,
+            "muted decay {}",
+            muted_decay
+        );
+    }
+
+    #[test]
+    fn test_plucked_string_voice_replucks_on_trigger() {
+        let waveform = Waveform::PluckedString { damping: 0.2 };
+        assert_eq!(waveform.kind_name(), "plucked_string");
+        assert!(waveform.is_pitched());
+
+        let mut voice = Voice::new(0);
+        let mut pluck = |frequency: f32| -> Vec<f32> {
+            voice.trigger_note(waveform.clone(), frequency, held_envelope());
+            (0..2048).map(|_| voice.process_sample(44100.0)).collect()
+        };
+        let first = pluck(110.0);
+        let higher = pluck(220.0);
+        let again = pluck(110.0);
+        assert!(first.iter().any(|s| s.abs() > 0.1));
+        assert_ne!(first, higher);
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn test_engine_plucks_without_allocating_and_decays_to_zero() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        let capacity = engine.voices[0].noise.string.delay_line.capacity();
+        assert!(capacity >= 2205, "capacity {}", capacity);
+
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 60.0,
+            decay_secs: 0.0,
+            sustain_level: 0.0,
+            release_secs: 0.0,
+            curve: EnvelopeCurve::LINEAR,
+        };
+        let damped = Waveform::PluckedString { damping: 1.0 };
+        engine.trigger_note(damped.clone(), 2000.0, envelope.clone());
+        let mut buffer = vec![0.0; 44100 * 2];
+        engine.process_buffer(&mut buffer);
+
+        // The damped string ends in true silence rather than a subnormal tail
+        assert!(buffer.iter().all(|s| !s.is_subnormal()));
+        assert!(buffer[44100..].iter().all(|s| *s == 0.0));
+
+        // Even the lowest pluck fits the line reserved up front
+        engine.trigger_note(damped, 1.0, envelope);
+        engine.process_buffer(&mut buffer[..64]);
+        assert!(engine
+            .voices
+            .iter()
+            .all(|voice| voice.noise.string.delay_line.capacity() == capacity));
+        assert!(engine
+            .voices
+            .iter()
+            .any(|voice| voice.noise.string.delay_line.len() > 2000));
+    }
+
+    #[test]
+    fn test_filtered_noise_voice_state_resets_on_trigger() {
+        let waveform = Waveform::FilteredNoise {
+            cutoff_hz: 6000.0,
+            resonance: 0.5,
+        };
+        assert_eq!(waveform.kind_name(), "filtered_noise");
+        assert!(!waveform.is_pitched());
+
+        let mut voice = Voice::new(0);
+        let burst = |voice: &mut Voice| -> Vec<f32> {
+            voice.trigger_note(waveform.clone(), 440.0, held_envelope());
+            (0..256).map(|_| voice.process_sample(44100.0)).collect()
+        };
+        let first = burst(&mut voice);
+        let second = burst(&mut voice);
+        assert!(first.iter().any(|s| s.abs() > 1e-3));
+        assert_eq!(first, second);
+    }
+
+    fn out_of_range_envelope() -> AdsrEnvelope {
+        AdsrEnvelope {
+            attack_secs: -0.5,
+            hold_secs: 0.0,
+            decay_secs: 0.01,
+            sustain_level: 1.5,
+            release_secs: f32::NAN,
+            curve: EnvelopeCurve::LINEAR,
+        }
+    }
+
+    #[test]
+    fn test_envelope_clamp_and_validation() {
+        let envelope = out_of_range_envelope();
+        assert_eq!(
+            envelope.clone().validated(),
+            Err(EnvelopeError::InvalidTime {
+                stage: "attack",
+                value: -0.5
+            })
+        );
+
+        let clamped = envelope.clamp();
+        assert_eq!(clamped.attack_secs, 0.0);
+        assert_eq!(clamped.decay_secs, 0.01);
+        assert_eq!(clamped.sustain_level, 1.0);
+        assert_eq!(clamped.release_secs, 0.0);
+        assert_eq!(clamped.clone().validated(), Ok(clamped));
+
+        let loud_sustain = AdsrEnvelope {
+            sustain_level: 1.5,
+            ..held_envelope()
+        };
+        assert_eq!(
+            loud_sustain.validated(),
+            Err(EnvelopeError::InvalidSustain(1.5))
+        );
+        assert_eq!(held_envelope().validated(), Ok(held_envelope()));
+    }
+
+    #[test]
+    fn test_playback_with_out_of_range_envelope_is_sane() {
+        let event = SoundEvent {
+            waveform: Waveform::Square,
+            start_frequency: 440.0,
+            end_frequency: 440.0,
+            duration_secs: 0.1,
+            envelope: out_of_range_envelope(),
+        };
+        let rendered = render_event(&event, 44100);
+        assert_eq!(rendered.len(), 4410);
+        // Instant attack to a sustain of 1.0: the square wave sits at full scale
+        assert!(rendered.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+        assert!((rendered[100].abs() - 1.0).abs() < 1e-6);
+
+        let mut engine = RealtimeEngine::new(44100.0);
+        let voice_id = engine
+            .trigger_note(Waveform::Square, 440.0, out_of_range_envelope())
+            .unwrap();
+        let mut buffer = vec![0.0; 512];
+        engine.process_buffer(&mut buffer);
+        assert!(buffer.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+        assert!(buffer[256].abs() > 0.9);
+
+        // Zero release stops the voice right away instead of hanging on NaN
+        engine.release_note(voice_id);
+        engine.process_buffer(&mut buffer);
+        assert!(buffer[256..].iter().all(|s| *s == 0.0));
+        assert_eq!(engine.get_active_voice_count(), 0);
+    }
+
+    #[test]
+    fn test_synth_rng_is_reproducible() {
+        let mut a = SynthRng::new(1234);
+        let mut b = SynthRng::new(1234);
+        let first: Vec<u64> = (0..100).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..100).map(|_| b.next_u64()).collect();
+        assert_eq!(first, second);
+
+        // Re-seeding restarts the sequence; other seeds (including 0) differ
+        a.seed(1234);
+        assert_eq!(a.next_u64(), first[0]);
+        assert_ne!(SynthRng::new(1235).next_u64(), first[0]);
+        let mut zero = SynthRng::new(0);
+        assert_ne!(zero.next_u64(), zero.next_u64());
+
+        // Noise voices restart their sequence on reset
+        let mut noise = NoiseFilter::with_seed(7);
+        let burst: Vec<f32> = (0..32).map(|_| noise.next_white()).collect();
+        noise.reset();
+        let replay: Vec<f32> = (0..32).map(|_| noise.next_white()).collect();
+        assert_eq!(burst, replay);
+    }
+
+    #[test]
+    fn test_synth_rng_is_uniform() {
+        let mut rng = SynthRng::new(99);
+        let count = 100_000;
+        let mut buckets = [0usize; 10];
+        let mut sum = 0.0f64;
+        for _ in 0..count {
+            let value = rng.next_f32();
+            assert!((0.0..1.0).contains(&value));
+            buckets[(value * 10.0) as usize] += 1;
+            sum += value as f64;
+        }
+
+        // Chi-squared with 9 degrees of freedom: 27.88 is the 0.1% critical value
+        let expected = count as f64 / 10.0;
+        let chi_squared: f64 = buckets
+            .iter()
+            .map(|&observed| (observed as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi_squared < 27.88, "chi squared {}", chi_squared);
+        assert!((sum / count as f64 - 0.5).abs() < 0.01);
+
+        let mut counts = [0usize; 3];
+        for _ in 0..3000 {
+            counts[rng.next_usize(3)] += 1;
+        }
+        assert!(counts.iter().all(|&c| (900..1100).contains(&c)));
+        assert_eq!(rng.next_usize(0), 0);
+    }
+
+    #[test]
+    fn test_phase_reset_off_shares_running_phase() {
+        let mut engine = RealtimeEngine::new(44100.0);
+        assert!(engine.get_phase_reset());
+
+        // Default: every note starts at phase 0
+        let mut buffer = vec![0.0; 100];
+        engine.process_buffer(&mut buffer);
+        let first = engine.trigger_note(Waveform::Sine, 441.0, held_envelope());
+        let index = engine
+            .voices
+            .iter()
+            .position(|voice| Some(voice.voice_id) == first)
+            .unwrap();
+        assert_eq!(engine.voices[index].phase, 0.0);
+
+        // Phase reset off: voices triggered together start at the same
+        // running phase; 125 samples at 441 Hz is 1.25 cycles
+        engine.stop_all_notes();
+        engine.set_phase_reset(false);
+        engine.process_buffer(&mut buffer[..25]);
+        let ids = [
+            engine.trigger_note(Waveform::Sine, 441.0, held_envelope()),
+            engine.trigger_note_with_volume(Waveform::Sine, 441.0, held_envelope(), 0.5),
+        ];
+        let phases: Vec<f32> = ids
+            .iter()
+            .map(|id| {
+                engine
+                    .voices
+                    .iter()
+                    .find(|voice| voice.is_active() && Some(voice.voice_id) == *id)
+                    .unwrap()
+                    .phase
+            })
+            .collect();
+        assert_eq!(phases[0], phases[1]);
+        assert!((phases[0] - PI / 2.0).abs() < 1e-4, "phase {}", phases[0]);
+    }
+
+    fn noise_event() -> SoundEvent {
+        SoundEvent {
+            waveform: Waveform::FilteredNoise {
+                cutoff_hz: 3000.0,
+                resonance: 0.3,
+            },
+            start_frequency: 440.0,
+            end_frequency: 440.0,
+            duration_secs: 0.05,
+            envelope: AdsrEnvelope {
+                attack_secs: 0.005,
+                hold_secs: 0.0,
+                decay_secs: 0.01,
+                sustain_level: 0.6,
+                release_secs: 0.01,
+                curve: EnvelopeCurve::LINEAR,
+            },
+        }
+    }
+
+    fn bits(samples: &[f32]) -> Vec<u32> {
+        samples.iter().map(|s| s.to_bits()).collect()
+    }
+
+    #[test]
+    fn test_seeded_render_is_bit_identical() {
+        let plain_noise = SoundEvent {
+            waveform: Waveform::Noise,
+            ..noise_event()
+        };
+        for event in [noise_event(), plain_noise] {
+            let first = render_event_seeded(&event, 44100, 42);
+            let second = render_event_seeded(&event, 44100, 42);
+            assert!(!first.is_empty());
+            assert_eq!(bits(&first), bits(&second));
+
+            let other_seed = render_event_seeded(&event, 44100, 43);
+            assert_ne!(bits(&first), bits(&other_seed));
+
+            // The unseeded render is the default-seed golden output
+            assert_eq!(
+                bits(&render_event(&event, 44100)),
+                bits(&render_event_seeded(
+                    &event,
+                    44100,
+                    NoiseFilter::DEFAULT_SEED
+                ))
+            );
+        }
+
+        // Pitched events ignore the seed
+        let tone = SoundEvent {
+            waveform: Waveform::Sawtooth,
+            ..noise_event()
+        };
+        assert_eq!(
+            bits(&render_event_seeded(&tone, 44100, 1)),
+            bits(&render_event_seeded(&tone, 44100, 2))
+        );
+    }
+
+    #[test]
+    fn test_timeline_render_is_bit_identical() {
+        let events = vec![
+            (0.0, noise_event()),
+            (
+                0.02,
+                SoundEvent {
+                    waveform: Waveform::Sine,
+                    ..noise_event()
+                },
+            ),
+        ];
+        let first = render_timeline(&events, 0.1, 44100);
+        let second = render_timeline(&events, 0.1, 44100);
+        assert_eq!(bits(&first), bits(&second));
+    }
+
+    fn hold_envelope(hold_secs: f32) -> AdsrEnvelope {
+        AdsrEnvelope {
+            attack_secs: 0.01,
+            hold_secs,
+            decay_secs: 0.01,
+            sustain_level: 0.5,
+            release_secs: 0.01,
+            curve: EnvelopeCurve::LINEAR,
+        }
+    }
+
+    #[test]
+    fn test_apply_envelope_holds_peak() {
+        // 1 kHz: attack 10 samples, hold 20, decay 10, release the last 10
+        let mut samples = vec![1.0; 100];
+        apply_envelope(&mut samples, &hold_envelope(0.02), 1000);
+
+        assert_eq!(samples[5], 0.5);
+        assert!(samples[10..30].iter().all(|&s| s == 1.0));
+        assert!((samples[35] - 0.75).abs() < 1e-6);
+        assert_eq!(samples[50], 0.5);
+
+        // No hold: decay starts straight after the attack, as plain ADSR
+        let mut plain = vec![1.0; 100];
+        apply_envelope(&mut plain, &hold_envelope(0.0), 1000);
+        assert_eq!(plain[10], 1.0);
+        assert!((plain[15] - 0.75).abs() < 1e-6);
+        assert_eq!(plain[20], 0.5);
+    }
+
+    #[test]
+    fn test_envelope_state_hold_phase() {
+        let envelope = hold_envelope(0.02);
+        let mut state = EnvelopeState::new();
+        let dt = 0.001;
+
+        for _ in 0..10 {
+            state.update(&envelope, dt, false);
+        }
+        assert_eq!(state.phase, EnvelopePhase::Hold);
+
+        // Level stays at peak for the whole hold time
+        for _ in 0..19 {
+            assert_eq!(state.update(&envelope, dt, false), 1.0);
+            assert_eq!(state.phase, EnvelopePhase::Hold);
+        }
+        state.update(&envelope, dt, false);
+        assert_eq!(state.phase, EnvelopePhase::Decay);
+        assert!(state.update(&envelope, dt, false) < 1.0);
+
+        // Without hold the attack goes straight to decay
+        let mut plain = EnvelopeState::new();
+        for _ in 0..10 {
+            plain.update(&hold_envelope(0.0), dt, false);
+        }
+        assert_eq!(plain.phase, EnvelopePhase::Decay);
+    }
+
+    #[test]
+    fn test_envelope_without_hold_field_deserializes() {
+        let json =
+            r#"{"attack_secs":0.01,"decay_secs":0.01,"sustain_level":0.5,"release_secs":0.01}"#;
+        let envelope: AdsrEnvelope = serde_json::from_str(json).unwrap();
+        assert_eq!(envelope, hold_envelope(0.0));
+    }
+
+    #[test]
+    fn test_exponential_decay_reaches_sustain_on_time() {
+        let shaped = |decay: CurveShape| AdsrEnvelope {
+            attack_secs: 0.0,
+            hold_secs: 0.0,
+            decay_secs: 0.1,
+            sustain_level: 0.2,
+            release_secs: 0.1,
+            curve: EnvelopeCurve {
+                decay,
+                ..EnvelopeCurve::LINEAR
+            },
+        };
+        let dt = 0.001;
+
+        // Level at each millisecond of the decay, and the step the sustain phase began
+        let run = |envelope: &AdsrEnvelope| {
+            let mut state = EnvelopeState::new();
+            state.update(envelope, dt, false); // Zero-length attack
+            let mut levels = Vec::new();
+            while state.phase != EnvelopePhase::Sustain {
+                levels.push(state.update(envelope, dt, false));
+            }
+            levels
+        };
+        let linear = run(&shaped(CurveShape::Linear));
+        let exponential = run(&shaped(CurveShape::Exponential));
+
+        assert_eq!(exponential.len(), linear.len());
+        assert_eq!(*exponential.last().unwrap(), 0.2);
+        assert!((linear[49] - 0.6).abs() < 0.02, "linear mid {}", linear[49]);
+        assert!(exponential[49] < 0.3, "exponential mid {}", exponential[49]);
+        assert!(exponential.windows(2).all(|pair| pair[1] <= pair[0]));
+
+        // The offline renderer follows the same curve
+        let mut rendered = vec![1.0; 1000];
+        apply_envelope(&mut rendered, &shaped(CurveShape::Exponential), 1000);
+        assert!((rendered[50] - exponential[49]).abs() < 0.02);
+        assert!((rendered[100] - 0.2).abs() < 1e-6);
+
+        // A logarithmic shape bends the other way, and both reach their ends exactly
+        assert!(CurveShape::Logarithmic.apply(0.5) < 0.5);
+        assert!(CurveShape::Exponential.apply(0.5) > 0.5);
+        for shape in [CurveShape::Exponential, CurveShape::Logarithmic] {
+            assert_eq!(shape.apply(0.0), 0.0);
+            assert!((shape.apply(1.0) - 1.0).abs() < 1e-6);
+        }
+
+        // Envelopes saved before curves existed load with linear stages
+        let json = r#"{"attack_secs":0.0,"decay_secs":0.1,"sustain_level":0.2,"release_secs":0.1}"#;
+        let envelope: AdsrEnvelope = serde_json::from_str(json).unwrap();
+        assert_eq!(envelope.curve, EnvelopeCurve::LINEAR);
+    }
 }
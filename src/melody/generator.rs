@@ -7,6 +7,7 @@
 use super::types::*;
 use super::theory::{ChordFunction, MusicTheory, StandardMusicTheory, VoiceLeading};
 use crate::timing::TimeSignature;
+use crate::SynthRng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -125,8 +126,25 @@ pub trait Randomizer {
     fn random_usize(&mut self, max: usize) -> usize;
 }
 
-/// Default randomizer using system random
-pub struct SystemRandomizer;
+/// Default randomizer backed by a seedable `SynthRng`
+#[derive(Debug, Clone, Default)]
+pub struct SystemRandomizer {
+    rng: SynthRng,
+}
+
+impl SystemRandomizer {
+    /// Create a randomizer whose sequence starts from `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: SynthRng::new(seed),
+        }
+    }
+
+    /// Restart the random sequence from `seed`
+    pub fn seed(&mut self, seed: u64) {
+        self.rng.seed(seed);
+    }
+}
 
 impl Randomizer for SystemRandomizer {
     fn weighted_choice<T: Clone>(&mut self, choices: &[(T, f32)]) -> Option<T> {
@@ -153,20 +171,11 @@ impl Randomizer for SystemRandomizer {
     }
 
     fn random_float(&mut self) -> f32 {
-        // Simple LCG for deterministic testing - in production could use proper random
-        static mut SEED: u32 = 12345;
-        unsafe {
-            SEED = SEED.wrapping_mul(1103515245).wrapping_add(12345);
-            (SEED >> 16) as f32 / 65536.0
-        }
+        self.rng.next_f32()
     }
 
     fn random_usize(&mut self, max: usize) -> usize {
-        if max == 0 {
-            0
-        } else {
-            (self.random_float() * max as f32) as usize % max
-        }
+        self.rng.next_usize(max)
     }
 }
 
@@ -191,7 +200,7 @@ impl MarkovChordGenerator {
             transition_weights: HashMap::new(),
             theory_engine: Arc::new(theory_engine),
             parameters: GenerationParameters::default(),
-            randomizer: SystemRandomizer,
+            randomizer: SystemRandomizer::default(),
             chord_vocabulary: Vec::new(),
         };
 
@@ -213,7 +222,7 @@ impl MarkovChordGenerator {
             transition_weights: HashMap::new(),
             theory_engine,
             parameters: GenerationParameters::default(),
-            randomizer: SystemRandomizer,
+            randomizer: SystemRandomizer::default(),
             chord_vocabulary: Vec::new(),
         };
 
@@ -222,6 +231,11 @@ impl MarkovChordGenerator {
         generator
     }
 
+    /// Seed the chord selection randomizer for reproducible progressions
+    pub fn set_seed(&mut self, seed: u64) {
+        self.randomizer.seed(seed);
+    }
+
     /// Initialize available chord qualities with complexity weights
     fn initialize_chord_vocabulary(&mut self) {
         self.chord_vocabulary = vec![
@@ -550,4 +564,24 @@ mod tests {
         assert!(context.was_used_recently(&c_major, 2));
         assert!(!context.was_used_recently(&Chord::new(Note::G, ChordQuality::Major), 2));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        let key_selection = KeySelection::for_major_key(Note::C);
+        let context = GenerationContext::new(TimeSignature::new(4, 4), 120.0);
+        let progression = |seed: u64| {
+            let mut generator = MarkovChordGenerator::new_default();
+            generator.set_seed(seed);
+            let mut chords: Vec<Chord> = Vec::new();
+            for _ in 0..8 {
+                let next = generator
+                    .generate_next_chord(chords.last(), &key_selection, &context)
+                    .unwrap();
+                chords.push(next);
+            }
+            chords
+        };
+
+        assert_eq!(progression(42), progression(42));
+    }
+}
@@ -11,6 +11,7 @@
 //! - **Moving Timeline Display**: Current/next/following chords with notation
 //! - **Metronome Integration**: Beat-synchronized chord changes
 //! - **Standalone Mode**: Practice chord progressions without backing tracks
+//! - **Pitch Quantizer**: Snap live synth pitches to the selected key
 //!
 //! # Architecture
 //!
@@ -44,6 +45,8 @@
 pub mod config;
 /// Intelligent chord progression generation using Markov chains and music theory.
 pub mod generator;
+/// Real-time pitch quantization snapping voices to the selected key.
+pub mod quantizer;
 /// Real-time melody assistant state management with timeline integration.
 pub mod state;
 /// Music theory fundamentals including scales, chord functions, and voice leading.
@@ -56,10 +59,11 @@ pub mod types;
 // Re-export main types for convenient access
 pub use config::{MelodyConfig, GenerationConfig, ComplexityLevel, ConfigPreset, UiConfig, ConfigManager};
 pub use generator::{ChordGenerator, GenerationParameters, MarkovChordGenerator, GenerationContext};
+pub use quantizer::PitchQuantizer;
 pub use state::{MelodyAssistantState, SharedMelodyAssistantState, MelodyAssistantBuilder};
-pub use theory::{CircleOfFifths, MusicTheory, StandardMusicTheory, VoiceLeading, ScaleType, ChordFunction};
+pub use theory::{CircleOfFifths, MusicTheory, StandardMusicTheory, VoiceLeading, ScaleType, ChordFunction, ProgressionDifficulty};
 pub use timeline::{ChordTimeline, MovingTimeline, TimelineDisplayData};
-pub use types::{Chord, ChordEvent, ChordQuality, KeySelection, Note, TimelineConfig};
+pub use types::{Chord, ChordEvent, ChordQuality, GuitarShape, KeySelection, Note, TimelineConfig, GUITAR_STANDARD_TUNING};
 
 /// Create a melody assistant with default configuration
 pub fn create_default_melody_assistant() -> MelodyAssistantState {
@@ -0,0 +1,237 @@
+/// Real-time pitch quantization keyed to the melody key selection
+///
+/// This module provides the PitchQuantizer, an "auto-tune lite" that snaps an
+/// incoming pitch (from MIDI or pitch detection) to the nearest note enabled
+/// in a KeySelection and glides a voice's frequency toward it.
+use super::types::KeySelection;
+use crate::{frequency_to_midi, midi_to_frequency};
+
+/// Snaps input pitches to the enabled notes of a key and glides toward them
+///
+/// Gliding is linear in semitones, so a glide takes `glide_secs` regardless
+/// of the interval. Call `advance` once per audio block and pass the result to
+/// `RealtimeEngine::set_voice_frequency`.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::melody::{KeySelection, Note, PitchQuantizer};
+///
+/// let mut quantizer = PitchQuantizer::new(KeySelection::for_major_key(Note::C), 44100.0)
+///     .with_glide(0.05);
+///
+/// // A slightly sharp A4 is pulled to 440 Hz
+/// quantizer.set_input(446.0);
+/// let frequency = quantizer.advance(256);
+/// assert!((frequency - 440.0).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PitchQuantizer {
+    /// Notes that input pitches snap to
+    key_selection: KeySelection,
+    /// Sample rate used to convert glide time into samples
+    sample_rate: f32,
+    /// Time to glide between target notes in seconds (0.0 = instant)
+    glide_secs: f32,
+    /// Current output pitch as a fractional MIDI note
+    current_note: Option<f32>,
+    /// Quantized target pitch as a MIDI note
+    target_note: Option<f32>,
+    /// Glide increment per sample in semitones
+    step: f32,
+}
+
+impl PitchQuantizer {
+    /// Create a quantizer for the given key selection with no glide
+    pub fn new(key_selection: KeySelection, sample_rate: f32) -> Self {
+        Self {
+            key_selection,
+            sample_rate,
+            glide_secs: 0.0,
+            current_note: None,
+            target_note: None,
+            step: 0.0,
+        }
+    }
+
+    /// Set glide time in seconds
+    pub fn with_glide(mut self, glide_secs: f32) -> Self {
+        self.set_glide_time(glide_secs);
+        self
+    }
+
+    /// Set glide time in seconds (0.0 jumps straight to the target)
+    pub fn set_glide_time(&mut self, glide_secs: f32) {
+        self.glide_secs = glide_secs.max(0.0);
+    }
+
+    /// Get glide time in seconds
+    pub fn glide_time(&self) -> f32 {
+        self.glide_secs
+    }
+
+    /// Change the notes that input pitches snap to
+    pub fn set_key_selection(&mut self, key_selection: KeySelection) {
+        self.key_selection = key_selection;
+    }
+
+    /// Get the notes that input pitches snap to
+    pub fn key_selection(&self) -> &KeySelection {
+        &self.key_selection
+    }
+
+    /// Snap a frequency to the nearest enabled note
+    ///
+    /// When two enabled notes are equally close (a disabled note between
+    /// them), the higher one wins. Returns `None` for invalid input or when no
+    /// notes are enabled.
+    pub fn quantize_frequency(&self, frequency: f32) -> Option<f32> {
+        self.quantize_note(frequency).map(midi_to_frequency)
+    }
+
+    /// Set the incoming pitch and start gliding toward its quantized note
+    ///
+    /// The first input after creation or `reset` is applied immediately.
+    /// Returns the quantized target frequency.
+    pub fn set_input(&mut self, frequency: f32) -> Option<f32> {
+        let target = self.quantize_note(frequency)?;
+        self.target_note = Some(target);
+
+        let glide_samples = self.glide_secs * self.sample_rate;
+        match self.current_note {
+            Some(current) if glide_samples >= 1.0 => {
+                self.step = (target - current).abs() / glide_samples;
+            }
+            _ => {
+                self.current_note = Some(target);
+                self.step = 0.0;
+            }
+        }
+
+        Some(midi_to_frequency(target))
+    }
+
+    /// Advance the glide by a number of samples and return the output frequency
+    ///
+    /// Returns 0.0 until an input has been set.
+    pub fn advance(&mut self, samples: usize) -> f32 {
+        let (Some(current), Some(target)) = (self.current_note, self.target_note) else {
+            return 0.0;
+        };
+
+        let distance = target - current;
+        let travel = self.step * samples as f32;
+        let next = if travel >= distance.abs() {
+            target
+        } else {
+            current + travel * distance.signum()
+        };
+
+        self.current_note = Some(next);
+        midi_to_frequency(next)
+    }
+
+    /// Current output frequency in Hz, if an input has been set
+    pub fn current_frequency(&self) -> Option<f32> {
+        self.current_note.map(midi_to_frequency)
+    }
+
+    /// Quantized target frequency in Hz, if an input has been set
+    pub fn target_frequency(&self) -> Option<f32> {
+        self.target_note.map(midi_to_frequency)
+    }
+
+    /// Whether the output has reached the target note
+    pub fn is_settled(&self) -> bool {
+        self.current_note == self.target_note
+    }
+
+    /// Forget the current pitch so the next input is applied immediately
+    pub fn reset(&mut self) {
+        self.current_note = None;
+        self.target_note = None;
+        self.step = 0.0;
+    }
+
+    /// Nearest enabled MIDI note for a frequency
+    fn quantize_note(&self, frequency: f32) -> Option<f32> {
+        if !(frequency > 0.0 && frequency.is_finite()) {
+            return None;
+        }
+
+        let input = frequency_to_midi(frequency);
+        let center = input.round() as i32;
+
+        ((center - 6)..=(center + 6))
+            .filter(|note| self.key_selection.enabled_notes[note.rem_euclid(12) as usize])
+            .map(|note| note as f32)
+            .min_by(|a, b| {
+                let by_distance = (a - input).abs().total_cmp(&(b - input).abs());
+                by_distance.then(b.total_cmp(a))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::melody::types::Note;
+
+    fn c_major() -> PitchQuantizer {
+        PitchQuantizer::new(KeySelection::for_major_key(Note::C), 1000.0)
+    }
+
+    #[test]
+    fn test_off_pitch_input_snaps_to_scale_note() {
+        let quantizer = c_major();
+
+        // A4 a third of a semitone sharp, and E4 a little flat
+        let a4 = quantizer.quantize_frequency(449.0).unwrap();
+        let e4 = quantizer.quantize_frequency(325.0).unwrap();
+        assert!((a4 - 440.0).abs() < 0.01);
+        assert!((e4 - Note::E.frequency(4)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_disabled_note_skipped_for_next_enabled() {
+        let mut quantizer = c_major();
+
+        // C# is not in C major: exactly between C and D, the higher note wins
+        let snapped = quantizer
+            .quantize_frequency(Note::CSharp.frequency(4))
+            .unwrap();
+        assert!((snapped - Note::D.frequency(4)).abs() < 0.01);
+
+        // Disabling D as well pushes a D input on to E
+        let mut key = KeySelection::for_major_key(Note::C);
+        key.set_note_enabled(Note::D, false);
+        quantizer.set_key_selection(key);
+        let snapped = quantizer
+            .quantize_frequency(Note::D.frequency(4) * 1.01)
+            .unwrap();
+        assert!((snapped - Note::E.frequency(4)).abs() < 0.01);
+
+        quantizer.set_key_selection(KeySelection::new());
+        assert_eq!(quantizer.quantize_frequency(440.0), None);
+    }
+
+    #[test]
+    fn test_glide_reaches_target_after_glide_time() {
+        let mut quantizer = c_major().with_glide(0.1);
+
+        // First input jumps straight to the note
+        quantizer.set_input(440.0);
+        assert!((quantizer.advance(1) - 440.0).abs() < 0.01);
+
+        // A4 -> B4 is two semitones over 100 samples
+        quantizer.set_input(Note::B.frequency(4));
+        let halfway = quantizer.advance(50);
+        assert!((halfway - midi_to_frequency(70.0)).abs() < 0.01);
+        assert!(!quantizer.is_settled());
+
+        let arrived = quantizer.advance(50);
+        assert!((arrived - Note::B.frequency(4)).abs() < 0.01);
+        assert!(quantizer.is_settled());
+        assert_eq!(quantizer.advance(10), arrived);
+    }
+}
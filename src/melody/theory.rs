@@ -172,6 +172,113 @@ impl VoiceLeading {
     }
 }
 
+/// Difficulty analysis for chord progressions
+///
+/// Scores a progression on three axes: how complex its chords are (sevenths,
+/// altered and suspended qualities), how often it leaves one major key for
+/// another, and how far the voices move between chords. The combined rating
+/// uses the same 1-5 scale as drum pattern difficulty.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressionDifficulty {
+    /// Mean chord complexity (0.0 = plain triads, 2.0 = most complex qualities)
+    pub chord_complexity: f32,
+    /// Number of key changes needed to cover the progression
+    pub modulations: usize,
+    /// Mean voice movement between consecutive chords in semitones
+    pub average_voice_movement: f32,
+}
+
+impl ProgressionDifficulty {
+    /// Voice movement that still counts as smooth, in semitones
+    const SMOOTH_MOVEMENT: f32 = 1.5;
+
+    /// Analyse a progression
+    pub fn analyze(chords: &[Chord]) -> Self {
+        let chord_complexity = if chords.is_empty() {
+            0.0
+        } else {
+            chords.iter().map(|chord| Self::quality_complexity(chord.quality)).sum::<f32>()
+                / chords.len() as f32
+        };
+
+        let transitions = chords.len().saturating_sub(1);
+        let average_voice_movement = if transitions == 0 {
+            0.0
+        } else {
+            chords.windows(2)
+                .map(|pair| VoiceLeading::calculate_smoothness(&pair[0], &pair[1]))
+                .sum::<f32>()
+                / transitions as f32
+        };
+
+        Self {
+            chord_complexity,
+            modulations: Self::count_modulations(chords),
+            average_voice_movement,
+        }
+    }
+
+    /// Rate a progression from 1 (beginner) to 5 (advanced)
+    pub fn rate(chords: &[Chord]) -> u8 {
+        Self::analyze(chords).rating()
+    }
+
+    /// Combined rating from 1 (beginner) to 5 (advanced)
+    pub fn rating(&self) -> u8 {
+        let leaps = (self.average_voice_movement - Self::SMOOTH_MOVEMENT).max(0.0);
+        let score = 1.0
+            + self.chord_complexity
+            + self.modulations.min(3) as f32 * 0.75
+            + leaps * 0.75;
+        score.round().clamp(1.0, 5.0) as u8
+    }
+
+    /// Complexity weight of a chord quality
+    fn quality_complexity(quality: ChordQuality) -> f32 {
+        match quality {
+            ChordQuality::Major | ChordQuality::Minor => 0.0,
+            ChordQuality::Sus2 | ChordQuality::Sus4 => 0.5,
+            ChordQuality::Major7 | ChordQuality::Minor7 | ChordQuality::Dominant7 => 1.0,
+            ChordQuality::Diminished | ChordQuality::Augmented => 1.5,
+            ChordQuality::MinorMajor7 => 2.0,
+        }
+    }
+
+    /// Count key changes, greedily extending each key region as far as it fits
+    ///
+    /// Chords that belong to no major key (augmented, minor-major seventh) are
+    /// chromatic colour and neither start nor break a region.
+    fn count_modulations(chords: &[Chord]) -> usize {
+        let mut candidates: Vec<Note> = Vec::new();
+        let mut modulations = 0;
+
+        for chord in chords {
+            let keys: Vec<Note> = Note::all()
+                .into_iter()
+                .filter(|&key| {
+                    let scale = KeySelection::for_major_key(key);
+                    chord.chord_tones().iter().all(|&tone| scale.is_note_enabled(tone))
+                })
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let shared: Vec<Note> = candidates.iter().copied().filter(|key| keys.contains(key)).collect();
+            if shared.is_empty() {
+                if !candidates.is_empty() {
+                    modulations += 1;
+                }
+                candidates = keys;
+            } else {
+                candidates = shared;
+            }
+        }
+
+        modulations
+    }
+}
+
 /// Default music theory implementation
 #[derive(Clone)]
 pub struct StandardMusicTheory {
@@ -412,4 +519,36 @@ mod tests {
         assert_eq!(theory.chord_function(&f_major, Note::C), ChordFunction::Subdominant);
         assert_eq!(theory.chord_function(&g_major, Note::C), ChordFunction::Dominant);
     }
+
+    #[test]
+    fn test_progression_difficulty() {
+        let simple = [
+            Chord::new(Note::C, ChordQuality::Major),
+            Chord::new(Note::F, ChordQuality::Major),
+            Chord::new(Note::G, ChordQuality::Major),
+            Chord::new(Note::C, ChordQuality::Major),
+        ];
+        let simple_difficulty = ProgressionDifficulty::analyze(&simple);
+        assert_eq!(simple_difficulty.chord_complexity, 0.0);
+        assert_eq!(simple_difficulty.modulations, 0);
+        assert_eq!(simple_difficulty.rating(), 1);
+
+        // ii-V-I in C, then a ii-V-I in E♭ with an augmented passing chord
+        let advanced = [
+            Chord::new(Note::D, ChordQuality::Minor7),
+            Chord::new(Note::G, ChordQuality::Dominant7),
+            Chord::new(Note::C, ChordQuality::Major7),
+            Chord::new(Note::C, ChordQuality::Augmented),
+            Chord::new(Note::F, ChordQuality::Minor7),
+            Chord::new(Note::ASharp, ChordQuality::Dominant7),
+            Chord::new(Note::DSharp, ChordQuality::Major7),
+        ];
+        let advanced_difficulty = ProgressionDifficulty::analyze(&advanced);
+        assert_eq!(advanced_difficulty.modulations, 1);
+        assert!(advanced_difficulty.chord_complexity > 1.0);
+        assert!(advanced_difficulty.rating() >= 3);
+        assert!(ProgressionDifficulty::rate(&advanced) > ProgressionDifficulty::rate(&simple));
+
+        assert_eq!(ProgressionDifficulty::rate(&[]), 1);
+    }
 }
\ No newline at end of file
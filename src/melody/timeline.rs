@@ -147,6 +147,13 @@ impl ChordTimeline {
         self.events.is_empty()
     }
 
+    /// Transpose every chord event by semitones
+    pub fn transpose(&mut self, semitones: i8) {
+        for event in self.events.iter_mut() {
+            event.transpose(semitones);
+        }
+    }
+
     /// Get all events (for debugging/inspection)
     pub fn all_events(&self) -> &VecDeque<ChordEvent> {
         &self.events
@@ -404,6 +411,25 @@ mod tests {
         assert_eq!(display.visible_chords().len(), 0);
     }
 
+    #[test]
+    fn test_timeline_transpose() {
+        let time_sig = TimeSignature::new(4, 4);
+        let config = TimelineConfig::default();
+        let mut timeline = ChordTimeline::new(time_sig, &config);
+        let c_major = create_test_chord(Note::C, ChordQuality::Major);
+        let a_minor_first = Chord::with_inversion(Note::A, ChordQuality::Minor, 1);
+        timeline.add_chord_event(ChordEvent::new(c_major, 0, 4, Note::C));
+        timeline.add_chord_event(ChordEvent::new(a_minor_first, 4, 4, Note::C));
+
+        // Up a perfect fifth: C -> G, Am/C -> Em/G
+        timeline.transpose(7);
+        let events = timeline.all_events();
+        assert_eq!(events[0].chord, create_test_chord(Note::G, ChordQuality::Major));
+        assert_eq!(events[1].chord, Chord::with_inversion(Note::E, ChordQuality::Minor, 1));
+        assert_eq!(events[1].key_center, Note::G);
+        assert_eq!(events[1].beat_position, 4);
+    }
+
     #[test]
     fn test_key_center_changes() {
         let time_sig = TimeSignature::new(4, 4);
@@ -159,6 +159,43 @@ impl fmt::Display for Note {
     }
 }
 
+/// Standard guitar tuning as MIDI note numbers, low E to high E (E2 A2 D3 G3 B3 E4)
+pub const GUITAR_STANDARD_TUNING: [u8; 6] = [40, 45, 50, 55, 59, 64];
+
+/// Fret positions for one guitar shape, low E to high E (`None` = muted string)
+pub type GuitarShape = [Option<u8>; 6];
+
+const X: Option<u8> = None;
+
+/// Common open-position shapes, used in preference to barre chords
+const OPEN_GUITAR_SHAPES: &[(Note, ChordQuality, GuitarShape)] = &[
+    (Note::C, ChordQuality::Major, [X, Some(3), Some(2), Some(0), Some(1), Some(0)]),
+    (Note::A, ChordQuality::Major, [X, Some(0), Some(2), Some(2), Some(2), Some(0)]),
+    (Note::G, ChordQuality::Major, [Some(3), Some(2), Some(0), Some(0), Some(0), Some(3)]),
+    (Note::E, ChordQuality::Major, [Some(0), Some(2), Some(2), Some(1), Some(0), Some(0)]),
+    (Note::D, ChordQuality::Major, [X, X, Some(0), Some(2), Some(3), Some(2)]),
+    (Note::A, ChordQuality::Minor, [X, Some(0), Some(2), Some(2), Some(1), Some(0)]),
+    (Note::E, ChordQuality::Minor, [Some(0), Some(2), Some(2), Some(0), Some(0), Some(0)]),
+    (Note::D, ChordQuality::Minor, [X, X, Some(0), Some(2), Some(3), Some(1)]),
+    (Note::C, ChordQuality::Major7, [X, Some(3), Some(2), Some(0), Some(0), Some(0)]),
+    (Note::A, ChordQuality::Major7, [X, Some(0), Some(2), Some(1), Some(2), Some(0)]),
+    (Note::D, ChordQuality::Major7, [X, X, Some(0), Some(2), Some(2), Some(2)]),
+    (Note::C, ChordQuality::Dominant7, [X, Some(3), Some(2), Some(3), Some(1), Some(0)]),
+    (Note::A, ChordQuality::Dominant7, [X, Some(0), Some(2), Some(0), Some(2), Some(0)]),
+    (Note::G, ChordQuality::Dominant7, [Some(3), Some(2), Some(0), Some(0), Some(0), Some(1)]),
+    (Note::E, ChordQuality::Dominant7, [Some(0), Some(2), Some(0), Some(1), Some(0), Some(0)]),
+    (Note::D, ChordQuality::Dominant7, [X, X, Some(0), Some(2), Some(1), Some(2)]),
+    (Note::B, ChordQuality::Dominant7, [X, Some(2), Some(1), Some(2), Some(0), Some(2)]),
+    (Note::A, ChordQuality::Minor7, [X, Some(0), Some(2), Some(0), Some(1), Some(0)]),
+    (Note::E, ChordQuality::Minor7, [Some(0), Some(2), Some(0), Some(0), Some(0), Some(0)]),
+    (Note::D, ChordQuality::Minor7, [X, X, Some(0), Some(2), Some(1), Some(1)]),
+    (Note::A, ChordQuality::Sus2, [X, Some(0), Some(2), Some(2), Some(0), Some(0)]),
+    (Note::D, ChordQuality::Sus2, [X, X, Some(0), Some(2), Some(3), Some(0)]),
+    (Note::A, ChordQuality::Sus4, [X, Some(0), Some(2), Some(2), Some(3), Some(0)]),
+    (Note::D, ChordQuality::Sus4, [X, X, Some(0), Some(2), Some(3), Some(3)]),
+    (Note::E, ChordQuality::Sus4, [Some(0), Some(2), Some(2), Some(2), Some(0), Some(0)]),
+];
+
 /// Quality of a chord (major, minor, etc.)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChordQuality {
@@ -217,6 +254,38 @@ impl ChordQuality {
         }
     }
 
+    /// Movable barre shape with the root on the low E string (fret 0 = E root)
+    fn e_barre_shape(&self) -> GuitarShape {
+        match self {
+            ChordQuality::Major => [Some(0), Some(2), Some(2), Some(1), Some(0), Some(0)],
+            ChordQuality::Minor => [Some(0), Some(2), Some(2), Some(0), Some(0), Some(0)],
+            ChordQuality::Diminished => [Some(0), Some(1), Some(2), Some(0), X, X],
+            ChordQuality::Augmented => [Some(0), X, Some(2), Some(1), Some(1), Some(0)],
+            ChordQuality::Major7 => [Some(0), X, Some(1), Some(1), Some(0), X],
+            ChordQuality::Minor7 => [Some(0), Some(2), Some(0), Some(0), Some(0), Some(0)],
+            ChordQuality::Dominant7 => [Some(0), Some(2), Some(0), Some(1), Some(0), Some(0)],
+            ChordQuality::Sus2 => [Some(0), Some(2), Some(4), Some(4), Some(0), Some(0)],
+            ChordQuality::Sus4 => [Some(0), Some(2), Some(2), Some(2), Some(0), Some(0)],
+            ChordQuality::MinorMajor7 => [Some(0), Some(2), Some(1), Some(0), Some(0), Some(0)],
+        }
+    }
+
+    /// Movable barre shape with the root on the A string (fret 0 = A root)
+    fn a_barre_shape(&self) -> GuitarShape {
+        match self {
+            ChordQuality::Major => [X, Some(0), Some(2), Some(2), Some(2), Some(0)],
+            ChordQuality::Minor => [X, Some(0), Some(2), Some(2), Some(1), Some(0)],
+            ChordQuality::Diminished => [X, Some(0), Some(1), Some(2), Some(1), X],
+            ChordQuality::Augmented => [X, Some(0), Some(3), Some(2), Some(2), Some(1)],
+            ChordQuality::Major7 => [X, Some(0), Some(2), Some(1), Some(2), Some(0)],
+            ChordQuality::Minor7 => [X, Some(0), Some(2), Some(0), Some(1), Some(0)],
+            ChordQuality::Dominant7 => [X, Some(0), Some(2), Some(0), Some(2), Some(0)],
+            ChordQuality::Sus2 => [X, Some(0), Some(2), Some(2), Some(0), Some(0)],
+            ChordQuality::Sus4 => [X, Some(0), Some(2), Some(2), Some(3), Some(0)],
+            ChordQuality::MinorMajor7 => [X, Some(0), Some(2), Some(1), Some(1), Some(0)],
+        }
+    }
+
     /// Check if chord is dissonant (requires resolution)
     pub fn is_dissonant(&self) -> bool {
         matches!(self,
@@ -265,6 +334,15 @@ impl Chord {
         }
     }
 
+    /// Transpose chord root by semitones, keeping quality and inversion
+    pub fn transpose(&self, semitones: i8) -> Chord {
+        Self {
+            root: self.root.transpose(semitones),
+            quality: self.quality,
+            inversion: self.inversion,
+        }
+    }
+
     /// Get chord symbol (e.g., "Cmaj7", "Dm", "G7")
     pub fn symbol(&self) -> String {
         let mut symbol = self.root.name().to_string();
@@ -334,6 +412,46 @@ impl Chord {
         frequencies
     }
 
+    /// Get fret positions for a playable guitar shape of this chord
+    ///
+    /// Uses a common open-position shape when one exists, otherwise the lower
+    /// of the E-string and A-string barre shapes. Inversions are ignored, as
+    /// guitar shapes fix the bass string for each chord.
+    pub fn guitar_shape(&self) -> GuitarShape {
+        if let Some((_, _, shape)) = OPEN_GUITAR_SHAPES
+            .iter()
+            .find(|(root, quality, _)| *root == self.root && *quality == self.quality)
+        {
+            return *shape;
+        }
+
+        let e_fret = (self.root.as_semitone() + 12 - Note::E.as_semitone()) % 12;
+        let a_fret = (self.root.as_semitone() + 12 - Note::A.as_semitone()) % 12;
+        let (template, fret) = if e_fret <= a_fret {
+            (self.quality.e_barre_shape(), e_fret)
+        } else {
+            (self.quality.a_barre_shape(), a_fret)
+        };
+
+        template.map(|string| string.map(|offset| offset + fret))
+    }
+
+    /// Get frequencies of a guitar voicing, low string to high, muted strings omitted
+    ///
+    /// Sounds like a strummed six-string chord rather than the close voicing
+    /// from `chord_frequencies`.
+    pub fn guitar_voicing(&self) -> Vec<f32> {
+        self.guitar_shape()
+            .iter()
+            .zip(GUITAR_STANDARD_TUNING.iter())
+            .filter_map(|(fret, &open_string)| fret.map(|fret| open_string + fret))
+            .map(|midi_note| {
+                let (note, octave) = Note::from_midi_note(midi_note);
+                note.frequency(octave)
+            })
+            .collect()
+    }
+
     /// Get bass note frequency for audio playback (low octave)
     pub fn bass_frequency(&self) -> f32 {
         self.bass_note().frequency(2) // Bass note in low octave (87-175 Hz range)
@@ -417,6 +535,12 @@ impl ChordEvent {
         }
     }
 
+    /// Transpose chord and key center by semitones
+    pub fn transpose(&mut self, semitones: i8) {
+        self.chord = self.chord.transpose(semitones);
+        self.key_center = self.key_center.transpose(semitones);
+    }
+
     /// Get end beat position
     pub fn end_beat(&self) -> u32 {
         self.beat_position + self.duration_beats
@@ -703,4 +827,70 @@ mod tests {
         let config = TimelineConfig::default().for_skill_level(0.8);
         assert_eq!(config.beats_per_chord, 1); // Expert level (0.8 >= 0.8)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_open_c_major_guitar_voicing() {
+        let c_major = Chord::new(Note::C, ChordQuality::Major);
+        assert_eq!(c_major.guitar_shape(), [None, Some(3), Some(2), Some(0), Some(1), Some(0)]);
+
+        // Low E muted, then C3 E3 G3 C4 E4
+        let voicing = c_major.guitar_voicing();
+        let expected = [
+            Note::C.frequency(3),
+            Note::E.frequency(3),
+            Note::G.frequency(3),
+            Note::C.frequency(4),
+            Note::E.frequency(4),
+        ];
+        assert_eq!(voicing.len(), expected.len());
+        for (actual, expected) in voicing.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 0.01);
+        }
+
+        assert_ne!(voicing, c_major.chord_frequencies());
+    }
+
+    #[test]
+    fn test_barre_guitar_voicing() {
+        // F major has no open shape: E-string barre at the first fret
+        let f_major = Chord::new(Note::F, ChordQuality::Major);
+        assert_eq!(f_major.guitar_shape(), [Some(1), Some(3), Some(3), Some(2), Some(1), Some(1)]);
+
+        // B minor: A-string barre at the second fret
+        let b_minor = Chord::new(Note::B, ChordQuality::Minor);
+        assert_eq!(b_minor.guitar_shape(), [None, Some(2), Some(4), Some(4), Some(3), Some(2)]);
+    }
+
+    #[test]
+    fn test_guitar_voicings_only_contain_chord_tones() {
+        let qualities = [
+            ChordQuality::Major,
+            ChordQuality::Minor,
+            ChordQuality::Diminished,
+            ChordQuality::Augmented,
+            ChordQuality::Major7,
+            ChordQuality::Minor7,
+            ChordQuality::Dominant7,
+            ChordQuality::Sus2,
+            ChordQuality::Sus4,
+            ChordQuality::MinorMajor7,
+        ];
+
+        for root in Note::all() {
+            for quality in qualities {
+                let chord = Chord::new(root, quality);
+                let shape = chord.guitar_shape();
+                let sounding: Vec<Note> = shape
+                    .iter()
+                    .zip(GUITAR_STANDARD_TUNING.iter())
+                    .filter_map(|(fret, &open)| fret.map(|fret| Note::from_midi_note(open + fret).0))
+                    .collect();
+
+                assert!(sounding.len() >= 4, "{} has too few strings", chord);
+                assert!(sounding.iter().all(|note| chord.contains_note(*note)), "{} has a wrong note", chord);
+                assert!(sounding.contains(&root), "{} is missing its root", chord);
+                assert_eq!(chord.guitar_voicing().len(), sounding.len());
+            }
+        }
+    }
+}
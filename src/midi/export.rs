@@ -0,0 +1,308 @@
+/// Standard MIDI File export of sound event timelines
+///
+/// This module writes the `(start_secs, SoundEvent)` timelines that
+/// `render_timeline` turns into audio as a single-track Standard MIDI File,
+/// so a part built in Polyphonica can be opened in a DAW and played on any
+/// instrument. Each event becomes one note at the MIDI pitch nearest its start
+/// frequency; pitch sweeps can optionally be written as pitch-bend messages.
+use crate::{frequency_to_midi, SoundEvent};
+
+/// Default pitch-bend range of General MIDI instruments (semitones)
+pub const DEFAULT_PITCH_BEND_RANGE: f32 = 2.0;
+
+/// Pitch-bend messages written across each sweeping note
+const PITCH_BEND_STEPS: u32 = 16;
+
+/// Center (no bend) value of a 14-bit pitch-bend message
+const PITCH_BEND_CENTER: i32 = 8192;
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const PITCH_BEND: u8 = 0xE0;
+
+/// Writes timelines as format 0 Standard MIDI Files
+///
+/// All notes go to channel 1. Velocity follows the envelope's sustain level
+/// (at least 1, since velocity 0 means note-off). Pitch bends are channel-wide
+/// in MIDI, so they only sound as intended on monophonic lines.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::midi::MidiExporter;
+/// use polyphonica::{AdsrEnvelope, EnvelopeCurve, SoundEvent, Waveform};
+///
+/// let slide = SoundEvent {
+///     waveform: Waveform::Sawtooth,
+///     start_frequency: 220.0,
+///     end_frequency: 247.0,
+///     duration_secs: 0.5,
+///     envelope: AdsrEnvelope {
+///         attack_secs: 0.01,
+///         hold_secs: 0.0,
+///         decay_secs: 0.1,
+///         sustain_level: 0.8,
+///         release_secs: 0.1,
+///         curve: EnvelopeCurve::LINEAR,
+///     },
+/// };
+///
+/// let bytes = MidiExporter::new(120.0, 480)
+///     .with_pitch_bends(2.0)
+///     .export(&[(0.0, slide)]);
+/// assert_eq!(&bytes[..4], b"MThd");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiExporter {
+    /// Tempo written to the file and used to convert seconds to ticks
+    tempo_bpm: f32,
+
+    /// Ticks per quarter note
+    ppq: u16,
+
+    /// Pitch-bend range of the receiving instrument, if sweeps are exported
+    pitch_bend_range: Option<f32>,
+}
+
+impl MidiExporter {
+    /// Create an exporter writing plain notes at the given tempo and resolution
+    pub fn new(tempo_bpm: f32, ppq: u16) -> Self {
+        Self {
+            tempo_bpm: tempo_bpm.max(1.0),
+            ppq: ppq.clamp(1, 0x7FFF),
+            pitch_bend_range: None,
+        }
+    }
+
+    /// Write pitch sweeps as pitch bends for an instrument bending `range_semitones`
+    pub fn with_pitch_bends(mut self, range_semitones: f32) -> Self {
+        self.pitch_bend_range = Some(range_semitones.max(0.01));
+        self
+    }
+
+    /// Encode a timeline as Standard MIDI File bytes
+    pub fn export(&self, events: &[(f32, SoundEvent)]) -> Vec<u8> {
+        // (tick, order at equal ticks, message): offs, then bends, then ons
+        let mut messages: Vec<(u32, u8, [u8; 3])> = Vec::new();
+        for (start_secs, event) in events {
+            let start_tick = self.ticks(*start_secs);
+            let end_tick = self
+                .ticks(start_secs + event.duration_secs)
+                .max(start_tick + 1);
+            let note = nearest_midi_note(event.start_frequency);
+            let velocity =
+                ((event.envelope.sustain_level.clamp(0.0, 1.0) * 127.0).round() as u8).max(1);
+
+            messages.push((start_tick, 2, [NOTE_ON, note, velocity]));
+            messages.push((end_tick, 0, [NOTE_OFF, note, 0]));
+
+            let sweeps = event.start_frequency != event.end_frequency;
+            if let Some(range) = self.pitch_bend_range.filter(|_| sweeps) {
+                for step in 1..PITCH_BEND_STEPS {
+                    let progress = step as f32 / PITCH_BEND_STEPS as f32;
+                    let tick = start_tick + ((end_tick - start_tick) as f32 * progress) as u32;
+                    let frequency = event.start_frequency
+                        + (event.end_frequency - event.start_frequency) * progress;
+                    let semitones = frequency_to_midi(frequency) - note as f32;
+                    messages.push((tick, 3, pitch_bend(semitones / range)));
+                }
+                messages.push((end_tick, 1, pitch_bend(0.0)));
+            }
+        }
+        messages.sort_by_key(|&(tick, order, _)| (tick, order));
+
+        let mut track = Vec::new();
+        let micros_per_quarter = (60_000_000.0 / self.tempo_bpm as f64).round() as u32;
+        write_variable_length(&mut track, 0);
+        track.extend_from_slice(&[0xFF, 0x51, 0x03]);
+        track.extend_from_slice(&micros_per_quarter.to_be_bytes()[1..]);
+
+        let mut last_tick = 0;
+        for (tick, _, message) in messages {
+            write_variable_length(&mut track, tick - last_tick);
+            track.extend_from_slice(&message);
+            last_tick = tick;
+        }
+        write_variable_length(&mut track, 0);
+        track.extend_from_slice(&[0xFF, 0x2F, 0x00]);
+
+        let mut bytes = Vec::with_capacity(22 + track.len());
+        bytes.extend_from_slice(b"MThd");
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&0u16.to_be_bytes());
+        bytes.extend_from_slice(&1u16.to_be_bytes());
+        bytes.extend_from_slice(&self.ppq.to_be_bytes());
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&track);
+        bytes
+    }
+
+    /// Convert a time in seconds to ticks at the export tempo
+    fn ticks(&self, secs: f32) -> u32 {
+        let beats = secs.max(0.0) as f64 * self.tempo_bpm as f64 / 60.0;
+        (beats * self.ppq as f64).round() as u32
+    }
+}
+
+/// Encode a timeline as Standard MIDI File bytes, without pitch bends
+///
+/// See `MidiExporter` for how events map to notes.
+pub fn export_timeline(events: &[(f32, SoundEvent)], tempo_bpm: f32, ppq: u16) -> Vec<u8> {
+    MidiExporter::new(tempo_bpm, ppq).export(events)
+}
+
+/// MIDI note nearest a frequency, clamped to the valid range
+fn nearest_midi_note(frequency: f32) -> u8 {
+    if frequency <= 0.0 {
+        return 0;
+    }
+    frequency_to_midi(frequency).round().clamp(0.0, 127.0) as u8
+}
+
+/// Pitch-bend message for a bend as a fraction of the full range (-1.0 to 1.0)
+fn pitch_bend(fraction: f32) -> [u8; 3] {
+    let value = (PITCH_BEND_CENTER + (fraction * PITCH_BEND_CENTER as f32).round() as i32)
+        .clamp(0, 0x3FFF) as u16;
+    [PITCH_BEND, (value & 0x7F) as u8, (value >> 7) as u8]
+}
+
+/// Append a MIDI variable-length quantity
+fn write_variable_length(bytes: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    bytes.extend(groups.into_iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AdsrEnvelope, EnvelopeCurve, Waveform};
+
+    fn note(frequency: f32, end_frequency: f32, duration_secs: f32, sustain: f32) -> SoundEvent {
+        SoundEvent {
+            waveform: Waveform::Sine,
+            start_frequency: frequency,
+            end_frequency,
+            duration_secs,
+            envelope: AdsrEnvelope {
+                attack_secs: 0.01,
+                hold_secs: 0.0,
+                decay_secs: 0.05,
+                sustain_level: sustain,
+                release_secs: 0.05,
+                curve: EnvelopeCurve::LINEAR,
+            },
+        }
+    }
+
+    fn read_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    /// Parse a format 0 file into its ticks per quarter and (absolute tick, message) list
+    fn parse(bytes: &[u8]) -> (u16, Vec<(u32, Vec<u8>)>) {
+        assert_eq!(&bytes[..4], b"MThd");
+        assert_eq!(read_u32(bytes, 4), 6);
+        assert_eq!(&bytes[8..12], &[0, 0, 0, 1]);
+        let ppq = u16::from_be_bytes([bytes[12], bytes[13]]);
+        assert_eq!(&bytes[14..18], b"MTrk");
+        let track = &bytes[22..];
+        assert_eq!(track.len(), read_u32(bytes, 18) as usize);
+
+        let mut messages = Vec::new();
+        let (mut at, mut tick) = (0, 0);
+        while at < track.len() {
+            let mut delta = 0;
+            loop {
+                delta = (delta << 7) | (track[at] & 0x7F) as u32;
+                at += 1;
+                if track[at - 1] & 0x80 == 0 {
+                    break;
+                }
+            }
+            tick += delta;
+            let length = if track[at] == 0xFF {
+                3 + track[at + 2] as usize
+            } else {
+                3
+            };
+            messages.push((tick, track[at..at + length].to_vec()));
+            at += length;
+        }
+        (ppq, messages)
+    }
+
+    #[test]
+    fn test_exported_notes_parse_back_with_matching_timing() {
+        let events = vec![
+            (0.0, note(440.0, 440.0, 0.5, 1.0)),
+            (0.5, note(261.63, 261.63, 0.25, 0.5)),
+            (0.5, note(329.63, 329.63, 1.0, 0.0)),
+            (2.0, note(1000.0, 1000.0, 0.0, 0.8)),
+        ];
+        let (ppq, messages) = parse(&export_timeline(&events, 120.0, 480));
+        assert_eq!(ppq, 480);
+
+        // Tempo first (500000 us per quarter), end of track last
+        assert_eq!(messages[0], (0, vec![0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20]));
+        assert_eq!(messages.last().unwrap().1, vec![0xFF, 0x2F, 0x00]);
+
+        let ons: Vec<(u32, u8, u8)> = messages
+            .iter()
+            .filter(|(_, m)| m[0] == NOTE_ON)
+            .map(|(tick, m)| (*tick, m[1], m[2]))
+            .collect();
+        let offs: Vec<(u32, u8)> = messages
+            .iter()
+            .filter(|(_, m)| m[0] == NOTE_OFF)
+            .map(|(tick, m)| (*tick, m[1]))
+            .collect();
+
+        // Half a second is one beat at 120 BPM
+        assert_eq!(
+            ons,
+            vec![(0, 69, 127), (480, 60, 64), (480, 64, 1), (1920, 83, 102)]
+        );
+        assert_eq!(offs, vec![(480, 69), (720, 60), (1440, 64), (1921, 83)]);
+
+        // The note ending at 480 is released before the notes starting there
+        let first_off = messages.iter().position(|(_, m)| m[0] == NOTE_OFF);
+        let second_on = messages.iter().position(|(_, m)| m[..2] == [NOTE_ON, 60]);
+        assert!(first_off < second_on);
+    }
+
+    #[test]
+    fn test_pitch_sweeps_export_as_bends() {
+        // A whole-tone slide from A3 to B3 over one beat
+        let events = vec![(0.0, note(220.0, 246.94, 0.5, 0.8))];
+        let plain = parse(&export_timeline(&events, 120.0, 96)).1;
+        assert!(plain.iter().all(|(_, m)| m[0] != PITCH_BEND));
+
+        let bytes = MidiExporter::new(120.0, 96)
+            .with_pitch_bends(DEFAULT_PITCH_BEND_RANGE)
+            .export(&events);
+        let bends: Vec<(u32, i32)> = parse(&bytes)
+            .1
+            .iter()
+            .filter(|(_, m)| m[0] == PITCH_BEND)
+            .map(|(tick, m)| {
+                (
+                    *tick,
+                    ((m[2] as i32) << 7 | m[1] as i32) - PITCH_BEND_CENTER,
+                )
+            })
+            .collect();
+        assert_eq!(bends.len(), PITCH_BEND_STEPS as usize);
+
+        // Bends rise toward the full range, then reset when the note ends
+        assert!(bends.windows(2).take(14).all(|pair| pair[1].1 > pair[0].1));
+        let (last_tick, last_bend) = bends[PITCH_BEND_STEPS as usize - 2];
+        assert!(last_tick < 96 && last_bend > 7000, "{:?}", bends);
+        assert_eq!(*bends.last().unwrap(), (96, 0));
+    }
+}
@@ -0,0 +1,466 @@
+/// Live note triggering from MIDI note messages
+///
+/// This module turns raw MIDI note-on and note-off messages into voices on a
+/// RealtimeEngine. MidiNoteRouter does the translation and remembers which
+/// voice each held key started, so the matching note-off releases exactly
+/// that voice. With the `midi-input` feature, MidiInput feeds a router from
+/// a hardware or virtual input port through midir.
+use super::note_frequency;
+use crate::{AdsrEnvelope, RealtimeEngine, Waveform};
+use std::collections::HashMap;
+
+#[cfg(feature = "midi-input")]
+use crate::SharedRealtimeEngine;
+
+/// Client and port name shown to other MIDI software
+#[cfg(feature = "midi-input")]
+const CLIENT_NAME: &str = "Polyphonica";
+
+/// A note message parsed from raw MIDI bytes
+///
+/// Channels are 0-based (0-15), as encoded in the status byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessage {
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    NoteOff { channel: u8, note: u8 },
+}
+
+impl MidiMessage {
+    /// Parse a note-on or note-off message; other messages give None
+    ///
+    /// A note-on with velocity 0 is read as a note-off, as the MIDI
+    /// specification requires.
+    pub fn parse(bytes: &[u8]) -> Option<MidiMessage> {
+        let [status, note, velocity, ..] = *bytes else {
+            return None;
+        };
+        if note > 0x7F || velocity > 0x7F {
+            return None;
+        }
+
+        let channel = status & 0x0F;
+        match status & 0xF0 {
+            0x90 if velocity > 0 => Some(MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            }),
+            0x80 | 0x90 => Some(MidiMessage::NoteOff { channel, note }),
+            _ => None,
+        }
+    }
+
+    /// Channel the message was sent on (0-15)
+    pub fn channel(&self) -> u8 {
+        match *self {
+            MidiMessage::NoteOn { channel, .. } | MidiMessage::NoteOff { channel, .. } => channel,
+        }
+    }
+}
+
+/// Engine operations needed to play MIDI notes
+///
+/// Implemented by RealtimeEngine; tests and other front ends can provide
+/// their own implementation.
+pub trait NoteEngine {
+    /// Start a note at a MIDI velocity, returning its voice ID
+    fn trigger_note_with_velocity(
+        &mut self,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        velocity: u8,
+    ) -> Option<u32>;
+
+    /// Release the note playing on a voice
+    fn release_note(&mut self, voice_id: u32);
+}
+
+impl NoteEngine for RealtimeEngine {
+    fn trigger_note_with_velocity(
+        &mut self,
+        waveform: Waveform,
+        frequency: f32,
+        envelope: AdsrEnvelope,
+        velocity: u8,
+    ) -> Option<u32> {
+        RealtimeEngine::trigger_note_with_velocity(self, waveform, frequency, envelope, velocity)
+    }
+
+    fn release_note(&mut self, voice_id: u32) {
+        RealtimeEngine::release_note(self, voice_id);
+    }
+}
+
+/// Plays MIDI note messages with one waveform and envelope
+///
+/// Each note-on starts a voice at the key's equal-tempered pitch, and its
+/// note-off releases that voice. Striking a key that is still held releases
+/// its previous voice first. By default every channel is played; set a
+/// channel to ignore messages on the others.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::midi::MidiNoteRouter;
+/// use polyphonica::{AdsrEnvelope, EnvelopeCurve, RealtimeEngine, Waveform};
+///
+/// let envelope = AdsrEnvelope {
+///     attack_secs: 0.01,
+///     hold_secs: 0.0,
+///     decay_secs: 0.2,
+///     sustain_level: 0.6,
+///     release_secs: 0.3,
+///     curve: EnvelopeCurve::LINEAR,
+/// };
+/// let mut engine = RealtimeEngine::new(44100.0);
+/// let mut router = MidiNoteRouter::new(Waveform::Triangle, envelope).with_channel(0);
+///
+/// // Middle C down, then up
+/// router.handle(&mut engine, &[0x90, 60, 100]);
+/// assert_eq!(router.held_notes(), 1);
+/// router.handle(&mut engine, &[0x80, 60, 0]);
+/// assert_eq!(router.held_notes(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MidiNoteRouter {
+    /// Waveform every note is played with
+    waveform: Waveform,
+
+    /// Envelope every note is played with
+    envelope: AdsrEnvelope,
+
+    /// Only channel played (0-15), or None for all channels
+    channel: Option<u8>,
+
+    /// Voice started by each held (channel, note)
+    held: HashMap<(u8, u8), u32>,
+}
+
+impl MidiNoteRouter {
+    /// Create a router playing every channel with the given sound
+    pub fn new(waveform: Waveform, envelope: AdsrEnvelope) -> Self {
+        Self {
+            waveform,
+            envelope,
+            channel: None,
+            held: HashMap::new(),
+        }
+    }
+
+    /// Only play messages on one channel (0-15)
+    pub fn with_channel(mut self, channel: u8) -> Self {
+        self.channel = Some(channel.min(15));
+        self
+    }
+
+    /// Channel being played, or None for all channels
+    pub fn channel(&self) -> Option<u8> {
+        self.channel
+    }
+
+    /// Number of keys currently held down
+    pub fn held_notes(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Play a raw MIDI message on the engine
+    ///
+    /// Returns the note message acted on, or None if the bytes were not a
+    /// note message or were on another channel.
+    pub fn handle<E: NoteEngine + ?Sized>(
+        &mut self,
+        engine: &mut E,
+        bytes: &[u8],
+    ) -> Option<MidiMessage> {
+        let message = MidiMessage::parse(bytes)?;
+        if self
+            .channel
+            .is_some_and(|channel| channel != message.channel())
+        {
+            return None;
+        }
+
+        match message {
+            MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => {
+                if let Some(voice_id) = self.held.remove(&(channel, note)) {
+                    engine.release_note(voice_id);
+                }
+                if let Some(voice_id) = engine.trigger_note_with_velocity(
+                    self.waveform.clone(),
+                    note_frequency(note),
+                    self.envelope.clone(),
+                    velocity,
+                ) {
+                    self.held.insert((channel, note), voice_id);
+                }
+            }
+            MidiMessage::NoteOff { channel, note } => {
+                if let Some(voice_id) = self.held.remove(&(channel, note)) {
+                    engine.release_note(voice_id);
+                }
+            }
+        }
+        Some(message)
+    }
+
+    /// Release every held note, e.g. when the input is disconnected
+    pub fn release_all<E: NoteEngine + ?Sized>(&mut self, engine: &mut E) {
+        for (_, voice_id) in self.held.drain() {
+            engine.release_note(voice_id);
+        }
+    }
+}
+
+/// Errors that can occur while opening a MIDI input port
+#[cfg(feature = "midi-input")]
+#[derive(Debug)]
+pub enum MidiInputError {
+    /// The platform MIDI client could not be created
+    Init(midir::InitError),
+    /// No input port exists at the requested index
+    PortNotFound(usize),
+    /// Connecting to the port failed
+    Connect(midir::ConnectErrorKind),
+}
+
+#[cfg(feature = "midi-input")]
+impl std::fmt::Display for MidiInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MidiInputError::Init(e) => write!(f, "MIDI init error: {}", e),
+            MidiInputError::PortNotFound(index) => write!(f, "No MIDI input port {}", index),
+            MidiInputError::Connect(e) => write!(f, "MIDI connect error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "midi-input")]
+impl std::error::Error for MidiInputError {}
+
+/// An open MIDI input port playing notes on a shared engine
+///
+/// Messages are handled on midir's callback thread, which locks the engine
+/// briefly for each note. Call `close` to disconnect and release any keys
+/// still held; dropping the input disconnects without releasing them.
+#[cfg(feature = "midi-input")]
+pub struct MidiInput {
+    /// Connection owning the router that handles incoming messages
+    connection: midir::MidiInputConnection<MidiNoteRouter>,
+
+    /// Engine the notes are played on
+    engine: SharedRealtimeEngine,
+}
+
+#[cfg(feature = "midi-input")]
+impl MidiInput {
+    /// Names of the available input ports, in port index order
+    pub fn port_names() -> Result<Vec<String>, MidiInputError> {
+        let input = midir::MidiInput::new(CLIENT_NAME).map_err(MidiInputError::Init)?;
+        Ok(input
+            .ports()
+            .iter()
+            .map(|port| input.port_name(port).unwrap_or_default())
+            .collect())
+    }
+
+    /// Listen on the input port at `port_index`, playing notes through `router`
+    pub fn connect(
+        port_index: usize,
+        engine: SharedRealtimeEngine,
+        router: MidiNoteRouter,
+    ) -> Result<Self, MidiInputError> {
+        let input = midir::MidiInput::new(CLIENT_NAME).map_err(MidiInputError::Init)?;
+        let ports = input.ports();
+        let port = ports
+            .get(port_index)
+            .ok_or(MidiInputError::PortNotFound(port_index))?;
+
+        let callback_engine = engine.clone();
+        let connection = input
+            .connect(
+                port,
+                CLIENT_NAME,
+                move |_timestamp_us, bytes, router: &mut MidiNoteRouter| {
+                    if let Ok(mut engine) = callback_engine.lock() {
+                        router.handle(&mut *engine, bytes);
+                    }
+                },
+                router,
+            )
+            .map_err(|e| MidiInputError::Connect(e.kind()))?;
+
+        Ok(Self { connection, engine })
+    }
+
+    /// Disconnect from the port and release every held note
+    pub fn close(self) {
+        let (_, mut router) = self.connection.close();
+        if let Ok(mut engine) = self.engine.lock() {
+            router.release_all(&mut *engine);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EnvelopeCurve;
+
+    /// Engine call recorded by MockEngine
+    #[derive(Debug, Clone, PartialEq)]
+    enum Call {
+        Trigger {
+            frequency: f32,
+            velocity: u8,
+            voice_id: u32,
+        },
+        Release(u32),
+    }
+
+    /// Engine that records calls and hands out sequential voice IDs
+    #[derive(Default)]
+    struct MockEngine {
+        calls: Vec<Call>,
+        next_voice_id: u32,
+    }
+
+    impl NoteEngine for MockEngine {
+        fn trigger_note_with_velocity(
+            &mut self,
+            _waveform: Waveform,
+            frequency: f32,
+            _envelope: AdsrEnvelope,
+            velocity: u8,
+        ) -> Option<u32> {
+            self.next_voice_id += 1;
+            self.calls.push(Call::Trigger {
+                frequency,
+                velocity,
+                voice_id: self.next_voice_id,
+            });
+            Some(self.next_voice_id)
+        }
+
+        fn release_note(&mut self, voice_id: u32) {
+            self.calls.push(Call::Release(voice_id));
+        }
+    }
+
+    fn router() -> MidiNoteRouter {
+        MidiNoteRouter::new(
+            Waveform::Sine,
+            AdsrEnvelope {
+                attack_secs: 0.01,
+                hold_secs: 0.0,
+                decay_secs: 0.1,
+                sustain_level: 0.7,
+                release_secs: 0.2,
+                curve: EnvelopeCurve::LINEAR,
+            },
+        )
+    }
+
+    fn trigger(frequency: f32, velocity: u8, voice_id: u32) -> Call {
+        Call::Trigger {
+            frequency,
+            velocity,
+            voice_id,
+        }
+    }
+
+    #[test]
+    fn test_parse_note_messages() {
+        assert_eq!(
+            MidiMessage::parse(&[0x93, 64, 90]),
+            Some(MidiMessage::NoteOn {
+                channel: 3,
+                note: 64,
+                velocity: 90
+            })
+        );
+        assert_eq!(
+            MidiMessage::parse(&[0x8F, 64, 40]),
+            Some(MidiMessage::NoteOff {
+                channel: 15,
+                note: 64
+            })
+        );
+        // Velocity 0 note-on is a note-off
+        assert_eq!(
+            MidiMessage::parse(&[0x90, 60, 0]),
+            Some(MidiMessage::NoteOff {
+                channel: 0,
+                note: 60
+            })
+        );
+
+        // Control change, truncated messages and bad data bytes are ignored
+        assert_eq!(MidiMessage::parse(&[0xB0, 7, 100]), None);
+        assert_eq!(MidiMessage::parse(&[0x90, 60]), None);
+        assert_eq!(MidiMessage::parse(&[0x90, 0x80, 100]), None);
+        assert_eq!(MidiMessage::parse(&[]), None);
+    }
+
+    #[test]
+    fn test_releases_target_the_voice_each_key_started() {
+        let mut engine = MockEngine::default();
+        let mut router = router();
+
+        router.handle(&mut engine, &[0x90, 69, 100]);
+        router.handle(&mut engine, &[0x91, 69, 50]);
+        router.handle(&mut engine, &[0x90, 57, 127]);
+        assert_eq!(router.held_notes(), 3);
+
+        // Same key on another channel is a different note
+        router.handle(&mut engine, &[0x81, 69, 0]);
+        // Unknown keys release nothing
+        router.handle(&mut engine, &[0x80, 72, 0]);
+        // A repeated key releases its old voice before restarting
+        router.handle(&mut engine, &[0x90, 57, 30]);
+        router.handle(&mut engine, &[0x90, 69, 0]);
+
+        let a4 = note_frequency(69);
+        let a3 = note_frequency(57);
+        assert!((a4 - 440.0).abs() < 1e-3 && (a3 - 220.0).abs() < 1e-3);
+        assert_eq!(
+            engine.calls,
+            vec![
+                trigger(a4, 100, 1),
+                trigger(a4, 50, 2),
+                trigger(a3, 127, 3),
+                Call::Release(2),
+                Call::Release(3),
+                trigger(a3, 30, 4),
+                Call::Release(1),
+            ]
+        );
+        assert_eq!(router.held_notes(), 1);
+
+        router.release_all(&mut engine);
+        assert_eq!(engine.calls.last(), Some(&Call::Release(4)));
+        assert_eq!(router.held_notes(), 0);
+    }
+
+    #[test]
+    fn test_channel_filter_ignores_other_channels() {
+        let mut engine = MockEngine::default();
+        let mut router = router().with_channel(9);
+
+        assert_eq!(router.handle(&mut engine, &[0x90, 36, 100]), None);
+        assert!(router.handle(&mut engine, &[0x99, 36, 100]).is_some());
+        assert_eq!(router.handle(&mut engine, &[0x80, 36, 0]), None);
+        assert_eq!(engine.calls, vec![trigger(note_frequency(36), 100, 1)]);
+
+        // The real engine plays and releases the voice
+        let mut engine = RealtimeEngine::new(44100.0);
+        router.handle(&mut engine, &[0x99, 36, 100]);
+        assert_eq!(engine.get_active_voice_count(), 1);
+        router.handle(&mut engine, &[0x89, 36, 0]);
+        assert_eq!(router.held_notes(), 0);
+    }
+}
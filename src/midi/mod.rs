@@ -0,0 +1,24 @@
+/// Standard MIDI File export of sound event timelines.
+pub mod export;
+/// MIDI module for Polyphonica
+///
+/// This module connects Polyphonica to the MIDI world in both directions:
+///
+/// - **Export**: Writes sound event timelines as Standard MIDI Files for DAWs
+/// - **Input**: Plays incoming note-on/note-off messages on a RealtimeEngine
+///
+/// Parsing and routing of note messages is always available; opening a
+/// hardware input port requires the `midi-input` feature, which pulls in
+/// midir.
+pub mod input;
+
+// Re-export commonly used types
+pub use export::{export_timeline, MidiExporter, DEFAULT_PITCH_BEND_RANGE};
+#[cfg(feature = "midi-input")]
+pub use input::{MidiInput, MidiInputError};
+pub use input::{MidiMessage, MidiNoteRouter, NoteEngine};
+
+/// Frequency in Hz of a MIDI note number
+pub fn note_frequency(note: u8) -> f32 {
+    crate::midi_to_frequency(note as f32)
+}
@@ -32,6 +32,9 @@ pub enum PatternValidationError {
 
     /// Tempo range is invalid
     InvalidTempoRange(u32, u32),
+
+    /// Patterns being merged have different time signatures
+    TimeSignatureMismatch(TimeSignature, TimeSignature),
 }
 
 impl std::fmt::Display for PatternValidationError {
@@ -55,12 +58,79 @@ impl std::fmt::Display for PatternValidationError {
             PatternValidationError::InvalidTempoRange(min, max) => {
                 write!(f, "Invalid tempo range: {}-{} BPM", min, max)
             }
+            PatternValidationError::TimeSignatureMismatch(a, b) => {
+                write!(
+                    f,
+                    "Time signatures differ: {}/{} and {}/{}",
+                    a.beats_per_measure, a.note_value, b.beats_per_measure, b.note_value
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for PatternValidationError {}
 
+/// Check a pattern against the rules `PatternBuilder::validate` applies
+///
+/// Used for patterns that did not come from a builder, such as imported ones.
+pub fn validate_pattern(pattern: &DrumPattern) -> Result<(), Vec<PatternValidationError>> {
+    let mut errors = Vec::new();
+
+    // Check pattern name
+    if pattern.name.is_empty() {
+        errors.push(PatternValidationError::InvalidName(
+            "Pattern name cannot be empty".to_string(),
+        ));
+    }
+
+    // Check if pattern has beats
+    if pattern.beats.is_empty() {
+        errors.push(PatternValidationError::EmptyPattern);
+        return Err(errors);
+    }
+
+    // Check tempo range
+    let (min, max) = pattern.tempo_range;
+    if min >= max || min == 0 || max > 300 {
+        errors.push(PatternValidationError::InvalidTempoRange(min, max));
+    }
+
+    // Check beat positions and duplicates
+    let max_position = pattern.time_signature.beats_per_measure as f32 + 0.999; // Beat N goes from N.0 to N.999
+    let mut positions = std::collections::HashSet::new();
+
+    for beat in &pattern.beats {
+        // Check position validity
+        if beat.beat_position < 1.0 || beat.beat_position > max_position {
+            errors.push(PatternValidationError::InvalidBeatPosition(
+                beat.beat_position,
+            ));
+        }
+
+        // Check for empty beats
+        if beat.samples.is_empty() {
+            errors.push(PatternValidationError::EmptyBeat(beat.beat_position));
+        }
+
+        // Check for duplicates (with small tolerance for floating point)
+        let pos_key = (beat.beat_position * 100.0) as i32;
+        if positions.contains(&pos_key) {
+            errors.push(PatternValidationError::DuplicateBeatPosition(
+                beat.beat_position,
+            ));
+        } else {
+            positions.insert(pos_key);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 impl PatternBuilder {
     /// Create a new pattern builder
     pub fn new(name: &str, time_signature: TimeSignature) -> Self {
@@ -133,11 +203,23 @@ impl PatternBuilder {
         self
     }
 
+    /// Set the grid resolution in pulses per quarter note
+    pub fn resolution_ppq(mut self, resolution_ppq: u32) -> Self {
+        self.pattern.resolution_ppq = resolution_ppq.max(1);
+        self
+    }
+
     /// Add a beat at the specified position
     pub fn beat(self, position: f32) -> BeatBuilder {
         BeatBuilder::new(self, position)
     }
 
+    /// Add a beat at a tick offset from the start of the measure (grid entry)
+    pub fn beat_at_tick(self, ticks: u32) -> BeatBuilder {
+        let position = self.pattern.ticks_to_position(ticks);
+        BeatBuilder::new(self, position)
+    }
+
     /// Add a kick drum hit
     pub fn kick(self, position: f32) -> Self {
         self.beat(position).kick().build()
@@ -191,60 +273,7 @@ impl PatternBuilder {
 
     /// Validate the pattern and return any errors
     pub fn validate(&self) -> Result<(), Vec<PatternValidationError>> {
-        let mut errors = Vec::new();
-
-        // Check pattern name
-        if self.pattern.name.is_empty() {
-            errors.push(PatternValidationError::InvalidName(
-                "Pattern name cannot be empty".to_string(),
-            ));
-        }
-
-        // Check if pattern has beats
-        if self.pattern.beats.is_empty() {
-            errors.push(PatternValidationError::EmptyPattern);
-            return Err(errors);
-        }
-
-        // Check tempo range
-        let (min, max) = self.pattern.tempo_range;
-        if min >= max || min == 0 || max > 300 {
-            errors.push(PatternValidationError::InvalidTempoRange(min, max));
-        }
-
-        // Check beat positions and duplicates
-        let max_position = self.pattern.time_signature.beats_per_measure as f32 + 0.999; // Beat N goes from N.0 to N.999
-        let mut positions = std::collections::HashSet::new();
-
-        for beat in &self.pattern.beats {
-            // Check position validity
-            if beat.beat_position < 1.0 || beat.beat_position > max_position {
-                errors.push(PatternValidationError::InvalidBeatPosition(
-                    beat.beat_position,
-                ));
-            }
-
-            // Check for empty beats
-            if beat.samples.is_empty() {
-                errors.push(PatternValidationError::EmptyBeat(beat.beat_position));
-            }
-
-            // Check for duplicates (with small tolerance for floating point)
-            let pos_key = (beat.beat_position * 100.0) as i32;
-            if positions.contains(&pos_key) {
-                errors.push(PatternValidationError::DuplicateBeatPosition(
-                    beat.beat_position,
-                ));
-            } else {
-                positions.insert(pos_key);
-            }
-        }
-
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+        validate_pattern(&self.pattern)
     }
 
     /// Build the pattern, returning an error if validation fails
@@ -450,6 +479,23 @@ mod tests {
         assert!(!pattern.beats[1].accent);
     }
 
+    #[test]
+    fn test_beat_at_tick_uses_pattern_resolution() {
+        let pattern = PatternBuilder::new("grid", TimeSignature::new(4, 4))
+            .resolution_ppq(96)
+            .beat_at_tick(0)
+            .kick()
+            .build()
+            .beat_at_tick(120)
+            .hihat_closed()
+            .build()
+            .build()
+            .unwrap();
+
+        assert_eq!(pattern.resolution_ppq, 96);
+        assert_eq!(pattern.beats[1].beat_position, 2.25);
+    }
+
     #[test]
     fn test_notation_builder() {
         let pattern = PatternBuilder::new("notation_test", TimeSignature::new(4, 4))
@@ -1,11 +1,14 @@
-use super::builder::PatternBuilder;
+use super::builder::{validate_pattern, PatternBuilder};
 /// Genre-specific pattern collections
 ///
 /// This module provides curated collections of drum patterns organized by
 /// musical genre and style. Each collection contains patterns that are
 /// commonly used in that genre with appropriate tempo ranges and complexity.
+use super::io::{PatternIoError, PatternLibraryFile};
 use super::types::{DrumPattern, PatternGenre};
 use crate::timing::TimeSignature;
+use std::ops::RangeBounds;
+use std::path::Path;
 
 /// Rock pattern collection
 pub struct RockPatterns;
@@ -851,6 +854,16 @@ impl ClassicalPatterns {
     }
 }
 
+/// How an imported library combines with the built-in patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryImportMode {
+    /// Use only the imported patterns
+    Replace,
+    /// Keep the built-in patterns, overriding those with the same name and
+    /// appending the rest
+    Merge,
+}
+
 /// Master pattern collection containing all genre patterns
 pub struct MasterCollection;
 
@@ -910,6 +923,39 @@ impl MasterCollection {
             .collect()
     }
 
+    /// Get patterns carrying a tag (case-insensitive exact match)
+    pub fn by_tag(tag: &str) -> Vec<DrumPattern> {
+        Self::all()
+            .into_iter()
+            .filter(|p| p.metadata.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .collect()
+    }
+
+    /// Get patterns whose difficulty falls within a range (e.g. `2..=3`)
+    pub fn by_difficulty<R: RangeBounds<u8>>(range: R) -> Vec<DrumPattern> {
+        Self::all()
+            .into_iter()
+            .filter(|p| range.contains(&p.metadata.difficulty))
+            .collect()
+    }
+
+    /// Search patterns by substring over name, description, and tags
+    pub fn search(query: &str) -> Vec<DrumPattern> {
+        let query = query.to_lowercase();
+        Self::all()
+            .into_iter()
+            .filter(|p| {
+                p.name.to_lowercase().contains(&query)
+                    || p.display_name.to_lowercase().contains(&query)
+                    || p.metadata.description.to_lowercase().contains(&query)
+                    || p.metadata
+                        .tags
+                        .iter()
+                        .any(|tag| tag.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
     /// Get patterns for a specific tempo range
     pub fn for_tempo_range(min_bpm: u32, max_bpm: u32) -> Vec<DrumPattern> {
         Self::all()
@@ -920,6 +966,51 @@ impl MasterCollection {
             })
             .collect()
     }
+
+    /// Write every pattern, with its full metadata, to a single JSON file
+    pub fn export_all<P: AsRef<Path>>(path: P) -> Result<(), PatternIoError> {
+        let library = PatternLibraryFile::from_patterns(&Self::all());
+        std::fs::write(path, library.to_json()?)?;
+        Ok(())
+    }
+
+    /// Load a pattern library written by `export_all` (or edited by hand)
+    ///
+    /// In `Merge` mode an imported pattern replaces the built-in pattern of
+    /// the same name in place, and patterns with new names are appended in
+    /// file order.
+    ///
+    /// The file's major version must match PATTERN_LIBRARY_VERSION, and every
+    /// pattern must pass the same checks as `PatternBuilder::validate`.
+    pub fn import_all<P: AsRef<Path>>(
+        path: P,
+        mode: LibraryImportMode,
+    ) -> Result<Vec<DrumPattern>, PatternIoError> {
+        let library = PatternLibraryFile::from_json(&std::fs::read_to_string(path)?)?;
+        library.check_version()?;
+        for pattern in &library.patterns {
+            validate_pattern(pattern).map_err(|errors| {
+                let reasons: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                PatternIoError::InvalidPattern(format!(
+                    "Pattern '{}': {}",
+                    pattern.name,
+                    reasons.join("; ")
+                ))
+            })?;
+        }
+
+        let mut patterns = match mode {
+            LibraryImportMode::Replace => Vec::new(),
+            LibraryImportMode::Merge => Self::all(),
+        };
+        for imported in library.patterns {
+            match patterns.iter_mut().find(|p| p.name == imported.name) {
+                Some(existing) => *existing = imported,
+                None => patterns.push(imported),
+            }
+        }
+        Ok(patterns)
+    }
 }
 
 #[cfg(test)]
@@ -978,4 +1069,145 @@ mod tests {
         let advanced = MasterCollection::advanced();
         assert!(advanced.iter().all(|p| p.metadata.difficulty >= 4));
     }
+
+    #[test]
+    fn test_library_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("library.json");
+        MasterCollection::export_all(&path).unwrap();
+
+        let original = MasterCollection::all();
+        let imported = MasterCollection::import_all(&path, LibraryImportMode::Replace).unwrap();
+        assert_eq!(names(&imported), names(&original));
+
+        // Every field, down to micro-timing and custom metadata, survives exactly
+        let sampled = original
+            .iter()
+            .find(|p| p.name == "brushes_ballad")
+            .unwrap();
+        let restored = imported
+            .iter()
+            .find(|p| p.name == "brushes_ballad")
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(sampled).unwrap(),
+            serde_json::to_value(restored).unwrap()
+        );
+        assert_eq!(restored.metadata.genre, PatternGenre::Jazz);
+        assert_eq!(restored.metadata.tags, sampled.metadata.tags);
+        assert_eq!(restored.tempo_range, sampled.tempo_range);
+        assert_eq!(restored.beats.len(), sampled.beats.len());
+    }
+
+    #[test]
+    fn test_library_import_merge_and_replace() {
+        let mut custom = RockPatterns::basic_rock().with_difficulty(5);
+        custom.metadata.description = "House variation".to_string();
+        let extra = PatternBuilder::new("house_shuffle", TimeSignature::new(4, 4))
+            .genre(PatternGenre::Custom)
+            .kick(1.0)
+            .build()
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.json");
+        let library = PatternLibraryFile::from_patterns(&[custom, extra]);
+        std::fs::write(&path, library.to_json().unwrap()).unwrap();
+
+        let replaced = MasterCollection::import_all(&path, LibraryImportMode::Replace).unwrap();
+        assert_eq!(names(&replaced), vec!["basic_rock", "house_shuffle"]);
+
+        let defaults = MasterCollection::all();
+        let merged = MasterCollection::import_all(&path, LibraryImportMode::Merge).unwrap();
+        assert_eq!(merged.len(), defaults.len() + 1);
+        assert_eq!(names(&merged[..defaults.len()]), names(&defaults));
+        assert_eq!(merged.last().unwrap().name, "house_shuffle");
+        let rock = merged.iter().find(|p| p.name == "basic_rock").unwrap();
+        assert_eq!(rock.metadata.difficulty, 5);
+        assert_eq!(rock.metadata.description, "House variation");
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(MasterCollection::import_all(&path, LibraryImportMode::Merge).is_err());
+    }
+
+    #[test]
+    fn test_library_import_rejects_invalid_patterns_and_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("library.json");
+
+        // Every built-in pattern survives an export and Replace re-import
+        MasterCollection::export_all(&path).unwrap();
+        let restored = MasterCollection::import_all(&path, LibraryImportMode::Replace).unwrap();
+        assert_eq!(names(&restored), names(&MasterCollection::all()));
+
+        let mut broken = RockPatterns::basic_rock();
+        broken.tempo_range = (200, 100);
+        broken.beats[1].beat_position = 9.0;
+        let library = PatternLibraryFile::from_patterns(&[broken]);
+        std::fs::write(&path, library.to_json().unwrap()).unwrap();
+        match MasterCollection::import_all(&path, LibraryImportMode::Merge) {
+            Err(PatternIoError::InvalidPattern(message)) => {
+                assert!(message.starts_with("Pattern 'basic_rock'"));
+                assert!(message.contains("200-100"));
+                assert!(message.contains("Invalid beat position: 9"));
+            }
+            other => panic!("expected InvalidPattern, got {:?}", other),
+        }
+
+        let mut future = PatternLibraryFile::from_patterns(&[RockPatterns::basic_rock()]);
+        future.library_version = "2.0".to_string();
+        std::fs::write(&path, future.to_json().unwrap()).unwrap();
+        assert!(matches!(
+            MasterCollection::import_all(&path, LibraryImportMode::Merge),
+            Err(PatternIoError::InvalidPattern(_))
+        ));
+    }
+
+    fn names(patterns: &[DrumPattern]) -> Vec<&str> {
+        patterns.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tag_query() {
+        let ballads = MasterCollection::by_tag("Ballad");
+        let expected: Vec<DrumPattern> = MasterCollection::all()
+            .into_iter()
+            .filter(|p| p.metadata.tags.iter().any(|t| t == "ballad"))
+            .collect();
+        assert_eq!(names(&ballads), names(&expected));
+        assert!(names(&ballads).contains(&"brushes_ballad"));
+        assert!(names(&ballads).contains(&"pop_ballad"));
+        assert!(!names(&ballads).contains(&"basic_rock"));
+
+        // Tags match whole words only
+        assert!(MasterCollection::by_tag("ball").is_empty());
+    }
+
+    #[test]
+    fn test_difficulty_range_query() {
+        let advanced = MasterCollection::by_difficulty(4..=5);
+        assert_eq!(names(&advanced), names(&MasterCollection::advanced()));
+
+        let middle = MasterCollection::by_difficulty(2..4);
+        assert!(!middle.is_empty());
+        assert!(middle
+            .iter()
+            .all(|p| (2..=3).contains(&p.metadata.difficulty)));
+        assert_eq!(
+            MasterCollection::by_difficulty(..).len(),
+            MasterCollection::all().len()
+        );
+    }
+
+    #[test]
+    fn test_search_matches_description() {
+        // Only present in the basic funk description
+        let results = MasterCollection::search("Syncopated Kicks");
+        assert_eq!(names(&results), vec!["basic_funk"]);
+
+        let brazilian = MasterCollection::search("brazil");
+        assert!(names(&brazilian).contains(&"bossa_nova"));
+        assert!(names(&brazilian).contains(&"samba"));
+        assert!(MasterCollection::search("no such pattern").is_empty());
+    }
 }
@@ -0,0 +1,139 @@
+/// Groove templates for per-step micro-timing
+///
+/// This module provides GrooveTemplate, a reusable set of timing pushes and
+/// pulls that can be stamped onto any DrumPattern. Unlike global swing, each
+/// grid step gets its own offset, which is how feels like MPC swing or a
+/// laid-back backbeat are built.
+use super::types::DrumPattern;
+use serde::{Deserialize, Serialize};
+
+/// Beat positions further than this from a grid step are left untouched
+const GRID_TOLERANCE: f32 = 0.01;
+
+/// Reusable per-step timing offsets applied to a pattern's beats
+///
+/// The template is a cycle of `step_offsets_ms.len()` grid steps, with
+/// `steps_per_beat` steps to a beat, repeated from beat 1 through the
+/// measure. Positive offsets drag a hit late, negative ones push it early.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::patterns::{DrumPatternBeat, DrumPattern, GrooveTemplate};
+/// use polyphonica::timing::{ClickType, TimeSignature};
+///
+/// let mut pattern = DrumPattern::new("hats", TimeSignature::new(4, 4))
+///     .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::HiHatClosed))
+///     .with_beat(DrumPatternBeat::new(1.25).with_sample(ClickType::HiHatClosed));
+///
+/// GrooveTemplate::mpc_swing().apply_to(&mut pattern);
+/// assert_eq!(pattern.beats[0].timing_offset_ms, 0.0);
+/// assert!(pattern.beats[1].timing_offset_ms > 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrooveTemplate {
+    /// Template name for identification
+    pub name: String,
+
+    /// Grid steps per beat that the offsets are indexed by
+    pub steps_per_beat: u8,
+
+    /// Timing offset of each step in the cycle in milliseconds
+    pub step_offsets_ms: Vec<f32>,
+}
+
+impl GrooveTemplate {
+    /// Create a groove template from per-step offsets
+    pub fn new(name: &str, steps_per_beat: u8, step_offsets_ms: Vec<f32>) -> Self {
+        Self {
+            name: name.to_string(),
+            steps_per_beat: steps_per_beat.max(1),
+            step_offsets_ms,
+        }
+    }
+
+    /// Sixteenth-note swing in the style of classic MPC grooves
+    ///
+    /// Every off-beat sixteenth drags 20 ms, roughly 58% swing at 120 BPM.
+    pub fn mpc_swing() -> Self {
+        Self::new("mpc_swing", 4, vec![0.0, 20.0, 0.0, 20.0])
+    }
+
+    /// Laid-back backbeat: beats 2 and 4 sit 15 ms behind the pulse
+    pub fn laid_back() -> Self {
+        Self::new("laid_back", 1, vec![0.0, 15.0, 0.0, 15.0])
+    }
+
+    /// Get all preset groove templates
+    pub fn presets() -> Vec<GrooveTemplate> {
+        vec![Self::mpc_swing(), Self::laid_back()]
+    }
+
+    /// Offset in milliseconds for a beat position (0.0 off the grid)
+    pub fn offset_for_position(&self, beat_position: f32) -> f32 {
+        if self.step_offsets_ms.is_empty() {
+            return 0.0;
+        }
+
+        let step = (beat_position - 1.0) * self.steps_per_beat as f32;
+        let nearest = step.round();
+        if (step - nearest).abs() > GRID_TOLERANCE * self.steps_per_beat as f32 {
+            return 0.0;
+        }
+
+        let index = (nearest as i64).rem_euclid(self.step_offsets_ms.len() as i64) as usize;
+        self.step_offsets_ms[index]
+    }
+
+    /// Set every beat's timing offset in the pattern from this template
+    pub fn apply_to(&self, pattern: &mut DrumPattern) {
+        for beat in &mut pattern.beats {
+            beat.timing_offset_ms = self.offset_for_position(beat.beat_position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::DrumPatternBeat;
+    use crate::timing::{ClickType, TimeSignature};
+
+    fn sixteenth_hats() -> DrumPattern {
+        (0..16).fold(
+            DrumPattern::new("hats", TimeSignature::new(4, 4)),
+            |pattern, step| {
+                pattern.with_beat(
+                    DrumPatternBeat::new(1.0 + step as f32 * 0.25)
+                        .with_sample(ClickType::HiHatClosed),
+                )
+            },
+        )
+    }
+
+    #[test]
+    fn test_mpc_swing_drags_offbeat_sixteenths() {
+        let mut pattern = sixteenth_hats();
+        GrooveTemplate::mpc_swing().apply_to(&mut pattern);
+
+        for (step, beat) in pattern.beats.iter().enumerate() {
+            let expected = if step % 2 == 1 { 20.0 } else { 0.0 };
+            assert_eq!(beat.timing_offset_ms, expected, "step {}", step);
+        }
+    }
+
+    #[test]
+    fn test_laid_back_cycles_over_measure() {
+        let groove = GrooveTemplate::laid_back();
+        assert_eq!(groove.offset_for_position(1.0), 0.0);
+        assert_eq!(groove.offset_for_position(2.0), 15.0);
+        assert_eq!(groove.offset_for_position(4.0), 15.0);
+
+        // Off-grid positions keep their timing
+        assert_eq!(groove.offset_for_position(2.5), 0.0);
+        assert_eq!(
+            GrooveTemplate::new("empty", 4, vec![]).offset_for_position(2.0),
+            0.0
+        );
+    }
+}
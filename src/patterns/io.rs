@@ -24,6 +24,8 @@ pub struct JsonPattern {
     pub time_signature: String,
     pub tempo_range: [u32; 2],
     pub pattern: Vec<JsonBeat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gain_db: Option<f32>,
 }
 
 /// JSON beat representation
@@ -34,6 +36,23 @@ pub struct JsonBeat {
     pub accent: bool,
 }
 
+/// Format version written into pattern library files
+///
+/// Files whose major version differs are rejected on import.
+pub const PATTERN_LIBRARY_VERSION: &str = "1.0";
+
+/// Full-fidelity pattern library file
+///
+/// Unlike PatternCatalog, this stores every pattern exactly as it is held in
+/// memory, including display names, metadata, micro-timing and resolution,
+/// so a library exported and re-imported is unchanged.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PatternLibraryFile {
+    pub library_version: String,
+    pub created: String,
+    pub patterns: Vec<DrumPattern>,
+}
+
 /// Error types for pattern I/O operations
 #[derive(Debug, thiserror::Error)]
 pub enum PatternIoError {
@@ -94,6 +113,40 @@ impl PatternCatalog {
     }
 }
 
+impl PatternLibraryFile {
+    /// Create a library file holding copies of the given patterns
+    pub fn from_patterns(patterns: &[DrumPattern]) -> Self {
+        Self {
+            library_version: PATTERN_LIBRARY_VERSION.to_string(),
+            created: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            patterns: patterns.to_vec(),
+        }
+    }
+
+    /// Load a library file from JSON string
+    pub fn from_json(json: &str) -> Result<Self, PatternIoError> {
+        serde_json::from_str(json).map_err(PatternIoError::JsonError)
+    }
+
+    /// Save the library file to JSON string
+    pub fn to_json(&self) -> Result<String, PatternIoError> {
+        serde_json::to_string_pretty(self).map_err(PatternIoError::JsonError)
+    }
+
+    /// Check that the file's major version matches PATTERN_LIBRARY_VERSION
+    pub fn check_version(&self) -> Result<(), PatternIoError> {
+        let major = |version: &str| version.split('.').next().unwrap_or("").trim().to_string();
+        if major(&self.library_version) == major(PATTERN_LIBRARY_VERSION) {
+            Ok(())
+        } else {
+            Err(PatternIoError::InvalidPattern(format!(
+                "Unsupported library version '{}' (expected {})",
+                self.library_version, PATTERN_LIBRARY_VERSION
+            )))
+        }
+    }
+}
+
 impl JsonPattern {
     /// Convert to internal DrumPattern
     pub fn to_drum_pattern(&self, key: &str) -> Result<DrumPattern, PatternIoError> {
@@ -112,6 +165,7 @@ impl JsonPattern {
         pattern.display_name = self.name.clone();
         pattern.tempo_range = (self.tempo_range[0], self.tempo_range[1]);
         pattern.beats = beats;
+        pattern.gain_db = self.gain_db.unwrap_or(0.0);
 
         // Try to infer genre from name/tags
         pattern.metadata.genre = Self::infer_genre(&self.name);
@@ -138,6 +192,7 @@ impl JsonPattern {
             time_signature: time_sig_str,
             tempo_range: [pattern.tempo_range.0, pattern.tempo_range.1],
             pattern: json_beats,
+            gain_db: (pattern.gain_db != 0.0).then_some(pattern.gain_db),
         }
     }
 
@@ -282,6 +337,23 @@ mod tests {
         assert_eq!(drum_pattern.beats[0].samples.len(), 2);
         assert!(drum_pattern.beats[0].accent);
         assert!(!drum_pattern.beats[1].accent);
+        assert_eq!(drum_pattern.gain_db, 0.0);
+    }
+
+    #[test]
+    fn test_json_pattern_gain_round_trip() {
+        let pattern = DrumPattern::new("quiet", TimeSignature::new(4, 4)).with_gain_db(-3.0);
+        let json = serde_json::to_string(&JsonPattern::from_drum_pattern(&pattern)).unwrap();
+        assert!(json.contains("gain_db"));
+
+        let json_pattern: JsonPattern = serde_json::from_str(&json).unwrap();
+        let restored = json_pattern.to_drum_pattern("quiet").unwrap();
+        assert_eq!(restored.gain_db, -3.0);
+
+        // Unity gain is left out of the catalog
+        let unity = DrumPattern::new("unity", TimeSignature::new(4, 4));
+        let json = serde_json::to_string(&JsonPattern::from_drum_pattern(&unity)).unwrap();
+        assert!(!json.contains("gain_db"));
     }
 
     #[test]
@@ -304,6 +376,7 @@ mod tests {
             time_signature: "3/4".to_string(),
             tempo_range: [60, 120],
             pattern: vec![],
+            gain_db: None,
         };
 
         let time_sig = json_pattern.parse_time_signature().unwrap();
@@ -2,10 +2,14 @@
 pub mod builder;
 /// Pre-defined pattern collections organized by musical genre.
 pub mod collections;
+/// Groove templates applying per-step micro-timing to patterns.
+pub mod groove;
 /// JSON import/export functionality for pattern catalogs.
 pub mod io;
 /// Pattern library management and factory system.
 pub mod library;
+/// Offline stereo rendering of patterns played on a drum kit.
+pub mod render;
 /// Real-time pattern state management for playback.
 pub mod state;
 /// Pattern library module for rhythm and drum patterns
@@ -22,12 +26,15 @@ pub mod state;
 /// - `state`: Pattern playback state management
 /// - `collections`: Genre-specific pattern collections
 /// - `io`: JSON import/export functionality
+/// - `render`: Offline stereo bounces of patterns
+/// - `groove`: Per-step micro-timing templates
 pub mod types;
 
 // Re-export commonly used types
 pub use builder::PatternBuilder;
 pub use collections::*;
-pub use io::{PatternCatalog, PatternIoError};
+pub use groove::GrooveTemplate;
+pub use io::{PatternCatalog, PatternIoError, PatternLibraryFile};
 pub use library::PatternLibrary;
-pub use state::PatternState;
-pub use types::{DrumPattern, DrumPatternBeat, PatternMetadata};
+pub use state::{EnvelopeScaling, Humanize, PatternState};
+pub use types::{DrumPattern, DrumPatternBeat, PatternMetadata, DEFAULT_RESOLUTION_PPQ};
@@ -0,0 +1,340 @@
+/// Offline rendering of drum patterns
+///
+/// This module bounces a DrumPattern played on a DrumKit into an interleaved
+/// stereo buffer, placing each hit according to the kit piece's pan (and its
+/// per-hit pan spread) so a groove can be exported without the live audio engine.
+use super::types::DrumPattern;
+use crate::audio::synthesis::AudioSynthesis;
+use crate::samples::DrumKit;
+use crate::timing::ClickType;
+use crate::{render_event_stereo, SoundEvent, SynthRng, Waveform};
+
+/// Constant-power gains (left, right) for a pan position (-1.0 to 1.0)
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+impl DrumPattern {
+    /// Render the pattern as interleaved stereo (left, right) for `repeats` measures
+    ///
+    /// Each hit uses the kit piece's loaded velocity layer when available and
+    /// the synthetic fallback sound otherwise. Accented hits play at full
+    /// velocity and the rest at the kit's default velocity, shaped by the
+    /// piece's velocity curve and scaled by the pattern and piece gains. Hits
+    /// are panned with a constant-power law and shifted by their micro-timing
+    /// offsets; stereo samples keep their own image, balanced by the pan. The
+    /// buffer covers exactly `repeats` measures; tails ringing past the end
+    /// are cut off. Returns an empty buffer for invalid input.
+    ///
+    /// Pieces with a pan spread move each hit with the default seed; use
+    /// `render_loop_stereo_seeded` to pick the variation.
+    pub fn render_loop_stereo(
+        &self,
+        kit: &DrumKit,
+        tempo_bpm: f32,
+        repeats: u32,
+        sample_rate: u32,
+    ) -> Vec<f32> {
+        self.render_loop_stereo_seeded(kit, tempo_bpm, repeats, sample_rate, SynthRng::DEFAULT_SEED)
+    }
+
+    /// Render like `render_loop_stereo`, seeding the per-hit pan spread
+    ///
+    /// The same seed always produces the same buffer.
+    pub fn render_loop_stereo_seeded(
+        &self,
+        kit: &DrumKit,
+        tempo_bpm: f32,
+        repeats: u32,
+        sample_rate: u32,
+        seed: u64,
+    ) -> Vec<f32> {
+        if !(tempo_bpm > 0.0 && tempo_bpm.is_finite()) || repeats == 0 || sample_rate == 0 {
+            return Vec::new();
+        }
+
+        let beat_secs = 60.0 / tempo_bpm;
+        let measure_beats = self.time_signature.beats_per_measure as f32;
+        let total_frames =
+            (repeats as f32 * measure_beats * beat_secs * sample_rate as f32) as usize;
+        let mut output = vec![0.0; total_frames * 2];
+        let mut rng = SynthRng::new(seed);
+
+        for beat in &self.beats {
+            for click_type in &beat.samples {
+                let hit = Self::render_hit(kit, click_type, beat.accent, sample_rate);
+                let gain = self.gain() * kit.piece_gain(click_type);
+                let velocity = Self::hit_velocity(kit, beat.accent);
+                let piece = kit.get_sample(click_type);
+
+                for repeat in 0..repeats {
+                    let pan = piece.map_or(0.0, |sample| sample.hit_pan(velocity, &mut rng));
+                    let (left, right) = pan_gains(pan);
+                    let beat_offset = repeat as f32 * measure_beats + beat.beat_position - 1.0;
+                    let start_secs = beat_offset * beat_secs + beat.timing_offset_ms / 1000.0;
+                    let start = (start_secs * sample_rate as f32).round().max(0.0) as usize;
+                    let frames = output.chunks_exact_mut(2).skip(start);
+                    for (frame, sample) in frames.zip(hit.chunks_exact(2)) {
+                        frame[0] += sample[0] * gain * left;
+                        frame[1] += sample[1] * gain * right;
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Velocity a hit plays at: full for accents, the kit default otherwise
+    fn hit_velocity(kit: &DrumKit, accent: bool) -> f32 {
+        if accent {
+            1.0
+        } else {
+            kit.default_velocity
+        }
+    }
+
+    /// Interleaved stereo audio for one hit of a kit piece
+    fn render_hit(
+        kit: &DrumKit,
+        click_type: &ClickType,
+        accent: bool,
+        sample_rate: u32,
+    ) -> Vec<f32> {
+        let velocity = Self::hit_velocity(kit, accent);
+        let piece = kit.get_sample(click_type);
+        let amplitude = piece.map_or(velocity, |sample| sample.apply_velocity(velocity));
+
+        let event = match piece.and_then(|sample| sample.layer_for_velocity(velocity)) {
+            Some(layer) => SoundEvent {
+                waveform: Waveform::DrumSample(layer.data.clone()),
+                start_frequency: layer.data.base_frequency,
+                end_frequency: layer.data.base_frequency,
+                duration_secs: layer.data.samples.len() as f32 / layer.data.sample_rate as f32,
+                envelope: click_type.get_sample_envelope(),
+            },
+            None => {
+                let (waveform, frequency, envelope) = click_type.get_synthetic_params();
+                SoundEvent {
+                    waveform,
+                    start_frequency: frequency,
+                    end_frequency: frequency,
+                    duration_secs: envelope.attack_secs
+                        + envelope.hold_secs
+                        + envelope.decay_secs
+                        + envelope.release_secs,
+                    envelope,
+                }
+            }
+        };
+
+        let mut hit = render_event_stereo(&event, sample_rate);
+        hit.iter_mut().for_each(|sample| *sample *= amplitude);
+        hit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::DrumPatternBeat;
+    use crate::samples::{DrumSample, PanSpreadSource, SampleMetadata, VelocityLayer};
+    use crate::timing::TimeSignature;
+    use crate::{Interpolation, SampleData};
+    use std::path::PathBuf;
+
+    fn kit_with_pans(kick_pan: f32, snare_pan: f32) -> DrumKit {
+        let mut kit = DrumKit::new("pan_test", "Pan Test");
+        for (click_type, pan) in [
+            (ClickType::AcousticKick, kick_pan),
+            (ClickType::AcousticSnare, snare_pan),
+        ] {
+            let metadata = SampleMetadata::new(click_type.name(), "unused.wav", 60.0);
+            kit.add_sample(
+                click_type,
+                DrumSample::new(metadata, click_type).with_pan(pan),
+            );
+        }
+        kit
+    }
+
+    fn channel_energy(stereo: &[f32], channel: usize, range: std::ops::Range<usize>) -> f32 {
+        stereo
+            .chunks_exact(2)
+            .skip(range.start)
+            .take(range.len())
+            .map(|frame| frame[channel] * frame[channel])
+            .sum()
+    }
+
+    #[test]
+    fn test_hard_left_piece_lands_in_left_channel() {
+        // Snare alone on beat 3, panned hard left
+        let pattern = DrumPattern::new("snare_only", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(3.0).with_sample(ClickType::AcousticSnare));
+        let kit = kit_with_pans(0.0, -1.0);
+
+        // 120 BPM at 1 kHz: 500 frames per beat, 2000 per measure
+        let stereo = pattern.render_loop_stereo(&kit, 120.0, 2, 1000);
+        assert_eq!(stereo.len(), 2 * 4000);
+
+        let left = channel_energy(&stereo, 0, 0..4000);
+        let right = channel_energy(&stereo, 1, 0..4000);
+        assert!(left > 0.0);
+        assert!(right < left * 1e-6, "left {} right {}", left, right);
+
+        // Nothing before the hit, and the second repeat matches the first
+        assert_eq!(channel_energy(&stereo, 0, 0..1000), 0.0);
+        let first = channel_energy(&stereo, 0, 1000..2000);
+        let second = channel_energy(&stereo, 0, 3000..4000);
+        assert!((first - second).abs() < first * 1e-4);
+    }
+
+    #[test]
+    fn test_centered_and_panned_pieces_mix() {
+        let pattern = DrumPattern::new("backbeat", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick))
+            .with_beat(DrumPatternBeat::new(2.0).with_sample(ClickType::AcousticSnare));
+        let kit = kit_with_pans(0.0, 0.5);
+        let stereo = pattern.render_loop_stereo(&kit, 120.0, 1, 1000);
+
+        // Centered kick is equal in both channels
+        for frame in stereo.chunks_exact(2).take(500) {
+            assert!((frame[0] - frame[1]).abs() < 1e-6);
+        }
+
+        // Snare panned right of center favours the right channel
+        let left = channel_energy(&stereo, 0, 500..1000);
+        let right = channel_energy(&stereo, 1, 500..1000);
+        assert!(right > 2.0 * left);
+
+        assert!(pattern.render_loop_stereo(&kit, 0.0, 1, 1000).is_empty());
+        assert!(pattern.render_loop_stereo(&kit, 120.0, 0, 1000).is_empty());
+    }
+
+    /// Kit whose kick is a stereo sample with all of its content on the left
+    fn kit_with_left_room_kick(pan: f32) -> DrumKit {
+        let left: Vec<f32> = (0..200)
+            .map(|i| 0.5 * (std::f32::consts::TAU * 50.0 * i as f32 / 1000.0).sin())
+            .collect();
+        let right = vec![0.0; left.len()];
+        let metadata = SampleMetadata::new("room_kick", "room_kick.wav", 60.0);
+        let data = SampleData {
+            samples: left.iter().map(|sample| sample / 2.0).collect(),
+            sample_rate: 1000,
+            base_frequency: 60.0,
+            loop_start: None,
+            loop_end: None,
+            interpolation: Interpolation::default(),
+            stereo_channels: Some((left, right)),
+            metadata: crate::SampleMetadata {
+                filename: "room_kick.wav".to_string(),
+                duration_secs: 0.2,
+                channels: 2,
+                bits_per_sample: 32,
+            },
+        };
+
+        let mut kick = DrumSample::new(metadata, ClickType::AcousticKick).with_pan(pan);
+        kick.layers.push(VelocityLayer {
+            min_velocity: 0.0,
+            max_velocity: 1.0,
+            file_path: PathBuf::from("room_kick.wav"),
+            data,
+        });
+        let mut kit = DrumKit::new("stereo_test", "Stereo Test");
+        kit.add_sample(ClickType::AcousticKick, kick);
+        kit
+    }
+
+    #[test]
+    fn test_stereo_sample_keeps_its_image_with_pan() {
+        let pattern = DrumPattern::new("kick_only", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick));
+
+        // Centered, the sample's left-only content stays on the left
+        let centered = pattern.render_loop_stereo(&kit_with_left_room_kick(0.0), 120.0, 1, 1000);
+        let left = channel_energy(&centered, 0, 0..500);
+        assert!(left > 0.0);
+        assert_eq!(channel_energy(&centered, 1, 0..500), 0.0);
+
+        // Placing it right of center balances the image without moving content across
+        let panned = pattern.render_loop_stereo(&kit_with_left_room_kick(0.5), 120.0, 1, 1000);
+        let panned_left = channel_energy(&panned, 0, 0..500);
+        assert_eq!(channel_energy(&panned, 1, 0..500), 0.0);
+        let expected = (pan_gains(0.5).0 / pan_gains(0.0).0).powi(2);
+        assert!((panned_left / left - expected).abs() < 1e-4);
+    }
+
+    /// Pan of each hit, recovered from the channel energies of its beat
+    fn hit_pans(stereo: &[f32], frames_per_hit: usize, hits: usize) -> Vec<f32> {
+        (0..hits)
+            .map(|hit| {
+                let range = hit * frames_per_hit..(hit + 1) * frames_per_hit;
+                let left = channel_energy(stereo, 0, range.clone()).sqrt();
+                let right = channel_energy(stereo, 1, range).sqrt();
+                right.atan2(left) / std::f32::consts::FRAC_PI_4 - 1.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pan_spread_varies_hits_within_range() {
+        // Snare on every beat, based slightly right with a random spread
+        let mut pattern = DrumPattern::new("four_snares", TimeSignature::new(4, 4));
+        for position in [1.0, 2.0, 3.0, 4.0] {
+            pattern = pattern
+                .with_beat(DrumPatternBeat::new(position).with_sample(ClickType::AcousticSnare));
+        }
+        let mut kit = DrumKit::new("spread_test", "Spread Test");
+        let metadata = SampleMetadata::new("snare", "unused.wav", 60.0);
+        kit.add_sample(
+            ClickType::AcousticSnare,
+            DrumSample::new(metadata, ClickType::AcousticSnare)
+                .with_pan(0.2)
+                .with_pan_spread(0.1, PanSpreadSource::Random),
+        );
+
+        let stereo = pattern.render_loop_stereo_seeded(&kit, 120.0, 4, 1000, 7);
+        let pans = hit_pans(&stereo, 500, 16);
+        assert!(
+            pans.iter().all(|pan| (pan - 0.2).abs() <= 0.1 + 1e-4),
+            "{:?}",
+            pans
+        );
+        let (low, high) = pans.iter().fold((f32::MAX, f32::MIN), |(low, high), &pan| {
+            (low.min(pan), high.max(pan))
+        });
+        assert!(high - low > 0.05, "{:?}", pans);
+
+        // Seeds are repeatable
+        assert_eq!(
+            stereo,
+            pattern.render_loop_stereo_seeded(&kit, 120.0, 4, 1000, 7)
+        );
+        assert_ne!(
+            stereo,
+            pattern.render_loop_stereo_seeded(&kit, 120.0, 4, 1000, 8)
+        );
+
+        // Without spread every hit sits at the piece's base position
+        let fixed = kit_with_pans(0.0, 0.2);
+        let stereo = pattern.render_loop_stereo_seeded(&fixed, 120.0, 4, 1000, 7);
+        for pan in hit_pans(&stereo, 500, 16) {
+            assert!((pan - 0.2).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_velocity_pan_spread_follows_hit_velocity() {
+        let metadata = SampleMetadata::new("kick", "unused.wav", 60.0);
+        let kick = DrumSample::new(metadata, ClickType::AcousticKick)
+            .with_pan_spread(0.3, PanSpreadSource::Velocity);
+        let mut rng = SynthRng::new(1);
+
+        assert!((kick.hit_pan(0.0, &mut rng) + 0.3).abs() < 1e-6);
+        assert!(kick.hit_pan(0.5, &mut rng).abs() < 1e-6);
+        assert!((kick.hit_pan(1.0, &mut rng) - 0.3).abs() < 1e-6);
+    }
+}
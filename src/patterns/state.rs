@@ -3,10 +3,18 @@
 /// This module handles the real-time state of pattern playback, including
 /// beat scheduling, pattern progression, and timing precision. It uses
 /// discrete beat scheduling to prevent timing drift during playback.
-use super::types::DrumPattern;
-use crate::timing::ClickType;
+use super::types::{DrumPattern, DrumPatternBeat};
+use crate::samples::DrumKit;
+use crate::timing::{ClickType, LATE_TOLERANCE_MS};
+use crate::{AdsrEnvelope, SynthRng};
 use std::time::{Duration, Instant};
 
+/// Beat positions closer than this are treated as the same beat
+const POSITION_TOLERANCE: f32 = 0.01;
+
+/// Largest timing jitter as a fraction of the gap to the nearest neighbouring beat
+const MAX_JITTER_GAP_FRACTION: f64 = 0.49;
+
 /// Pattern playback state manager
 ///
 /// Manages the real-time playback state of drum patterns using discrete
@@ -17,17 +25,77 @@ pub struct PatternState {
     /// Currently loaded pattern
     current_pattern: Option<DrumPattern>,
 
+    /// Edited copy of the pattern, swapped in when the next loop starts
+    pending_pattern: Option<DrumPattern>,
+
     /// Current beat index in the pattern (0-based)
     current_beat_index: usize,
 
-    /// Absolute time when next beat should trigger
-    next_beat_time: Option<Instant>,
+    /// Absolute time of beat position 1.0 in the current loop
+    loop_start: Option<Instant>,
+
+    /// Tempo `loop_start` was laid out at, so tempo changes can rescale it
+    schedule_tempo_bpm: Option<f32>,
+
+    /// When playback was paused, so resuming can shift the schedule
+    paused_at: Option<Instant>,
 
     /// Whether pattern playback is enabled
     pattern_enabled: bool,
 
     /// Pattern playback statistics
     stats: PatternStats,
+
+    /// Tempo-based scaling applied to trigger envelopes
+    envelope_scaling: EnvelopeScaling,
+
+    /// Kit whose piece gains are applied to triggers
+    drum_kit: Option<DrumKit>,
+
+    /// Timing and velocity jitter applied to triggers
+    humanize: Humanize,
+
+    /// Random source for the jitter, reseeded whenever playback restarts
+    humanize_rng: SynthRng,
+
+    /// Bipolar timing draw for the next beat, fixed until it fires
+    next_beat_jitter: Option<f32>,
+}
+
+/// Timing and velocity jitter that loosens up pattern playback
+///
+/// Each beat is moved by up to `timing_jitter_ms` either way, but never by
+/// as much as half the gap to a neighbouring beat, so hits keep their order.
+/// Each trigger's gain is scaled by up to `velocity_jitter` either way. The
+/// jitter comes from a `SynthRng` seeded with `seed` whenever playback
+/// (re)starts, so a performance can be reproduced exactly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Humanize {
+    /// Largest timing deviation either side of the grid (milliseconds)
+    pub timing_jitter_ms: f32,
+
+    /// Largest gain deviation as a fraction of the trigger gain (0.0 to 1.0)
+    pub velocity_jitter: f32,
+
+    /// Seed of the jitter sequence
+    pub seed: u64,
+}
+
+/// Tempo-based envelope release scaling for pattern triggers
+///
+/// Fixed release times smear hits together at fast tempos and cut them short
+/// at slow ones. When enabled, the release is scaled by how far the tempo is
+/// from `reference_bpm` and then capped to a fraction of the beat interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvelopeScaling {
+    /// Whether release times follow the tempo
+    pub enabled: bool,
+
+    /// Tempo at which envelopes play back unscaled
+    pub reference_bpm: f32,
+
+    /// Longest release allowed, as a fraction of one beat
+    pub max_beat_fraction: f32,
 }
 
 /// Pattern playback statistics
@@ -60,6 +128,9 @@ pub struct PatternTrigger {
 
     /// Beat number for display (1-based)
     pub beat_number: u8,
+
+    /// Linear gain from the pattern and kit piece (1.0 = unity)
+    pub gain: f32,
 }
 
 impl PatternState {
@@ -67,25 +138,132 @@ impl PatternState {
     pub fn new() -> Self {
         Self {
             current_pattern: None,
+            pending_pattern: None,
             current_beat_index: 0,
-            next_beat_time: None,
+            loop_start: None,
+            schedule_tempo_bpm: None,
+            paused_at: None,
             pattern_enabled: false,
             stats: PatternStats::new(),
+            envelope_scaling: EnvelopeScaling::default(),
+            drum_kit: None,
+            humanize: Humanize::default(),
+            humanize_rng: SynthRng::new(Humanize::default().seed),
+            next_beat_jitter: None,
         }
     }
 
     /// Load a pattern for playback
     pub fn set_pattern(&mut self, pattern: DrumPattern) {
         self.current_pattern = Some(pattern);
+        self.pending_pattern = None;
         self.reset_playback_state();
     }
 
     /// Clear the current pattern
     pub fn clear_pattern(&mut self) {
         self.current_pattern = None;
+        self.pending_pattern = None;
         self.reset_playback_state();
     }
 
+    /// Add a beat, merging its samples into any beat already at that position
+    ///
+    /// During playback the edit is queued and takes effect when the next loop
+    /// starts, so the loop in progress plays out unchanged. Returns false if
+    /// no pattern is loaded, the beat has no samples, or its position lies
+    /// outside the measure.
+    pub fn add_beat(&mut self, beat: DrumPatternBeat) -> bool {
+        if !beat.has_samples() {
+            return false;
+        }
+        self.edit_pattern(|pattern| {
+            if !position_in_measure(pattern, beat.beat_position) {
+                return false;
+            }
+            match find_beat(pattern, beat.beat_position) {
+                Some(index) => {
+                    let existing = &mut pattern.beats[index];
+                    for sample in beat.samples {
+                        if !existing.samples.contains(&sample) {
+                            existing.samples.push(sample);
+                        }
+                    }
+                    existing.accent |= beat.accent;
+                }
+                None => {
+                    let index = pattern
+                        .beats
+                        .partition_point(|other| other.beat_position < beat.beat_position);
+                    pattern.beats.insert(index, beat);
+                }
+            }
+            true
+        })
+    }
+
+    /// Remove the beat at a position; returns false if there is none
+    ///
+    /// Queued until the next loop during playback, like `add_beat`.
+    pub fn remove_beat(&mut self, position: f32) -> bool {
+        self.edit_pattern(|pattern| match find_beat(pattern, position) {
+            Some(index) => {
+                pattern.beats.remove(index);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Add a sample at a position, or remove it if it is already there
+    ///
+    /// Queued until the next loop during playback, like `add_beat`. Returns
+    /// whether the sample is present at the position after the edit.
+    pub fn toggle_beat(&mut self, position: f32, click_type: ClickType) -> bool {
+        let present = self
+            .edited_pattern()
+            .and_then(|pattern| find_beat(pattern, position).map(|index| &pattern.beats[index]))
+            .is_some_and(|beat| beat.samples.contains(&click_type));
+        if !present {
+            return self.add_beat(DrumPatternBeat::new(position).with_sample(click_type));
+        }
+
+        self.edit_pattern(|pattern| {
+            if let Some(index) = find_beat(pattern, position) {
+                let beat = &mut pattern.beats[index];
+                beat.samples.retain(|&sample| sample != click_type);
+                if beat.samples.is_empty() {
+                    pattern.beats.remove(index);
+                }
+            }
+            true
+        });
+        false
+    }
+
+    /// Pattern including any edits still waiting for the next loop
+    pub fn edited_pattern(&self) -> Option<&DrumPattern> {
+        self.pending_pattern
+            .as_ref()
+            .or(self.current_pattern.as_ref())
+    }
+
+    /// Whether edits are waiting for the next loop to start
+    pub fn has_pending_edits(&self) -> bool {
+        self.pending_pattern.is_some()
+    }
+
+    /// Apply an edit to the pattern, or queue it on a copy while a loop is playing
+    fn edit_pattern(&mut self, edit: impl FnOnce(&mut DrumPattern) -> bool) -> bool {
+        if self.loop_start.is_none() {
+            return self.current_pattern.as_mut().is_some_and(edit);
+        }
+        if self.pending_pattern.is_none() {
+            self.pending_pattern.clone_from(&self.current_pattern);
+        }
+        self.pending_pattern.as_mut().is_some_and(edit)
+    }
+
     /// Start pattern playback
     pub fn start(&mut self) {
         self.pattern_enabled = true;
@@ -102,11 +280,20 @@ impl PatternState {
 
     /// Pause pattern playback (preserves state)
     pub fn pause(&mut self) {
+        if self.pattern_enabled {
+            self.paused_at = Some(Instant::now());
+        }
         self.pattern_enabled = false;
     }
 
     /// Resume pattern playback
+    ///
+    /// The schedule is shifted by the time spent paused, so the pattern picks
+    /// up where it left off.
     pub fn resume(&mut self) {
+        if let (Some(paused_at), Some(loop_start)) = (self.paused_at.take(), self.loop_start) {
+            self.loop_start = Some(loop_start + paused_at.elapsed());
+        }
         self.pattern_enabled = true;
     }
 
@@ -152,71 +339,130 @@ impl PatternState {
     /// This method uses absolute time scheduling to prevent timing drift
     /// that can accumulate with relative timing approaches.
     pub fn check_pattern_triggers(&mut self, tempo_bpm: f32) -> Vec<PatternTrigger> {
-        if !self.pattern_enabled {
+        self.check_pattern_triggers_at(tempo_bpm, Instant::now())
+    }
+
+    /// Check for pattern triggers as of a given time
+    ///
+    /// Every beat is scheduled from the start of its loop: its position plus
+    /// its micro-timing offset. Loops advance by exactly one measure, so
+    /// offsets never accumulate and the pattern stays phase-locked. All
+    /// beats that have come due are returned together. Beats more than
+    /// `LATE_TOLERANCE_MS` overdue, e.g. after a stalled poll, are skipped
+    /// along with any whole measures missed, so playback resumes on the grid
+    /// instead of replaying them in a burst. When the tempo changes, the loop
+    /// start is moved so the playback position in beats carries over instead
+    /// of the schedule jumping.
+    pub fn check_pattern_triggers_at(
+        &mut self,
+        tempo_bpm: f32,
+        now: Instant,
+    ) -> Vec<PatternTrigger> {
+        if !self.pattern_enabled || tempo_bpm <= 0.0 {
             return vec![];
         }
 
+        // Edits made during the previous loop start with this one
+        if self.current_beat_index == 0 {
+            if let Some(pattern) = self.pending_pattern.take() {
+                self.current_pattern = Some(pattern);
+            }
+        }
+
         let Some(ref pattern) = self.current_pattern else {
             return vec![];
         };
 
-        if pattern.beats.is_empty() {
+        let beat_count = pattern.beats.len();
+        let beat_interval_ms = 60000.0 / tempo_bpm as f64;
+        let measure_ms = pattern.time_signature.beats_per_measure as f64 * beat_interval_ms;
+        let mut loop_start = *self.loop_start.get_or_insert(now);
+        if let Some(previous_bpm) = self.schedule_tempo_bpm.replace(tempo_bpm) {
+            if previous_bpm != tempo_bpm {
+                let elapsed_ms = signed_ms_between(loop_start, now);
+                let elapsed_beats = elapsed_ms * previous_bpm as f64 / 60000.0;
+                loop_start = offset_instant(now, -elapsed_beats * beat_interval_ms);
+            }
+        }
+
+        if beat_count == 0 {
+            // Keep the measure grid moving, so beats added back start on the
+            // next measure instead of catching up on every silent loop
+            let elapsed_ms = signed_ms_between(loop_start, now);
+            if elapsed_ms >= 0.0 && measure_ms > 0.0 {
+                let loops = (elapsed_ms / measure_ms).floor() + 1.0;
+                loop_start = offset_instant(loop_start, loops * measure_ms);
+            }
+            self.loop_start = Some(loop_start);
             return vec![];
         }
 
-        let now = Instant::now();
-
-        match self.next_beat_time {
-            None => {
-                // Start pattern playback - find first beat at position 1.0
-                let first_beat_triggers = self.collect_triggers_at_position(1.0);
-                if !first_beat_triggers.is_empty() {
-                    self.schedule_next_beat(tempo_bpm);
-                    self.stats.beats_played += 1;
-                    self.stats.last_beat_time = Some(now);
-                    first_beat_triggers
-                } else {
-                    // No beat at position 1.0, schedule first available beat
-                    self.current_beat_index = 0;
-                    self.schedule_next_beat(tempo_bpm);
-                    vec![]
+        if self.current_beat_index == 0 {
+            loop_start = skip_missed_measures(loop_start, now, measure_ms);
+        }
+
+        let mut triggers = Vec::new();
+        for _ in 0..beat_count {
+            let beat = &pattern.beats[self.current_beat_index];
+            let mut due_ms = beat_due_ms(pattern, self.current_beat_index, beat_interval_ms);
+            if self.humanize.timing_jitter_ms > 0.0 {
+                let draw = *self
+                    .next_beat_jitter
+                    .get_or_insert_with(|| self.humanize_rng.next_bipolar());
+                let limit = jitter_limit_ms(
+                    pattern,
+                    self.current_beat_index,
+                    beat_interval_ms,
+                    measure_ms,
+                );
+                due_ms +=
+                    (draw as f64 * self.humanize.timing_jitter_ms as f64).clamp(-limit, limit);
+            }
+            let due = offset_instant(loop_start, due_ms);
+            if now < due {
+                break;
+            }
+            self.next_beat_jitter = None;
+
+            // Beats missed by more than the tolerance are dropped, not replayed
+            if signed_ms_between(due, now) <= LATE_TOLERANCE_MS {
+                let beat_number = (beat.beat_position.floor() as u8).max(1);
+                for &sample in &beat.samples {
+                    let mut gain = self.trigger_gain(pattern, &sample);
+                    if self.humanize.velocity_jitter > 0.0 {
+                        let draw = self.humanize_rng.next_bipolar();
+                        gain *= (1.0 + draw * self.humanize.velocity_jitter).max(0.0);
+                    }
+                    triggers.push(PatternTrigger {
+                        click_type: sample,
+                        is_accent: beat.accent,
+                        beat_position: beat.beat_position,
+                        beat_number,
+                        gain,
+                    });
                 }
+                self.stats.beats_played += 1;
+                self.stats.last_beat_time = Some(now);
             }
-            Some(next_time) => {
-                // Check if it's time for the next beat
-                if now >= next_time {
-                    let current_beat = &pattern.beats[self.current_beat_index];
-                    let _triggers = [PatternTrigger {
-                        click_type: current_beat.samples[0], // Take first sample for now
-                        is_accent: current_beat.accent,
-                        beat_position: current_beat.beat_position,
-                        beat_number: self.current_beat_number(),
-                    }];
-
-                    // Collect all sample triggers for this beat
-                    let all_triggers: Vec<PatternTrigger> = current_beat
-                        .samples
-                        .iter()
-                        .map(|&sample| PatternTrigger {
-                            click_type: sample,
-                            is_accent: current_beat.accent,
-                            beat_position: current_beat.beat_position,
-                            beat_number: self.current_beat_number(),
-                        })
-                        .collect();
-
-                    // Advance to next beat with timing reset
-                    self.advance_to_next_beat(tempo_bpm);
-                    self.stats.beats_played += 1;
-                    self.stats.last_beat_time = Some(now);
-
-                    all_triggers
-                } else {
-                    // Not time for next beat yet
-                    vec![]
+
+            // Advance beat index with pattern looping
+            self.current_beat_index = (self.current_beat_index + 1) % beat_count;
+            if self.current_beat_index == 0 {
+                self.stats.loops_completed += 1;
+                loop_start = offset_instant(loop_start, measure_ms);
+                loop_start = skip_missed_measures(loop_start, now, measure_ms);
+                if self.pending_pattern.is_some() {
+                    break;
                 }
             }
         }
+
+        self.loop_start = Some(loop_start);
+        if self.current_beat_index == 0 && self.pending_pattern.is_some() {
+            // Continue into the new loop with the edited pattern
+            triggers.extend(self.check_pattern_triggers_at(tempo_bpm, now));
+        }
+        triggers
     }
 
     /// Get playback statistics
@@ -224,13 +470,66 @@ impl PatternState {
         &self.stats
     }
 
+    /// Configure tempo-based envelope scaling for pattern triggers
+    pub fn set_envelope_scaling(&mut self, envelope_scaling: EnvelopeScaling) {
+        self.envelope_scaling = envelope_scaling;
+    }
+
+    /// Get tempo-based envelope scaling settings
+    pub fn envelope_scaling(&self) -> EnvelopeScaling {
+        self.envelope_scaling
+    }
+
+    /// Adapt a trigger envelope to the current tempo
+    ///
+    /// Returns the envelope unchanged when scaling is disabled.
+    pub fn scale_envelope(&self, envelope: &AdsrEnvelope, tempo_bpm: f32) -> AdsrEnvelope {
+        self.envelope_scaling.apply(envelope, tempo_bpm)
+    }
+
+    /// Set the kit whose per-piece gains are applied to triggers
+    pub fn set_drum_kit(&mut self, drum_kit: Option<DrumKit>) {
+        self.drum_kit = drum_kit;
+    }
+
+    /// Get the kit applying per-piece gains
+    pub fn drum_kit(&self) -> Option<&DrumKit> {
+        self.drum_kit.as_ref()
+    }
+
+    /// Configure timing and velocity jitter, restarting its random sequence
+    pub fn set_humanize(&mut self, humanize: Humanize) {
+        self.humanize = humanize;
+        self.humanize_rng.seed(humanize.seed);
+        self.next_beat_jitter = None;
+    }
+
+    /// Get the timing and velocity jitter settings
+    pub fn humanize(&self) -> Humanize {
+        self.humanize
+    }
+
+    /// Gain for a trigger: pattern gain stacked with the kit piece gain
+    fn trigger_gain(&self, pattern: &DrumPattern, click_type: &ClickType) -> f32 {
+        let piece_gain = self
+            .drum_kit
+            .as_ref()
+            .map_or(1.0, |kit| kit.piece_gain(click_type));
+        pattern.gain() * piece_gain
+    }
+
     /// Reset internal playback state
     fn reset_playback_state(&mut self) {
         self.current_beat_index = 0;
-        self.next_beat_time = None;
+        self.loop_start = None;
+        self.schedule_tempo_bpm = None;
+        self.paused_at = None;
+        self.humanize_rng.seed(self.humanize.seed);
+        self.next_beat_jitter = None;
     }
 
     /// Collect all triggers at a specific beat position
+    #[cfg(test)]
     fn collect_triggers_at_position(&self, position: f32) -> Vec<PatternTrigger> {
         let Some(ref pattern) = self.current_pattern else {
             return vec![];
@@ -239,78 +538,155 @@ impl PatternState {
         pattern
             .beats
             .iter()
-            .filter(|beat| (beat.beat_position - position).abs() < 0.01)
+            .filter(|beat| (beat.beat_position - position).abs() < POSITION_TOLERANCE)
             .flat_map(|beat| {
-                beat.samples.iter().map(|&sample| PatternTrigger {
+                beat.samples.iter().map(move |&sample| PatternTrigger {
                     click_type: sample,
                     is_accent: beat.accent,
                     beat_position: beat.beat_position,
                     beat_number: (beat.beat_position.floor() as u8).max(1),
+                    gain: self.trigger_gain(pattern, &sample),
                 })
             })
             .collect()
     }
+}
 
-    /// Schedule the next beat trigger time
-    fn schedule_next_beat(&mut self, tempo_bpm: f32) {
-        let Some(ref pattern) = self.current_pattern else {
-            return;
-        };
+/// Time of a beat from the start of its loop, including its micro-timing offset
+fn beat_due_ms(pattern: &DrumPattern, index: usize, beat_interval_ms: f64) -> f64 {
+    let beat = &pattern.beats[index];
+    (beat.beat_position - 1.0) as f64 * beat_interval_ms + beat.timing_offset_ms as f64
+}
 
-        if pattern.beats.is_empty() {
-            return;
-        }
+/// Largest timing jitter that keeps a beat strictly between its neighbours
+///
+/// Neighbours wrap around the loop, so the first beat is bounded by the
+/// previous loop's last beat and the last beat by the next loop's first.
+fn jitter_limit_ms(
+    pattern: &DrumPattern,
+    index: usize,
+    beat_interval_ms: f64,
+    measure_ms: f64,
+) -> f64 {
+    let count = pattern.beats.len();
+    let due = beat_due_ms(pattern, index, beat_interval_ms);
+    let previous = match index {
+        0 => beat_due_ms(pattern, count - 1, beat_interval_ms) - measure_ms,
+        _ => beat_due_ms(pattern, index - 1, beat_interval_ms),
+    };
+    let next = if index + 1 == count {
+        beat_due_ms(pattern, 0, beat_interval_ms) + measure_ms
+    } else {
+        beat_due_ms(pattern, index + 1, beat_interval_ms)
+    };
+    ((due - previous).min(next - due) * MAX_JITTER_GAP_FRACTION).max(0.0)
+}
 
-        let beat_interval_ms = 60000.0 / tempo_bpm as f64;
-        let current_beat = &pattern.beats[self.current_beat_index];
+/// Index of the beat at a position, within `POSITION_TOLERANCE`
+fn find_beat(pattern: &DrumPattern, position: f32) -> Option<usize> {
+    pattern
+        .beats
+        .iter()
+        .position(|beat| (beat.beat_position - position).abs() < POSITION_TOLERANCE)
+}
+
+/// Whether a beat position falls inside one measure of the pattern
+fn position_in_measure(pattern: &DrumPattern, position: f32) -> bool {
+    position >= 1.0 && position < pattern.time_signature.beats_per_measure as f32 + 1.0
+}
 
-        // Calculate milliseconds from beat 1 to this beat position
-        let ms_from_beat_1 = (current_beat.beat_position - 1.0) as f64 * beat_interval_ms;
+/// Move a loop start past every whole measure that ended over `LATE_TOLERANCE_MS` ago
+fn skip_missed_measures(loop_start: Instant, now: Instant, measure_ms: f64) -> Instant {
+    let missed_ms = signed_ms_between(loop_start, now) - measure_ms - LATE_TOLERANCE_MS;
+    if missed_ms < 0.0 || measure_ms <= 0.0 {
+        return loop_start;
+    }
+    let loops = (missed_ms / measure_ms).floor() + 1.0;
+    offset_instant(loop_start, loops * measure_ms)
+}
 
-        // Schedule absolute trigger time
-        self.next_beat_time = Some(Instant::now() + Duration::from_millis(ms_from_beat_1 as u64));
+/// Signed milliseconds from `from` to `to`
+fn signed_ms_between(from: Instant, to: Instant) -> f64 {
+    match to.checked_duration_since(from) {
+        Some(elapsed) => elapsed.as_secs_f64() * 1000.0,
+        None => -(from.duration_since(to).as_secs_f64() * 1000.0),
     }
+}
 
-    /// Advance to next beat with timing reset to prevent drift
-    fn advance_to_next_beat(&mut self, tempo_bpm: f32) {
-        let Some(ref pattern) = self.current_pattern else {
-            return;
-        };
+/// Instant a signed number of milliseconds from `base` (clamped to `base` if it underflows)
+fn offset_instant(base: Instant, offset_ms: f64) -> Instant {
+    let offset = Duration::from_secs_f64(offset_ms.abs() / 1000.0);
+    if offset_ms >= 0.0 {
+        base + offset
+    } else {
+        base.checked_sub(offset).unwrap_or(base)
+    }
+}
 
-        if pattern.beats.is_empty() {
-            return;
+impl EnvelopeScaling {
+    /// Create enabled scaling with the given reference tempo and beat fraction cap
+    pub fn new(reference_bpm: f32, max_beat_fraction: f32) -> Self {
+        Self {
+            enabled: true,
+            reference_bpm,
+            max_beat_fraction,
         }
+    }
 
-        // Advance beat index with pattern looping
-        let was_at_end = self.current_beat_index == pattern.beats.len() - 1;
-        self.current_beat_index = (self.current_beat_index + 1) % pattern.beats.len();
+    /// Compute the effective release time at the given tempo
+    pub fn effective_release(&self, release_secs: f32, tempo_bpm: f32) -> f32 {
+        if !self.enabled || tempo_bpm <= 0.0 || self.reference_bpm <= 0.0 {
+            return release_secs;
+        }
 
-        if was_at_end {
-            self.stats.loops_completed += 1;
+        let beat_secs = 60.0 / tempo_bpm;
+        let scaled = release_secs * (self.reference_bpm / tempo_bpm);
+        scaled.min(beat_secs * self.max_beat_fraction.max(0.0))
+    }
+
+    /// Return a copy of the envelope with its release scaled to the tempo
+    pub fn apply(&self, envelope: &AdsrEnvelope, tempo_bpm: f32) -> AdsrEnvelope {
+        AdsrEnvelope {
+            release_secs: self.effective_release(envelope.release_secs, tempo_bpm),
+            ..envelope.clone()
         }
+    }
+}
 
-        let beat_interval_ms = 60000.0 / tempo_bpm as f64;
-        let current_beat = &pattern.beats[self.current_beat_index];
-        let next_beat_position = current_beat.beat_position;
-
-        // Calculate interval to next beat
-        let current_time = Instant::now();
-        let interval_ms = if self.current_beat_index == 0 {
-            // Looped back to start of pattern
-            let last_beat = &pattern.beats[pattern.beats.len() - 1];
-            let measure_length = pattern.time_signature.beats_per_measure as f32;
-            let remaining_time =
-                (measure_length + 1.0 - last_beat.beat_position) as f64 * beat_interval_ms;
-            let next_beat_time = (next_beat_position - 1.0) as f64 * beat_interval_ms;
-            remaining_time + next_beat_time
-        } else {
-            // Normal advance within pattern
-            let prev_beat = &pattern.beats[self.current_beat_index - 1];
-            (next_beat_position - prev_beat.beat_position) as f64 * beat_interval_ms
-        };
+impl Default for EnvelopeScaling {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            reference_bpm: 120.0,
+            max_beat_fraction: 0.5,
+        }
+    }
+}
+
+impl Humanize {
+    /// Create jitter settings with the default seed
+    pub fn new(timing_jitter_ms: f32, velocity_jitter: f32) -> Self {
+        Self {
+            timing_jitter_ms: timing_jitter_ms.max(0.0),
+            velocity_jitter: velocity_jitter.clamp(0.0, 1.0),
+            ..Self::default()
+        }
+    }
 
-        // Reset timing base to prevent drift accumulation
-        self.next_beat_time = Some(current_time + Duration::from_millis(interval_ms as u64));
+    /// Set the seed of the jitter sequence
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for Humanize {
+    fn default() -> Self {
+        Self {
+            timing_jitter_ms: 0.0,
+            velocity_jitter: 0.0,
+            seed: SynthRng::DEFAULT_SEED,
+        }
     }
 }
 
@@ -368,6 +744,7 @@ mod tests {
     use super::*;
     use crate::patterns::types::DrumPatternBeat;
     use crate::timing::TimeSignature;
+    use crate::EnvelopeCurve;
 
     fn create_test_pattern() -> DrumPattern {
         DrumPattern::new("test", TimeSignature::new(4, 4))
@@ -470,4 +847,377 @@ mod tests {
         assert_eq!(state.current_beat_position(), 1.0);
         assert_eq!(state.current_beat_number(), 1);
     }
+
+    #[test]
+    fn test_envelope_scaling_follows_tempo() {
+        let mut state = PatternState::new();
+        state.set_envelope_scaling(EnvelopeScaling::new(120.0, 0.5));
+
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.001,
+            hold_secs: 0.0,
+            decay_secs: 0.05,
+            sustain_level: 0.0,
+            release_secs: 0.3,
+            curve: EnvelopeCurve::LINEAR,
+        };
+
+        let fast = state.scale_envelope(&envelope, 180.0);
+        let slow = state.scale_envelope(&envelope, 60.0);
+        assert!(fast.release_secs < slow.release_secs);
+
+        // Fast tempo is capped to half a beat, slow tempo stretches the release
+        assert!(fast.release_secs <= (60.0 / 180.0) * 0.5 + 1e-6);
+        assert!(slow.release_secs > envelope.release_secs);
+        assert_eq!(fast.attack_secs, envelope.attack_secs);
+    }
+
+    #[test]
+    fn test_envelope_scaling_disabled_by_default() {
+        let state = PatternState::new();
+        assert!(!state.envelope_scaling().enabled);
+
+        let envelope = AdsrEnvelope {
+            attack_secs: 0.001,
+            hold_secs: 0.0,
+            decay_secs: 0.05,
+            sustain_level: 0.0,
+            release_secs: 0.3,
+            curve: EnvelopeCurve::LINEAR,
+        };
+
+        assert_eq!(state.scale_envelope(&envelope, 180.0).release_secs, 0.3);
+        assert_eq!(state.scale_envelope(&envelope, 60.0).release_secs, 0.3);
+    }
+
+    #[test]
+    fn test_pattern_gain_scales_triggers() {
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern().with_gain_db(-3.0));
+        state.start();
+
+        let triggers = state.check_pattern_triggers(120.0);
+        assert_eq!(triggers.len(), 1);
+        assert!((triggers[0].gain - 0.7079).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_kit_piece_gain_stacks_with_pattern_gain() {
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern().with_gain_db(-6.0));
+        state.set_drum_kit(Some(DrumKit::acoustic_kit_01().with_volume(0.5)));
+
+        // Kick piece is stored at unity, snare at 0.9
+        let pattern_gain = 10.0_f32.powf(-6.0 / 20.0);
+        let kick = state.collect_triggers_at_position(1.0);
+        let snare = state.collect_triggers_at_position(2.0);
+        assert!((kick[0].gain - pattern_gain * 0.5).abs() < 1e-6);
+        assert!((snare[0].gain - pattern_gain * 0.5 * 0.9).abs() < 1e-6);
+
+        state.set_drum_kit(None);
+        let snare = state.collect_triggers_at_position(2.0);
+        assert!((snare[0].gain - pattern_gain).abs() < 1e-6);
+    }
+
+    /// Times (ms after start) at which each check returns triggers
+    fn fire_times(state: &mut PatternState, start: Instant, until_ms: u64) -> Vec<(u64, usize)> {
+        (0..=until_ms)
+            .filter_map(|ms| {
+                let triggers =
+                    state.check_pattern_triggers_at(120.0, start + Duration::from_millis(ms));
+                (!triggers.is_empty()).then_some((ms, triggers.len()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_beats_fire_at_base_time_plus_offset() {
+        let pattern = DrumPattern::new("groove", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick))
+            .with_beat(
+                DrumPatternBeat::new(2.0)
+                    .with_sample(ClickType::AcousticSnare)
+                    .with_timing_offset_ms(15.0),
+            )
+            .with_beat(
+                DrumPatternBeat::new(3.5)
+                    .with_sample(ClickType::HiHatClosed)
+                    .with_timing_offset_ms(-10.0),
+            );
+        let mut state = PatternState::new();
+        state.set_pattern(pattern);
+        state.start();
+
+        // 120 BPM: 500 ms beats, 2000 ms measures
+        let start = Instant::now();
+        let fired: Vec<u64> = fire_times(&mut state, start, 5999)
+            .into_iter()
+            .map(|(ms, _)| ms)
+            .collect();
+        assert_eq!(
+            fired,
+            vec![0, 515, 1240, 2000, 2515, 3240, 4000, 4515, 5240]
+        );
+        assert_eq!(state.stats().loops_completed, 3);
+    }
+
+    #[test]
+    fn test_tempo_change_keeps_beat_position() {
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern());
+        state.start();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Half-way between beats 3 and 4 at 60 BPM
+        let fired = (0..2500)
+            .filter(|&ms| !state.check_pattern_triggers_at(60.0, at(ms)).is_empty())
+            .count();
+        assert_eq!(fired, 3);
+
+        // At 200 BPM the remaining half beat takes 150 ms, not a backlog
+        let fired: Vec<(u64, f32)> = (2500..=3300)
+            .flat_map(|ms| {
+                state
+                    .check_pattern_triggers_at(200.0, at(ms))
+                    .into_iter()
+                    .map(move |trigger| (ms, trigger.beat_position))
+            })
+            .collect();
+        assert_eq!(fired, vec![(2650, 4.0), (2950, 1.0), (3250, 2.0)]);
+    }
+
+    #[test]
+    fn test_toggled_beat_takes_effect_on_next_loop() {
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern());
+        state.start();
+        let start = Instant::now();
+
+        // Half-way through the first loop, add an off-beat hi-hat
+        assert_eq!(fire_times(&mut state, start, 700).len(), 2);
+        assert!(state.toggle_beat(2.5, ClickType::HiHatClosed));
+        assert!(state.has_pending_edits());
+        assert_eq!(state.current_pattern().unwrap().beats.len(), 4);
+        assert_eq!(state.edited_pattern().unwrap().beats.len(), 5);
+
+        // The loop in progress plays out unchanged and keeps its place
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let fired: Vec<u64> = (701..=1999)
+            .filter(|&ms| !state.check_pattern_triggers_at(120.0, at(ms)).is_empty())
+            .collect();
+        assert_eq!(fired, vec![1000, 1500]);
+        assert_eq!(state.stats().loops_completed, 1);
+        assert_eq!(state.stats().beats_played, 4);
+
+        // The next loop includes it
+        let mut fired = Vec::new();
+        for ms in 2000..=3999 {
+            for trigger in state.check_pattern_triggers_at(120.0, at(ms)) {
+                fired.push((ms, trigger.click_type));
+            }
+        }
+        assert!(fired.contains(&(2750, ClickType::HiHatClosed)));
+        assert_eq!(fired.len(), 5);
+        assert!(!state.has_pending_edits());
+        assert_eq!(state.stats().loops_completed, 2);
+
+        // Toggling it off removes it from the following loop only
+        assert!(!state.toggle_beat(2.5, ClickType::HiHatClosed));
+        assert!(state.remove_beat(4.0));
+        let late = state.check_pattern_triggers_at(120.0, at(4000));
+        assert_eq!(late.len(), 1);
+        let fired: Vec<f32> = (4001..=5999)
+            .flat_map(|ms| state.check_pattern_triggers_at(120.0, at(ms)))
+            .map(|trigger| trigger.beat_position)
+            .collect();
+        assert_eq!(fired, vec![2.0, 3.0]);
+        assert_eq!(state.stats().loops_completed, 3);
+    }
+
+    #[test]
+    fn test_beats_added_to_empty_pattern_do_not_catch_up() {
+        let pattern = DrumPattern::new("kick", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick));
+        let mut state = PatternState::new();
+        state.set_pattern(pattern);
+        state.start();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Remove every beat, then leave the pattern empty for several loops
+        assert_eq!(fire_times(&mut state, start, 1000), vec![(0, 1)]);
+        assert!(state.remove_beat(1.0));
+        assert!((1001..12000).all(|ms| state.check_pattern_triggers_at(120.0, at(ms)).is_empty()));
+        assert!(state.current_pattern().unwrap().beats.is_empty());
+
+        // Adding the kick back plays it once per measure from the next one
+        assert!(state.toggle_beat(1.0, ClickType::AcousticKick));
+        let fired: Vec<u64> = (12000..=15999)
+            .filter(|&ms| !state.check_pattern_triggers_at(120.0, at(ms)).is_empty())
+            .collect();
+        assert_eq!(fired, vec![12000, 14000]);
+    }
+
+    #[test]
+    fn test_stalled_poll_skips_missed_beats_without_a_burst() {
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern());
+        state.start();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        assert!(!state.check_pattern_triggers_at(120.0, at(0)).is_empty());
+
+        // Stall for several measures: nothing overdue is replayed
+        let played = state.stats().beats_played;
+        assert!(state.check_pattern_triggers_at(120.0, at(7300)).is_empty());
+        assert!(state.check_pattern_triggers_at(120.0, at(7300)).is_empty());
+        assert_eq!(state.stats().beats_played, played);
+
+        // Playback resumes on the original grid
+        let resumed: Vec<f32> = (7301..=9000)
+            .flat_map(|ms| state.check_pattern_triggers_at(120.0, at(ms)))
+            .map(|trigger| trigger.beat_position)
+            .collect();
+        assert_eq!(resumed, vec![4.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_edits_before_playback_apply_immediately() {
+        let mut state = PatternState::new();
+        assert!(!state.toggle_beat(1.0, ClickType::AcousticKick));
+
+        state.set_pattern(create_test_pattern());
+        assert!(!state.add_beat(DrumPatternBeat::new(5.0).with_sample(ClickType::HiHatClosed)));
+        assert!(state.add_beat(
+            DrumPatternBeat::new(1.0)
+                .with_sample(ClickType::HiHatClosed)
+                .with_sample(ClickType::AcousticKick)
+        ));
+        assert!(state.toggle_beat(1.5, ClickType::HiHatClosed));
+        assert!(!state.has_pending_edits());
+
+        let pattern = state.current_pattern().unwrap();
+        assert_eq!(pattern.beats.len(), 5);
+        assert_eq!(pattern.beats[1].beat_position, 1.5);
+        assert_eq!(
+            pattern.beats[0].samples,
+            vec![ClickType::AcousticKick, ClickType::HiHatClosed]
+        );
+        assert!(!state.remove_beat(2.25));
+    }
+
+    /// Fire times and trigger gains over four loops of the test pattern at 120 BPM
+    fn humanized_run(humanize: Humanize) -> Vec<(u64, f32)> {
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern());
+        state.set_humanize(humanize);
+        state.start();
+
+        let start = Instant::now();
+        (0..8000)
+            .flat_map(|ms| {
+                state
+                    .check_pattern_triggers_at(120.0, start + Duration::from_millis(ms))
+                    .into_iter()
+                    .map(move |trigger| (ms, trigger.gain))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_humanize_is_seeded_and_bounded() {
+        let humanize = Humanize::new(20.0, 0.2).with_seed(7);
+        let run = humanized_run(humanize);
+        assert_eq!(run.len(), 16);
+
+        // Timing and velocity draws alternate, one pair per beat
+        let mut rng = SynthRng::new(7);
+        for (beat, &(ms, gain)) in run.iter().enumerate() {
+            let due = beat as f32 * 500.0 + rng.next_bipolar() * 20.0;
+            let velocity = 1.0 + rng.next_bipolar() * 0.2;
+            assert_eq!(ms, due.max(0.0).ceil() as u64, "beat {}", beat);
+            assert!((gain - velocity).abs() < 1e-6, "beat {}", beat);
+
+            let grid = beat as f32 * 500.0;
+            assert!((ms as f32 - grid).abs() <= 21.0);
+            assert!((0.8..=1.2).contains(&gain));
+        }
+
+        // The same seed replays the same performance; another seed does not
+        assert_eq!(humanized_run(humanize), run);
+        assert_ne!(humanized_run(humanize.with_seed(8)), run);
+    }
+
+    #[test]
+    fn test_humanize_never_reorders_beats() {
+        // Jitter far wider than the 125 ms gaps between sixteenth notes
+        let mut pattern = DrumPattern::new("sixteenths", TimeSignature::new(4, 4));
+        for step in 0..16 {
+            pattern = pattern.with_beat(
+                DrumPatternBeat::new(1.0 + step as f32 * 0.25).with_sample(ClickType::HiHatClosed),
+            );
+        }
+        let mut state = PatternState::new();
+        state.set_pattern(pattern);
+        state.set_humanize(Humanize::new(500.0, 0.0).with_seed(3));
+        state.start();
+
+        let start = Instant::now();
+        let positions: Vec<f32> = (0..6000)
+            .flat_map(|ms| {
+                state.check_pattern_triggers_at(120.0, start + Duration::from_millis(ms))
+            })
+            .map(|trigger| trigger.beat_position)
+            .collect();
+        assert!(positions.len() >= 40);
+        for (index, position) in positions.iter().enumerate() {
+            assert_eq!(*position, 1.0 + (index % 16) as f32 * 0.25);
+        }
+
+        // Each check fires at most one beat, so no two beats landed together
+        let mut state = PatternState::new();
+        state.set_pattern(create_test_pattern());
+        state.set_humanize(Humanize::new(500.0, 0.0));
+        state.start();
+        let fired: Vec<u64> = fire_times(&mut state, start, 7999)
+            .into_iter()
+            .map(|(ms, count)| {
+                assert_eq!(count, 1);
+                ms
+            })
+            .collect();
+        assert!(fired.windows(2).all(|pair| pair[1] - pair[0] >= 10));
+    }
+
+    #[test]
+    fn test_groove_stays_phase_locked_over_loops() {
+        let mut pattern = create_test_pattern();
+        crate::patterns::GrooveTemplate::laid_back().apply_to(&mut pattern);
+        let mut state = PatternState::new();
+        state.set_pattern(pattern);
+        state.start();
+
+        // Polling late never shifts later loops: each downbeat lands on the measure
+        let start = Instant::now();
+        let mut downbeats = Vec::new();
+        let mut ms = 0;
+        while downbeats.len() < 20 {
+            let now = start + Duration::from_millis(ms);
+            let triggers = state.check_pattern_triggers_at(120.0, now);
+            if triggers.iter().any(|trigger| trigger.beat_position == 1.0) {
+                downbeats.push(ms);
+            }
+            ms += 7;
+        }
+        for (loop_index, &time) in downbeats.iter().enumerate() {
+            let expected = loop_index as u64 * 2000;
+            assert!(
+                time >= expected && time < expected + 7,
+                "loop {} at {}",
+                loop_index,
+                time
+            );
+        }
+    }
 }
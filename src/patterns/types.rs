@@ -4,10 +4,21 @@
 /// drum patterns, rhythms, and musical arrangements. These types are designed
 /// to integrate seamlessly with the timing system while providing flexibility
 /// for complex rhythmic patterns.
-use crate::timing::{ClickType, TimeSignature};
+use super::builder::PatternValidationError;
+use crate::timing::{ClickType, Subdivision, TimeSignature};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Default pattern grid resolution in pulses per quarter note
+///
+/// 480 PPQ divides evenly into sixteenth notes, triplets, and quintuplets,
+/// and matches the resolution most sequencers write to MIDI files.
+pub const DEFAULT_RESOLUTION_PPQ: u32 = 480;
+
+fn default_resolution_ppq() -> u32 {
+    DEFAULT_RESOLUTION_PPQ
+}
+
 /// A single beat within a drum pattern
 ///
 /// Represents a specific point in time within a pattern where one or more
@@ -24,6 +35,10 @@ pub struct DrumPatternBeat {
 
     /// Whether this beat should be accented (emphasized)
     pub accent: bool,
+
+    /// Micro-timing push (negative) or drag (positive) in milliseconds
+    #[serde(default)]
+    pub timing_offset_ms: f32,
 }
 
 /// Complete drum pattern definition
@@ -50,6 +65,14 @@ pub struct DrumPattern {
 
     /// Pattern metadata
     pub metadata: PatternMetadata,
+
+    /// Level adjustment applied to every trigger in dB (0.0 = unity)
+    #[serde(default)]
+    pub gain_db: f32,
+
+    /// Grid resolution in pulses (ticks) per quarter note
+    #[serde(default = "default_resolution_ppq")]
+    pub resolution_ppq: u32,
 }
 
 /// Additional metadata for patterns
@@ -136,6 +159,8 @@ impl DrumPattern {
             tempo_range: (60, 120),
             beats: Vec::new(),
             metadata: PatternMetadata::default(),
+            gain_db: 0.0,
+            resolution_ppq: DEFAULT_RESOLUTION_PPQ,
         }
     }
 
@@ -181,6 +206,162 @@ impl DrumPattern {
         self
     }
 
+    /// Set pattern gain in dB
+    pub fn with_gain_db(mut self, gain_db: f32) -> Self {
+        self.gain_db = gain_db;
+        self
+    }
+
+    /// Set the grid resolution in pulses per quarter note (minimum 1)
+    pub fn with_resolution_ppq(mut self, resolution_ppq: u32) -> Self {
+        self.resolution_ppq = resolution_ppq.max(1);
+        self
+    }
+
+    /// Ticks in one beat of the time signature
+    ///
+    /// A beat is a quarter note in x/4 time, so this is the PPQ there; in 6/8
+    /// each eighth-note beat is half a quarter.
+    pub fn ticks_per_beat(&self) -> f64 {
+        self.resolution_ppq.max(1) as f64 * 4.0 / self.time_signature.note_value.max(1) as f64
+    }
+
+    /// Convert a beat position to ticks from the start of the measure
+    ///
+    /// Beat position 1.0 is tick 0. Positions between ticks round to the
+    /// nearest tick and positions before the measure start clamp to 0.
+    pub fn position_to_ticks(&self, beat_position: f32) -> u32 {
+        ((beat_position as f64 - 1.0) * self.ticks_per_beat())
+            .round()
+            .max(0.0) as u32
+    }
+
+    /// Convert ticks from the start of the measure to a beat position
+    pub fn ticks_to_position(&self, ticks: u32) -> f32 {
+        (1.0 + ticks as f64 / self.ticks_per_beat()) as f32
+    }
+
+    /// Snap a beat position to the nearest tick of the pattern grid
+    pub fn snap_to_resolution(&self, beat_position: f32) -> f32 {
+        self.ticks_to_position(self.position_to_ticks(beat_position))
+    }
+
+    /// Move beat positions toward the nearest line of a subdivision grid
+    ///
+    /// `strength` sets how far each beat moves (0.0 = unchanged, 1.0 = exactly
+    /// on the grid), so partial quantization tightens timing while keeping
+    /// some of the played feel. Micro-timing offsets are left untouched.
+    ///
+    /// A beat moved onto the downbeat after the measure wraps to beat 1.0.
+    /// Beats that end up at the same position are combined into one, keeping
+    /// each click type once, accented if either beat was.
+    pub fn quantize(&mut self, grid: Subdivision, strength: f32) {
+        let pulses = grid.pulses_per_beat() as f32;
+        let strength = strength.clamp(0.0, 1.0);
+        let beats_per_measure = self.time_signature.beats_per_measure.max(1) as f32;
+
+        let mut quantized: Vec<DrumPatternBeat> = Vec::new();
+        for mut beat in std::mem::take(&mut self.beats) {
+            let target = 1.0 + ((beat.beat_position - 1.0) * pulses).round() / pulses;
+            beat.beat_position += (target - beat.beat_position) * strength;
+            if beat.beat_position >= beats_per_measure + 1.0 {
+                beat.beat_position -= beats_per_measure;
+            }
+
+            let shared = quantized
+                .iter_mut()
+                .find(|existing| (existing.beat_position - beat.beat_position).abs() < 0.01);
+            match shared {
+                Some(existing) => {
+                    for click_type in beat.samples {
+                        if !existing.samples.contains(&click_type) {
+                            existing.samples.push(click_type);
+                        }
+                    }
+                    existing.accent |= beat.accent;
+                }
+                None => quantized.push(beat),
+            }
+        }
+        quantized.sort_by(|a, b| a.beat_position.total_cmp(&b.beat_position));
+        self.beats = quantized;
+    }
+
+    /// Layer another pattern's beats over this one
+    ///
+    /// The composite keeps this pattern's name, gain, resolution, and
+    /// metadata, narrows the tempo range to the overlap of both (when they
+    /// overlap), and takes the higher difficulty. Where both patterns hit the
+    /// same click type at the same position, the hit is kept once, accented
+    /// if either was. Fails if the time signatures differ.
+    pub fn merge(&self, other: &DrumPattern) -> Result<DrumPattern, PatternValidationError> {
+        if self.time_signature != other.time_signature {
+            return Err(PatternValidationError::TimeSignatureMismatch(
+                self.time_signature,
+                other.time_signature,
+            ));
+        }
+
+        // Flatten to individual hits, keeping the accented copy of any duplicate
+        let mut hits: Vec<(f32, ClickType, bool, f32)> = Vec::new();
+        for beat in self.beats.iter().chain(&other.beats) {
+            for &click_type in &beat.samples {
+                let duplicate = hits.iter_mut().find(|(position, existing, _, _)| {
+                    *existing == click_type && (position - beat.beat_position).abs() < 0.01
+                });
+                match duplicate {
+                    Some(hit) => hit.2 |= beat.accent,
+                    None => hits.push((
+                        beat.beat_position,
+                        click_type,
+                        beat.accent,
+                        beat.timing_offset_ms,
+                    )),
+                }
+            }
+        }
+
+        // Regroup hits sharing position, accent, and micro-timing into beats
+        let mut beats: Vec<DrumPatternBeat> = Vec::new();
+        for (position, click_type, accent, offset_ms) in hits {
+            let shared = beats.iter_mut().find(|beat| {
+                (beat.beat_position - position).abs() < 0.01
+                    && beat.accent == accent
+                    && beat.timing_offset_ms == offset_ms
+            });
+            match shared {
+                Some(beat) => beat.samples.push(click_type),
+                None => beats.push(
+                    DrumPatternBeat::new(position)
+                        .with_sample(click_type)
+                        .with_accent(accent)
+                        .with_timing_offset_ms(offset_ms),
+                ),
+            }
+        }
+        beats.sort_by(|a, b| a.beat_position.total_cmp(&b.beat_position));
+
+        let mut merged = self.clone();
+        merged.beats = beats;
+        let min_bpm = self.tempo_range.0.max(other.tempo_range.0);
+        let max_bpm = self.tempo_range.1.min(other.tempo_range.1);
+        if min_bpm <= max_bpm {
+            merged.tempo_range = (min_bpm, max_bpm);
+        }
+        merged.metadata.difficulty = self.metadata.difficulty.max(other.metadata.difficulty);
+        for tag in &other.metadata.tags {
+            if !merged.metadata.tags.contains(tag) {
+                merged.metadata.tags.push(tag.clone());
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Pattern gain as a linear amplitude factor
+    pub fn gain(&self) -> f32 {
+        10.0_f32.powf(self.gain_db / 20.0)
+    }
+
     /// Check if tempo is within recommended range
     pub fn is_tempo_suitable(&self, tempo_bpm: u32) -> bool {
         tempo_bpm >= self.tempo_range.0 && tempo_bpm <= self.tempo_range.1
@@ -227,6 +408,7 @@ impl DrumPatternBeat {
             beat_position: position,
             samples: Vec::new(),
             accent: false,
+            timing_offset_ms: 0.0,
         }
     }
 
@@ -248,6 +430,12 @@ impl DrumPatternBeat {
         self
     }
 
+    /// Set the micro-timing offset in milliseconds (positive = late)
+    pub fn with_timing_offset_ms(mut self, offset_ms: f32) -> Self {
+        self.timing_offset_ms = offset_ms;
+        self
+    }
+
     /// Check if this beat has any samples
     pub fn has_samples(&self) -> bool {
         !self.samples.is_empty()
@@ -340,6 +528,176 @@ mod tests {
         assert_eq!(beats_at_2.len(), 1);
     }
 
+    #[test]
+    fn test_pattern_gain() {
+        let pattern = DrumPattern::new("test", TimeSignature::new(4, 4));
+        assert_eq!(pattern.gain(), 1.0);
+
+        let quiet = pattern.with_gain_db(-6.0);
+        assert!((quiet.gain() - 0.501).abs() < 0.001);
+
+        // Patterns saved before gains existed load at unity
+        let mut json = serde_json::to_value(&quiet).unwrap();
+        json.as_object_mut().unwrap().remove("gain_db");
+        let loaded: DrumPattern = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.gain_db, 0.0);
+    }
+
+    #[test]
+    fn test_quantize_snaps_toward_grid() {
+        let played = DrumPattern::new("test", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.02).with_sample(ClickType::AcousticKick))
+            .with_beat(DrumPatternBeat::new(2.29).with_sample(ClickType::HiHatClosed))
+            .with_beat(DrumPatternBeat::new(3.7).with_sample(ClickType::AcousticSnare));
+        let positions = |pattern: &DrumPattern| -> Vec<f32> {
+            pattern
+                .beats
+                .iter()
+                .map(|beat| beat.beat_position)
+                .collect()
+        };
+
+        let mut full = played.clone();
+        full.quantize(Subdivision::Sixteenth, 1.0);
+        assert_eq!(positions(&full), vec![1.0, 2.25, 3.75]);
+
+        let mut half = played.clone();
+        half.quantize(Subdivision::Sixteenth, 0.5);
+        for (moved, expected) in positions(&half).iter().zip([1.01, 2.27, 3.725]) {
+            assert!((moved - expected).abs() < 1e-5, "{} vs {}", moved, expected);
+        }
+
+        let mut untouched = played.clone();
+        untouched.quantize(Subdivision::Sixteenth, 0.0);
+        assert_eq!(positions(&untouched), positions(&played));
+    }
+
+    #[test]
+    fn test_quantize_wraps_and_combines_colliding_beats() {
+        let mut played = DrumPattern::new("test", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick))
+            .with_beat(DrumPatternBeat::new(2.24).with_sample(ClickType::HiHatClosed))
+            .with_beat(
+                DrumPatternBeat::new(2.26)
+                    .with_sample(ClickType::AcousticSnare)
+                    .with_accent(true),
+            )
+            .with_beat(DrumPatternBeat::new(4.97).with_sample(ClickType::HiHatClosed));
+        played.quantize(Subdivision::Sixteenth, 1.0);
+
+        // The late hat wraps onto the downbeat and joins the kick there
+        assert_eq!(played.beats.len(), 2);
+        assert_eq!(played.beats[0].beat_position, 1.0);
+        assert_eq!(
+            played.beats[0].samples,
+            vec![ClickType::AcousticKick, ClickType::HiHatClosed]
+        );
+        assert_eq!(played.beats[1].beat_position, 2.25);
+        assert_eq!(
+            played.beats[1].samples,
+            vec![ClickType::HiHatClosed, ClickType::AcousticSnare]
+        );
+        assert!(played.beats[1].accent);
+        assert!(crate::patterns::builder::validate_pattern(&played).is_ok());
+    }
+
+    #[test]
+    fn test_merge_layers_kick_and_hat_patterns() {
+        let kick = DrumPattern::new("kick", TimeSignature::new(4, 4))
+            .with_tempo_range(70, 140)
+            .with_beat(
+                DrumPatternBeat::new(1.0)
+                    .with_sample(ClickType::AcousticKick)
+                    .with_accent(true),
+            )
+            .with_beat(DrumPatternBeat::new(3.0).with_sample(ClickType::AcousticKick));
+        let mut hats = DrumPattern::new("hats", TimeSignature::new(4, 4))
+            .with_tempo_range(90, 160)
+            .with_difficulty(3);
+        for eighth in 0..8 {
+            hats = hats.with_beat(
+                DrumPatternBeat::new(1.0 + eighth as f32 * 0.5).with_sample(ClickType::HiHatClosed),
+            );
+        }
+
+        let merged = kick.merge(&hats).unwrap();
+        assert_eq!(merged.name, "kick");
+        assert_eq!(merged.tempo_range, (90, 140));
+        assert_eq!(merged.metadata.difficulty, 3);
+
+        let hits = |click_type: ClickType| -> Vec<f32> {
+            merged
+                .beats
+                .iter()
+                .filter(|beat| beat.samples.contains(&click_type))
+                .map(|beat| beat.beat_position)
+                .collect()
+        };
+        assert_eq!(hits(ClickType::AcousticKick), vec![1.0, 3.0]);
+        assert_eq!(
+            hits(ClickType::HiHatClosed),
+            vec![1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 4.5]
+        );
+
+        // The accented kick keeps its accent without accenting the hat beside it
+        let downbeat = merged.beats_at_position(1.0);
+        assert_eq!(downbeat.len(), 2);
+        assert!(downbeat
+            .iter()
+            .all(|beat| beat.accent == beat.samples.contains(&ClickType::AcousticKick)));
+
+        // The same click type at the same position is kept once, accented if either was
+        let accented_hats = DrumPattern::new("accents", TimeSignature::new(4, 4)).with_beat(
+            DrumPatternBeat::new(2.0)
+                .with_sample(ClickType::HiHatClosed)
+                .with_accent(true),
+        );
+        let layered = hats.merge(&accented_hats).unwrap();
+        let at_two = layered.beats_at_position(2.0);
+        assert_eq!(at_two.len(), 1);
+        assert!(at_two[0].accent);
+        assert_eq!(layered.beats.len(), 8);
+
+        let waltz = DrumPattern::new("waltz", TimeSignature::new(3, 4));
+        assert!(matches!(
+            kick.merge(&waltz),
+            Err(PatternValidationError::TimeSignatureMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_resolution_tick_conversion() {
+        let pattern = DrumPattern::new("test", TimeSignature::new(4, 4));
+        assert_eq!(pattern.resolution_ppq, DEFAULT_RESOLUTION_PPQ);
+
+        // The second sixteenth of beat 2 sits a beat and a sixteenth in
+        let sixteenth = 2.25;
+        assert_eq!(pattern.position_to_ticks(sixteenth), 600);
+        assert_eq!(pattern.ticks_to_position(600), sixteenth);
+
+        let coarse = pattern.clone().with_resolution_ppq(96);
+        assert_eq!(coarse.position_to_ticks(sixteenth), 120);
+        assert_eq!(coarse.ticks_to_position(120), sixteenth);
+
+        // Off-grid positions snap to the nearest tick; eighth-note beats halve the ticks
+        assert_eq!(
+            coarse
+                .clone()
+                .with_resolution_ppq(4)
+                .snap_to_resolution(1.3),
+            1.25
+        );
+        let compound = DrumPattern::new("six_eight", TimeSignature::new(6, 8));
+        assert_eq!(compound.position_to_ticks(2.0), 240);
+        assert_eq!(compound.position_to_ticks(0.5), 0);
+
+        // Patterns saved before resolutions existed load at the default
+        let mut json = serde_json::to_value(&coarse).unwrap();
+        json.as_object_mut().unwrap().remove("resolution_ppq");
+        let loaded: DrumPattern = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.resolution_ppq, DEFAULT_RESOLUTION_PPQ);
+    }
+
     #[test]
     fn test_pattern_genre_display() {
         assert_eq!(PatternGenre::Rock.display_name(), "Rock");
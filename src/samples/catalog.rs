@@ -3,7 +3,7 @@
 /// This module provides structured metadata and configuration for samples,
 /// including categorization, envelope settings, and organizational tools
 /// for managing large sample libraries.
-use crate::AdsrEnvelope;
+use crate::{AdsrEnvelope, EnvelopeCurve};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -70,39 +70,51 @@ impl SampleMetadata {
         let envelope = match drum_type {
             DrumType::Kick => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.15,
                 sustain_level: 0.2,
                 release_secs: 0.3,
+                curve: EnvelopeCurve::LINEAR,
             },
             DrumType::Snare => AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.08,
                 sustain_level: 0.1,
                 release_secs: 0.15,
+                curve: EnvelopeCurve::LINEAR,
             },
             DrumType::HiHat => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.05,
                 sustain_level: 0.0,
                 release_secs: 0.1,
+                curve: EnvelopeCurve::LINEAR,
             },
             DrumType::Cymbal => AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.2,
                 sustain_level: 0.3,
                 release_secs: 0.8,
+                curve: EnvelopeCurve::LINEAR,
             },
             DrumType::Tom => AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.12,
                 sustain_level: 0.2,
                 release_secs: 0.25,
+                curve: EnvelopeCurve::LINEAR,
             },
             DrumType::Percussion => AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.1,
                 release_secs: 0.2,
+                curve: EnvelopeCurve::LINEAR,
             },
         };
 
@@ -365,9 +377,11 @@ impl DrumEnvelopeExt for AdsrEnvelope {
     fn default_drum() -> Self {
         Self {
             attack_secs: 0.002,
+            hold_secs: 0.0,
             decay_secs: 0.1,
             sustain_level: 0.3,
             release_secs: 0.2,
+            curve: EnvelopeCurve::LINEAR,
         }
     }
 }
@@ -4,10 +4,15 @@
 /// easy setup of common drum configurations. It integrates with the
 /// timing module's ClickType for seamless metronome and pattern usage.
 use super::catalog::{DrumType, SampleMetadata};
+use crate::patterns::DrumPattern;
 use crate::timing::ClickType;
-use crate::AdsrEnvelope;
+use crate::{AdsrEnvelope, EnvelopeCurve, SampleData, SampleError, SynthRng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Choke group shared by the open and closed hi-hats of the preset kits
+pub const HIHAT_CHOKE_GROUP: u32 = 1;
 
 /// Complete drum kit configuration
 ///
@@ -48,6 +53,172 @@ pub struct DrumSample {
 
     /// Sample-specific volume
     pub volume: f32,
+
+    /// Stereo position (-1.0 = left, 0.0 = centre, 1.0 = right)
+    #[serde(default)]
+    pub pan: f32,
+
+    /// Largest per-hit pan deviation from `pan` (0.0 = fixed position)
+    #[serde(default)]
+    pub pan_spread: f32,
+
+    /// What moves each hit within the pan spread
+    #[serde(default)]
+    pub pan_spread_source: PanSpreadSource,
+
+    /// Choke group: striking any piece in the group cuts off the others
+    #[serde(default)]
+    pub choke_group: Option<u32>,
+
+    /// Loaded audio for each velocity layer (empty until loaded)
+    #[serde(skip)]
+    pub layers: Vec<VelocityLayer>,
+}
+
+/// One velocity layer of a drum sample with its loaded audio
+#[derive(Debug, Clone)]
+pub struct VelocityLayer {
+    /// Lowest velocity (0.0 - 1.0) that selects this layer
+    pub min_velocity: f32,
+
+    /// Highest velocity (0.0 - 1.0) that selects this layer
+    pub max_velocity: f32,
+
+    /// Path the audio was loaded from
+    pub file_path: PathBuf,
+
+    /// Loaded sample audio
+    pub data: SampleData,
+}
+
+/// JSON manifest describing a shareable drum kit
+///
+/// Sample paths are resolved relative to the manifest file.
+///
+/// ```json
+/// {
+///   "name": "my_kit",
+///   "volume": 0.9,
+///   "slots": [
+///     { "click_type": "AcousticKick", "gain": 1.0, "pan": 0.0,
+///       "layers": [
+///         { "file": "kick-soft.wav", "max_velocity": 0.5 },
+///         { "file": "kick-hard.wav", "min_velocity": 0.5 }
+///       ] },
+///     { "click_type": "AcousticSnare", "file": "snare.wav", "pan": -0.2 }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KitManifest {
+    /// Kit name and identifier
+    pub name: String,
+
+    /// Display name for user interfaces (defaults to `name`)
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    /// Kit description
+    #[serde(default)]
+    pub description: String,
+
+    /// Kit-wide volume adjustment
+    #[serde(default = "default_gain")]
+    pub volume: f32,
+
+    /// Drum slots in this kit
+    pub slots: Vec<ManifestSlot>,
+}
+
+/// Drum slot entry in a kit manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSlot {
+    /// Timing system sound this slot plays
+    pub click_type: ClickType,
+
+    /// Base frequency of the samples (Hz)
+    #[serde(default = "default_drum_frequency")]
+    pub base_frequency: f32,
+
+    /// Slot gain, stored as the drum sample volume
+    #[serde(default = "default_gain")]
+    pub gain: f32,
+
+    /// Stereo position (-1.0 to 1.0)
+    #[serde(default)]
+    pub pan: f32,
+
+    /// Per-hit pan deviation, moved by random amounts (0.0 = fixed position)
+    #[serde(default)]
+    pub pan_spread: f32,
+
+    /// Choke group shared with other slots (e.g. open and closed hi-hat)
+    #[serde(default)]
+    pub choke_group: Option<u32>,
+
+    /// Single sample file, shorthand for one full-range layer
+    #[serde(default)]
+    pub file: Option<String>,
+
+    /// Velocity layers, each mapped to its own file
+    #[serde(default)]
+    pub layers: Vec<ManifestLayer>,
+}
+
+/// Velocity layer entry in a kit manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLayer {
+    /// Sample file, relative to the manifest
+    pub file: String,
+
+    /// Lowest velocity that selects this layer
+    #[serde(default)]
+    pub min_velocity: f32,
+
+    /// Highest velocity that selects this layer
+    #[serde(default = "default_gain")]
+    pub max_velocity: f32,
+}
+
+/// Error types for kit manifest loading
+#[derive(Debug, thiserror::Error)]
+pub enum KitManifestError {
+    #[error("IO error reading manifest: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Manifest parsing error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Missing sample files: {}", .0.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "))]
+    MissingFiles(Vec<PathBuf>),
+
+    #[error("Failed to load {}: {source}", .path.display())]
+    SampleError { path: PathBuf, source: SampleError },
+}
+
+fn default_gain() -> f32 {
+    1.0
+}
+
+fn default_drum_frequency() -> f32 {
+    60.0
+}
+
+/// Source of the per-hit pan movement within a drum sample's pan spread
+///
+/// Real strikes land on slightly different spots of a drum, so a little pan
+/// movement per hit adds width to a kit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PanSpreadSource {
+    /// A random amount per hit, repeatable under a seed
+    #[default]
+    Random,
+
+    /// Hit velocity: the softest hits sit at one edge, the hardest at the other
+    Velocity,
 }
 
 /// Velocity response curve for dynamic playing
@@ -62,6 +233,9 @@ pub enum VelocityCurve {
     /// Logarithmic response (compressed dynamics)
     Logarithmic,
 
+    /// S-shaped response (soft and hard ends compressed, middle expanded)
+    SCurve,
+
     /// Custom curve with control points
     Custom(Vec<(f32, f32)>),
 }
@@ -97,6 +271,32 @@ impl DrumKit {
             .collect()
     }
 
+    /// Combined kit and piece gain for a click type (1.0 if the kit lacks it)
+    pub fn piece_gain(&self, click_type: &ClickType) -> f32 {
+        self.get_sample(click_type)
+            .map_or(1.0, |sample| self.volume * sample.volume)
+    }
+
+    /// Choke group of the piece for a click type, if any
+    pub fn choke_group(&self, click_type: &ClickType) -> Option<u32> {
+        self.get_sample(click_type)
+            .and_then(|sample| sample.choke_group)
+    }
+
+    /// Click types used by a pattern that this kit has no sample for
+    ///
+    /// Each missing type is listed once, in order of first use, so a UI can
+    /// warn before playback falls back to synthesized sounds.
+    pub fn validate_pattern(&self, pattern: &DrumPattern) -> Vec<ClickType> {
+        let mut missing = Vec::new();
+        for click_type in pattern.beats.iter().flat_map(|beat| &beat.samples) {
+            if !self.samples.contains_key(click_type) && !missing.contains(click_type) {
+                missing.push(*click_type);
+            }
+        }
+        missing
+    }
+
     /// Get click types supported by this kit
     pub fn supported_click_types(&self) -> Vec<ClickType> {
         self.samples.keys().cloned().collect()
@@ -129,13 +329,20 @@ impl DrumKit {
             .with_volume(1.0)
             .with_envelope(AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.15,
                 sustain_level: 0.2,
                 release_secs: 0.3,
+                curve: EnvelopeCurve::LINEAR,
             }),
             click_type: ClickType::AcousticKick,
             velocity_curve: VelocityCurve::Exponential(1.5),
             volume: 1.0,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: None,
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::AcousticKick, kick_sample);
 
@@ -149,13 +356,20 @@ impl DrumKit {
             .with_volume(0.9)
             .with_envelope(AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.08,
                 sustain_level: 0.1,
                 release_secs: 0.15,
+                curve: EnvelopeCurve::LINEAR,
             }),
             click_type: ClickType::AcousticSnare,
             velocity_curve: VelocityCurve::Exponential(1.2),
             volume: 0.9,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: None,
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::AcousticSnare, snare_sample);
 
@@ -169,13 +383,20 @@ impl DrumKit {
             .with_volume(0.7)
             .with_envelope(AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.05,
                 sustain_level: 0.0,
                 release_secs: 0.1,
+                curve: EnvelopeCurve::LINEAR,
             }),
             click_type: ClickType::HiHatClosed,
             velocity_curve: VelocityCurve::Linear,
             volume: 0.7,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: Some(HIHAT_CHOKE_GROUP),
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::HiHatClosed, hihat_closed_sample);
 
@@ -189,13 +410,20 @@ impl DrumKit {
             .with_volume(0.8)
             .with_envelope(AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.2,
                 sustain_level: 0.3,
                 release_secs: 0.4,
+                curve: EnvelopeCurve::LINEAR,
             }),
             click_type: ClickType::HiHatOpen,
             velocity_curve: VelocityCurve::Linear,
             volume: 0.8,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: Some(HIHAT_CHOKE_GROUP),
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::HiHatOpen, hihat_open_sample);
 
@@ -209,13 +437,20 @@ impl DrumKit {
             .with_volume(0.8)
             .with_envelope(AdsrEnvelope {
                 attack_secs: 0.001,
+                hold_secs: 0.0,
                 decay_secs: 0.06,
                 sustain_level: 0.0,
                 release_secs: 0.12,
+                curve: EnvelopeCurve::LINEAR,
             }),
             click_type: ClickType::RimShot,
             velocity_curve: VelocityCurve::Linear,
             volume: 0.8,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: None,
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::RimShot, rimshot_sample);
 
@@ -229,13 +464,20 @@ impl DrumKit {
             .with_volume(0.6)
             .with_envelope(AdsrEnvelope {
                 attack_secs: 0.0005,
+                hold_secs: 0.0,
                 decay_secs: 0.03,
                 sustain_level: 0.0,
                 release_secs: 0.06,
+                curve: EnvelopeCurve::LINEAR,
             }),
             click_type: ClickType::Stick,
             velocity_curve: VelocityCurve::Linear,
             volume: 0.6,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: None,
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::Stick, stick_sample);
 
@@ -253,20 +495,122 @@ impl DrumKit {
             metadata: SampleMetadata::new("synth_kick", "synthetic://kick", 60.0).with_envelope(
                 AdsrEnvelope {
                     attack_secs: 0.001,
+                    hold_secs: 0.0,
                     decay_secs: 0.2,
                     sustain_level: 0.1,
                     release_secs: 0.4,
+                    curve: EnvelopeCurve::LINEAR,
                 },
             ),
             click_type: ClickType::AcousticKick,
             velocity_curve: VelocityCurve::Exponential(2.0),
             volume: 1.0,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: None,
+            layers: Vec::new(),
         };
         kit.add_sample(ClickType::AcousticKick, kick_sample);
 
         kit
     }
 
+    /// Load a kit and all of its samples from a JSON manifest
+    ///
+    /// Every referenced file is checked before anything is loaded, so a
+    /// `MissingFiles` error lists all missing samples at once.
+    pub fn from_manifest<P: AsRef<Path>>(path: P) -> Result<Self, KitManifestError> {
+        let path = path.as_ref();
+        let manifest: KitManifest = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::from_manifest_data(&manifest, base_dir)
+    }
+
+    /// Build a kit from a parsed manifest, resolving files against `base_dir`
+    pub fn from_manifest_data(
+        manifest: &KitManifest,
+        base_dir: &Path,
+    ) -> Result<Self, KitManifestError> {
+        let mut slot_layers = Vec::with_capacity(manifest.slots.len());
+        for slot in &manifest.slots {
+            let mut layers = slot.layers.clone();
+            if let Some(file) = &slot.file {
+                layers.push(ManifestLayer {
+                    file: file.clone(),
+                    min_velocity: 0.0,
+                    max_velocity: 1.0,
+                });
+            }
+            if layers.is_empty() {
+                return Err(KitManifestError::InvalidManifest(format!(
+                    "slot {:?} has no sample files",
+                    slot.click_type
+                )));
+            }
+            if let Some(layer) = layers.iter().find(|layer| {
+                layer.min_velocity < 0.0
+                    || layer.max_velocity > 1.0
+                    || layer.min_velocity > layer.max_velocity
+            }) {
+                return Err(KitManifestError::InvalidManifest(format!(
+                    "invalid velocity range for {}",
+                    layer.file
+                )));
+            }
+            slot_layers.push(layers);
+        }
+
+        let missing: Vec<PathBuf> = slot_layers
+            .iter()
+            .flatten()
+            .map(|layer| base_dir.join(&layer.file))
+            .filter(|file_path| !file_path.is_file())
+            .collect();
+        if !missing.is_empty() {
+            return Err(KitManifestError::MissingFiles(missing));
+        }
+
+        let display_name = manifest.display_name.as_deref().unwrap_or(&manifest.name);
+        let mut kit = Self::new(&manifest.name, display_name)
+            .with_description(&manifest.description)
+            .with_volume(manifest.volume);
+
+        for (slot, layers) in manifest.slots.iter().zip(slot_layers) {
+            let mut loaded = Vec::with_capacity(layers.len());
+            for layer in layers {
+                let file_path = base_dir.join(&layer.file);
+                let data =
+                    SampleData::from_file(&file_path, slot.base_frequency).map_err(|source| {
+                        KitManifestError::SampleError {
+                            path: file_path.clone(),
+                            source,
+                        }
+                    })?;
+                loaded.push(VelocityLayer {
+                    min_velocity: layer.min_velocity,
+                    max_velocity: layer.max_velocity,
+                    file_path,
+                    data,
+                });
+            }
+
+            let first_file = loaded[0].file_path.to_string_lossy().to_string();
+            let metadata =
+                SampleMetadata::new(slot.click_type.name(), &first_file, slot.base_frequency)
+                    .with_volume(slot.gain);
+            let mut sample = DrumSample::new(metadata, slot.click_type)
+                .with_volume(slot.gain)
+                .with_pan(slot.pan)
+                .with_pan_spread(slot.pan_spread, PanSpreadSource::Random);
+            sample.choke_group = slot.choke_group;
+            sample.layers = loaded;
+            kit.add_sample(slot.click_type, sample);
+        }
+
+        Ok(kit)
+    }
+
     /// Get all available preset drum kits
     pub fn available_kits() -> Vec<DrumKit> {
         vec![Self::acoustic_kit_01(), Self::synthetic_kit()]
@@ -281,9 +625,70 @@ impl DrumSample {
             click_type,
             velocity_curve: VelocityCurve::Linear,
             volume: 1.0,
+            pan: 0.0,
+            pan_spread: 0.0,
+            pan_spread_source: PanSpreadSource::Random,
+            choke_group: None,
+            layers: Vec::new(),
         }
     }
 
+    /// Set stereo position (-1.0 = left, 1.0 = right)
+    pub fn with_pan(mut self, pan: f32) -> Self {
+        self.pan = pan.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Vary the pan of each hit by up to `spread` either side of the base position
+    pub fn with_pan_spread(mut self, spread: f32, source: PanSpreadSource) -> Self {
+        self.pan_spread = spread.clamp(0.0, 1.0);
+        self.pan_spread_source = source;
+        self
+    }
+
+    /// Pan position for one hit at the given velocity
+    ///
+    /// Stays at `pan` when there is no spread. `Random` spread draws one value
+    /// from `rng` per hit; `Velocity` spread is fully determined by the velocity
+    /// and leaves `rng` untouched. The result is clamped to -1.0..=1.0.
+    pub fn hit_pan(&self, velocity: f32, rng: &mut SynthRng) -> f32 {
+        if self.pan_spread <= 0.0 {
+            return self.pan;
+        }
+        let offset = match self.pan_spread_source {
+            PanSpreadSource::Random => rng.next_bipolar(),
+            PanSpreadSource::Velocity => velocity.clamp(0.0, 1.0) * 2.0 - 1.0,
+        };
+        (self.pan + offset * self.pan_spread).clamp(-1.0, 1.0)
+    }
+
+    /// Put the sample in a choke group
+    pub fn with_choke_group(mut self, choke_group: u32) -> Self {
+        self.choke_group = Some(choke_group);
+        self
+    }
+
+    /// Velocity layer to play for a given velocity
+    ///
+    /// Falls back to the nearest layer when no range contains the velocity.
+    pub fn layer_for_velocity(&self, velocity: f32) -> Option<&VelocityLayer> {
+        let velocity = velocity.clamp(0.0, 1.0);
+        self.layers
+            .iter()
+            .filter(|layer| velocity >= layer.min_velocity && velocity <= layer.max_velocity)
+            .max_by(|a, b| a.min_velocity.total_cmp(&b.min_velocity))
+            .or_else(|| {
+                self.layers.iter().min_by(|a, b| {
+                    let distance = |layer: &VelocityLayer| {
+                        (layer.min_velocity - velocity)
+                            .abs()
+                            .min((layer.max_velocity - velocity).abs())
+                    };
+                    distance(a).total_cmp(&distance(b))
+                })
+            })
+    }
+
     /// Set velocity curve
     pub fn with_velocity_curve(mut self, curve: VelocityCurve) -> Self {
         self.velocity_curve = curve;
@@ -298,9 +703,16 @@ impl DrumSample {
 
     /// Apply velocity curve to input velocity
     pub fn apply_velocity(&self, input_velocity: f32) -> f32 {
-        let clamped = input_velocity.clamp(0.0, 1.0);
+        self.velocity_curve.apply(input_velocity)
+    }
+}
+
+impl VelocityCurve {
+    /// Map a velocity (0.0 - 1.0) through the curve to an amplitude
+    pub fn apply(&self, velocity: f32) -> f32 {
+        let clamped = velocity.clamp(0.0, 1.0);
 
-        match &self.velocity_curve {
+        match self {
             VelocityCurve::Linear => clamped,
             VelocityCurve::Exponential(power) => clamped.powf(*power),
             VelocityCurve::Logarithmic => {
@@ -310,6 +722,7 @@ impl DrumSample {
                     (clamped.ln() + 1.0).max(0.0)
                 }
             }
+            VelocityCurve::SCurve => clamped * clamped * (3.0 - 2.0 * clamped),
             VelocityCurve::Custom(points) => {
                 // Linear interpolation between control points
                 if points.is_empty() {
@@ -339,9 +752,7 @@ impl DrumSample {
             }
         }
     }
-}
 
-impl VelocityCurve {
     /// Create an exponential curve with the given power
     pub fn exponential(power: f32) -> Self {
         Self::Exponential(power)
@@ -377,6 +788,17 @@ mod tests {
 
         let supported = kit.supported_click_types();
         assert!(supported.contains(&ClickType::AcousticKick));
+
+        // Closing the hi-hat chokes the open hat
+        assert_eq!(
+            kit.choke_group(&ClickType::HiHatClosed),
+            Some(HIHAT_CHOKE_GROUP)
+        );
+        assert_eq!(
+            kit.choke_group(&ClickType::HiHatOpen),
+            Some(HIHAT_CHOKE_GROUP)
+        );
+        assert_eq!(kit.choke_group(&ClickType::AcousticKick), None);
     }
 
     #[test]
@@ -400,6 +822,27 @@ mod tests {
         assert_eq!(sample.apply_velocity(0.8), 0.8);
     }
 
+    #[test]
+    fn test_velocity_curve_s_curve() {
+        let curve = VelocityCurve::SCurve;
+
+        // Ends and middle are fixed, soft hits are softer and hard hits harder
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert_eq!(curve.apply(0.5), 0.5);
+        assert_eq!(curve.apply(1.0), 1.0);
+        assert!(curve.apply(0.25) < 0.25);
+        assert!(curve.apply(0.75) > 0.75);
+    }
+
+    #[test]
+    fn test_piece_gain_stacks_with_kit_volume() {
+        let kit = DrumKit::acoustic_kit_01().with_volume(0.5);
+
+        // Snare piece is stored at 0.9
+        assert!((kit.piece_gain(&ClickType::AcousticSnare) - 0.45).abs() < 1e-6);
+        assert_eq!(kit.piece_gain(&ClickType::Cowbell), 1.0);
+    }
+
     #[test]
     fn test_available_kits() {
         let kits = DrumKit::available_kits();
@@ -408,4 +851,117 @@ mod tests {
         let kit_names: Vec<String> = kits.iter().map(|k| k.name.clone()).collect();
         assert!(kit_names.contains(&"acoustic_kit_01".to_string()));
     }
+
+    fn write_test_wav(path: &Path, level: f32) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for _ in 0..441 {
+            writer.write_sample(level).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    const TWO_SLOT_MANIFEST: &str = r#"{
+        "name": "test_kit",
+        "display_name": "Test Kit",
+        "volume": 0.8,
+        "slots": [
+            {
+                "click_type": "AcousticKick",
+                "base_frequency": 55.0,
+                "gain": 0.9,
+                "layers": [
+                    { "file": "kick-soft.wav", "max_velocity": 0.6 },
+                    { "file": "kick-hard.wav", "min_velocity": 0.6 }
+                ]
+            },
+            { "click_type": "AcousticSnare", "file": "snare.wav", "gain": 0.7, "pan": -0.25 }
+        ]
+    }"#;
+
+    #[test]
+    fn test_kit_from_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        write_test_wav(&dir.path().join("kick-soft.wav"), 0.25);
+        write_test_wav(&dir.path().join("kick-hard.wav"), 0.75);
+        write_test_wav(&dir.path().join("snare.wav"), 0.5);
+        let manifest_path = dir.path().join("kit.json");
+        std::fs::write(&manifest_path, TWO_SLOT_MANIFEST).unwrap();
+
+        let kit = DrumKit::from_manifest(&manifest_path).unwrap();
+        assert_eq!(kit.name, "test_kit");
+        assert_eq!(kit.display_name, "Test Kit");
+        assert_eq!(kit.volume, 0.8);
+        assert_eq!(kit.samples.len(), 2);
+
+        let kick = kit.get_sample(&ClickType::AcousticKick).unwrap();
+        assert_eq!(kick.layers.len(), 2);
+        assert_eq!(kick.volume, 0.9);
+        assert_eq!(kick.pan, 0.0);
+        assert_eq!(kick.metadata.base_frequency, 55.0);
+        assert_eq!(kick.layers[0].data.base_frequency, 55.0);
+        assert!((kick.layer_for_velocity(0.3).unwrap().data.samples[0] - 0.25).abs() < 1e-6);
+        assert!((kick.layer_for_velocity(0.9).unwrap().data.samples[0] - 0.75).abs() < 1e-6);
+
+        let snare = kit.get_sample(&ClickType::AcousticSnare).unwrap();
+        assert_eq!(snare.layers.len(), 1);
+        assert_eq!(
+            (snare.layers[0].min_velocity, snare.layers[0].max_velocity),
+            (0.0, 1.0)
+        );
+        assert_eq!(snare.volume, 0.7);
+        assert_eq!(snare.pan, -0.25);
+        assert!((kit.piece_gain(&ClickType::AcousticSnare) - 0.56).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_kit_manifest_lists_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_test_wav(&dir.path().join("kick-soft.wav"), 0.25);
+        let manifest_path = dir.path().join("kit.json");
+        std::fs::write(&manifest_path, TWO_SLOT_MANIFEST).unwrap();
+
+        match DrumKit::from_manifest(&manifest_path) {
+            Err(KitManifestError::MissingFiles(missing)) => {
+                assert_eq!(missing.len(), 2);
+                assert!(missing[0].ends_with("kick-hard.wav"));
+                assert!(missing[1].ends_with("snare.wav"));
+            }
+            other => panic!("expected missing files, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_pattern_reports_missing_click_types() {
+        use crate::patterns::DrumPatternBeat;
+        use crate::timing::TimeSignature;
+
+        let pattern = DrumPattern::new("ride_groove", TimeSignature::new(4, 4))
+            .with_beat(
+                DrumPatternBeat::new(1.0)
+                    .with_samples(vec![ClickType::AcousticKick, ClickType::Ride]),
+            )
+            .with_beat(
+                DrumPatternBeat::new(2.0)
+                    .with_samples(vec![ClickType::AcousticSnare, ClickType::Ride]),
+            )
+            .with_beat(DrumPatternBeat::new(3.0).with_sample(ClickType::Cowbell));
+
+        let kit = DrumKit::acoustic_kit_01();
+        assert!(kit.get_sample(&ClickType::Ride).is_none());
+        assert_eq!(
+            kit.validate_pattern(&pattern),
+            vec![ClickType::Ride, ClickType::Cowbell]
+        );
+
+        let covered = DrumPattern::new("backbeat", TimeSignature::new(4, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick))
+            .with_beat(DrumPatternBeat::new(2.0).with_sample(ClickType::AcousticSnare));
+        assert!(kit.validate_pattern(&covered).is_empty());
+    }
 }
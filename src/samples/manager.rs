@@ -1,6 +1,6 @@
 use super::catalog::SampleMetadata;
 use super::library::{SampleError, SampleLibrary};
-use crate::{AdsrEnvelope, Waveform};
+use crate::{AdsrEnvelope, EnvelopeCurve, Waveform};
 /// Real-time sample playback and triggering management
 ///
 /// This module provides zero-allocation sample triggering for real-time
@@ -75,9 +75,11 @@ impl SampleManager {
             trigger_cache: std::collections::HashMap::new(),
             default_envelope: AdsrEnvelope {
                 attack_secs: 0.002, // Very fast attack for percussive samples
+                hold_secs: 0.0,
                 decay_secs: 0.1,    // Quick decay
                 sustain_level: 0.3, // Low sustain for drums
                 release_secs: 0.2,  // Natural release
+                curve: EnvelopeCurve::LINEAR,
             },
         }
     }
@@ -89,9 +91,11 @@ impl SampleManager {
             trigger_cache: std::collections::HashMap::new(),
             default_envelope: AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.3,
                 release_secs: 0.2,
+                curve: EnvelopeCurve::LINEAR,
             },
         }
     }
@@ -263,9 +267,11 @@ mod tests {
             frequency: 440.0,
             envelope: AdsrEnvelope {
                 attack_secs: 0.002,
+                hold_secs: 0.0,
                 decay_secs: 0.1,
                 sustain_level: 0.3,
                 release_secs: 0.2,
+                curve: EnvelopeCurve::LINEAR,
             },
             volume: 1.0,
         };
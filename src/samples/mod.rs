@@ -45,6 +45,9 @@ pub mod manager;
 
 // Re-export core types for convenient access
 pub use catalog::{SampleCatalog, SampleMetadata};
-pub use drumkit::{DrumKit, DrumSample};
+pub use drumkit::{
+    DrumKit, DrumSample, KitManifest, KitManifestError, PanSpreadSource, VelocityCurve,
+    VelocityLayer, HIHAT_CHOKE_GROUP,
+};
 pub use library::SampleLibrary;
 pub use manager::SampleManager;
@@ -0,0 +1,252 @@
+/// Visual/audio offset calibration from tapped responses
+///
+/// This module measures the latency between when a click is scheduled and
+/// when the user perceives it on a given system. The app plays a run of
+/// clicks, records when the user taps along, and stores the resulting offset
+/// in the metronome config so the visual flash can be shifted to line up with
+/// the audible click.
+use std::time::Instant;
+
+/// Default number of clicks played in a calibration run
+pub const DEFAULT_CALIBRATION_CLICKS: usize = 16;
+
+/// Fewest paired taps needed for a usable offset
+pub const MIN_CALIBRATION_TAPS: usize = 4;
+
+/// Taps further than this from every click are not paired (milliseconds)
+pub(super) const MAX_PAIRING_MS: f32 = 250.0;
+
+/// Offsets further than this many median absolute deviations from the median are outliers
+const OUTLIER_MAD_SCALE: f32 = 3.0;
+
+/// Outlier threshold floor, so very steady tappers keep normal jitter (milliseconds)
+const MIN_OUTLIER_SPREAD_MS: f32 = 10.0;
+
+/// Records clicks and the user's taps during a calibration run
+///
+/// The offset is positive when taps land after the clicks, i.e. the click is
+/// heard later than it is scheduled, and the visual flash should be delayed
+/// by the same amount.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::timing::Calibration;
+/// use std::time::{Duration, Instant};
+///
+/// let mut calibration = Calibration::new(8);
+/// let start = Instant::now();
+/// for beat in 0..8 {
+///     let click = start + Duration::from_millis(500 * beat);
+///     calibration.record_click(click);
+///     calibration.record_tap(click + Duration::from_millis(40));
+/// }
+///
+/// assert!(calibration.is_complete());
+/// let offset_ms = calibration.offset_ms().unwrap();
+/// assert!((offset_ms - 40.0).abs() < 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    /// Number of clicks in the run
+    click_count: usize,
+
+    /// Times the clicks were triggered
+    click_times: Vec<Instant>,
+
+    /// Times the user tapped
+    tap_times: Vec<Instant>,
+}
+
+impl Calibration {
+    /// Create a calibration run of `click_count` clicks
+    pub fn new(click_count: usize) -> Self {
+        Self {
+            click_count: click_count.max(MIN_CALIBRATION_TAPS),
+            click_times: Vec::new(),
+            tap_times: Vec::new(),
+        }
+    }
+
+    /// Record that a click was triggered
+    pub fn record_click(&mut self, at: Instant) {
+        self.click_times.push(at);
+    }
+
+    /// Record a user tap
+    pub fn record_tap(&mut self, at: Instant) {
+        self.tap_times.push(at);
+    }
+
+    /// Number of clicks in the run
+    pub fn click_count(&self) -> usize {
+        self.click_count
+    }
+
+    /// Whether every click of the run has been played
+    pub fn is_complete(&self) -> bool {
+        self.click_times.len() >= self.click_count
+    }
+
+    /// Discard recorded clicks and taps to start the run over
+    pub fn reset(&mut self) {
+        self.click_times.clear();
+        self.tap_times.clear();
+    }
+
+    /// Perceived offset of the recorded run in milliseconds
+    pub fn offset_ms(&self) -> Option<f32> {
+        Self::from_taps(&self.click_times, &self.tap_times)
+    }
+
+    /// Perceived offset in milliseconds between clicks and the taps responding to them
+    ///
+    /// Each click is paired with its nearest tap within 250 ms (or half the
+    /// shortest click interval, if smaller); unpaired clicks and stray taps are
+    /// ignored. Offsets more than three median absolute deviations from the
+    /// median are rejected as outliers and the rest are averaged. Returns None
+    /// when fewer than `MIN_CALIBRATION_TAPS` taps could be paired.
+    pub fn from_taps(click_times: &[Instant], tap_times: &[Instant]) -> Option<f32> {
+        let offsets: Vec<f32> = pair_taps(click_times, tap_times, 0.0)
+            .into_iter()
+            .flatten()
+            .collect();
+        if offsets.len() < MIN_CALIBRATION_TAPS {
+            return None;
+        }
+
+        let center = median(offsets.clone());
+        let spread = median(
+            offsets
+                .iter()
+                .map(|offset| (offset - center).abs())
+                .collect(),
+        );
+        let threshold = (spread * OUTLIER_MAD_SCALE).max(MIN_OUTLIER_SPREAD_MS);
+        let kept: Vec<f32> = offsets
+            .into_iter()
+            .filter(|offset| (offset - center).abs() <= threshold)
+            .collect();
+        Some(kept.iter().sum::<f32>() / kept.len() as f32)
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::new(DEFAULT_CALIBRATION_CLICKS)
+    }
+}
+
+/// Offset of the tap nearest each click, less `latency_ms` (milliseconds)
+///
+/// Only taps within `MAX_PAIRING_MS` of the click (or half the shortest click
+/// interval, if smaller) after the latency is taken off are considered;
+/// clicks without one give None.
+pub(super) fn pair_taps(
+    click_times: &[Instant],
+    tap_times: &[Instant],
+    latency_ms: f32,
+) -> Vec<Option<f32>> {
+    let shortest_interval = click_times
+        .windows(2)
+        .map(|pair| signed_ms(pair[1], pair[0]).abs())
+        .fold(f32::INFINITY, f32::min);
+    let window = MAX_PAIRING_MS.min(shortest_interval / 2.0);
+
+    click_times
+        .iter()
+        .map(|&click| {
+            tap_times
+                .iter()
+                .map(|&tap| signed_ms(tap, click) - latency_ms)
+                .filter(|offset| offset.abs() <= window)
+                .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+        })
+        .collect()
+}
+
+/// Milliseconds from `reference` to `time`, negative when `time` is earlier
+pub(super) fn signed_ms(time: Instant, reference: Instant) -> f32 {
+    if time >= reference {
+        time.duration_since(reference).as_secs_f32() * 1000.0
+    } else {
+        -(reference.duration_since(time).as_secs_f32() * 1000.0)
+    }
+}
+
+/// Median of a non-empty list of values
+fn median(mut values: Vec<f32>) -> f32 {
+    values.sort_by(f32::total_cmp);
+    // The two middle indices coincide for odd lengths
+    let len = values.len();
+    (values[(len - 1) / 2] + values[len / 2]) / 2.0
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Clicks every 500 ms, starting a second after `start`
+    pub(in crate::timing) fn clicks(start: Instant, count: u64) -> Vec<Instant> {
+        (0..count)
+            .map(|beat| start + Duration::from_millis(1000 + 500 * beat))
+            .collect()
+    }
+
+    /// Shift a time by a signed number of milliseconds
+    pub(in crate::timing) fn shifted(time: Instant, offset_ms: i64) -> Instant {
+        if offset_ms >= 0 {
+            time + Duration::from_millis(offset_ms as u64)
+        } else {
+            time - Duration::from_millis(offset_ms.unsigned_abs())
+        }
+    }
+
+    #[test]
+    fn test_constant_offset_recovered_despite_outliers() {
+        let start = Instant::now();
+        let click_times = clicks(start, 12);
+
+        // Taps 35 ms late with a little symmetric jitter
+        let jitter = [-3, 2, 0, 3, -2, 1, -1, 0, 2, -2];
+        let mut tap_times: Vec<Instant> = click_times[..10]
+            .iter()
+            .zip(jitter)
+            .map(|(&click, jitter)| shifted(click, 35 + jitter))
+            .collect();
+        // Two distracted taps, and one stray tap between clicks
+        tap_times.push(shifted(click_times[10], 180));
+        tap_times.push(shifted(click_times[11], -110));
+        tap_times.push(shifted(click_times[5], 250));
+
+        let offset = Calibration::from_taps(&click_times, &tap_times).unwrap();
+        assert!((offset - 35.0).abs() < 0.5, "offset {}", offset);
+
+        // Early taps give a negative offset
+        let early: Vec<Instant> = click_times.iter().map(|&c| shifted(c, -20)).collect();
+        let offset = Calibration::from_taps(&click_times, &early).unwrap();
+        assert!((offset + 20.0).abs() < 0.5, "offset {}", offset);
+    }
+
+    #[test]
+    fn test_too_few_paired_taps_gives_no_offset() {
+        let start = Instant::now();
+        let click_times = clicks(start, 8);
+        let taps: Vec<Instant> = click_times[..3].iter().map(|&c| shifted(c, 30)).collect();
+        assert_eq!(Calibration::from_taps(&click_times, &taps), None);
+        assert_eq!(Calibration::from_taps(&[], &taps), None);
+
+        let mut calibration = Calibration::new(4);
+        for &click in &click_times[..4] {
+            calibration.record_click(click);
+            calibration.record_tap(shifted(click, 30));
+        }
+        assert!(calibration.is_complete());
+        assert!((calibration.offset_ms().unwrap() - 30.0).abs() < 0.5);
+
+        calibration.reset();
+        assert!(!calibration.is_complete());
+        assert_eq!(calibration.offset_ms(), None);
+    }
+}
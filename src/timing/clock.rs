@@ -197,16 +197,19 @@ impl DiscreteScheduler {
     /// Returns true if it's time for the next beat. When true is returned,
     /// the caller should create a BeatEvent and call advance_beat().
     pub fn should_trigger(&mut self, tempo_bpm: f32) -> bool {
+        self.should_trigger_at(tempo_bpm, Instant::now())
+    }
+
+    /// Check if a beat should be triggered as of `now`
+    pub fn should_trigger_at(&mut self, tempo_bpm: f32, now: Instant) -> bool {
         if !self.is_running || !matches!(self.state, SchedulerState::Running) {
             return false;
         }
 
-        let now = Instant::now();
-
         match self.next_beat_time {
             None => {
                 // First beat - trigger immediately and schedule next
-                self.schedule_next_beat(tempo_bpm);
+                self.schedule_next_beat(tempo_bpm, now);
                 true
             }
             Some(scheduled_time) => {
@@ -222,6 +225,11 @@ impl DiscreteScheduler {
     /// BeatEvent has been created. It advances the beat counter and schedules
     /// the next beat timing.
     pub fn advance_beat(&mut self, tempo_bpm: f32) {
+        self.advance_beat_at(tempo_bpm, Instant::now());
+    }
+
+    /// Advance to the next beat, scheduling it from `now`
+    pub fn advance_beat_at(&mut self, tempo_bpm: f32, now: Instant) {
         // Advance beat counter with wrapping
         self.current_beat += 1;
         if self.current_beat > self.time_signature.beats_per_measure {
@@ -229,7 +237,7 @@ impl DiscreteScheduler {
         }
 
         // Schedule next beat with timing base reset (prevents drift)
-        self.schedule_next_beat(tempo_bpm);
+        self.schedule_next_beat(tempo_bpm, now);
     }
 
     /// Get current beat number
@@ -261,12 +269,12 @@ impl DiscreteScheduler {
     /// This is the core of the discrete scheduling algorithm. Instead of
     /// accumulating timing offsets, it calculates the absolute time when
     /// the next beat should occur and resets the timing base.
-    fn schedule_next_beat(&mut self, tempo_bpm: f32) {
+    fn schedule_next_beat(&mut self, tempo_bpm: f32, now: Instant) {
         let beat_interval_ms = self.time_signature.beat_duration_ms(tempo_bpm);
         let next_beat_delay = Duration::from_millis(beat_interval_ms as u64);
 
         // Reset timing base - this is crucial for preventing drift
-        self.next_beat_time = Some(Instant::now() + next_beat_delay);
+        self.next_beat_time = Some(now + next_beat_delay);
     }
 }
 
@@ -3,7 +3,19 @@
 /// This module provides a straightforward metronome that plays regular beats
 /// at a specified tempo and time signature.
 use super::clock::{BeatClock, DiscreteScheduler};
-use super::types::{BeatEvent, ClickType, TimeSignature, TriggerResult};
+use super::types::{BeatEvent, ClickType, Subdivision, TimeSignature, TriggerResult};
+use crate::audio::synthesis::{get_calibrated_sound_params, AudioSampleAdapter};
+use crate::{render_stems, GroupId, SoundEvent, Waveform, MAX_SAMPLE_RATE};
+use std::time::Instant;
+
+/// Default number of tap intervals averaged for tap tempo
+pub const DEFAULT_TAP_WINDOW: usize = 4;
+
+/// Default deviation from the median tap interval beyond which a tap interval is rejected (percent)
+pub const DEFAULT_TAP_OUTLIER_PERCENT: f32 = 25.0;
+
+/// Taps further apart than this start a new tap-tempo run (seconds)
+const TAP_RESET_SECONDS: f32 = 2.0;
 
 /// Simple metronome for regular beat timing
 ///
@@ -22,6 +34,117 @@ pub struct Metronome {
 
     /// Accent click sound type (for downbeats)
     accent_click_type: ClickType,
+
+    /// Beat subdivision stamped on each beat for counting and visual feedback
+    subdivision: Subdivision,
+
+    /// Optional gap-click drill muting beats at the current stage
+    gap_schedule: Option<GapClickSchedule>,
+
+    /// Transport run counter, incremented on every start
+    run_id: u32,
+
+    /// Sequence number for the next emitted beat
+    next_sequence: u64,
+
+    /// Recent tap-tempo taps, oldest first
+    tap_times: Vec<Instant>,
+
+    /// Number of tap intervals averaged
+    tap_window: usize,
+
+    /// Tap intervals deviating from the median by more than this are ignored (percent)
+    tap_outlier_percent: f32,
+
+    /// Tempo inferred from the current tap run
+    tapped_tempo: Option<f32>,
+}
+
+/// One stage of a gap-click drill
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GapStage {
+    /// 1-based beat numbers whose clicks are muted
+    pub silenced_beats: Vec<u8>,
+}
+
+impl GapStage {
+    /// Create a stage muting the given beats
+    pub fn new(silenced_beats: Vec<u8>) -> Self {
+        Self { silenced_beats }
+    }
+
+    /// Check if a beat is muted in this stage
+    pub fn is_beat_silenced(&self, beat_number: u8) -> bool {
+        self.silenced_beats.contains(&beat_number)
+    }
+}
+
+/// Gap-click trainer schedule that removes more of the click at each stage
+///
+/// Muted beats are still reported as (silenced) beat events so the
+/// visualizer keeps counting while the player fills in the gaps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapClickSchedule {
+    /// Stages in the order they are practised
+    stages: Vec<GapStage>,
+
+    /// Index of the active stage
+    current_stage: usize,
+}
+
+impl GapClickSchedule {
+    /// Create a schedule from explicit stages (an empty list means no muting)
+    pub fn new(stages: Vec<GapStage>) -> Self {
+        let stages = if stages.is_empty() {
+            vec![GapStage::default()]
+        } else {
+            stages
+        };
+        Self {
+            stages,
+            current_stage: 0,
+        }
+    }
+
+    /// Standard drill: full click, drop the last beat, then keep only the downbeat
+    pub fn progressive(beats_per_measure: u8) -> Self {
+        let last_beat = beats_per_measure.max(1);
+        Self::new(vec![
+            GapStage::default(),
+            GapStage::new(vec![last_beat]),
+            GapStage::new((2..=last_beat).collect()),
+        ])
+    }
+
+    /// Get the active stage
+    pub fn current(&self) -> &GapStage {
+        &self.stages[self.current_stage]
+    }
+
+    /// Get the index of the active stage
+    pub fn current_stage(&self) -> usize {
+        self.current_stage
+    }
+
+    /// Get the number of stages
+    pub fn stage_count(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Move to the next stage; returns `false` if already at the last stage
+    pub fn advance(&mut self) -> bool {
+        if self.current_stage + 1 < self.stages.len() {
+            self.current_stage += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Return to the first stage
+    pub fn reset(&mut self) {
+        self.current_stage = 0;
+    }
 }
 
 impl Metronome {
@@ -32,6 +155,14 @@ impl Metronome {
             accent_first_beat: true,
             click_type: ClickType::WoodBlock,
             accent_click_type: ClickType::Cowbell,
+            subdivision: Subdivision::Quarter,
+            gap_schedule: None,
+            run_id: 0,
+            next_sequence: 0,
+            tap_times: Vec::new(),
+            tap_window: DEFAULT_TAP_WINDOW,
+            tap_outlier_percent: DEFAULT_TAP_OUTLIER_PERCENT,
+            tapped_tempo: None,
         }
     }
 
@@ -54,27 +185,210 @@ impl Metronome {
     pub fn set_accent_click_type(&mut self, accent_click_type: ClickType) {
         self.accent_click_type = accent_click_type;
     }
-}
 
-impl BeatClock for Metronome {
-    fn start(&mut self) {
-        self.scheduler.start();
+    /// Set the beat subdivision
+    pub fn set_subdivision(&mut self, subdivision: Subdivision) {
+        self.subdivision = subdivision;
     }
 
-    fn stop(&mut self) {
-        self.scheduler.stop();
+    /// Get the current beat subdivision
+    pub fn subdivision(&self) -> Subdivision {
+        self.subdivision
     }
 
-    fn pause(&mut self) {
-        self.scheduler.pause();
+    /// Set or clear the gap-click drill schedule
+    pub fn set_gap_schedule(&mut self, schedule: Option<GapClickSchedule>) {
+        self.gap_schedule = schedule;
     }
 
-    fn resume(&mut self) {
-        self.scheduler.resume();
+    /// Get the gap-click drill schedule
+    pub fn gap_schedule(&self) -> Option<&GapClickSchedule> {
+        self.gap_schedule.as_ref()
     }
 
-    fn check_triggers(&mut self, tempo_bpm: f32) -> TriggerResult {
-        if self.scheduler.should_trigger(tempo_bpm) {
+    /// Advance the gap-click drill to its next stage
+    ///
+    /// Returns `false` if no schedule is set or it is already at the last stage.
+    pub fn advance_gap_stage(&mut self) -> bool {
+        self.gap_schedule
+            .as_mut()
+            .is_some_and(|schedule| schedule.advance())
+    }
+
+    /// Check if the click for a beat is muted by the gap-click drill
+    pub fn is_beat_silenced(&self, beat_number: u8) -> bool {
+        self.gap_schedule
+            .as_ref()
+            .is_some_and(|schedule| schedule.current().is_beat_silenced(beat_number))
+    }
+
+    /// Render the click track offline for the given number of measures
+    ///
+    /// Uses the same click and accent sounds as live playback, placed on
+    /// exact sample positions via `render_stems` and scaled by the same
+    /// calibration gains. The buffer covers the full measures; a click tail is
+    /// cut off at the end of the last one. Beats muted by the current
+    /// gap-click stage are left silent.
+    pub fn render(
+        &self,
+        tempo_bpm: f32,
+        measures: u32,
+        sample_rate: u32,
+        sample_adapter: &AudioSampleAdapter,
+    ) -> Vec<f32> {
+        let rate_valid = sample_rate > 0 && sample_rate as f32 <= MAX_SAMPLE_RATE;
+        if tempo_bpm <= 0.0 || measures == 0 || !rate_valid {
+            return Vec::new();
+        }
+
+        let beats_per_measure = self.scheduler.time_signature().beats_per_measure as u32;
+        let beat_secs = 60.0 / tempo_bpm;
+        let total_beats = measures * beats_per_measure;
+
+        // Stem 0 holds the regular clicks and stem 1 the accents
+        let clicks = [
+            Self::click_event(self.click_type, sample_adapter),
+            Self::click_event(self.accent_click_type, sample_adapter),
+        ];
+
+        let events: Vec<(f32, GroupId, SoundEvent)> = (0..total_beats)
+            .filter(|beat_index| {
+                let beat_number = (beat_index % beats_per_measure) as u8 + 1;
+                !self.is_beat_silenced(beat_number)
+            })
+            .map(|beat_index| {
+                let is_downbeat = beat_index % beats_per_measure == 0;
+                let stem = usize::from(self.accent_first_beat && is_downbeat);
+                let (event, _) = &clicks[stem];
+                let onset = beat_index as f32 * beat_secs;
+                (onset, stem as GroupId, event.clone())
+            })
+            .collect();
+
+        let total_secs = total_beats as f32 * beat_secs;
+        let mut buffer = vec![0.0; (total_secs * sample_rate as f32) as usize];
+        for (stem, samples) in render_stems(&events, total_secs, sample_rate) {
+            let (_, gain) = clicks[stem as usize];
+            for (mixed, sample) in buffer.iter_mut().zip(samples) {
+                *mixed += sample * gain;
+            }
+        }
+        for sample in buffer.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+        buffer
+    }
+
+    /// Build a one-shot sound event for a click type, with its calibration gain
+    fn click_event(
+        click_type: ClickType,
+        sample_adapter: &AudioSampleAdapter,
+    ) -> (SoundEvent, f32) {
+        let (waveform, frequency, envelope, gain) =
+            get_calibrated_sound_params(click_type, sample_adapter);
+
+        let duration_secs = match &waveform {
+            Waveform::Sample(sample) | Waveform::DrumSample(sample) => {
+                sample.samples.len() as f32 / sample.sample_rate as f32
+            }
+            _ => {
+                envelope.attack_secs
+                    + envelope.hold_secs
+                    + envelope.decay_secs
+                    + envelope.release_secs
+            }
+        };
+
+        let event = SoundEvent {
+            waveform,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration_secs,
+            envelope,
+        };
+        (event, gain)
+    }
+
+    /// Register a tap-tempo tap now and return the inferred tempo in BPM
+    ///
+    /// The tempo is the mean of the last few tap intervals, ignoring intervals
+    /// too far from their median. A pause of more than two seconds starts a
+    /// new run. Returns None until a run has at least two taps. Pass the
+    /// result to `check_triggers` to play at the tapped tempo.
+    pub fn tap(&mut self) -> Option<f32> {
+        self.tap_at(Instant::now())
+    }
+
+    /// Register a tap-tempo tap made at `at`
+    pub fn tap_at(&mut self, at: Instant) -> Option<f32> {
+        let continues_run = self.tap_times.last().is_some_and(|&last| {
+            at >= last && at.duration_since(last).as_secs_f32() <= TAP_RESET_SECONDS
+        });
+        if !continues_run {
+            self.reset_taps();
+        }
+
+        self.tap_times.push(at);
+        if self.tap_times.len() > self.tap_window + 1 {
+            self.tap_times.remove(0);
+        }
+
+        let mut intervals: Vec<f32> = self
+            .tap_times
+            .windows(2)
+            .map(|pair| pair[1].duration_since(pair[0]).as_secs_f32())
+            .collect();
+        if intervals.is_empty() {
+            return None;
+        }
+
+        let mut sorted = intervals.clone();
+        sorted.sort_by(f32::total_cmp);
+        let len = sorted.len();
+        let median = (sorted[(len - 1) / 2] + sorted[len / 2]) / 2.0;
+        let tolerance = median * self.tap_outlier_percent / 100.0;
+        intervals.retain(|interval| (interval - median).abs() <= tolerance);
+        if intervals.is_empty() {
+            intervals.push(median);
+        }
+
+        let mean = intervals.iter().sum::<f32>() / intervals.len() as f32;
+        if mean > 0.0 {
+            self.tapped_tempo = Some(60.0 / mean);
+        }
+        self.tapped_tempo
+    }
+
+    /// Tempo inferred from the current tap run, if any
+    pub fn tapped_tempo(&self) -> Option<f32> {
+        self.tapped_tempo
+    }
+
+    /// Discard recorded taps and the inferred tempo
+    pub fn reset_taps(&mut self) {
+        self.tap_times.clear();
+        self.tapped_tempo = None;
+    }
+
+    /// Set the number of tap intervals averaged (at least 1)
+    pub fn set_tap_window(&mut self, intervals: usize) {
+        self.tap_window = intervals.max(1);
+        let keep = self.tap_window + 1;
+        if self.tap_times.len() > keep {
+            self.tap_times.drain(..self.tap_times.len() - keep);
+        }
+    }
+
+    /// Set how far a tap interval may stray from the median before it is ignored (percent)
+    pub fn set_tap_outlier_percent(&mut self, percent: f32) {
+        self.tap_outlier_percent = percent.max(0.0);
+    }
+
+    /// Check for beats due as of `now`
+    ///
+    /// Same as `check_triggers`, with the current time supplied by the caller.
+    pub fn check_triggers_at(&mut self, tempo_bpm: f32, now: Instant) -> TriggerResult {
+        if self.scheduler.should_trigger_at(tempo_bpm, now) {
             let current_beat = self.scheduler.current_beat();
             let is_downbeat = current_beat == 1;
             let should_accent = self.accent_first_beat && is_downbeat;
@@ -93,16 +407,43 @@ impl BeatClock for Metronome {
                 vec![click_sound],
                 tempo_bpm,
                 self.scheduler.time_signature(),
-            );
+            )
+            .with_silenced(self.is_beat_silenced(current_beat))
+            .with_subdivision(self.subdivision)
+            .with_sequence(self.run_id, self.next_sequence);
+            self.next_sequence += 1;
 
             // Advance to next beat
-            self.scheduler.advance_beat(tempo_bpm);
+            self.scheduler.advance_beat_at(tempo_bpm, now);
 
             vec![event]
         } else {
             vec![]
         }
     }
+}
+
+impl BeatClock for Metronome {
+    fn start(&mut self) {
+        self.run_id = self.run_id.wrapping_add(1);
+        self.scheduler.start();
+    }
+
+    fn stop(&mut self) {
+        self.scheduler.stop();
+    }
+
+    fn pause(&mut self) {
+        self.scheduler.pause();
+    }
+
+    fn resume(&mut self) {
+        self.scheduler.resume();
+    }
+
+    fn check_triggers(&mut self, tempo_bpm: f32) -> TriggerResult {
+        self.check_triggers_at(tempo_bpm, Instant::now())
+    }
 
     fn is_running(&self) -> bool {
         self.scheduler.is_running()
@@ -127,6 +468,63 @@ mod tests {
     use std::thread;
     use std::time::Duration;
 
+    /// Shift a time forward by a number of milliseconds
+    fn after(time: Instant, ms: u64) -> Instant {
+        time + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_tap_tempo_converges_and_rejects_outliers() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        let start = Instant::now();
+
+        assert_eq!(metronome.tap_at(start), None);
+        let mut at = start;
+        for interval in [520, 490, 505, 495, 500] {
+            at = after(at, interval);
+            metronome.tap_at(at);
+        }
+        // Last four intervals average to 497.5 ms
+        let bpm = metronome.tapped_tempo().unwrap();
+        assert!((bpm - 60.0 / 0.4975).abs() < 0.1, "bpm {}", bpm);
+
+        // A hesitant tap is ignored rather than dragging the tempo down
+        at = after(at, 800);
+        let bpm = metronome.tap_at(at).unwrap();
+        assert!((bpm - 120.0).abs() < 1.0, "bpm {}", bpm);
+
+        // Steady taps at the new tempo take over the window
+        for _ in 0..4 {
+            at = after(at, 400);
+            metronome.tap_at(at);
+        }
+        let bpm = metronome.tapped_tempo().unwrap();
+        assert!((bpm - 150.0).abs() < 0.1, "bpm {}", bpm);
+
+        metronome.start();
+        let events = metronome.check_triggers(bpm);
+        assert_eq!(events[0].tempo_bpm, bpm);
+    }
+
+    #[test]
+    fn test_tap_tempo_resets_after_long_pause() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        let start = Instant::now();
+        metronome.tap_at(start);
+        metronome.tap_at(after(start, 1000));
+        assert!((metronome.tapped_tempo().unwrap() - 60.0).abs() < 0.1);
+
+        // A pause of more than two seconds starts over
+        let restart = after(start, 3500);
+        assert_eq!(metronome.tap_at(restart), None);
+        assert_eq!(metronome.tapped_tempo(), None);
+        let bpm = metronome.tap_at(after(restart, 250)).unwrap();
+        assert!((bpm - 240.0).abs() < 0.1, "bpm {}", bpm);
+
+        metronome.reset_taps();
+        assert_eq!(metronome.tapped_tempo(), None);
+    }
+
     #[test]
     fn test_metronome_creation() {
         let metronome = Metronome::new(TimeSignature::new(4, 4));
@@ -167,6 +565,31 @@ mod tests {
         assert!(event.is_downbeat());
     }
 
+    #[test]
+    fn test_beat_sequence_increases_across_restarts() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        let start = Instant::now();
+        metronome.start();
+        let first_run = metronome.check_triggers_at(120.0, start).remove(0);
+
+        metronome.stop();
+        metronome.start();
+        let second_run = metronome.check_triggers_at(120.0, start).remove(0);
+
+        // Both runs begin on beat 1, but the second is ordered after the first
+        assert_eq!(first_run.beat_number, 1);
+        assert_eq!(second_run.beat_number, 1);
+        assert!(second_run.sequence > first_run.sequence);
+        assert!(second_run.run_id > first_run.run_id);
+
+        // Pausing continues the same run
+        metronome.pause();
+        metronome.resume();
+        let resumed = metronome.check_triggers_at(120.0, after(start, 510)).remove(0);
+        assert_eq!(resumed.run_id, second_run.run_id);
+        assert!(resumed.sequence > second_run.sequence);
+    }
+
     #[test]
     fn test_metronome_beat_progression() {
         let mut metronome = Metronome::new(TimeSignature::new(3, 4));
@@ -248,6 +671,20 @@ mod tests {
         assert_eq!(metronome.current_beat(), 2);
     }
 
+    #[test]
+    fn test_metronome_subdivision_setting() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        assert_eq!(metronome.subdivision(), Subdivision::Quarter);
+
+        metronome.set_subdivision(Subdivision::Eighth);
+        assert_eq!(metronome.subdivision(), Subdivision::Eighth);
+
+        // Beats carry the subdivision so the visualizer can follow it
+        metronome.start();
+        let events = metronome.check_triggers_at(120.0, Instant::now());
+        assert_eq!(events[0].subdivision, Some(Subdivision::Eighth));
+    }
+
     #[test]
     fn test_metronome_time_signature_change() {
         let mut metronome = Metronome::new(TimeSignature::new(4, 4));
@@ -256,4 +693,139 @@ mod tests {
         assert_eq!(metronome.time_signature().beats_per_measure, 3);
         assert_eq!(metronome.time_signature().note_value, 8);
     }
+
+    #[test]
+    fn test_metronome_render_click_positions() {
+        let metronome = Metronome::new(TimeSignature::new(4, 4));
+        let adapter = AudioSampleAdapter::new();
+        let buffer = metronome.render(120.0, 4, 44100, &adapter);
+
+        // 16 beats of 0.5s each
+        let beat_samples = 22050;
+        assert_eq!(buffer.len(), 16 * beat_samples);
+
+        for beat in 0..16 {
+            let onset = beat * beat_samples;
+            let click = &buffer[onset..onset + 2000];
+            assert!(
+                click.iter().any(|s| s.abs() > 0.01),
+                "missing click at beat {}",
+                beat
+            );
+
+            // Clicks are shorter than a beat, so the tail before each onset is silent
+            if beat > 0 {
+                let tail = &buffer[onset - 1000..onset];
+                assert!(tail.iter().all(|s| *s == 0.0), "click before beat {}", beat);
+            }
+        }
+    }
+
+    #[test]
+    fn test_metronome_render_accents_downbeats() {
+        let metronome = Metronome::new(TimeSignature::new(4, 4));
+        let adapter = AudioSampleAdapter::new();
+        let buffer = metronome.render(120.0, 4, 44100, &adapter);
+        let beat_samples = 22050;
+        let segment = |beat: usize| &buffer[beat * beat_samples..(beat + 1) * beat_samples];
+
+        // Downbeats use the accent click (Cowbell) and differ from regular beats
+        assert_eq!(segment(0), segment(4));
+        assert_eq!(segment(0), segment(12));
+        assert_ne!(segment(0), segment(1));
+
+        // Without accents the downbeat renders the regular click
+        let mut plain = Metronome::new(TimeSignature::new(4, 4));
+        plain.set_accent_first_beat(false);
+        let plain_buffer = plain.render(120.0, 1, 44100, &adapter);
+        let regular_click = &plain_buffer[..2000];
+        assert!(regular_click.iter().any(|s| s.abs() > 0.01));
+        assert_ne!(regular_click, &segment(0)[..2000]);
+    }
+
+    #[test]
+    fn test_metronome_render_applies_calibration_gain() {
+        let mut adapter = AudioSampleAdapter::new();
+        adapter.set_click_type_gain(ClickType::WoodBlock, 0.5);
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        metronome.set_accent_first_beat(false);
+        let buffer = metronome.render(120.0, 1, 44100, &adapter);
+
+        // The rendered click peaks where the live gain puts it
+        let (event, gain) = Metronome::click_event(ClickType::WoodBlock, &adapter);
+        assert_eq!(gain, 0.5);
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |p, s| p.max(s.abs()));
+        let live_peak = peak(&crate::render_event(&event, 44100)) * gain;
+        assert!(live_peak > 0.01);
+        assert!((peak(&buffer[..22050]) - live_peak).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_metronome_render_invalid_input() {
+        let metronome = Metronome::new(TimeSignature::new(4, 4));
+        let adapter = AudioSampleAdapter::new();
+        assert!(metronome.render(0.0, 4, 44100, &adapter).is_empty());
+        assert!(metronome.render(120.0, 0, 44100, &adapter).is_empty());
+    }
+
+    /// Beats (1-based) that produced audio over one measure of live triggers
+    fn audible_beats(metronome: &mut Metronome) -> Vec<u8> {
+        metronome.start();
+        let start = Instant::now();
+        let audible = (0..4)
+            .flat_map(|beat| metronome.check_triggers_at(120.0, after(start, beat * 500)))
+            .filter(|event| event.is_audible())
+            .map(|event| event.beat_number)
+            .collect();
+        metronome.stop();
+        audible
+    }
+
+    #[test]
+    fn test_gap_click_schedule_stages() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        metronome.set_gap_schedule(Some(GapClickSchedule::progressive(4)));
+        assert_eq!(metronome.gap_schedule().unwrap().stage_count(), 3);
+
+        // Stage 0: full click
+        assert_eq!(audible_beats(&mut metronome), vec![1, 2, 3, 4]);
+
+        // Stage 1: beat 4 dropped
+        assert!(metronome.advance_gap_stage());
+        assert_eq!(audible_beats(&mut metronome), vec![1, 2, 3]);
+
+        // Stage 2: only the downbeat remains
+        assert!(metronome.advance_gap_stage());
+        assert_eq!(audible_beats(&mut metronome), vec![1]);
+        assert!(!metronome.advance_gap_stage());
+    }
+
+    #[test]
+    fn test_gap_click_render_skips_silenced_beats() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        let mut schedule = GapClickSchedule::progressive(4);
+        schedule.advance();
+        metronome.set_gap_schedule(Some(schedule));
+
+        let adapter = AudioSampleAdapter::new();
+        let buffer = metronome.render(120.0, 1, 44100, &adapter);
+        let beat = |n: usize| &buffer[n * 22050..n * 22050 + 2000];
+
+        assert!(beat(0).iter().any(|s| s.abs() > 0.01));
+        assert!(beat(2).iter().any(|s| s.abs() > 0.01));
+        assert!(beat(3).iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_gap_click_without_schedule() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        assert!(!metronome.advance_gap_stage());
+        assert!(!metronome.is_beat_silenced(4));
+
+        let mut schedule = GapClickSchedule::new(vec![GapStage::new(vec![2, 4])]);
+        assert!(schedule.current().is_beat_silenced(2));
+        assert!(!schedule.advance());
+        schedule.reset();
+        assert_eq!(schedule.current_stage(), 0);
+    }
 }
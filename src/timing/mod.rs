@@ -1,9 +1,21 @@
+/// Visual/audio offset calibration from tapped responses to clicks.
+pub mod calibration;
 /// High-precision beat timing abstraction with discrete scheduling.
 pub mod clock;
 /// Simple metronome implementation using BeatClock for regular beats.
 pub mod metronome;
 /// Pattern player for complex rhythm sequences with BeatClock integration.
 pub mod patterns;
+/// Loop practice driver ramping the tempo after each successful pass.
+pub mod practice;
+/// Grid quantization of user-triggered events to a running BeatClock.
+pub mod quantize;
+/// Gradual tempo ramping stepping the tempo every few measures.
+pub mod ramp;
+/// Lookahead scheduling of beats and pattern hits with exact future times.
+pub mod scheduler;
+/// Perfect-time scoring of user taps against the metronome's beats.
+pub mod scoring;
 /// Beat event tracking and observation for audio-visual coupling.
 pub mod tracker;
 /// Timing subsystem for Polyphonica - Precision timing for musical applications
@@ -14,9 +26,15 @@ pub mod tracker;
 ///
 /// - **Core Types**: Shared timing data structures (TimeSignature, BeatEvent, etc.)
 /// - **BeatClock**: High-precision timing abstraction with discrete scheduling
-/// - **Metronome**: Simple regular beat implementation using BeatClock
+/// - **Metronome**: Simple regular beat implementation using BeatClock, with tap tempo
 /// - **PatternPlayer**: Complex rhythm pattern implementation using BeatClock
+/// - **TriggerQuantizer**: Snaps auditioned chords and arpeggios to the beat grid
+/// - **PracticeLoop**: Steps the tempo up after each clean pass of a section
+/// - **TempoRamp**: Steps the tempo toward a target every few measures
+/// - **Scheduler**: Hands out beats ahead of time for sample-accurate placement
 /// - **BeatTracker**: Event observation and emission for visualizer coupling
+/// - **Calibration**: Measures the perceived click latency from user taps
+/// - **TimingScorer**: Grades user taps against the beats of a practice session
 ///
 /// # Design Principles
 ///
@@ -43,8 +61,16 @@ pub mod tracker;
 pub mod types;
 
 // Re-export core types for convenient access
+pub use calibration::Calibration;
 pub use clock::BeatClock;
-pub use metronome::Metronome;
+pub use metronome::{
+    GapClickSchedule, GapStage, Metronome, DEFAULT_TAP_OUTLIER_PERCENT, DEFAULT_TAP_WINDOW,
+};
 pub use patterns::PatternPlayer;
+pub use practice::PracticeLoop;
+pub use quantize::{QuantizeGrid, TriggerQuantizer};
+pub use ramp::TempoRamp;
+pub use scheduler::{frames_until, Scheduler, DEFAULT_LOOKAHEAD_MS, LATE_TOLERANCE_MS};
+pub use scoring::{TimingScore, TimingScorer, DEFAULT_SCORE_WINDOW_MS};
 pub use tracker::{BeatObserver, BeatTracker};
-pub use types::{BeatEvent, ClickType, TimeSignature};
+pub use types::{BeatEvent, ClickType, NoteDivision, Subdivision, TimeSignature};
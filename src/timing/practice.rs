@@ -0,0 +1,277 @@
+/// Loop practice driver that steps the tempo up after each clean pass
+///
+/// This module provides PracticeLoop, which repeats a section starting at a
+/// reduced tempo and raises the tempo by a fixed step after every successful
+/// loop until the target tempo is reached, then holds it there.
+use super::clock::BeatClock;
+use super::types::{BeatEvent, TriggerResult};
+use crate::patterns::state::PatternTrigger;
+use crate::patterns::PatternState;
+
+/// Tempo ramp over repeated passes of a practice section
+///
+/// Loops are counted in measures, either from the beats of any BeatClock
+/// (`sync`) or from the loops of a playing pattern (`sync_pattern`). Passing
+/// the driver's tempo to the clock is handled by those methods, so the clock
+/// always plays at `current_tempo`.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::timing::{BeatClock, Metronome, PracticeLoop, TimeSignature};
+///
+/// let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+/// let mut practice = PracticeLoop::new(80.0, 120.0, 5.0).with_measures_per_loop(2);
+/// metronome.start();
+///
+/// // In the update loop
+/// for _event in practice.sync(&mut metronome) {
+///     // Trigger clicks as usual
+/// }
+/// println!("Loop {} at {} BPM", practice.loop_index(), practice.current_tempo());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PracticeLoop {
+    /// Tempo of the first pass
+    start_bpm: f32,
+
+    /// Tempo the ramp stops at
+    target_bpm: f32,
+
+    /// Tempo change after each successful loop
+    step_bpm: f32,
+
+    /// Length of the practice section in measures
+    measures_per_loop: u32,
+
+    /// Tempo of the loop in progress
+    current_bpm: f32,
+
+    /// Number of loops completed
+    loop_index: u32,
+
+    /// Measures completed in the loop in progress
+    measures_in_loop: u32,
+
+    /// Whether the loop in progress was marked as failed
+    loop_failed: bool,
+}
+
+impl PracticeLoop {
+    /// Create a practice loop ramping from `start_bpm` to `target_bpm`
+    ///
+    /// The ramp runs downward if the target is below the start tempo. Each
+    /// loop is one measure long until changed with `with_measures_per_loop`.
+    pub fn new(start_bpm: f32, target_bpm: f32, step_bpm: f32) -> Self {
+        Self {
+            start_bpm,
+            target_bpm,
+            step_bpm: step_bpm.abs(),
+            measures_per_loop: 1,
+            current_bpm: start_bpm,
+            loop_index: 0,
+            measures_in_loop: 0,
+            loop_failed: false,
+        }
+    }
+
+    /// Set the section length in measures
+    pub fn with_measures_per_loop(mut self, measures: u32) -> Self {
+        self.measures_per_loop = measures.max(1);
+        self
+    }
+
+    /// Tempo the section should currently be played at
+    pub fn current_tempo(&self) -> f32 {
+        self.current_bpm
+    }
+
+    /// Number of loops completed so far
+    pub fn loop_index(&self) -> u32 {
+        self.loop_index
+    }
+
+    /// Number of measures completed since the start
+    pub fn measures_completed(&self) -> u32 {
+        self.loop_index * self.measures_per_loop + self.measures_in_loop
+    }
+
+    /// Length of the practice section in measures
+    pub fn measures_per_loop(&self) -> u32 {
+        self.measures_per_loop
+    }
+
+    /// Whether the ramp has reached the target tempo
+    pub fn is_at_target(&self) -> bool {
+        self.current_bpm == self.target_bpm
+    }
+
+    /// Keep the current tempo for the next pass instead of stepping it
+    pub fn mark_loop_failed(&mut self) {
+        self.loop_failed = true;
+    }
+
+    /// Count a completed measure; returns `true` if it completed a loop
+    pub fn measure_completed(&mut self) -> bool {
+        self.measures_in_loop += 1;
+        if self.measures_in_loop < self.measures_per_loop {
+            return false;
+        }
+
+        self.measures_in_loop = 0;
+        self.loop_index += 1;
+        if !std::mem::take(&mut self.loop_failed) {
+            self.current_bpm = self.next_tempo();
+        }
+        true
+    }
+
+    /// Follow a beat from a clock; the last beat of each measure ends it
+    ///
+    /// Ending the measure on its last beat lets the next downbeat already be
+    /// requested at the new tempo. Returns `true` if the beat completed a loop.
+    pub fn observe_beat(&mut self, event: &BeatEvent) -> bool {
+        if event.beat_number < event.time_signature.beats_per_measure {
+            return false;
+        }
+        self.measure_completed()
+    }
+
+    /// Check a clock at the practice tempo and follow the beats it emits
+    ///
+    /// Returns the clock's beat events so the caller can still trigger them.
+    pub fn sync<C: BeatClock + ?Sized>(&mut self, clock: &mut C) -> TriggerResult {
+        let events = clock.check_triggers(self.current_bpm);
+        for event in &events {
+            self.observe_beat(event);
+        }
+        events
+    }
+
+    /// Check a playing pattern at the practice tempo, counting each pattern loop as a measure
+    pub fn sync_pattern(&mut self, state: &mut PatternState) -> Vec<PatternTrigger> {
+        let loops_before = state.stats().loops_completed;
+        let triggers = state.check_pattern_triggers(self.current_bpm);
+        for _ in loops_before..state.stats().loops_completed {
+            self.measure_completed();
+        }
+        triggers
+    }
+
+    /// Start over from the first pass at the start tempo
+    pub fn reset(&mut self) {
+        self.current_bpm = self.start_bpm;
+        self.loop_index = 0;
+        self.measures_in_loop = 0;
+        self.loop_failed = false;
+    }
+
+    /// Tempo after one more step toward the target
+    fn next_tempo(&self) -> f32 {
+        if self.start_bpm <= self.target_bpm {
+            (self.current_bpm + self.step_bpm).min(self.target_bpm)
+        } else {
+            (self.current_bpm - self.step_bpm).max(self.target_bpm)
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::timing::{ClickType, TimeSignature};
+
+    /// Clock that emits one beat per check, recording the tempo it was asked for
+    pub(in crate::timing) struct MockClock {
+        beats_per_measure: u8,
+        next_beat: u8,
+        pub(in crate::timing) tempos: Vec<f32>,
+    }
+
+    impl MockClock {
+        pub(in crate::timing) fn new(beats_per_measure: u8) -> Self {
+            Self {
+                beats_per_measure,
+                next_beat: 1,
+                tempos: Vec::new(),
+            }
+        }
+    }
+
+    impl BeatClock for MockClock {
+        fn start(&mut self) {}
+
+        fn stop(&mut self) {}
+
+        fn pause(&mut self) {}
+
+        fn resume(&mut self) {}
+
+        fn check_triggers(&mut self, tempo_bpm: f32) -> TriggerResult {
+            self.tempos.push(tempo_bpm);
+            let beat_number = self.next_beat;
+            self.next_beat = beat_number % self.beats_per_measure + 1;
+            vec![BeatEvent::new(
+                beat_number,
+                beat_number == 1,
+                vec![ClickType::WoodBlock],
+                tempo_bpm,
+                self.time_signature(),
+            )]
+        }
+
+        fn is_running(&self) -> bool {
+            true
+        }
+
+        fn current_beat(&self) -> u8 {
+            self.next_beat
+        }
+
+        fn time_signature(&self) -> TimeSignature {
+            TimeSignature::new(self.beats_per_measure, 4)
+        }
+
+        fn set_time_signature(&mut self, _time_signature: TimeSignature) {}
+    }
+
+    #[test]
+    fn test_tempo_steps_each_loop_then_holds() {
+        let mut clock = MockClock::new(4);
+        let mut practice = PracticeLoop::new(60.0, 75.0, 5.0).with_measures_per_loop(2);
+
+        // Six two-measure loops of four beats each
+        for _ in 0..6 * 8 {
+            practice.sync(&mut clock);
+        }
+
+        // Each loop is played at one tempo, stepping until the target holds
+        let loop_tempos: Vec<f32> = clock.tempos.chunks(8).map(|chunk| chunk[0]).collect();
+        assert_eq!(loop_tempos, vec![60.0, 65.0, 70.0, 75.0, 75.0, 75.0]);
+        for chunk in clock.tempos.chunks(8) {
+            assert!(chunk.iter().all(|&tempo| tempo == chunk[0]));
+        }
+        assert_eq!(practice.loop_index(), 6);
+        assert!(practice.is_at_target());
+    }
+
+    #[test]
+    fn test_failed_loop_repeats_tempo() {
+        let mut practice = PracticeLoop::new(100.0, 80.0, 15.0);
+        assert!(practice.measure_completed());
+        assert_eq!(practice.current_tempo(), 85.0);
+
+        practice.mark_loop_failed();
+        practice.measure_completed();
+        assert_eq!(practice.current_tempo(), 85.0);
+
+        // Downward ramps stop at the target too
+        practice.measure_completed();
+        assert_eq!(practice.current_tempo(), 80.0);
+        assert_eq!(practice.loop_index(), 3);
+
+        practice.reset();
+        assert_eq!(practice.current_tempo(), 100.0);
+        assert_eq!(practice.loop_index(), 0);
+    }
+}
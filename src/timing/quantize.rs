@@ -0,0 +1,348 @@
+/// Grid quantization for user-triggered events
+///
+/// This module defers events such as chord or arpeggio auditions so they land
+/// on the next beat or subdivision of a running BeatClock instead of whenever
+/// a button happens to be pressed.
+use super::clock::BeatClock;
+use super::types::{BeatEvent, Subdivision, TriggerResult};
+use std::time::{Duration, Instant};
+
+/// Requests this close to a grid line fire immediately instead of waiting
+const GRID_TOLERANCE: Duration = Duration::from_millis(2);
+
+/// Grid that triggered events snap to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantizeGrid {
+    /// Fire events immediately
+    #[default]
+    Off,
+    /// Defer events to the next beat
+    Beat,
+    /// Defer events to the next subdivision pulse
+    Subdivision(Subdivision),
+}
+
+impl QuantizeGrid {
+    /// Number of grid lines per beat (0 when quantization is off)
+    pub fn pulses_per_beat(&self) -> u8 {
+        match self {
+            QuantizeGrid::Off => 0,
+            QuantizeGrid::Beat => 1,
+            QuantizeGrid::Subdivision(subdivision) => subdivision.pulses_per_beat(),
+        }
+    }
+}
+
+/// Defers triggered events to the next grid position of a BeatClock
+///
+/// The quantizer follows the clock through the beat events it emits, so it
+/// works with any BeatClock (Metronome, PatternPlayer). Events requested while
+/// no clock is running fire immediately.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::timing::{BeatClock, Metronome, QuantizeGrid, TimeSignature, TriggerQuantizer};
+/// use std::time::Instant;
+///
+/// let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+/// let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Beat);
+/// metronome.start();
+///
+/// // In the update loop
+/// for _event in quantizer.sync(&mut metronome, 120.0) {
+///     // Trigger clicks as usual
+/// }
+/// if let Some(chord) = quantizer.request("Am", Instant::now()) {
+///     println!("Play {} now", chord);
+/// }
+/// for chord in quantizer.poll(Instant::now()) {
+///     println!("Play {} on the grid", chord);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TriggerQuantizer<T> {
+    /// Grid that requests snap to
+    grid: QuantizeGrid,
+
+    /// Time and tempo of the most recent beat from the clock
+    last_beat: Option<(Instant, f32)>,
+
+    /// Deferred events with the time they become due
+    pending: Vec<(Instant, T)>,
+}
+
+impl<T> TriggerQuantizer<T> {
+    /// Create a quantizer snapping to the given grid
+    pub fn new(grid: QuantizeGrid) -> Self {
+        Self {
+            grid,
+            last_beat: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Set the quantization grid
+    pub fn set_grid(&mut self, grid: QuantizeGrid) {
+        self.grid = grid;
+    }
+
+    /// Get the quantization grid
+    pub fn grid(&self) -> QuantizeGrid {
+        self.grid
+    }
+
+    /// Number of events waiting for a grid position
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check the clock for beats and follow any that fire
+    ///
+    /// Returns the clock's beat events so the caller can still trigger them.
+    pub fn sync<C: BeatClock + ?Sized>(&mut self, clock: &mut C, tempo_bpm: f32) -> TriggerResult {
+        if !clock.is_running() {
+            self.last_beat = None;
+        }
+
+        let events = clock.check_triggers(tempo_bpm);
+        for event in &events {
+            self.observe_beat(event);
+        }
+        events
+    }
+
+    /// Follow a beat emitted by the clock
+    ///
+    /// Deferred events expected within half a grid step of this beat are
+    /// aligned to the actual beat time, absorbing scheduler jitter.
+    pub fn observe_beat(&mut self, event: &BeatEvent) {
+        self.last_beat = Some((event.timestamp, event.tempo_bpm));
+
+        if let Some(pulse) = self.pulse_duration(event.tempo_bpm) {
+            let window = event.timestamp + pulse / 2;
+            for (due, _) in self.pending.iter_mut() {
+                if *due <= window {
+                    *due = (*due).min(event.timestamp);
+                }
+            }
+        }
+    }
+
+    /// Request an event, returning it if it should fire now
+    ///
+    /// Returns `None` when the event was deferred to the next grid position;
+    /// it is then handed back by [`TriggerQuantizer::poll`] once due.
+    pub fn request(&mut self, item: T, now: Instant) -> Option<T> {
+        let Some((beat_time, tempo_bpm)) = self.last_beat else {
+            return Some(item);
+        };
+        let Some(pulse) = self.pulse_duration(tempo_bpm) else {
+            return Some(item);
+        };
+
+        let elapsed = now.saturating_duration_since(beat_time);
+        let pulses_elapsed = elapsed.as_secs_f64() / pulse.as_secs_f64();
+        let previous_line = beat_time + pulse.mul_f64(pulses_elapsed.floor());
+        if now.saturating_duration_since(previous_line) <= GRID_TOLERANCE {
+            return Some(item);
+        }
+
+        let next_line = beat_time + pulse.mul_f64(pulses_elapsed.floor() + 1.0);
+        self.pending.push((next_line, item));
+        None
+    }
+
+    /// Take all deferred events that are due at `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<T> {
+        let mut ready = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len() {
+            if self.pending[index].0 <= now {
+                ready.push(self.pending.remove(index).1);
+            } else {
+                index += 1;
+            }
+        }
+        ready
+    }
+
+    /// Drop all deferred events and forget the clock position
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.last_beat = None;
+    }
+
+    /// Duration of one grid step at the given tempo
+    fn pulse_duration(&self, tempo_bpm: f32) -> Option<Duration> {
+        let pulses = self.grid.pulses_per_beat();
+        if pulses == 0 || tempo_bpm <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(
+            60.0 / tempo_bpm as f64 / pulses as f64,
+        ))
+    }
+}
+
+impl<T> Default for TriggerQuantizer<T> {
+    fn default() -> Self {
+        Self::new(QuantizeGrid::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing::{ClickType, TimeSignature};
+
+    /// Clock that emits queued beat events on demand
+    struct MockClock {
+        running: bool,
+        queued: Vec<BeatEvent>,
+    }
+
+    impl MockClock {
+        fn new() -> Self {
+            Self {
+                running: true,
+                queued: Vec::new(),
+            }
+        }
+
+        fn queue_beat(&mut self, beat_number: u8, timestamp: Instant) {
+            let mut event = BeatEvent::new(
+                beat_number,
+                beat_number == 1,
+                vec![ClickType::WoodBlock],
+                120.0,
+                TimeSignature::new(4, 4),
+            );
+            event.timestamp = timestamp;
+            self.queued.push(event);
+        }
+    }
+
+    impl BeatClock for MockClock {
+        fn start(&mut self) {
+            self.running = true;
+        }
+
+        fn stop(&mut self) {
+            self.running = false;
+        }
+
+        fn pause(&mut self) {
+            self.running = false;
+        }
+
+        fn resume(&mut self) {
+            self.running = true;
+        }
+
+        fn check_triggers(&mut self, _tempo_bpm: f32) -> TriggerResult {
+            std::mem::take(&mut self.queued)
+        }
+
+        fn is_running(&self) -> bool {
+            self.running
+        }
+
+        fn current_beat(&self) -> u8 {
+            1
+        }
+
+        fn time_signature(&self) -> TimeSignature {
+            TimeSignature::new(4, 4)
+        }
+
+        fn set_time_signature(&mut self, _time_signature: TimeSignature) {}
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_quantize_defers_to_next_beat() {
+        let start = Instant::now();
+        let mut clock = MockClock::new();
+        let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Beat);
+
+        clock.queue_beat(1, start);
+        assert_eq!(quantizer.sync(&mut clock, 120.0).len(), 1);
+
+        // Mid-beat request waits for the beat at 500ms
+        assert_eq!(quantizer.request("C", start + ms(200)), None);
+        assert_eq!(quantizer.pending_count(), 1);
+        assert!(quantizer.poll(start + ms(300)).is_empty());
+        assert!(quantizer.poll(start + ms(499)).is_empty());
+        assert_eq!(quantizer.poll(start + ms(500)), vec!["C"]);
+        assert_eq!(quantizer.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_quantize_off_fires_immediately() {
+        let start = Instant::now();
+        let mut clock = MockClock::new();
+        let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Off);
+
+        clock.queue_beat(1, start);
+        quantizer.sync(&mut clock, 120.0);
+
+        assert_eq!(quantizer.request("C", start + ms(200)), Some("C"));
+        assert_eq!(quantizer.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_quantize_without_running_clock_fires_immediately() {
+        let mut clock = MockClock::new();
+        clock.stop();
+        let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Beat);
+        quantizer.sync(&mut clock, 120.0);
+
+        assert_eq!(quantizer.request("C", Instant::now()), Some("C"));
+    }
+
+    #[test]
+    fn test_quantize_on_grid_fires_immediately() {
+        let start = Instant::now();
+        let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Beat);
+        let mut clock = MockClock::new();
+        clock.queue_beat(2, start);
+        quantizer.sync(&mut clock, 120.0);
+
+        assert_eq!(quantizer.request("C", start + ms(1)), Some("C"));
+    }
+
+    #[test]
+    fn test_quantize_to_subdivision() {
+        let start = Instant::now();
+        let mut clock = MockClock::new();
+        let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Subdivision(Subdivision::Eighth));
+
+        clock.queue_beat(1, start);
+        quantizer.sync(&mut clock, 120.0);
+
+        // Eighth notes at 120 BPM fall every 250ms
+        assert_eq!(quantizer.request("arp", start + ms(100)), None);
+        assert!(quantizer.poll(start + ms(249)).is_empty());
+        assert_eq!(quantizer.poll(start + ms(250)), vec!["arp"]);
+    }
+
+    #[test]
+    fn test_quantize_aligns_to_early_clock_beat() {
+        let start = Instant::now();
+        let mut clock = MockClock::new();
+        let mut quantizer = TriggerQuantizer::new(QuantizeGrid::Beat);
+
+        clock.queue_beat(1, start);
+        quantizer.sync(&mut clock, 120.0);
+        assert_eq!(quantizer.request("C", start + ms(300)), None);
+
+        // The clock's next beat arrives slightly early; the chord goes with it
+        clock.queue_beat(2, start + ms(495));
+        quantizer.sync(&mut clock, 120.0);
+        assert_eq!(quantizer.poll(start + ms(495)), vec!["C"]);
+    }
+}
@@ -0,0 +1,148 @@
+/// Gradual tempo ramping for tempo-trainer practice
+///
+/// This module provides TempoRamp, which raises (or lowers) the tempo by a
+/// fixed step every few measures until a target tempo is reached. It is a
+/// PracticeLoop whose loops never fail, so it suits unattended speed
+/// building with a plain metronome.
+use super::clock::BeatClock;
+use super::practice::PracticeLoop;
+use super::types::{BeatEvent, TriggerResult};
+
+/// Tempo schedule stepping toward a target every few measures
+///
+/// Measures are counted from the beats a BeatClock emits, ending each
+/// measure on its last beat so the following downbeat is already scheduled
+/// at the new tempo. `sync` passes the ramp's tempo to the clock, so the
+/// clock always plays at `current_bpm`.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::timing::{BeatClock, Metronome, TempoRamp, TimeSignature};
+///
+/// let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+/// let mut ramp = TempoRamp::new(80.0, 120.0, 4.0).with_measures_per_step(2);
+/// metronome.start();
+///
+/// // In the update loop
+/// for _event in ramp.sync(&mut metronome) {
+///     // Trigger clicks as usual
+/// }
+/// println!("Measure {} at {} BPM", ramp.measures_completed(), ramp.current_bpm());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoRamp {
+    /// Practice loop one step long, never marked as failed
+    steps: PracticeLoop,
+}
+
+impl TempoRamp {
+    /// Create a ramp from `start_bpm` to `target_bpm` in steps of `step_bpm`
+    ///
+    /// The ramp runs downward if the target is below the start tempo. The
+    /// tempo steps after every measure until changed with
+    /// `with_measures_per_step`.
+    pub fn new(start_bpm: f32, target_bpm: f32, step_bpm: f32) -> Self {
+        Self {
+            steps: PracticeLoop::new(start_bpm, target_bpm, step_bpm),
+        }
+    }
+
+    /// Set the number of measures played at each tempo
+    pub fn with_measures_per_step(mut self, measures: u32) -> Self {
+        self.steps = self.steps.with_measures_per_loop(measures);
+        self
+    }
+
+    /// Tempo to pass to the clock for the current measure
+    pub fn current_bpm(&self) -> f32 {
+        self.steps.current_tempo()
+    }
+
+    /// Number of measures completed since the start
+    pub fn measures_completed(&self) -> u32 {
+        self.steps.measures_completed()
+    }
+
+    /// Measures played at each tempo before stepping
+    pub fn measures_per_step(&self) -> u32 {
+        self.steps.measures_per_loop()
+    }
+
+    /// Whether the ramp has reached the target tempo
+    pub fn is_at_target(&self) -> bool {
+        self.steps.is_at_target()
+    }
+
+    /// Count a completed measure; returns `true` if the tempo stepped
+    pub fn measure_completed(&mut self) -> bool {
+        let before = self.current_bpm();
+        self.steps.measure_completed();
+        self.current_bpm() != before
+    }
+
+    /// Follow a beat from a clock; the last beat of each measure ends it
+    ///
+    /// Returns `true` if the beat stepped the tempo.
+    pub fn observe_beat(&mut self, event: &BeatEvent) -> bool {
+        let before = self.current_bpm();
+        self.steps.observe_beat(event);
+        self.current_bpm() != before
+    }
+
+    /// Check a clock at the ramp tempo and follow the beats it emits
+    ///
+    /// Returns the clock's beat events so the caller can still trigger them.
+    pub fn sync<C: BeatClock + ?Sized>(&mut self, clock: &mut C) -> TriggerResult {
+        self.steps.sync(clock)
+    }
+
+    /// Start over at the start tempo
+    pub fn reset(&mut self) {
+        self.steps.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing::practice::tests::MockClock;
+
+    #[test]
+    fn test_tempo_steps_at_measure_boundaries() {
+        let mut clock = MockClock::new(3);
+        let mut ramp = TempoRamp::new(90.0, 100.0, 4.0).with_measures_per_step(2);
+
+        // Ten measures of three beats each
+        for _ in 0..10 * 3 {
+            ramp.sync(&mut clock);
+        }
+        assert_eq!(ramp.measures_completed(), 10);
+
+        // Every measure is played at one tempo, stepping after each pair
+        let measure_tempos: Vec<f32> = clock.tempos.chunks(3).map(|chunk| chunk[0]).collect();
+        assert_eq!(
+            measure_tempos,
+            vec![90.0, 90.0, 94.0, 94.0, 98.0, 98.0, 100.0, 100.0, 100.0, 100.0]
+        );
+        for chunk in clock.tempos.chunks(3) {
+            assert!(chunk.iter().all(|&tempo| tempo == chunk[0]));
+        }
+        assert!(ramp.is_at_target());
+
+        ramp.reset();
+        assert_eq!(ramp.current_bpm(), 90.0);
+        assert_eq!(ramp.measures_completed(), 0);
+    }
+
+    #[test]
+    fn test_downward_ramp_stops_at_target() {
+        let mut ramp = TempoRamp::new(120.0, 110.0, 6.0);
+        assert!(ramp.measure_completed());
+        assert_eq!(ramp.current_bpm(), 114.0);
+        assert!(ramp.measure_completed());
+        assert_eq!(ramp.current_bpm(), 110.0);
+        assert!(!ramp.measure_completed());
+        assert_eq!(ramp.current_bpm(), 110.0);
+    }
+}
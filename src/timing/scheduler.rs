@@ -0,0 +1,400 @@
+/// Lookahead scheduling of beats and pattern hits ahead of playback
+///
+/// This module provides Scheduler, which hands out events a short window
+/// before they are due, each stamped with the exact time it should sound.
+/// The GUI loop polls it as usual, but instead of triggering whatever is due
+/// at poll time (and so late by up to one poll interval), it passes the
+/// events to the audio thread, which places each one at its sample offset.
+use super::types::{BeatEvent, ClickType, TimeSignature, TriggerResult};
+use crate::patterns::DrumPattern;
+use std::time::{Duration, Instant};
+
+/// Default time events are scheduled ahead of playback (milliseconds)
+pub const DEFAULT_LOOKAHEAD_MS: f64 = 100.0;
+
+/// How late an event may be and still be handed out (milliseconds)
+///
+/// Covers the jitter of a GUI poll loop. Events missed by more than this,
+/// e.g. while the loop was stalled, are dropped instead of sounding at once.
+pub const LATE_TOLERANCE_MS: f64 = 50.0;
+
+/// Schedules metronome beats or pattern hits a lookahead window in advance
+///
+/// Without a pattern, the scheduler emits one click per beat of its time
+/// signature, accenting the downbeat like Metronome does. With a pattern, it
+/// emits the pattern's beats, micro-timing offsets included, looping every
+/// measure. Event times are accumulated from the previous event, so a tempo
+/// change applies from the next unscheduled event on and never moves events
+/// already handed out.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::timing::{frames_until, Scheduler, TimeSignature};
+/// use std::time::Instant;
+///
+/// let mut scheduler = Scheduler::new(TimeSignature::new(4, 4)).with_lookahead_ms(120.0);
+/// scheduler.start();
+///
+/// // In the GUI poll loop
+/// let now = Instant::now();
+/// for event in scheduler.schedule_at(120.0, now) {
+///     let offset = frames_until(event.timestamp, now, 44100.0);
+///     // Queue event.samples on the audio thread, `offset` frames from now
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    /// Time signature of the metronome beats
+    time_signature: TimeSignature,
+
+    /// Pattern whose beats are scheduled instead of plain clicks
+    pattern: Option<DrumPattern>,
+
+    /// How far ahead of now events are handed out (milliseconds)
+    lookahead_ms: f64,
+
+    /// Click sound for normal beats
+    click_type: ClickType,
+
+    /// Click sound for downbeats
+    accent_click_type: ClickType,
+
+    /// Time playback started; None while stopped
+    origin: Option<Instant>,
+
+    /// Index of the next step to emit within the measure
+    next_step: usize,
+
+    /// Grid time of the next step from the origin (milliseconds)
+    next_grid_ms: f64,
+
+    /// Transport run, incremented each time the scheduler starts
+    run_id: u32,
+
+    /// Sequence number of the next emitted event
+    next_sequence: u64,
+}
+
+/// One step of the measure being scheduled
+struct Step {
+    position: f32,
+    samples: Vec<ClickType>,
+    accent: bool,
+    timing_offset_ms: f32,
+}
+
+impl Scheduler {
+    /// Create a stopped scheduler of metronome beats
+    pub fn new(time_signature: TimeSignature) -> Self {
+        Self {
+            time_signature,
+            pattern: None,
+            lookahead_ms: DEFAULT_LOOKAHEAD_MS,
+            click_type: ClickType::WoodBlock,
+            accent_click_type: ClickType::Cowbell,
+            origin: None,
+            next_step: 0,
+            next_grid_ms: 0.0,
+            run_id: 0,
+            next_sequence: 0,
+        }
+    }
+
+    /// Set how far ahead of now events are handed out (milliseconds)
+    pub fn with_lookahead_ms(mut self, lookahead_ms: f64) -> Self {
+        self.lookahead_ms = lookahead_ms.max(0.0);
+        self
+    }
+
+    /// How far ahead of now events are handed out (milliseconds)
+    pub fn lookahead_ms(&self) -> f64 {
+        self.lookahead_ms
+    }
+
+    /// Set the time signature of the metronome beats
+    pub fn set_time_signature(&mut self, time_signature: TimeSignature) {
+        self.time_signature = time_signature;
+        self.next_step %= self.step_count();
+    }
+
+    /// Set the click sound type
+    pub fn set_click_type(&mut self, click_type: ClickType) {
+        self.click_type = click_type;
+    }
+
+    /// Set the accent click sound type (for downbeats)
+    pub fn set_accent_click_type(&mut self, accent_click_type: ClickType) {
+        self.accent_click_type = accent_click_type;
+    }
+
+    /// Schedule a pattern's beats instead of plain clicks, from its first beat
+    pub fn set_pattern(&mut self, mut pattern: DrumPattern) {
+        pattern
+            .beats
+            .sort_by(|a, b| a.beat_position.total_cmp(&b.beat_position));
+        self.pattern = (!pattern.beats.is_empty()).then_some(pattern);
+        self.next_step = 0;
+    }
+
+    /// Go back to scheduling plain clicks
+    pub fn clear_pattern(&mut self) {
+        self.pattern = None;
+        self.next_step = 0;
+    }
+
+    /// Start scheduling from the first step now
+    pub fn start(&mut self) {
+        self.start_at(Instant::now());
+    }
+
+    /// Start scheduling from the first step at `origin`
+    pub fn start_at(&mut self, origin: Instant) {
+        self.run_id = self.run_id.wrapping_add(1);
+        self.origin = Some(origin);
+        self.next_step = 0;
+        self.next_grid_ms = 0.0;
+    }
+
+    /// Stop scheduling
+    pub fn stop(&mut self) {
+        self.origin = None;
+    }
+
+    /// Check if the scheduler is running
+    pub fn is_running(&self) -> bool {
+        self.origin.is_some()
+    }
+
+    /// Events due within the lookahead window from now
+    pub fn schedule(&mut self, tempo_bpm: f32) -> TriggerResult {
+        self.schedule_at(tempo_bpm, Instant::now())
+    }
+
+    /// Events due before `now` plus the lookahead, stamped with their due times
+    ///
+    /// Each event is returned at most once; events not yet within the window
+    /// are left for a later call. Events already more than
+    /// `LATE_TOLERANCE_MS` overdue are skipped, along with their sequence
+    /// numbers, so a stalled poll resumes on the grid instead of replaying
+    /// the missed beats in a burst.
+    pub fn schedule_at(&mut self, tempo_bpm: f32, now: Instant) -> TriggerResult {
+        let Some(origin) = self.origin.filter(|_| tempo_bpm > 0.0) else {
+            return Vec::new();
+        };
+        let time_signature = self.current_time_signature();
+        let beat_interval_ms = time_signature.beat_duration_ms(tempo_bpm);
+        let horizon = now + Duration::from_secs_f64(self.lookahead_ms / 1000.0);
+        let oldest = now
+            .checked_sub(Duration::from_secs_f64(LATE_TOLERANCE_MS / 1000.0))
+            .unwrap_or(origin);
+
+        let mut events = Vec::new();
+        loop {
+            let step = self.step(self.next_step);
+            let due_ms = (self.next_grid_ms + step.timing_offset_ms as f64).max(0.0);
+            let timestamp = origin + Duration::from_secs_f64(due_ms / 1000.0);
+            if timestamp > horizon {
+                break;
+            }
+
+            if timestamp >= oldest {
+                let beat_number = (step.position.floor() as u8).max(1);
+                let mut event = BeatEvent::new(
+                    beat_number,
+                    step.accent,
+                    step.samples,
+                    tempo_bpm,
+                    time_signature,
+                )
+                .with_sequence(self.run_id, self.next_sequence);
+                event.timestamp = timestamp;
+                events.push(event);
+            }
+            self.next_sequence += 1;
+
+            // Advance the grid by the distance to the following step, wrapping the measure
+            self.next_step += 1;
+            let next_position = if self.next_step == self.step_count() {
+                self.next_step = 0;
+                self.step(0).position + time_signature.beats_per_measure.max(1) as f32
+            } else {
+                self.step(self.next_step).position
+            };
+            self.next_grid_ms += (next_position - step.position) as f64 * beat_interval_ms;
+        }
+        events
+    }
+
+    /// Time signature of whatever is being scheduled
+    fn current_time_signature(&self) -> TimeSignature {
+        self.pattern
+            .as_ref()
+            .map_or(self.time_signature, |pattern| pattern.time_signature)
+    }
+
+    /// Number of steps in one measure
+    fn step_count(&self) -> usize {
+        match &self.pattern {
+            Some(pattern) => pattern.beats.len(),
+            None => self.time_signature.beats_per_measure.max(1) as usize,
+        }
+    }
+
+    /// Step at an index within the measure
+    fn step(&self, index: usize) -> Step {
+        match &self.pattern {
+            Some(pattern) => {
+                let beat = &pattern.beats[index];
+                Step {
+                    position: beat.beat_position,
+                    samples: beat.samples.clone(),
+                    accent: beat.accent,
+                    timing_offset_ms: beat.timing_offset_ms,
+                }
+            }
+            None => Step {
+                position: index as f32 + 1.0,
+                samples: vec![if index == 0 {
+                    self.accent_click_type
+                } else {
+                    self.click_type
+                }],
+                accent: index == 0,
+                timing_offset_ms: 0.0,
+            },
+        }
+    }
+}
+
+/// Number of frames from `now` until `timestamp` at the given sample rate
+///
+/// Zero if the timestamp has already passed.
+pub fn frames_until(timestamp: Instant, now: Instant, sample_rate: f32) -> usize {
+    let delay = timestamp.saturating_duration_since(now);
+    (delay.as_secs_f64() * sample_rate as f64).round() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::DrumPatternBeat;
+
+    fn at(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    /// Poll every `interval_ms` up to `until_ms`, recording each event with its poll time
+    fn poll(
+        scheduler: &mut Scheduler,
+        start: Instant,
+        interval_ms: u64,
+        until_ms: u64,
+    ) -> Vec<(Instant, BeatEvent)> {
+        (0..=until_ms)
+            .step_by(interval_ms as usize)
+            .flat_map(|ms| {
+                let now = at(start, ms);
+                scheduler
+                    .schedule_at(120.0, now)
+                    .into_iter()
+                    .map(move |event| (now, event))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_beats_scheduled_ahead_with_exact_times() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::new(TimeSignature::new(3, 4)).with_lookahead_ms(100.0);
+        assert!(scheduler.schedule_at(120.0, start).is_empty());
+        scheduler.start_at(start);
+
+        // Only the first beat is inside the first window; the next arrives 100 ms early
+        assert_eq!(scheduler.schedule_at(120.0, start).len(), 1);
+        assert!(scheduler.schedule_at(120.0, at(start, 399)).is_empty());
+        let events = scheduler.schedule_at(120.0, at(start, 400));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].timestamp, at(start, 500));
+        assert!(scheduler.schedule_at(120.0, at(start, 450)).is_empty());
+
+        // A 33 ms poll loop gets every beat once, ahead of time and on the grid
+        scheduler.start_at(start);
+        let polled = poll(&mut scheduler, start, 33, 3000);
+        assert_eq!(polled.len(), 7);
+        for (index, (polled_at, event)) in polled.iter().enumerate() {
+            assert_eq!(event.timestamp, at(start, 500 * index as u64));
+            assert!(*polled_at <= event.timestamp);
+            assert!(event.timestamp <= at(*polled_at, 100));
+            assert_eq!(event.beat_number as usize, index % 3 + 1);
+            assert_eq!(event.accent, index % 3 == 0);
+            assert_eq!(event.sequence, polled[0].1.sequence + index as u64);
+        }
+
+        // A stalled poll drops the beats it missed and resumes on the grid
+        scheduler.start_at(start);
+        let first = scheduler.schedule_at(120.0, start);
+        let events = scheduler.schedule_at(120.0, at(start, 1950));
+        let times: Vec<Instant> = events.iter().map(|event| event.timestamp).collect();
+        assert_eq!(times, vec![at(start, 2000)]);
+        assert_eq!(events[0].sequence, first[0].sequence + 4);
+
+        // Slightly late events are still handed out
+        scheduler.start_at(start);
+        scheduler.schedule_at(120.0, start);
+        let events = scheduler.schedule_at(120.0, at(start, 540));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].timestamp, at(start, 500));
+
+        scheduler.stop();
+        assert!(scheduler.schedule_at(120.0, at(start, 5000)).is_empty());
+
+        // A zero-beat measure is counted as one beat instead of stalling the grid
+        let mut empty_measure = Scheduler::new(TimeSignature::new(0, 4));
+        empty_measure.start_at(start);
+        let events = empty_measure.schedule_at(120.0, at(start, 1000));
+        let times: Vec<Instant> = events.iter().map(|event| event.timestamp).collect();
+        assert_eq!(times, vec![at(start, 1000)]);
+    }
+
+    #[test]
+    fn test_pattern_hits_scheduled_with_offsets_across_loops() {
+        let mut pattern = DrumPattern::new("test", TimeSignature::new(2, 4))
+            .with_beat(DrumPatternBeat::new(1.0).with_sample(ClickType::AcousticKick))
+            .with_beat(DrumPatternBeat::new(2.0).with_sample(ClickType::AcousticSnare))
+            .with_beat(DrumPatternBeat::new(1.5).with_sample(ClickType::HiHatClosed));
+        pattern.beats[1].timing_offset_ms = 20.0;
+
+        let start = Instant::now();
+        let mut scheduler = Scheduler::new(TimeSignature::new(4, 4));
+        scheduler.set_pattern(pattern);
+        scheduler.start_at(start);
+
+        let polled = poll(&mut scheduler, start, 10, 1950);
+        let hits: Vec<(u64, ClickType)> = polled
+            .iter()
+            .map(|(_, event)| {
+                let ms = event.timestamp.duration_since(start).as_millis() as u64;
+                (ms, event.samples[0])
+            })
+            .collect();
+        assert_eq!(
+            hits,
+            vec![
+                (0, ClickType::AcousticKick),
+                (250, ClickType::HiHatClosed),
+                (520, ClickType::AcousticSnare),
+                (1000, ClickType::AcousticKick),
+                (1250, ClickType::HiHatClosed),
+                (1520, ClickType::AcousticSnare),
+                (2000, ClickType::AcousticKick),
+            ]
+        );
+        for (polled_at, event) in &polled {
+            assert!(event.timestamp <= at(*polled_at, 100));
+        }
+
+        assert_eq!(frames_until(at(start, 20), start, 48000.0), 960);
+        assert_eq!(frames_until(start, at(start, 20), 48000.0), 0);
+    }
+}
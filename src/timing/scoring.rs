@@ -0,0 +1,235 @@
+/// Perfect-time scoring of user taps against the metronome's beats
+///
+/// This module grades a practice session: the app records when each beat was
+/// triggered and when the user tapped along, and TimingScorer reports how far
+/// the taps landed from the beats, how many fell inside a tight window, and
+/// the longest run of consecutive beats hit inside it.
+use super::calibration::{pair_taps, MAX_PAIRING_MS};
+use super::types::BeatEvent;
+use std::time::Instant;
+
+/// Default half-width of the window a tap must land in to count as on time (milliseconds)
+pub const DEFAULT_SCORE_WINDOW_MS: f32 = 20.0;
+
+/// Grade of a session of taps against the beat grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingScore {
+    /// Number of beats graded
+    pub beats: usize,
+
+    /// Beats answered by a tap
+    pub hits: usize,
+
+    /// Mean absolute distance of the taps from their beats (milliseconds)
+    pub mean_error_ms: f32,
+
+    /// Mean signed distance; positive when dragging behind the beat (milliseconds)
+    pub mean_offset_ms: f32,
+
+    /// Percentage of beats answered by a tap inside the window (0.0 - 100.0)
+    pub in_window_percent: f32,
+
+    /// Longest run of consecutive beats answered inside the window
+    pub longest_streak: usize,
+
+    /// Run of beats answered inside the window at the end of the session
+    pub current_streak: usize,
+}
+
+/// Records beats and the user's taps over a practice session
+///
+/// Each beat is paired with its nearest tap within 250 ms (or half the
+/// shortest beat interval, if smaller). Beats without a tap count as misses
+/// and break the streak; taps that answer no beat are ignored. A calibrated
+/// latency offset can be set so a system's perceived click delay is not
+/// counted against the player.
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::timing::TimingScorer;
+/// use std::time::{Duration, Instant};
+///
+/// let mut scorer = TimingScorer::new();
+/// let start = Instant::now();
+/// for beat in 0..8 {
+///     let click = start + Duration::from_millis(500 * beat);
+///     scorer.record_beat(click);
+///     scorer.record_tap(click + Duration::from_millis(10));
+/// }
+///
+/// let score = scorer.score().unwrap();
+/// assert!((score.mean_error_ms - 10.0).abs() < 0.5);
+/// assert_eq!(score.longest_streak, 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimingScorer {
+    /// Half-width of the on-time window (milliseconds)
+    window_ms: f32,
+
+    /// Calibrated latency subtracted from every tap (milliseconds)
+    latency_offset_ms: f32,
+
+    /// Times the beats were triggered
+    beat_times: Vec<Instant>,
+
+    /// Times the user tapped
+    tap_times: Vec<Instant>,
+}
+
+impl TimingScorer {
+    /// Create a scorer with the default window and no latency offset
+    pub fn new() -> Self {
+        Self {
+            window_ms: DEFAULT_SCORE_WINDOW_MS,
+            latency_offset_ms: 0.0,
+            beat_times: Vec::new(),
+            tap_times: Vec::new(),
+        }
+    }
+
+    /// Set the half-width of the on-time window (milliseconds)
+    pub fn with_window_ms(mut self, window_ms: f32) -> Self {
+        self.window_ms = window_ms.clamp(0.0, MAX_PAIRING_MS);
+        self
+    }
+
+    /// Set the calibrated latency subtracted from every tap (milliseconds)
+    pub fn with_latency_offset_ms(mut self, offset_ms: f32) -> Self {
+        self.latency_offset_ms = offset_ms;
+        self
+    }
+
+    /// Record that a beat was triggered
+    pub fn record_beat(&mut self, at: Instant) {
+        self.beat_times.push(at);
+    }
+
+    /// Record a beat emitted by a clock or tracker
+    pub fn record_beat_event(&mut self, event: &BeatEvent) {
+        self.record_beat(event.timestamp);
+    }
+
+    /// Record a user tap
+    pub fn record_tap(&mut self, at: Instant) {
+        self.tap_times.push(at);
+    }
+
+    /// Half-width of the on-time window (milliseconds)
+    pub fn window_ms(&self) -> f32 {
+        self.window_ms
+    }
+
+    /// Discard recorded beats and taps to start a new session
+    pub fn reset(&mut self) {
+        self.beat_times.clear();
+        self.tap_times.clear();
+    }
+
+    /// Grade the session so far
+    ///
+    /// Returns None until at least one beat has been answered by a tap.
+    pub fn score(&self) -> Option<TimingScore> {
+        let offsets = pair_taps(&self.beat_times, &self.tap_times, self.latency_offset_ms);
+
+        let hit_offsets: Vec<f32> = offsets.iter().flatten().copied().collect();
+        if hit_offsets.is_empty() {
+            return None;
+        }
+
+        let mut in_window = 0;
+        let mut longest_streak = 0;
+        let mut current_streak = 0;
+        for offset in &offsets {
+            if offset.is_some_and(|offset| offset.abs() <= self.window_ms) {
+                in_window += 1;
+                current_streak += 1;
+                longest_streak = longest_streak.max(current_streak);
+            } else {
+                current_streak = 0;
+            }
+        }
+
+        let hits = hit_offsets.len() as f32;
+        Some(TimingScore {
+            beats: offsets.len(),
+            hits: hit_offsets.len(),
+            mean_error_ms: hit_offsets.iter().map(|offset| offset.abs()).sum::<f32>() / hits,
+            mean_offset_ms: hit_offsets.iter().sum::<f32>() / hits,
+            in_window_percent: 100.0 * in_window as f32 / offsets.len() as f32,
+            longest_streak,
+            current_streak,
+        })
+    }
+}
+
+impl Default for TimingScorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing::calibration::tests::{clicks as beats, shifted};
+
+    #[test]
+    fn test_known_offsets_give_expected_error_and_window_percentage() {
+        let start = Instant::now();
+        let beat_times = beats(start, 10);
+        let offsets = [5, -10, 15, -30, 40, 0, -5, 10, 25, -20];
+
+        let mut scorer = TimingScorer::new();
+        for (&beat, offset) in beat_times.iter().zip(offsets) {
+            scorer.record_beat(beat);
+            scorer.record_tap(shifted(beat, offset));
+        }
+        // A stray tap between beats answers nothing
+        scorer.record_tap(shifted(beat_times[2], 260));
+
+        let score = scorer.score().unwrap();
+        assert_eq!(score.beats, 10);
+        assert_eq!(score.hits, 10);
+        assert!((score.mean_error_ms - 16.0).abs() < 0.1, "{:?}", score);
+        assert!((score.mean_offset_ms - 3.0).abs() < 0.1, "{:?}", score);
+
+        // 5, -10, 15, 0, -5, 10 and -20 are within 20 ms
+        assert!((score.in_window_percent - 70.0).abs() < 1e-3);
+        assert_eq!(score.longest_streak, 3);
+        assert_eq!(score.current_streak, 1);
+
+        // A wider window forgives the 25 and 30 ms taps
+        let score = scorer.with_window_ms(30.0).score().unwrap();
+        assert!((score.in_window_percent - 90.0).abs() < 1e-3);
+        assert_eq!(score.longest_streak, 5);
+        assert_eq!(score.current_streak, 5);
+    }
+
+    #[test]
+    fn test_missed_beats_and_latency_offset() {
+        let start = Instant::now();
+        let beat_times = beats(start, 8);
+
+        // Every tap 45 ms late, as heard through a laggy system, and beat 4 missed
+        let mut scorer = TimingScorer::new().with_latency_offset_ms(45.0);
+        for (index, &beat) in beat_times.iter().enumerate() {
+            scorer.record_beat(beat);
+            if index != 3 {
+                scorer.record_tap(shifted(beat, 45));
+            }
+        }
+
+        let score = scorer.score().unwrap();
+        assert_eq!(score.hits, 7);
+        assert!(score.mean_error_ms < 0.5);
+        assert!((score.in_window_percent - 87.5).abs() < 1e-3);
+        assert_eq!(score.longest_streak, 4);
+        assert_eq!(score.current_streak, 4);
+
+        scorer.reset();
+        assert_eq!(scorer.score(), None);
+        scorer.record_beat(beat_times[0]);
+        assert_eq!(scorer.score(), None);
+    }
+}
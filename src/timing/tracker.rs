@@ -3,7 +3,7 @@
 /// This module provides the observer pattern for coupling audio triggers
 /// with visual feedback. The BeatTracker captures timing events and
 /// notifies observers when beats occur.
-use super::types::BeatEvent;
+use super::types::{BeatEvent, Subdivision};
 
 /// Observer trait for beat events
 ///
@@ -68,6 +68,11 @@ impl BeatTracker {
         }
     }
 
+    /// Get the subdivision the last beat was counted in, if its clock set one
+    pub fn get_current_subdivision(&self) -> Option<Subdivision> {
+        self.current_beat.as_ref().and_then(|event| event.subdivision)
+    }
+
     /// Get the last beat timestamp for timing analysis
     pub fn get_last_beat_time(&self) -> Option<std::time::Instant> {
         self.current_beat.as_ref().map(|event| event.timestamp)
@@ -183,6 +183,157 @@ impl ClickType {
     }
 }
 
+/// Beat subdivision for practicing at finer rhythmic resolution
+///
+/// Describes how each main beat is divided for counting and display.
+/// `Quarter` means no subdivision: one pulse per beat.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::timing::Subdivision;
+///
+/// assert_eq!(Subdivision::Eighth.pulses_per_beat(), 2);
+/// assert_eq!(Subdivision::Sixteenth.count_label(2), "&");
+/// ```
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum Subdivision {
+    /// One pulse per beat (no subdivision)
+    #[default]
+    Quarter,
+    /// Two pulses per beat ("1 &")
+    Eighth,
+    /// Three pulses per beat ("1 trip let")
+    Triplet,
+    /// Four pulses per beat ("1 e & a")
+    Sixteenth,
+}
+
+impl Subdivision {
+    /// Get all available subdivisions
+    pub fn all() -> Vec<Subdivision> {
+        vec![
+            Subdivision::Quarter,
+            Subdivision::Eighth,
+            Subdivision::Triplet,
+            Subdivision::Sixteenth,
+        ]
+    }
+
+    /// Get the display name for this subdivision
+    pub fn name(self) -> &'static str {
+        match self {
+            Subdivision::Quarter => "Quarter Notes",
+            Subdivision::Eighth => "Eighth Notes",
+            Subdivision::Triplet => "Triplets",
+            Subdivision::Sixteenth => "Sixteenth Notes",
+        }
+    }
+
+    /// Number of evenly spaced pulses within one beat (including the beat itself)
+    pub fn pulses_per_beat(self) -> u8 {
+        match self {
+            Subdivision::Quarter => 1,
+            Subdivision::Eighth => 2,
+            Subdivision::Triplet => 3,
+            Subdivision::Sixteenth => 4,
+        }
+    }
+
+    /// Spoken counting syllable for a pulse index within the beat (0 = the beat itself)
+    pub fn count_label(self, pulse_index: u8) -> &'static str {
+        match (self, pulse_index) {
+            (_, 0) => "",
+            (Subdivision::Eighth, 1) => "&",
+            (Subdivision::Triplet, 1) => "trip",
+            (Subdivision::Triplet, 2) => "let",
+            (Subdivision::Sixteenth, 1) => "e",
+            (Subdivision::Sixteenth, 2) => "&",
+            (Subdivision::Sixteenth, 3) => "a",
+            _ => "",
+        }
+    }
+
+    /// Duration of one subdivision pulse in milliseconds for the given beat duration
+    pub fn pulse_duration_ms(self, beat_duration_ms: f64) -> f64 {
+        beat_duration_ms / self.pulses_per_beat() as f64
+    }
+}
+
+/// Note length used to sync periodic effects to the tempo
+///
+/// Lengths are measured in quarter-note beats, so `Whole` is one bar of 4/4.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::timing::NoteDivision;
+///
+/// assert_eq!(NoteDivision::Whole.beats(), 4.0);
+/// assert_eq!(NoteDivision::Eighth.duration_secs(120.0), 0.25);
+/// ```
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum NoteDivision {
+    /// Four beats (one bar of 4/4)
+    #[default]
+    Whole,
+    /// Two beats
+    Half,
+    /// One beat
+    Quarter,
+    /// Half a beat
+    Eighth,
+    /// A quarter of a beat
+    Sixteenth,
+}
+
+impl NoteDivision {
+    /// Get all available note divisions
+    pub fn all() -> Vec<NoteDivision> {
+        vec![
+            NoteDivision::Whole,
+            NoteDivision::Half,
+            NoteDivision::Quarter,
+            NoteDivision::Eighth,
+            NoteDivision::Sixteenth,
+        ]
+    }
+
+    /// Get the display name for this note division
+    pub fn name(self) -> &'static str {
+        match self {
+            NoteDivision::Whole => "1/1",
+            NoteDivision::Half => "1/2",
+            NoteDivision::Quarter => "1/4",
+            NoteDivision::Eighth => "1/8",
+            NoteDivision::Sixteenth => "1/16",
+        }
+    }
+
+    /// Length in quarter-note beats
+    pub fn beats(self) -> f32 {
+        match self {
+            NoteDivision::Whole => 4.0,
+            NoteDivision::Half => 2.0,
+            NoteDivision::Quarter => 1.0,
+            NoteDivision::Eighth => 0.5,
+            NoteDivision::Sixteenth => 0.25,
+        }
+    }
+
+    /// Length in seconds at the given tempo (0.0 for non-positive tempos)
+    pub fn duration_secs(self, tempo_bpm: f32) -> f32 {
+        if tempo_bpm <= 0.0 {
+            return 0.0;
+        }
+        self.beats() * 60.0 / tempo_bpm
+    }
+}
+
 /// Beat event for coupling audio triggers with visualization
 ///
 /// Represents a single beat event that was triggered by the timing system.
@@ -213,6 +364,21 @@ pub struct BeatEvent {
 
     /// Time signature when this beat was triggered
     pub time_signature: TimeSignature,
+
+    /// Whether the click was muted (e.g. by a gap-click drill) but the beat still counted
+    pub silenced: bool,
+
+    /// Transport run this beat belongs to, incremented each time the clock starts
+    pub run_id: u32,
+
+    /// Position of this beat among all beats emitted by the clock
+    ///
+    /// Keeps increasing across stop/start, so events from different runs can be
+    /// ordered even though beat numbers restart at 1.
+    pub sequence: u64,
+
+    /// Subdivision the clock was counting in, if it has one
+    pub subdivision: Option<Subdivision>,
 }
 
 impl BeatEvent {
@@ -231,9 +397,37 @@ impl BeatEvent {
             timestamp: Instant::now(),
             tempo_bpm,
             time_signature,
+            silenced: false,
+            run_id: 0,
+            sequence: 0,
+            subdivision: None,
         }
     }
 
+    /// Mark whether the click for this beat is muted
+    pub fn with_silenced(mut self, silenced: bool) -> Self {
+        self.silenced = silenced;
+        self
+    }
+
+    /// Record the subdivision the clock was counting in
+    pub fn with_subdivision(mut self, subdivision: Subdivision) -> Self {
+        self.subdivision = Some(subdivision);
+        self
+    }
+
+    /// Stamp the event with its transport run and sequence number
+    pub fn with_sequence(mut self, run_id: u32, sequence: u64) -> Self {
+        self.run_id = run_id;
+        self.sequence = sequence;
+        self
+    }
+
+    /// Check if this beat should produce audio
+    pub fn is_audible(&self) -> bool {
+        !self.silenced
+    }
+
     /// Check if this is the first beat of a measure (downbeat)
     pub fn is_downbeat(&self) -> bool {
         self.beat_number == 1
@@ -295,6 +489,16 @@ mod tests {
         assert!(drums.contains(&ClickType::AcousticKick));
     }
 
+    #[test]
+    fn test_subdivision_pulses() {
+        assert_eq!(Subdivision::default(), Subdivision::Quarter);
+        assert_eq!(Subdivision::Quarter.pulses_per_beat(), 1);
+        assert_eq!(Subdivision::Triplet.pulses_per_beat(), 3);
+        assert_eq!(Subdivision::Eighth.pulse_duration_ms(500.0), 250.0);
+        assert_eq!(Subdivision::Eighth.count_label(1), "&");
+        assert_eq!(Subdivision::Sixteenth.count_label(3), "a");
+    }
+
     #[test]
     fn test_beat_event_creation() {
         let event = BeatEvent::new(
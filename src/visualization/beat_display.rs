@@ -4,7 +4,7 @@ use crate::patterns::PatternState;
 /// This module contains the core beat visualization logic that was previously
 /// embedded in the BeatVisualizationPanel GUI component. It provides a clean,
 /// framework-agnostic API for beat visualization state management.
-use crate::timing::{BeatTracker, TimeSignature};
+use crate::timing::{BeatTracker, Subdivision, TimeSignature};
 
 /// Visual representation of a single beat
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +19,19 @@ pub struct BeatVisual {
     pub color: BeatColor,
     /// Visual symbol for this beat
     pub symbol: BeatSymbol,
+    /// Subdivision markers following this beat (empty for quarter notes)
+    pub subdivisions: Vec<SubdivisionMarker>,
+}
+
+/// Visual marker for a subdivision pulse between two main beats
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubdivisionMarker {
+    /// Pulse index within the beat (1 = first pulse after the beat itself)
+    pub pulse_index: u8,
+    /// Counting syllable for this pulse ("&", "e", "a", ...)
+    pub label: &'static str,
+    /// Whether this subdivision is the one currently sounding
+    pub is_active: bool,
 }
 
 /// Color coding for beat visualization
@@ -72,6 +85,8 @@ pub struct BeatVisualState {
     pub time_signature: TimeSignature,
     /// Beat interval in milliseconds
     pub beat_interval_ms: f64,
+    /// Subdivision used to generate the beat markers
+    pub subdivision: Subdivision,
     /// Additional info for pattern mode
     pub pattern_info: Option<PatternDisplayInfo>,
 }
@@ -104,6 +119,8 @@ pub struct BeatDisplay {
     time_signature: TimeSignature,
     /// Color scheme
     color_scheme: BeatColorScheme,
+    /// Subdivision markers to show when the tracked beat does not carry one
+    subdivision: Subdivision,
     /// Cached visual state
     cached_state: Option<BeatVisualState>,
     /// Whether cache is dirty
@@ -117,6 +134,7 @@ impl BeatDisplay {
             mode: VisualizationMode::Metronome,
             time_signature,
             color_scheme: BeatColorScheme::default(),
+            subdivision: Subdivision::Quarter,
             cached_state: None,
             cache_dirty: true,
         }
@@ -144,6 +162,22 @@ impl BeatDisplay {
         self.cache_dirty = true;
     }
 
+    /// Set the subdivision shown between beats
+    ///
+    /// Beats from a clock that stamps its subdivision, such as the Metronome,
+    /// show that subdivision instead, so the markers follow the metronome.
+    pub fn set_subdivision(&mut self, subdivision: Subdivision) {
+        if self.subdivision != subdivision {
+            self.subdivision = subdivision;
+            self.cache_dirty = true;
+        }
+    }
+
+    /// Get the subdivision shown between beats
+    pub fn subdivision(&self) -> Subdivision {
+        self.subdivision
+    }
+
     /// Generate visual state for current beat configuration
     ///
     /// This is the core logic extracted from BeatVisualizationPanel::show()
@@ -187,6 +221,19 @@ impl BeatDisplay {
             }
         };
 
+        // Calculate beat interval
+        let beat_interval_ms = 60000.0 / tempo_bpm as f64;
+
+        // Locate the sounding subdivision from time elapsed since the last beat
+        let subdivision = beat_tracker
+            .get_current_subdivision()
+            .unwrap_or(self.subdivision);
+        let active_pulse = if is_playing {
+            Self::active_pulse_index(beat_tracker, subdivision, beat_interval_ms)
+        } else {
+            None
+        };
+
         // Generate beat visuals
         let mut beats = Vec::new();
         for beat in 1..=effective_time_sig.beats_per_measure {
@@ -233,18 +280,24 @@ impl BeatDisplay {
                 BeatSymbol::EmptyCircle
             };
 
+            let subdivisions = (1..subdivision.pulses_per_beat())
+                .map(|pulse_index| SubdivisionMarker {
+                    pulse_index,
+                    label: subdivision.count_label(pulse_index),
+                    is_active: is_current && active_pulse == Some(pulse_index),
+                })
+                .collect();
+
             beats.push(BeatVisual {
                 beat_number: beat,
                 is_current,
                 is_accent,
                 color,
                 symbol,
+                subdivisions,
             });
         }
 
-        // Calculate beat interval
-        let beat_interval_ms = 60000.0 / tempo_bpm as f64;
-
         // Generate pattern info if in pattern mode
         let pattern_info = if self.mode == VisualizationMode::Pattern {
             pattern_state.and_then(|ps| {
@@ -262,10 +315,31 @@ impl BeatDisplay {
             beats,
             time_signature: effective_time_sig,
             beat_interval_ms,
+            subdivision,
             pattern_info,
         }
     }
 
+    /// Determine which pulse of the current beat is sounding (0 = the beat itself)
+    ///
+    /// Uses the last tracked beat time so the highlight follows the audio
+    /// triggers rather than a free-running UI clock.
+    fn active_pulse_index(
+        beat_tracker: &BeatTracker,
+        subdivision: Subdivision,
+        beat_interval_ms: f64,
+    ) -> Option<u8> {
+        let last_beat_time = beat_tracker.get_last_beat_time()?;
+        let elapsed_ms = last_beat_time.elapsed().as_secs_f64() * 1000.0;
+        let pulse_ms = subdivision.pulse_duration_ms(beat_interval_ms);
+        if pulse_ms <= 0.0 {
+            return Some(0);
+        }
+
+        let last_pulse = subdivision.pulses_per_beat() - 1;
+        Some(((elapsed_ms / pulse_ms).floor() as u8).min(last_pulse))
+    }
+
     /// Get current visual state (with caching)
     pub fn get_visual_state(
         &mut self,
@@ -366,7 +440,8 @@ pub fn get_legacy_beat_visuals(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::timing::BeatTracker;
+    use crate::timing::{BeatClock, BeatEvent, BeatTracker, Metronome};
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_beat_display_creation() {
@@ -410,4 +485,96 @@ mod tests {
         assert_eq!(state.time_signature.beats_per_measure, 4);
         assert_eq!(state.beat_interval_ms, 500.0); // 60000 / 120
     }
+
+    fn tracker_with_beat_elapsed(beat_number: u8, elapsed: Duration) -> BeatTracker {
+        let mut beat_tracker = BeatTracker::new();
+        let mut event = BeatEvent::new(beat_number, false, vec![], 120.0, TimeSignature::new(4, 4));
+        event.timestamp = Instant::now() - elapsed;
+        beat_tracker.record_beat(event);
+        beat_tracker
+    }
+
+    #[test]
+    fn test_no_subdivision_markers_by_default() {
+        let display = BeatDisplay::new(TimeSignature::new(4, 4));
+        let beat_tracker = tracker_with_beat_elapsed(2, Duration::from_millis(300));
+
+        let state = display.generate_visual_state(&beat_tracker, None, 120.0, true, true);
+
+        assert_eq!(state.subdivision, Subdivision::Quarter);
+        assert!(state.beats.iter().all(|beat| beat.subdivisions.is_empty()));
+    }
+
+    #[test]
+    fn test_eighth_note_subdivision_markers() {
+        let mut display = BeatDisplay::new(TimeSignature::new(4, 4));
+        display.set_subdivision(Subdivision::Eighth);
+
+        // 300ms into beat 2 at 120 BPM (500ms beats) is the "and" of 2
+        let beat_tracker = tracker_with_beat_elapsed(2, Duration::from_millis(300));
+        let state = display.generate_visual_state(&beat_tracker, None, 120.0, true, true);
+
+        assert_eq!(state.beats.len(), 4);
+        for beat in &state.beats {
+            assert_eq!(beat.subdivisions.len(), 1);
+            assert_eq!(beat.subdivisions[0].pulse_index, 1);
+            assert_eq!(beat.subdivisions[0].label, "&");
+        }
+
+        let active: Vec<_> = state
+            .beats
+            .iter()
+            .filter(|beat| beat.subdivisions.iter().any(|marker| marker.is_active))
+            .map(|beat| beat.beat_number)
+            .collect();
+        assert_eq!(active, vec![2]);
+    }
+
+    #[test]
+    fn test_subdivision_marker_inactive_on_the_beat() {
+        let mut display = BeatDisplay::new(TimeSignature::new(4, 4));
+        display.set_subdivision(Subdivision::Eighth);
+
+        // 100ms into beat 3 is still the downbeat pulse, not the "and"
+        let beat_tracker = tracker_with_beat_elapsed(3, Duration::from_millis(100));
+        let state = display.generate_visual_state(&beat_tracker, None, 120.0, true, true);
+
+        assert!(state.beats[2].is_current);
+        assert!(!state.beats[2].subdivisions[0].is_active);
+    }
+
+    #[test]
+    fn test_markers_follow_the_metronome_subdivision() {
+        let mut metronome = Metronome::new(TimeSignature::new(4, 4));
+        metronome.set_subdivision(Subdivision::Triplet);
+        metronome.start();
+        let mut beat_tracker = BeatTracker::new();
+        for event in metronome.check_triggers_at(120.0, Instant::now()) {
+            beat_tracker.record_beat(event);
+        }
+
+        // The display's own setting is only a fallback for unstamped beats
+        let display = BeatDisplay::new(TimeSignature::new(4, 4));
+        let state = display.generate_visual_state(&beat_tracker, None, 120.0, true, true);
+        assert_eq!(state.subdivision, Subdivision::Triplet);
+        assert_eq!(state.beats[0].subdivisions.len(), 2);
+    }
+
+    #[test]
+    fn test_sixteenth_subdivision_highlights_active_pulse() {
+        let mut display = BeatDisplay::new(TimeSignature::new(3, 4));
+        display.set_subdivision(Subdivision::Sixteenth);
+
+        // 400ms into a 500ms beat is the fourth sixteenth ("a")
+        let beat_tracker = tracker_with_beat_elapsed(1, Duration::from_millis(400));
+        let state = display.generate_visual_state(&beat_tracker, None, 120.0, true, true);
+
+        let markers = &state.beats[0].subdivisions;
+        assert_eq!(markers.len(), 3);
+        let labels: Vec<_> = markers.iter().map(|marker| marker.label).collect();
+        assert_eq!(labels, vec!["e", "&", "a"]);
+        assert!(!markers[0].is_active);
+        assert!(!markers[1].is_active);
+        assert!(markers[2].is_active);
+    }
 }
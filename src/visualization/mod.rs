@@ -8,6 +8,8 @@
 /// - **Visual State**: Current beat position, accent status, and timing info
 /// - **Color Schemes**: Customizable color coding for different beat states
 /// - **Layout**: Beat arrangement and spacing calculations
+/// - **Spectrum**: Windowed FFT analysis grouped into display bands
+/// - **Scope**: Recent-sample ring reduced to min/max columns for drawing
 ///
 /// # Current Implementation Status
 ///
@@ -16,9 +18,11 @@
 /// - Time signature display (4/4, 3/4, 2/4, 6/8, etc.)
 /// - Beat accent highlighting
 /// - Current beat position indication
+/// - Subdivision markers between beats with the active pulse highlighted
+/// - Spectrum analyzer with log-spaced bands for frequency displays
+/// - Oscilloscope buffer with per-column min/max envelopes
 ///
 /// **Limitations (Prototype Stage):**
-/// - No waveform display or spectrum analysis
 /// - Limited to simple circular beat indicators
 /// - No advanced visualization effects or animations
 /// - Color schemes are basic (not fully customizable)
@@ -63,8 +67,14 @@
 /// # }
 /// ```
 pub mod beat_display;
+/// Oscilloscope buffer reducing recent output to per-column min/max pairs.
+pub mod scope;
+/// Real-time windowed FFT spectrum analysis grouped into display bands.
+pub mod spectrum;
 
 // Re-export core types for convenient access
 pub use beat_display::{
-    BeatColorScheme, BeatDisplay, BeatVisual, BeatVisualState, VisualizationMode,
+    BeatColorScheme, BeatDisplay, BeatVisual, BeatVisualState, SubdivisionMarker, VisualizationMode,
 };
+pub use scope::WaveformScope;
+pub use spectrum::SpectrumAnalyzer;
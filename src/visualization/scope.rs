@@ -0,0 +1,128 @@
+/// Oscilloscope display buffer for drawing recent output
+///
+/// This module provides WaveformScope, which keeps the most recent samples
+/// pushed from the audio callback and reduces them to one min/max pair per
+/// pixel column for drawing. Storage is a lock-free ScopeTap, so the audio
+/// thread never blocks on a repainting GUI.
+use crate::audio::scope::ScopeTap;
+
+/// Default number of samples a WaveformScope keeps (about 46 ms at 44.1kHz)
+pub const DEFAULT_SCOPE_SAMPLES: usize = 2048;
+
+/// Ring of recent samples with a per-column min/max envelope for drawing
+///
+/// Clones share the same ring: keep one in the stream callback and another
+/// in the GUI.
+///
+/// # Examples
+///
+/// ```rust
+/// use polyphonica::visualization::WaveformScope;
+///
+/// let scope = WaveformScope::new(1024);
+/// let gui_scope = scope.clone();
+///
+/// // Audio callback
+/// scope.push_samples(&[0.0, 0.5, -0.5, 1.0]);
+///
+/// // GUI repaint: one (min, max) pair per pixel column
+/// let columns = gui_scope.envelope(2);
+/// assert_eq!(columns, vec![(0.0, 0.5), (-0.5, 1.0)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaveformScope {
+    tap: ScopeTap,
+}
+
+impl WaveformScope {
+    /// Create a scope keeping the last `capacity` samples (at least one)
+    pub fn new(capacity: usize) -> Self {
+        Self::from_tap(ScopeTap::new(capacity))
+    }
+
+    /// Draw from an existing tap, e.g. one already fed by the output stream
+    pub fn from_tap(tap: ScopeTap) -> Self {
+        Self { tap }
+    }
+
+    /// Number of samples kept
+    pub fn capacity(&self) -> usize {
+        self.tap.capacity()
+    }
+
+    /// Append a buffer of samples (audio thread)
+    pub fn push_samples(&self, samples: &[f32]) {
+        self.tap.push(samples);
+    }
+
+    /// Reduce the stored samples to `columns` (min, max) pairs, oldest first
+    ///
+    /// Each column covers an equal share of the samples; when there are more
+    /// columns than samples, neighbouring columns repeat the same sample.
+    /// Before anything has been pushed every column is (0.0, 0.0).
+    pub fn envelope(&self, columns: usize) -> Vec<(f32, f32)> {
+        let mut frame = vec![0.0; self.capacity()];
+        let count = self.tap.pull(&mut frame).samples;
+        let frame = &frame[..count];
+        if frame.is_empty() {
+            return vec![(0.0, 0.0); columns];
+        }
+
+        (0..columns)
+            .map(|column| {
+                let start = column * frame.len() / columns;
+                let end = ((column + 1) * frame.len() / columns).max(start + 1);
+                frame[start..end]
+                    .iter()
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &s| {
+                        (min.min(s), max.max(s))
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Default for WaveformScope {
+    fn default() -> Self {
+        Self::new(DEFAULT_SCOPE_SAMPLES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp_envelope_is_monotonic() {
+        let scope = WaveformScope::new(256);
+        let ramp: Vec<f32> = (0..256).map(|i| i as f32 / 128.0 - 1.0).collect();
+        scope.push_samples(&ramp);
+
+        let columns = scope.envelope(16);
+        assert_eq!(columns.len(), 16);
+        assert_eq!(columns[0].0, -1.0);
+        assert_eq!(columns[15].1, ramp[255]);
+        for (min, max) in &columns {
+            assert!(min < max);
+        }
+        for pair in columns.windows(2) {
+            assert!(pair[1].0 > pair[0].0);
+            assert!(pair[1].1 > pair[0].1);
+            assert!(pair[1].0 > pair[0].1);
+        }
+    }
+
+    #[test]
+    fn test_sparse_and_empty_envelopes() {
+        let scope = WaveformScope::new(64);
+        assert_eq!(scope.envelope(3), vec![(0.0, 0.0); 3]);
+        assert!(scope.envelope(0).is_empty());
+
+        // More columns than samples repeats samples instead of leaving gaps
+        scope.push_samples(&[-1.0, 1.0]);
+        assert_eq!(
+            scope.envelope(4),
+            vec![(-1.0, -1.0), (-1.0, -1.0), (1.0, 1.0), (1.0, 1.0)]
+        );
+    }
+}
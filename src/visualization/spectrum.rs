@@ -0,0 +1,308 @@
+/// Real-time spectrum analysis for frequency displays
+///
+/// This module provides SpectrumAnalyzer, which keeps the most recent audio
+/// in a window, runs a Hann-windowed FFT over it as new samples arrive, and
+/// groups the magnitudes into logarithmically spaced bands ready to draw as
+/// bars. Every buffer is allocated up front, so feeding it from the audio
+/// callback or a scope tap never allocates.
+use crate::audio::fft::{hann_window, Fft};
+
+/// Default FFT length (about 46 ms at 44.1kHz, 21.5 Hz per bin)
+pub const DEFAULT_FFT_SIZE: usize = 2048;
+
+/// Default number of display bands
+pub const DEFAULT_BAND_COUNT: usize = 32;
+
+/// Default lowest frequency shown (Hz)
+pub const DEFAULT_MIN_BAND_HZ: f32 = 20.0;
+
+/// Default highest frequency shown (Hz)
+pub const DEFAULT_MAX_BAND_HZ: f32 = 20000.0;
+
+/// Smallest and largest supported FFT lengths
+const MIN_FFT_SIZE: usize = 64;
+const MAX_FFT_SIZE: usize = 32768;
+
+/// Windowed FFT analyzer producing per-bin magnitudes and display bands
+///
+/// Magnitudes are scaled so a full-scale sine centered on a bin reads 1.0.
+/// A band's level is the root of the summed squared magnitudes of its bins,
+/// so it reflects the energy in the band. The spectrum is refreshed whenever
+/// half a window of new samples has arrived (50% overlap).
+///
+/// # Usage Example
+///
+/// ```rust
+/// use polyphonica::visualization::SpectrumAnalyzer;
+///
+/// let mut analyzer = SpectrumAnalyzer::new(44100.0, 1024).with_log_bands(16, 40.0, 16000.0);
+///
+/// // Audio callback or GUI update
+/// let buffer = vec![0.0; 512];
+/// analyzer.feed(&buffer);
+///
+/// for (index, level) in analyzer.bands().iter().enumerate() {
+///     let (low_hz, high_hz) = analyzer.band_range(index);
+///     // Draw a bar of height `level` for low_hz..high_hz
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpectrumAnalyzer {
+    /// Sample rate of the fed audio in Hz
+    sample_rate: f32,
+
+    /// FFT length, a power of two
+    fft_size: usize,
+
+    /// Hann window coefficients
+    window: Vec<f32>,
+
+    /// Transform with its tables precomputed
+    fft: Fft,
+
+    /// Most recent samples, oldest at `write_position`
+    history: Vec<f32>,
+
+    /// Next history slot to overwrite
+    write_position: usize,
+
+    /// Samples fed since the spectrum was last refreshed
+    pending: usize,
+
+    /// FFT working buffers
+    re: Vec<f32>,
+    im: Vec<f32>,
+
+    /// Magnitude of each bin from DC to Nyquist
+    magnitudes: Vec<f32>,
+
+    /// Frequency edges of the bands, one more than the band count
+    band_edges: Vec<f32>,
+
+    /// Bin range (start, end exclusive) summed into each band
+    band_bins: Vec<(usize, usize)>,
+
+    /// Level of each band
+    bands: Vec<f32>,
+}
+
+impl SpectrumAnalyzer {
+    /// Create an analyzer with the default log-spaced bands
+    ///
+    /// `fft_size` is rounded up to a power of two between 64 and 32768.
+    pub fn new(sample_rate: f32, fft_size: usize) -> Self {
+        let fft_size = fft_size
+            .clamp(MIN_FFT_SIZE, MAX_FFT_SIZE)
+            .next_power_of_two();
+        let sample_rate = if sample_rate.is_finite() && sample_rate > 0.0 {
+            sample_rate
+        } else {
+            crate::DEFAULT_SAMPLE_RATE
+        };
+
+        Self {
+            sample_rate,
+            fft_size,
+            window: hann_window(fft_size),
+            fft: Fft::new(fft_size),
+            history: vec![0.0; fft_size],
+            write_position: 0,
+            pending: 0,
+            re: vec![0.0; fft_size],
+            im: vec![0.0; fft_size],
+            magnitudes: vec![0.0; fft_size / 2 + 1],
+            band_edges: Vec::new(),
+            band_bins: Vec::new(),
+            bands: Vec::new(),
+        }
+        .with_log_bands(DEFAULT_BAND_COUNT, DEFAULT_MIN_BAND_HZ, DEFAULT_MAX_BAND_HZ)
+    }
+
+    /// Group the spectrum into `count` logarithmically spaced bands
+    ///
+    /// The range is limited to the Nyquist frequency. Bands too narrow to
+    /// contain a bin center show the bin nearest their center.
+    pub fn with_log_bands(mut self, count: usize, min_hz: f32, max_hz: f32) -> Self {
+        let count = count.max(1);
+        let nyquist = self.sample_rate / 2.0;
+        let max_hz = max_hz.clamp(1.0, nyquist);
+        let min_hz = min_hz.clamp(1.0, max_hz);
+        let ratio = (max_hz / min_hz).powf(1.0 / count as f32);
+
+        self.band_edges = (0..=count)
+            .map(|edge| min_hz * ratio.powi(edge as i32))
+            .collect();
+        let bin_hz = self.bin_hz();
+        let last_bin = self.magnitudes.len() - 1;
+        self.band_bins = self
+            .band_edges
+            .windows(2)
+            .map(|edges| {
+                let start = ((edges[0] / bin_hz).ceil() as usize).min(last_bin);
+                let end = ((edges[1] / bin_hz).ceil() as usize).min(last_bin + 1);
+                if start < end {
+                    (start, end)
+                } else {
+                    let center = ((edges[0] * edges[1]).sqrt() / bin_hz).round() as usize;
+                    (center.min(last_bin), center.min(last_bin) + 1)
+                }
+            })
+            .collect();
+        self.bands = vec![0.0; count];
+        self.update_bands();
+        self
+    }
+
+    /// Add audio and refresh the spectrum if half a window has arrived
+    pub fn feed(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.history[self.write_position] = sample;
+            self.write_position = (self.write_position + 1) % self.fft_size;
+        }
+        self.pending += samples.len();
+        if self.pending >= self.fft_size / 2 {
+            self.pending = 0;
+            self.analyze();
+        }
+    }
+
+    /// Level of each band, lowest frequency first
+    pub fn bands(&self) -> &[f32] {
+        &self.bands
+    }
+
+    /// Magnitude of each FFT bin from DC to Nyquist
+    pub fn magnitudes(&self) -> &[f32] {
+        &self.magnitudes
+    }
+
+    /// Frequency range of a band in Hz (low, high)
+    pub fn band_range(&self, band: usize) -> (f32, f32) {
+        (self.band_edges[band], self.band_edges[band + 1])
+    }
+
+    /// Frequency spacing of the FFT bins in Hz
+    pub fn bin_hz(&self) -> f32 {
+        self.sample_rate / self.fft_size as f32
+    }
+
+    /// FFT length in samples
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Clear the history and spectrum, e.g. when playback stops
+    pub fn reset(&mut self) {
+        self.history.fill(0.0);
+        self.magnitudes.fill(0.0);
+        self.bands.fill(0.0);
+        self.write_position = 0;
+        self.pending = 0;
+    }
+
+    /// Transform the current window into magnitudes and band levels
+    fn analyze(&mut self) {
+        let n = self.fft_size;
+        for i in 0..n {
+            self.re[i] = self.history[(self.write_position + i) % n] * self.window[i];
+            self.im[i] = 0.0;
+        }
+        self.fft.forward(&mut self.re, &mut self.im);
+
+        // The Hann window halves a sine's amplitude; one-sided bins halve it again
+        let scale = 4.0 / n as f32;
+        for (bin, magnitude) in self.magnitudes.iter_mut().enumerate() {
+            *magnitude = (self.re[bin] * self.re[bin] + self.im[bin] * self.im[bin]).sqrt() * scale;
+        }
+        self.update_bands();
+    }
+
+    /// Recompute band levels from the bin magnitudes
+    fn update_bands(&mut self) {
+        for (level, &(start, end)) in self.bands.iter_mut().zip(&self.band_bins) {
+            *level = self.magnitudes[start..end]
+                .iter()
+                .map(|magnitude| magnitude * magnitude)
+                .sum::<f32>()
+                .sqrt();
+        }
+    }
+}
+
+impl Default for SpectrumAnalyzer {
+    fn default() -> Self {
+        Self::new(crate::DEFAULT_SAMPLE_RATE, DEFAULT_FFT_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn sine(frequency: f32, sample_rate: f32, length: usize) -> Vec<f32> {
+        (0..length)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_sine_energy_lands_in_its_bin_and_band() {
+        let mut analyzer = SpectrumAnalyzer::new(44100.0, 2048);
+        for chunk in sine(1000.0, 44100.0, 8192).chunks(512) {
+            analyzer.feed(chunk);
+        }
+
+        // 1 kHz falls in bin 46 (990.5 - 1012.0 Hz)
+        let magnitudes = analyzer.magnitudes();
+        let peak_bin = (0..magnitudes.len())
+            .max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b]))
+            .unwrap();
+        assert_eq!(peak_bin, (1000.0 / analyzer.bin_hz()) as usize);
+        assert!(magnitudes[peak_bin] > 0.6, "{}", magnitudes[peak_bin]);
+        let total: f32 = magnitudes.iter().map(|m| m * m).sum();
+        let near: f32 = magnitudes[peak_bin - 2..=peak_bin + 3]
+            .iter()
+            .map(|m| m * m)
+            .sum();
+        assert!(near / total > 0.999, "{}", near / total);
+
+        // The band holding 1 kHz is the loudest, and bands an octave away are silent
+        let bands = analyzer.bands();
+        let loudest = (0..bands.len())
+            .max_by(|&a, &b| bands[a].total_cmp(&bands[b]))
+            .unwrap();
+        let (low, high) = analyzer.band_range(loudest);
+        assert!(low <= 1000.0 && 1000.0 < high, "{}..{}", low, high);
+        for (index, level) in bands.iter().enumerate() {
+            let (low, high) = analyzer.band_range(index);
+            if high < 500.0 || low > 2000.0 {
+                assert!(*level < 1e-3, "{}..{} Hz: {}", low, high, level);
+            }
+        }
+
+        analyzer.reset();
+        assert!(analyzer.bands().iter().all(|&level| level == 0.0));
+    }
+
+    #[test]
+    fn test_bands_cover_range_without_gaps() {
+        let analyzer = SpectrumAnalyzer::new(48000.0, 1000).with_log_bands(10, 50.0, 30000.0);
+        assert_eq!(analyzer.fft_size(), 1024);
+        assert_eq!(analyzer.bands().len(), 10);
+
+        // The top is limited to Nyquist, and each band starts where the last ended
+        assert!((analyzer.band_range(0).0 - 50.0).abs() < 1e-3);
+        assert!((analyzer.band_range(9).1 - 24000.0).abs() < 1.0);
+        for band in 1..10 {
+            assert_eq!(analyzer.band_range(band).0, analyzer.band_range(band - 1).1);
+        }
+
+        // Feeding less than half a window leaves the spectrum alone
+        let mut analyzer = analyzer;
+        analyzer.feed(&sine(440.0, 48000.0, 100));
+        assert!(analyzer.magnitudes().iter().all(|&m| m == 0.0));
+        analyzer.feed(&sine(440.0, 48000.0, 500));
+        assert!(analyzer.magnitudes().iter().any(|&m| m > 0.01));
+    }
+}